@@ -311,6 +311,12 @@ pub mod message {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -338,6 +344,16 @@ pub mod message {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -397,6 +413,16 @@ pub mod message {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_unimplemented(
+            self,
+        ) -> ::capnp::Result<crate::rpc_capnp::message::Builder<'a>> {
+            self.builder.set_data_field::<u16>(0, 0);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_unimplemented(&self) -> bool {
             if self.builder.get_data_field::<u16>(0) != 0 {
                 return false;
@@ -421,6 +447,14 @@ pub mod message {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_abort(self) -> ::capnp::Result<crate::rpc_capnp::exception::Builder<'a>> {
+            self.builder.set_data_field::<u16>(0, 1);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_abort(&self) -> bool {
             if self.builder.get_data_field::<u16>(0) != 1 {
                 return false;
@@ -445,6 +479,14 @@ pub mod message {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_call(self) -> ::capnp::Result<crate::rpc_capnp::call::Builder<'a>> {
+            self.builder.set_data_field::<u16>(0, 2);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_call(&self) -> bool {
             if self.builder.get_data_field::<u16>(0) != 2 {
                 return false;
@@ -469,6 +511,14 @@ pub mod message {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_return(self) -> ::capnp::Result<crate::rpc_capnp::return_::Builder<'a>> {
+            self.builder.set_data_field::<u16>(0, 3);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_return(&self) -> bool {
             if self.builder.get_data_field::<u16>(0) != 3 {
                 return false;
@@ -493,6 +543,14 @@ pub mod message {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_finish(self) -> ::capnp::Result<crate::rpc_capnp::finish::Builder<'a>> {
+            self.builder.set_data_field::<u16>(0, 4);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_finish(&self) -> bool {
             if self.builder.get_data_field::<u16>(0) != 4 {
                 return false;
@@ -517,6 +575,14 @@ pub mod message {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_resolve(self) -> ::capnp::Result<crate::rpc_capnp::resolve::Builder<'a>> {
+            self.builder.set_data_field::<u16>(0, 5);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_resolve(&self) -> bool {
             if self.builder.get_data_field::<u16>(0) != 5 {
                 return false;
@@ -541,6 +607,14 @@ pub mod message {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_release(self) -> ::capnp::Result<crate::rpc_capnp::release::Builder<'a>> {
+            self.builder.set_data_field::<u16>(0, 6);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_release(&self) -> bool {
             if self.builder.get_data_field::<u16>(0) != 6 {
                 return false;
@@ -579,6 +653,16 @@ pub mod message {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_bootstrap(
+            self,
+        ) -> ::capnp::Result<crate::rpc_capnp::bootstrap::Builder<'a>> {
+            self.builder.set_data_field::<u16>(0, 8);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_bootstrap(&self) -> bool {
             if self.builder.get_data_field::<u16>(0) != 8 {
                 return false;
@@ -617,6 +701,14 @@ pub mod message {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_provide(self) -> ::capnp::Result<crate::rpc_capnp::provide::Builder<'a>> {
+            self.builder.set_data_field::<u16>(0, 10);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_provide(&self) -> bool {
             if self.builder.get_data_field::<u16>(0) != 10 {
                 return false;
@@ -641,6 +733,14 @@ pub mod message {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_accept(self) -> ::capnp::Result<crate::rpc_capnp::accept::Builder<'a>> {
+            self.builder.set_data_field::<u16>(0, 11);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_accept(&self) -> bool {
             if self.builder.get_data_field::<u16>(0) != 11 {
                 return false;
@@ -665,6 +765,14 @@ pub mod message {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_join(self) -> ::capnp::Result<crate::rpc_capnp::join::Builder<'a>> {
+            self.builder.set_data_field::<u16>(0, 12);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_join(&self) -> bool {
             if self.builder.get_data_field::<u16>(0) != 12 {
                 return false;
@@ -689,6 +797,16 @@ pub mod message {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_disembargo(
+            self,
+        ) -> ::capnp::Result<crate::rpc_capnp::disembargo::Builder<'a>> {
+            self.builder.set_data_field::<u16>(0, 13);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_disembargo(&self) -> bool {
             if self.builder.get_data_field::<u16>(0) != 13 {
                 return false;
@@ -1249,6 +1367,12 @@ pub mod bootstrap {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -1276,6 +1400,16 @@ pub mod bootstrap {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -1608,6 +1742,12 @@ pub mod call {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -1635,6 +1775,16 @@ pub mod call {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -1707,6 +1857,15 @@ pub mod call {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_target(
+            self,
+        ) -> ::capnp::Result<crate::rpc_capnp::message_target::Builder<'a>> {
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_target(&self) -> bool {
             !self.builder.is_pointer_field_null(0)
         }
@@ -1749,6 +1908,13 @@ pub mod call {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(1), 0)
         }
         #[inline]
+        pub fn try_init_params(self) -> ::capnp::Result<crate::rpc_capnp::payload::Builder<'a>> {
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(1),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_params(&self) -> bool {
             !self.builder.is_pointer_field_null(1)
         }
@@ -2147,6 +2313,12 @@ pub mod call {
             }
         }
 
+        impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -2179,6 +2351,16 @@ pub mod call {
                     .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: ::capnp::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> ::capnp::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: ::capnp::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -2559,6 +2741,12 @@ pub mod return_ {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -2586,6 +2774,16 @@ pub mod return_ {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -2661,6 +2859,14 @@ pub mod return_ {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_results(self) -> ::capnp::Result<crate::rpc_capnp::payload::Builder<'a>> {
+            self.builder.set_data_field::<u16>(3, 0);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_results(&self) -> bool {
             if self.builder.get_data_field::<u16>(3) != 0 {
                 return false;
@@ -2685,6 +2891,16 @@ pub mod return_ {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_exception(
+            self,
+        ) -> ::capnp::Result<crate::rpc_capnp::exception::Builder<'a>> {
+            self.builder.set_data_field::<u16>(3, 1);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_exception(&self) -> bool {
             if self.builder.get_data_field::<u16>(3) != 1 {
                 return false;
@@ -3118,6 +3334,12 @@ pub mod finish {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -3145,6 +3367,16 @@ pub mod finish {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -3483,6 +3715,12 @@ pub mod resolve {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -3510,6 +3748,16 @@ pub mod resolve {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -3577,6 +3825,16 @@ pub mod resolve {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_cap(
+            self,
+        ) -> ::capnp::Result<crate::rpc_capnp::cap_descriptor::Builder<'a>> {
+            self.builder.set_data_field::<u16>(2, 0);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_cap(&self) -> bool {
             if self.builder.get_data_field::<u16>(2) != 0 {
                 return false;
@@ -3601,6 +3859,16 @@ pub mod resolve {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_exception(
+            self,
+        ) -> ::capnp::Result<crate::rpc_capnp::exception::Builder<'a>> {
+            self.builder.set_data_field::<u16>(2, 1);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_exception(&self) -> bool {
             if self.builder.get_data_field::<u16>(2) != 1 {
                 return false;
@@ -3875,6 +4143,12 @@ pub mod release {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -3902,6 +4176,16 @@ pub mod release {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -4187,6 +4471,12 @@ pub mod disembargo {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -4214,6 +4504,16 @@ pub mod disembargo {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -4278,6 +4578,15 @@ pub mod disembargo {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_target(
+            self,
+        ) -> ::capnp::Result<crate::rpc_capnp::message_target::Builder<'a>> {
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_target(&self) -> bool {
             !self.builder.is_pointer_field_null(0)
         }
@@ -4522,6 +4831,12 @@ pub mod disembargo {
             }
         }
 
+        impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -4554,6 +4869,16 @@ pub mod disembargo {
                     .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: ::capnp::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> ::capnp::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: ::capnp::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -4907,6 +5232,12 @@ pub mod provide {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -4934,6 +5265,16 @@ pub mod provide {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -5006,6 +5347,15 @@ pub mod provide {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_target(
+            self,
+        ) -> ::capnp::Result<crate::rpc_capnp::message_target::Builder<'a>> {
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_target(&self) -> bool {
             !self.builder.is_pointer_field_null(0)
         }
@@ -5276,6 +5626,12 @@ pub mod accept {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -5303,6 +5659,16 @@ pub mod accept {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -5631,6 +5997,12 @@ pub mod join {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -5658,6 +6030,16 @@ pub mod join {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -5730,6 +6112,15 @@ pub mod join {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_target(
+            self,
+        ) -> ::capnp::Result<crate::rpc_capnp::message_target::Builder<'a>> {
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_target(&self) -> bool {
             !self.builder.is_pointer_field_null(0)
         }
@@ -6004,6 +6395,12 @@ pub mod message_target {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -6031,6 +6428,16 @@ pub mod message_target {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -6095,6 +6502,16 @@ pub mod message_target {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_promised_answer(
+            self,
+        ) -> ::capnp::Result<crate::rpc_capnp::promised_answer::Builder<'a>> {
+            self.builder.set_data_field::<u16>(2, 1);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_promised_answer(&self) -> bool {
             if self.builder.get_data_field::<u16>(2) != 1 {
                 return false;
@@ -6360,6 +6777,12 @@ pub mod payload {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -6387,6 +6810,16 @@ pub mod payload {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -6475,6 +6908,18 @@ pub mod payload {
             )
         }
         #[inline]
+        pub fn try_init_cap_table(
+            self,
+            size: u32,
+        ) -> ::capnp::Result<
+            ::capnp::struct_list::Builder<'a, crate::rpc_capnp::cap_descriptor::Owned>,
+        > {
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(1),
+                size,
+            )
+        }
+        #[inline]
         pub fn has_cap_table(&self) -> bool {
             !self.builder.is_pointer_field_null(1)
         }
@@ -6747,6 +7192,12 @@ pub mod cap_descriptor {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -6774,6 +7225,16 @@ pub mod cap_descriptor {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -6852,6 +7313,16 @@ pub mod cap_descriptor {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_receiver_answer(
+            self,
+        ) -> ::capnp::Result<crate::rpc_capnp::promised_answer::Builder<'a>> {
+            self.builder.set_data_field::<u16>(0, 4);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_receiver_answer(&self) -> bool {
             if self.builder.get_data_field::<u16>(0) != 4 {
                 return false;
@@ -6878,6 +7349,16 @@ pub mod cap_descriptor {
             ::capnp::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_third_party_hosted(
+            self,
+        ) -> ::capnp::Result<crate::rpc_capnp::third_party_cap_descriptor::Builder<'a>> {
+            self.builder.set_data_field::<u16>(0, 5);
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_third_party_hosted(&self) -> bool {
             if self.builder.get_data_field::<u16>(0) != 5 {
                 return false;
@@ -7255,6 +7736,12 @@ pub mod promised_answer {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -7282,6 +7769,16 @@ pub mod promised_answer {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -7365,6 +7862,18 @@ pub mod promised_answer {
             )
         }
         #[inline]
+        pub fn try_init_transform(
+            self,
+            size: u32,
+        ) -> ::capnp::Result<
+            ::capnp::struct_list::Builder<'a, crate::rpc_capnp::promised_answer::op::Owned>,
+        > {
+            ::capnp::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                size,
+            )
+        }
+        #[inline]
         pub fn has_transform(&self) -> bool {
             !self.builder.is_pointer_field_null(0)
         }
@@ -7605,6 +8114,12 @@ pub mod promised_answer {
             }
         }
 
+        impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -7637,6 +8152,16 @@ pub mod promised_answer {
                     .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: ::capnp::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> ::capnp::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: ::capnp::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -7930,6 +8455,12 @@ pub mod third_party_cap_descriptor {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -7957,6 +8488,16 @@ pub mod third_party_cap_descriptor {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -8275,6 +8816,12 @@ pub mod exception {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -8302,6 +8849,16 @@ pub mod exception {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -8355,10 +8912,31 @@ pub mod exception {
             self.builder.reborrow().get_pointer_field(0).set_text(value);
         }
         #[inline]
+        pub fn try_set_reason(&mut self, value: ::capnp::text::Reader<'_>) -> ::capnp::Result<()> {
+            self.builder
+                .reborrow()
+                .get_pointer_field(0)
+                .try_set_text(value)
+        }
+        #[inline]
         pub fn init_reason(self, size: u32) -> ::capnp::text::Builder<'a> {
             self.builder.get_pointer_field(0).init_text(size)
         }
         #[inline]
+        pub fn try_init_reason(self, size: u32) -> ::capnp::Result<::capnp::text::Builder<'a>> {
+            self.builder.get_pointer_field(0).try_init_text(size)
+        }
+        #[inline]
+        pub fn reserve_reason(
+            self,
+            max_len: u32,
+        ) -> ::capnp::Result<::capnp::text::Reservation<'a>> {
+            ::capnp::text::reserve(
+                ::capnp::any_pointer::Builder::new(self.builder.get_pointer_field(0)),
+                max_len,
+            )
+        }
+        #[inline]
         pub fn has_reason(&self) -> bool {
             !self.builder.is_pointer_field_null(0)
         }
@@ -8401,10 +8979,31 @@ pub mod exception {
             self.builder.reborrow().get_pointer_field(1).set_text(value);
         }
         #[inline]
+        pub fn try_set_trace(&mut self, value: ::capnp::text::Reader<'_>) -> ::capnp::Result<()> {
+            self.builder
+                .reborrow()
+                .get_pointer_field(1)
+                .try_set_text(value)
+        }
+        #[inline]
         pub fn init_trace(self, size: u32) -> ::capnp::text::Builder<'a> {
             self.builder.get_pointer_field(1).init_text(size)
         }
         #[inline]
+        pub fn try_init_trace(self, size: u32) -> ::capnp::Result<::capnp::text::Builder<'a>> {
+            self.builder.get_pointer_field(1).try_init_text(size)
+        }
+        #[inline]
+        pub fn reserve_trace(
+            self,
+            max_len: u32,
+        ) -> ::capnp::Result<::capnp::text::Reservation<'a>> {
+            ::capnp::text::reserve(
+                ::capnp::any_pointer::Builder::new(self.builder.get_pointer_field(1)),
+                max_len,
+            )
+        }
+        #[inline]
         pub fn has_trace(&self) -> bool {
             !self.builder.is_pointer_field_null(1)
         }
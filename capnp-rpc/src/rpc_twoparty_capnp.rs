@@ -216,6 +216,12 @@ pub mod vat_id {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -243,6 +249,16 @@ pub mod vat_id {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -498,6 +514,12 @@ pub mod provision_id {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -525,6 +547,16 @@ pub mod provision_id {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -771,6 +803,12 @@ pub mod recipient_id {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -798,6 +836,16 @@ pub mod recipient_id {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -1017,6 +1065,12 @@ pub mod third_party_cap_id {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -1044,6 +1098,16 @@ pub mod third_party_cap_id {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -1276,6 +1340,12 @@ pub mod join_key_part {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -1303,6 +1373,16 @@ pub mod join_key_part {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
@@ -1614,6 +1694,12 @@ pub mod join_result {
         }
     }
 
+    impl<'a> ::capnp::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> ::capnp::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for ::capnp::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(::capnp::dynamic_struct::Builder::new(
@@ -1641,6 +1727,16 @@ pub mod join_result {
                 .init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> ::capnp::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as ::capnp::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: ::capnp::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [::capnp::Word]>,
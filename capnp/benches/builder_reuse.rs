@@ -0,0 +1,57 @@
+//! Measures encoding many small messages with a new `message::Builder` for each, against
+//! reusing one builder with `Builder::clear()`, which keeps its first segment and so does not
+//! allocate once the first message has been built.
+//!
+//! Run with `cargo bench --bench builder_reuse`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use capnp::message::{self, Allocator};
+use capnp::schema_capnp::node;
+use capnp::{serialize, struct_list};
+
+const NODE_COUNT: u32 = 8;
+const ITERATIONS: u32 = 100_000;
+
+fn build<A: Allocator>(builder: &mut message::Builder<A>, seed: u64) {
+    let mut nodes: struct_list::Builder<node::Owned> = builder.initn_root(NODE_COUNT);
+    for idx in 0..NODE_COUNT {
+        let mut node = nodes.reborrow().get(idx);
+        node.set_id(seed ^ u64::from(idx));
+        node.set_display_name("bench.capnp:Node".into());
+    }
+}
+
+fn main() {
+    let mut out = Vec::new();
+    let mut seed = 0;
+    measure("new builder per message", || {
+        let mut builder = message::Builder::new_default();
+        build(&mut builder, seed);
+        seed += 1;
+        out.clear();
+        serialize::write_message(&mut out, &builder).unwrap();
+        black_box(out.len());
+    });
+
+    let mut builder = message::Builder::new_default();
+    measure("cleared builder", || {
+        builder.clear();
+        build(&mut builder, seed);
+        seed += 1;
+        out.clear();
+        serialize::write_message(&mut out, &builder).unwrap();
+        black_box(out.len());
+    });
+}
+
+fn measure(name: &str, mut run: impl FnMut()) {
+    run();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        run();
+    }
+    let elapsed: Duration = start.elapsed() / ITERATIONS;
+    println!("{name}: {elapsed:?}");
+}
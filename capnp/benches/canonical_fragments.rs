@@ -0,0 +1,75 @@
+//! Compares `any_pointer::Builder::set_from_canonical_words()` and its unchecked variant against
+//! `set_as()` with a reader over the same words, when gathering many small pre-encoded fragments
+//! into one message.
+//!
+//! Run with `cargo bench --bench canonical_fragments`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::code_generator_request::requested_file;
+use capnp::{any_pointer, any_pointer_list, Word};
+
+const FRAGMENT_COUNT: u32 = 5000;
+const ITERATIONS: u32 = 50;
+
+fn fragment(id: u64) -> Vec<Word> {
+    let mut builder = message::Builder::new_default();
+    let mut file: requested_file::Builder = builder.init_root();
+    file.set_id(id);
+    file.set_filename(format!("fragment-{id}.capnp").as_str().into());
+    let mut imports = file.init_imports(4);
+    for idx in 0..4 {
+        imports.reborrow().get(idx).set_id(id + u64::from(idx));
+    }
+    builder.into_reader().canonicalize().unwrap()
+}
+
+fn time(name: &str, mut gather: impl FnMut(any_pointer_list::Builder)) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        let mut builder = message::Builder::new_default();
+        gather(builder.initn_root(FRAGMENT_COUNT));
+        black_box(builder.get_segments_for_output().len());
+    }
+    let elapsed = start.elapsed() / ITERATIONS;
+    println!("{name:>36}: {elapsed:?} per message of {FRAGMENT_COUNT} fragments");
+    elapsed
+}
+
+fn main() {
+    let fragments: Vec<Vec<Word>> = (0..u64::from(FRAGMENT_COUNT)).map(fragment).collect();
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(None);
+
+    time("set_as", |mut list| {
+        for (idx, words) in fragments.iter().enumerate() {
+            let segments = [Word::words_to_bytes(words)];
+            let reader = message::Reader::new(message::SegmentArray::new(&segments), options);
+            let root: any_pointer::Reader = reader.get_root().unwrap();
+            list.reborrow().get(idx as u32).set_as(root).unwrap();
+        }
+    });
+
+    time("set_from_canonical_words", |mut list| {
+        for (idx, words) in fragments.iter().enumerate() {
+            list.reborrow()
+                .get(idx as u32)
+                .set_from_canonical_words(words)
+                .unwrap();
+        }
+    });
+
+    time("set_from_canonical_words_unchecked", |mut list| {
+        for (idx, words) in fragments.iter().enumerate() {
+            // Every fragment comes from `canonicalize()`.
+            unsafe {
+                list.reborrow()
+                    .get(idx as u32)
+                    .set_from_canonical_words_unchecked(words)
+                    .unwrap()
+            };
+        }
+    });
+}
@@ -0,0 +1,142 @@
+//! Compares decoding all twelve primitive fields of a market-data tick with one
+//! `get_data_field()` call per field against copying the data section once with
+//! `read_data_prefix()` and decoding the fields from the copy.
+//!
+//! Run with `cargo bench --bench data_prefix`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use capnp::message::{self, ReaderOptions};
+use capnp::private::layout::{
+    ElementSize, ListBuilder, ListReader, PointerBuilder, PointerReader, StructReader, StructSize,
+};
+use capnp::traits::{FromPointerBuilder, FromPointerReader};
+use capnp::Result;
+
+const TICK_COUNT: u32 = 100_000;
+const ITERATIONS: u32 = 50;
+
+/// ```capnp
+/// struct Tick {
+///   timestamp @0 :UInt64;     # bytes 0..8
+///   instrument @1 :UInt64;    # bytes 8..16
+///   bid @2 :Float64;          # bytes 16..24
+///   ask @3 :Float64;          # bytes 24..32
+///   bidSize @4 :UInt32;       # bytes 32..36
+///   askSize @5 :UInt32;       # bytes 36..40
+///   lastSize @6 :UInt32;      # bytes 40..44
+///   sequence @7 :UInt32;      # bytes 44..48
+///   venue @8 :UInt16;         # bytes 48..50
+///   flags @9 :UInt16;         # bytes 50..52
+///   side @10 :UInt8;          # byte 52
+///   kind @11 :UInt8;          # byte 53
+/// }
+/// ```
+const TICK_SIZE: StructSize = StructSize {
+    data: 7,
+    pointers: 0,
+};
+
+struct Ticks<'a>(ListBuilder<'a>);
+
+impl<'a> FromPointerBuilder<'a> for Ticks<'a> {
+    fn init_pointer(builder: PointerBuilder<'a>, length: u32) -> Self {
+        Ticks(builder.init_struct_list(length, TICK_SIZE))
+    }
+
+    fn get_from_pointer(
+        builder: PointerBuilder<'a>,
+        default: Option<&'a [capnp::Word]>,
+    ) -> Result<Self> {
+        Ok(Ticks(builder.get_struct_list(TICK_SIZE, default)?))
+    }
+}
+
+struct TickList<'a>(ListReader<'a>);
+
+impl<'a> FromPointerReader<'a> for TickList<'a> {
+    fn get_from_pointer(
+        reader: &PointerReader<'a>,
+        default: Option<&'a [capnp::Word]>,
+    ) -> Result<Self> {
+        Ok(TickList(
+            reader.get_list(ElementSize::InlineComposite, default)?,
+        ))
+    }
+}
+
+fn per_field(tick: &StructReader) -> u64 {
+    tick.get_data_field::<u64>(0)
+        ^ tick.get_data_field::<u64>(1)
+        ^ tick.get_data_field::<f64>(2).to_bits()
+        ^ tick.get_data_field::<f64>(3).to_bits()
+        ^ u64::from(tick.get_data_field::<u32>(8))
+        ^ u64::from(tick.get_data_field::<u32>(9))
+        ^ u64::from(tick.get_data_field::<u32>(10))
+        ^ u64::from(tick.get_data_field::<u32>(11))
+        ^ u64::from(tick.get_data_field::<u16>(24))
+        ^ u64::from(tick.get_data_field::<u16>(25))
+        ^ u64::from(tick.get_data_field::<u8>(52))
+        ^ u64::from(tick.get_data_field::<u8>(53))
+}
+
+fn prefix_copy(tick: &StructReader) -> u64 {
+    let mut data = [0; 54];
+    tick.read_data_prefix(&mut data);
+    let u64_at = |at: usize| u64::from_le_bytes(data[at..at + 8].try_into().unwrap());
+    let u32_at = |at: usize| u32::from_le_bytes(data[at..at + 4].try_into().unwrap());
+    let u16_at = |at: usize| u16::from_le_bytes(data[at..at + 2].try_into().unwrap());
+    u64_at(0)
+        ^ u64_at(8)
+        ^ f64::from_le_bytes(data[16..24].try_into().unwrap()).to_bits()
+        ^ f64::from_le_bytes(data[24..32].try_into().unwrap()).to_bits()
+        ^ u64::from(u32_at(32))
+        ^ u64::from(u32_at(36))
+        ^ u64::from(u32_at(40))
+        ^ u64::from(u32_at(44))
+        ^ u64::from(u16_at(48))
+        ^ u64::from(u16_at(50))
+        ^ u64::from(data[52])
+        ^ u64::from(data[53])
+}
+
+fn time(name: &str, list: &ListReader, decode: impl Fn(&StructReader) -> u64) -> Duration {
+    let start = Instant::now();
+    let mut checksum = 0;
+    for _ in 0..ITERATIONS {
+        for idx in 0..list.len() {
+            checksum = decode(&black_box(list.get_struct_element(idx))).wrapping_add(checksum);
+        }
+    }
+    black_box(checksum);
+    let elapsed = start.elapsed() / ITERATIONS;
+    println!("{name:>12}: {elapsed:?} per list of {TICK_COUNT} ticks (checksum {checksum:x})");
+    elapsed
+}
+
+fn main() {
+    let mut builder = message::Builder::new_default();
+    {
+        let Ticks(mut list) = builder
+            .init_root::<capnp::any_pointer::Builder>()
+            .initn_as(TICK_COUNT);
+        for idx in 0..TICK_COUNT {
+            let tick = list.reborrow().get_struct_element(idx);
+            let mut data = [0; 54];
+            for (offset, byte) in data.iter_mut().enumerate() {
+                *byte = (idx as usize * 54 + offset) as u8;
+            }
+            tick.write_data_prefix(&data);
+        }
+    }
+
+    let segments = builder.get_segments_for_output();
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(None);
+    let reader = message::Reader::new(message::SegmentArray::new(&segments), options);
+    let TickList(list) = reader.get_root().unwrap();
+
+    time("per field", &list, per_field);
+    time("prefix copy", &list, prefix_copy);
+}
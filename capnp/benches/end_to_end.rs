@@ -0,0 +1,17 @@
+//! Prints the throughput of each scenario in `capnp::bench` on the standard message.
+//!
+//! Run with `cargo bench --features bench --bench end_to_end`.
+
+use std::time::Duration;
+
+use capnp::bench::{Fixture, Scenario, Shape};
+
+fn main() {
+    let fixture = Fixture::new(Shape::STANDARD);
+    for scenario in Scenario::ALL {
+        let measurement = fixture
+            .measure(scenario, Duration::from_secs(2), 10)
+            .unwrap();
+        println!("{measurement}");
+    }
+}
@@ -0,0 +1,68 @@
+//! Compares filling in long struct and primitive lists one index at a time, with `get()` and
+//! `set()`, against walking them with a cursor.
+//!
+//! Run with `cargo bench --bench list_cursor`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use capnp::message;
+use capnp::schema_capnp::capnp_version;
+use capnp::{primitive_list, struct_list};
+
+const ELEMENT_COUNT: u32 = 10_000_000;
+const ITERATIONS: u32 = 5;
+
+fn time(name: &str, mut fill: impl FnMut()) -> Duration {
+    fill();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        fill();
+    }
+    let elapsed = start.elapsed() / ITERATIONS;
+    println!("{name:>18}: {elapsed:?} per list of {ELEMENT_COUNT} elements");
+    elapsed
+}
+
+fn set_version(mut version: capnp_version::Builder, idx: u32) {
+    version.set_major(idx as u16);
+    version.set_minor((idx >> 16) as u8);
+    version.set_micro(idx as u8);
+}
+
+fn main() {
+    let mut builder = message::Builder::new_default();
+    let mut list: struct_list::Builder<capnp_version::Owned> = builder.initn_root(ELEMENT_COUNT);
+    time("struct indexed", || {
+        for idx in 0..ELEMENT_COUNT {
+            set_version(list.reborrow().get(idx), idx);
+        }
+        black_box(&mut list);
+    });
+    time("struct cursor", || {
+        let mut cursor = list.cursor();
+        let mut idx = 0;
+        while let Some(version) = cursor.next() {
+            set_version(version, idx);
+            idx += 1;
+        }
+        black_box(&mut list);
+    });
+
+    let mut builder = message::Builder::new_default();
+    let mut list: primitive_list::Builder<u32> = builder.initn_root(ELEMENT_COUNT);
+    time("primitive indexed", || {
+        for idx in 0..ELEMENT_COUNT {
+            list.set(idx, idx);
+        }
+        black_box(&mut list);
+    });
+    time("primitive cursor", || {
+        let mut cursor = list.cursor();
+        let mut idx = 0;
+        while cursor.write_next(idx).is_some() {
+            idx += 1;
+        }
+        black_box(&mut list);
+    });
+}
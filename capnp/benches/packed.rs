@@ -0,0 +1,78 @@
+//! Measures packing and unpacking a message of about 1 MB, against writing and reading it
+//! unpacked. The message is a list of structs with a mix of zero, sparse and dense words, as
+//! well as text.
+//!
+//! Run with `cargo bench --bench packed`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::{serialize, serialize_packed, struct_list};
+
+const NODE_COUNT: u32 = 8 * 1024;
+const ITERATIONS: u32 = 100;
+
+fn main() {
+    let mut builder = message::Builder::new_default();
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    {
+        let mut nodes: struct_list::Builder<node::Owned> = builder.initn_root(NODE_COUNT);
+        for idx in 0..NODE_COUNT {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            let mut node = nodes.reborrow().get(idx);
+            node.set_id(state);
+            node.set_scope_id(u64::from(idx));
+            node.set_display_name(format!("bench.capnp:Node{idx}").as_str().into());
+            node.set_display_name_prefix_length(11);
+            let mut annotations = node.init_annotations(2);
+            annotations.reborrow().get(0).set_id(state >> 32);
+            annotations.reborrow().get(1).set_id(u64::from(idx) * 3);
+        }
+    }
+    let unpacked = serialize::write_message_to_words(&builder);
+    let packed = serialize_packed::write_message_to_bytes(&builder);
+    println!(
+        "message of {} bytes, {} bytes packed",
+        unpacked.len(),
+        packed.len()
+    );
+
+    let mut out = Vec::with_capacity(unpacked.len());
+    measure("write_message", || {
+        out.clear();
+        serialize::write_message(&mut out, &builder).unwrap();
+        black_box(out.len());
+    });
+    measure("serialize_packed::write_message", || {
+        out.clear();
+        serialize_packed::write_message(&mut out, &builder).unwrap();
+        black_box(out.len());
+    });
+    measure("read_message", || {
+        let message = serialize::read_message(&unpacked[..], ReaderOptions::new()).unwrap();
+        black_box(message.into_segments().len());
+    });
+    measure("serialize_packed::read_message", || {
+        let message = serialize_packed::read_message(&packed[..], ReaderOptions::new()).unwrap();
+        black_box(message.into_segments().len());
+    });
+    measure("serialize_packed::read_message_from_slice", || {
+        let message =
+            serialize_packed::read_message_from_slice(&packed, ReaderOptions::new()).unwrap();
+        black_box(message.into_segments().len());
+    });
+}
+
+fn measure(name: &str, mut run: impl FnMut()) {
+    run();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        run();
+    }
+    let elapsed: Duration = start.elapsed() / ITERATIONS;
+    println!("{name}: {elapsed:?}");
+}
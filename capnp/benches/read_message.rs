@@ -0,0 +1,52 @@
+//! Measures reading a 100 MB message with `serialize::read_message()`, which allocates the
+//! buffer for its segments and then fills it from the stream, and reading it from a
+//! `Cursor<Vec<u8>>` with `read_message()` and with `serialize::read_message_buffered()`.
+//!
+//! Run with `cargo bench --bench read_message`.
+
+use std::hint::black_box;
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+
+use capnp::message::{self, ReaderOptions};
+use capnp::serialize;
+
+const MESSAGE_BYTES: u32 = 100 * 1024 * 1024;
+const ITERATIONS: u32 = 10;
+
+fn main() {
+    let mut builder = message::Builder::new_default();
+    builder
+        .set_root(&vec![0xa5u8; MESSAGE_BYTES as usize][..])
+        .unwrap();
+    let bytes = serialize::write_message_to_words(&builder);
+
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(None);
+    measure("read_message", || {
+        let message = serialize::read_message(&bytes[..], options).unwrap();
+        black_box(message.get_root::<capnp::data::Reader>().unwrap().len());
+    });
+
+    let mut cursor = Cursor::new(bytes);
+    measure("read_message from a cursor", || {
+        cursor.set_position(0);
+        let message = serialize::read_message(&mut cursor, options).unwrap();
+        black_box(message.get_root::<capnp::data::Reader>().unwrap().len());
+    });
+    measure("read_message_buffered from a cursor", || {
+        cursor.set_position(0);
+        let message = serialize::read_message_buffered(&mut cursor, options).unwrap();
+        black_box(message.get_root::<capnp::data::Reader>().unwrap().len());
+    });
+}
+
+fn measure(name: &str, mut read: impl FnMut()) {
+    read();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        read();
+    }
+    let elapsed: Duration = start.elapsed() / ITERATIONS;
+    println!("{name}: {elapsed:?} per message of {MESSAGE_BYTES} bytes");
+}
@@ -26,6 +26,7 @@ use alloc::{boxed::Box, vec::Vec};
 
 #[cfg(feature = "alloc")]
 use crate::capability::FromClientHook;
+use crate::private::arena::{LimitedReaderArena, ReaderArenaImpl};
 #[cfg(feature = "alloc")]
 use crate::private::capability::{ClientHook, PipelineHook, PipelineOp};
 use crate::private::layout::{PointerBuilder, PointerReader};
@@ -55,11 +56,22 @@ pub struct Reader<'a> {
     pub(crate) reader: PointerReader<'a>,
 }
 
+/// Prints only the kind of the pointer, such as `struct` or `far`, as read from the pointer itself.
+/// The pointer is not followed, so this neither fails nor counts against the traversal limit.
+impl core::fmt::Debug for Reader<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "any_pointer::Reader({})", self.reader.kind_name())
+    }
+}
+
 impl<'a> Reader<'a> {
     pub fn new(reader: PointerReader<'_>) -> Reader<'_> {
         Reader { reader }
     }
 
+    /// Returns whether the pointer is null, which is how a field that was never set reads. An
+    /// explicitly set empty text, data or list is not null. This is what generated `has_*()`
+    /// methods check; it neither validates the pointer nor counts against any read limit.
     #[inline]
     pub fn is_null(&self) -> bool {
         self.reader.is_null()
@@ -70,11 +82,45 @@ impl<'a> Reader<'a> {
         self.reader.total_size()
     }
 
+    /// Returns whether this pointer and `other` point to equal values, which may be in
+    /// different messages and laid out differently. Two null pointers are equal. Structs are
+    /// equal if their data sections and pointers are, where the data past the end of the shorter
+    /// data section must be zero and the pointers past the end of the shorter pointer section
+    /// null, so a struct written against an older version of its schema equals the same value
+    /// written against a newer one. Lists are equal if their elements have the same encoding and
+    /// are equal; texts and data are compared byte by byte.
+    ///
+    /// A message holds only the cap table indices of its capabilities, so a comparison that
+    /// reaches a capability returns `CannotCompareCapabilities`. The comparison counts against
+    /// the traversal and nesting limits of both messages, like any other read.
+    pub fn eq_deep(&self, other: &Reader<'_>) -> Result<bool> {
+        self.reader.eq_deep(&other.reader)
+    }
+
     #[inline]
     pub fn get_as<T: FromPointerReader<'a>>(&self) -> Result<T> {
         FromPointerReader::get_from_pointer(&self.reader, None)
     }
 
+    /// Returns a view of this value whose reads are limited by the traversal limits, nesting
+    /// limits and `max_caps` of `options` as well as by those of the message, so these can only
+    /// tighten the limits. The limits are counted as the value is read, like the message's own,
+    /// and cover everything read through the view. Exceeding the word or dereference limit of
+    /// `options` is reported as `Overloaded`. The remaining fields of `options` only matter when
+    /// a message is first read, and are ignored.
+    pub fn with_options(&self, options: &crate::message::ReaderOptions) -> LimitedReader<'a> {
+        LimitedReader {
+            arena: LimitedReaderArena::with_options(self.reader.arena(), options),
+            reader: self.reader,
+            options: *options,
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn visit_capabilities(&self, on_cap: &mut dyn FnMut(u32)) -> Result<()> {
+        self.reader.visit_capabilities(on_cap)
+    }
+
     #[cfg(feature = "alloc")]
     pub fn get_as_capability<T: FromClientHook>(&self) -> Result<T> {
         Ok(FromClientHook::new(self.reader.get_capability()?))
@@ -105,6 +151,24 @@ impl<'a> crate::traits::IntoInternalStructReader<'a> for Reader<'a> {
     }
 }
 
+/// A value read under tighter limits than those of its message. Returned by
+/// `Reader::with_options()`.
+pub struct LimitedReader<'a> {
+    arena: LimitedReaderArena<'a>,
+    reader: PointerReader<'a>,
+    options: crate::message::ReaderOptions,
+}
+
+impl LimitedReader<'_> {
+    /// Interprets the value as the given type, reading it under the tighter limits.
+    pub fn get_as<'b, T: FromPointerReader<'b>>(&'b self) -> Result<T> {
+        FromPointerReader::get_from_pointer(
+            &self.reader.with_options(&self.arena, &self.options),
+            None,
+        )
+    }
+}
+
 impl<'a> FromPointerReader<'a> for Reader<'a> {
     fn get_from_pointer(
         reader: &PointerReader<'a>,
@@ -135,6 +199,27 @@ impl<'a> crate::traits::Imbue<'a> for Reader<'a> {
     }
 }
 
+/// Checks that `words` is a canonical single-segment message, starting with its root pointer.
+fn check_canonical_words(words: &[crate::Word]) -> Result<()> {
+    let segments = [crate::Word::words_to_bytes(words)];
+    let mut options = crate::message::ReaderOptions::new();
+    // The canonicality check visits each word at most once.
+    options.traversal_limit_in_words(None);
+    let arena = ReaderArenaImpl::new(crate::message::SegmentArray::new(&segments), options);
+    match crate::message::first_segment_canonical_violation(&arena)? {
+        None => Ok(()),
+        Some(violation) => {
+            let mut error = crate::Error::from_kind(crate::ErrorKind::Failed);
+            write!(
+                error,
+                "fragment is not canonical: {}",
+                violation.description()
+            );
+            Err(error)
+        }
+    }
+}
+
 pub struct Builder<'a> {
     builder: PointerBuilder<'a>,
 }
@@ -150,10 +235,18 @@ impl<'a> Builder<'a> {
         }
     }
 
+    /// Like `Reader::is_null()`.
     pub fn is_null(&self) -> bool {
         self.builder.is_null()
     }
 
+    /// Copies `value` here, copying capability pointers as they are, for a copy within the same
+    /// cap table.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn set_keeping_cap_indices(&mut self, value: Reader) -> Result<()> {
+        self.builder.copy_from_keeping_cap_indices(value.reader)
+    }
+
     /// Gets the total size of the target and all of its children. Does not count far pointer overhead.
     pub fn target_size(&self) -> Result<crate::MessageSize> {
         self.builder.as_reader().total_size()
@@ -171,6 +264,54 @@ impl<'a> Builder<'a> {
         FromPointerBuilder::init_pointer(self.builder, size)
     }
 
+    /// Like `init_as()`, but fails with `Overloaded` instead of panicking if the allocator has no
+    /// room for the value.
+    pub fn try_init_as<T: FromPointerBuilder<'a>>(self) -> Result<T> {
+        FromPointerBuilder::try_init_pointer(self.builder, 0)
+    }
+
+    /// Like `initn_as()`, but fails with `Overloaded` instead of panicking if the allocator has no
+    /// room for the value.
+    pub fn try_initn_as<T: FromPointerBuilder<'a>>(self, size: u32) -> Result<T> {
+        FromPointerBuilder::try_init_pointer(self.builder, size)
+    }
+
+    /// Initializes the pointer as a data blob of up to `max_len` bytes, to be truncated to its
+    /// actual length once that is known. Fails with `BlobTooLong` if `max_len` bytes do not fit
+    /// in a list.
+    pub fn reserve_data(self, max_len: u32) -> Result<crate::data::Reservation<'a>> {
+        Ok(crate::data::Reservation::new(
+            self.builder.reserve_blob(max_len, false)?,
+        ))
+    }
+
+    /// Initializes the pointer as text of up to `max_len` bytes, to be truncated to its actual
+    /// length once that is known. Fails with `BlobTooLong` if `max_len` bytes and a NUL
+    /// terminator do not fit in a list.
+    pub fn reserve_text(self, max_len: u32) -> Result<crate::text::Reservation<'a>> {
+        Ok(crate::text::Reservation::new(
+            self.builder.reserve_blob(max_len, true)?,
+        ))
+    }
+
+    /// Gets the data blob that the pointer already points to as a reservation, so that it can be
+    /// truncated. This is how a blob reserved by `reserve_data()` is finished after other objects
+    /// have been allocated.
+    pub fn get_data_reservation(self) -> Result<crate::data::Reservation<'a>> {
+        Ok(crate::data::Reservation::new(
+            self.builder.get_blob_reservation(false)?,
+        ))
+    }
+
+    /// Gets the text that the pointer already points to as a reservation, so that it can be
+    /// truncated. This is how text reserved by `reserve_text()` is finished after other objects
+    /// have been allocated.
+    pub fn get_text_reservation(self) -> Result<crate::text::Reservation<'a>> {
+        Ok(crate::text::Reservation::new(
+            self.builder.get_blob_reservation(true)?,
+        ))
+    }
+
     pub fn init_dynamic(
         self,
         schema: crate::schema::StructSchema,
@@ -191,10 +332,43 @@ impl<'a> Builder<'a> {
         }
     }
 
+    /// Sets this pointer to a copy of `value`. The value may be a [`Reader`] of whatever type, such
+    /// as an unknown payload to forward, in this or another message: the copy follows far
+    /// pointers, keeps the section sizes of structs as they are, and clears this pointer if the
+    /// value is null. Reading the value counts against the traversal limit of its message, and
+    /// its capabilities are added to the cap table of this message, which must have one.
     pub fn set_as<From: SetPointerBuilder>(&mut self, value: From) -> Result<()> {
         SetPointerBuilder::set_pointer_builder(self.builder.reborrow(), value, false)
     }
 
+    /// Sets this pointer to a copy of a pre-encoded fragment: the words of a canonical
+    /// single-segment message, starting with its root pointer, as produced by
+    /// `message::Reader::canonicalize()`. The fragment is checked for canonicality and then
+    /// copied as a block. Returns a `Failed` error if the fragment is not canonical, and
+    /// `Overloaded` if the allocator has no room for the copy.
+    ///
+    /// The check costs about as much as copying the value with `set_as()`. To copy fragments
+    /// that were already checked, e.g. when they were stored, use
+    /// `set_from_canonical_words_unchecked()`, which is several times faster.
+    pub fn set_from_canonical_words(&mut self, words: &[crate::Word]) -> Result<()> {
+        check_canonical_words(words)?;
+        unsafe { self.builder.set_canonical_words(words) }
+    }
+
+    /// Like `set_from_canonical_words()`, but does not check the fragment, so the only error is
+    /// `Overloaded`.
+    ///
+    /// # Safety
+    /// `words` must be a canonical single-segment message, as accepted by
+    /// `set_from_canonical_words()`. Otherwise the copy may contain pointers to memory outside of
+    /// it, leading to undefined behavior when the message is later built or read.
+    pub unsafe fn set_from_canonical_words_unchecked(
+        &mut self,
+        words: &[crate::Word],
+    ) -> Result<()> {
+        unsafe { self.builder.set_canonical_words(words) }
+    }
+
     // XXX value should be a user client.
     #[cfg(feature = "alloc")]
     pub fn set_as_capability(&mut self, value: Box<dyn ClientHook>) {
@@ -159,6 +159,12 @@ impl<'a> FromPointerBuilder<'a> for Builder<'a> {
         }
     }
 
+    fn try_init_pointer(builder: PointerBuilder<'a>, size: u32) -> Result<Builder<'a>> {
+        Ok(Builder {
+            builder: builder.try_init_list(Pointer, size)?,
+        })
+    }
+
     fn get_from_pointer(
         builder: PointerBuilder<'a>,
         default: Option<&'a [crate::Word]>,
@@ -208,9 +214,6 @@ impl<'a> From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
 }
 impl<'a> core::fmt::Debug for Reader<'a> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        core::fmt::Debug::fmt(
-            &::core::convert::Into::<crate::dynamic_value::Reader<'_>>::into(*self),
-            f,
-        )
+        crate::stringify::print_pointer_list_summary("any_pointer_list::Reader", self.len(), f)
     }
 }
@@ -0,0 +1,219 @@
+//! Random messages for property tests with [quickcheck](https://docs.rs/quickcheck).
+//!
+//! Random words almost never make up a message that a reader accepts, so property tests over
+//! them only exercise the paths that reject garbage. An [`ArbitraryMessage`] is instead built
+//! with a `message::Builder`, and so is always structurally valid: it holds structs of random
+//! sizes, lists of every element size, lists of lists, struct lists, text and data, spread over
+//! several segments so that some of its pointers are far pointers.
+//!
+//! Only available with the `quickcheck` and `alloc` features.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use quickcheck::{Arbitrary, Gen};
+
+use crate::message::{self, AllocationStrategy, HeapAllocator, ReaderSegments};
+use crate::private::layout::{
+    ElementSize, PointerBuilder, PrimitiveElement, StructBuilder, StructSize,
+};
+use crate::traits::FromPointerBuilder;
+use crate::{Result, Word};
+
+/// The deepest that pointers of a generated message nest.
+const MAX_DEPTH: u32 = 4;
+
+/// The most pointers of a generated message that are set to values, which keeps the number of
+/// segments well within the default `ReaderOptions::segments_limit`.
+const MAX_VALUES: u32 = 64;
+
+/// A structurally valid message with random contents, as the segments that a
+/// `message::Builder` output for it. Read it with `message::Reader::new()`, or write it with
+/// `serialize::write_message_segments()`, through its `ReaderSegments` impl.
+///
+/// The `Gen`'s size bounds the lengths of lists and blobs.
+#[derive(Clone, Debug)]
+pub struct ArbitraryMessage {
+    segments: Vec<Vec<Word>>,
+}
+
+impl ArbitraryMessage {
+    pub fn segments(&self) -> &[Vec<Word>] {
+        &self.segments
+    }
+
+    pub fn into_segments(self) -> Vec<Vec<Word>> {
+        self.segments
+    }
+
+    /// A reader of the message.
+    pub fn to_reader(&self, options: message::ReaderOptions) -> message::Reader<&Self> {
+        message::Reader::new(self, options)
+    }
+}
+
+impl ReaderSegments for ArbitraryMessage {
+    fn get_segment(&self, idx: u32) -> Option<&[u8]> {
+        self.segments
+            .get(idx as usize)
+            .map(|segment| Word::words_to_bytes(segment))
+    }
+
+    fn len(&self) -> usize {
+        self.segments.len()
+    }
+}
+
+impl Arbitrary for ArbitraryMessage {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let mut filler = Filler {
+            g,
+            values_left: MAX_VALUES,
+        };
+        let strategy = if filler.below(2) == 0 {
+            AllocationStrategy::FixedSize
+        } else {
+            AllocationStrategy::GrowHeuristically
+        };
+        let allocator = HeapAllocator::new()
+            .first_segment_words(8 + filler.below(32))
+            .allocation_strategy(strategy);
+        let mut builder = message::Builder::new(allocator);
+        let Root(root) = builder.init_root();
+        let size = StructSize {
+            pointers: 1 + filler.below(4) as u16,
+            ..filler.struct_size()
+        };
+        filler.fill_struct(root.init_struct(size), size, MAX_DEPTH);
+
+        let segments = builder
+            .get_segments_for_output()
+            .iter()
+            .map(|segment| {
+                let mut words = Word::allocate_zeroed_vec(segment.len() / 8);
+                Word::words_to_bytes_mut(&mut words).copy_from_slice(segment);
+                words
+            })
+            .collect();
+        Self { segments }
+    }
+}
+
+/// The root pointer of a message, to be initialized as any kind of value.
+struct Root<'a>(PointerBuilder<'a>);
+
+impl<'a> FromPointerBuilder<'a> for Root<'a> {
+    fn init_pointer(builder: PointerBuilder<'a>, _length: u32) -> Self {
+        Self(builder)
+    }
+
+    fn get_from_pointer(builder: PointerBuilder<'a>, _default: Option<&'a [Word]>) -> Result<Self> {
+        Ok(Self(builder))
+    }
+}
+
+/// Fills in a message with random values from `g`.
+struct Filler<'g> {
+    g: &'g mut Gen,
+    values_left: u32,
+}
+
+impl Filler<'_> {
+    /// A number in `0..bound`.
+    fn below(&mut self, bound: u32) -> u32 {
+        u32::arbitrary(self.g) % bound
+    }
+
+    /// A length of a list or blob.
+    fn length(&mut self) -> u32 {
+        self.below(self.g.size() as u32 + 1)
+    }
+
+    fn struct_size(&mut self) -> StructSize {
+        StructSize {
+            data: self.below(4) as u16,
+            pointers: self.below(4) as u16,
+        }
+    }
+
+    fn fill_struct(&mut self, mut builder: StructBuilder<'_>, size: StructSize, depth: u32) {
+        for idx in 0..usize::from(size.data) {
+            builder.set_data_field(idx, u64::arbitrary(self.g));
+        }
+        for idx in 0..size.pointers {
+            self.fill_pointer(builder.get_pointer_field_mut(idx.into()), depth);
+        }
+    }
+
+    /// Sets `builder` to a random value: null, a struct, a list of any element size, text or
+    /// data. At a `depth` of zero, only values without pointers are chosen, and once the budget
+    /// of values is spent, only null.
+    fn fill_pointer(&mut self, mut builder: PointerBuilder<'_>, depth: u32) {
+        if self.values_left == 0 {
+            return;
+        }
+        self.values_left -= 1;
+        let kinds = if depth == 0 { 10 } else { 13 };
+        match self.below(kinds) {
+            0 => {}
+            1 => {
+                let len = self.length();
+                builder.init_list(ElementSize::Void, len);
+            }
+            2 => self.fill_list::<bool>(builder, ElementSize::Bit),
+            3 => self.fill_list::<u8>(builder, ElementSize::Byte),
+            4 => self.fill_list::<u16>(builder, ElementSize::TwoBytes),
+            5 => self.fill_list::<u32>(builder, ElementSize::FourBytes),
+            6 => self.fill_list::<u64>(builder, ElementSize::EightBytes),
+            7 => {
+                let text: String = (0..self.length())
+                    .map(|_| char::from(b' ' + self.below(95) as u8))
+                    .collect();
+                builder.set_text(text.as_str().into());
+            }
+            8 => {
+                let data: Vec<u8> = (0..self.length()).map(|_| u8::arbitrary(self.g)).collect();
+                builder.set_data(&data);
+            }
+            9 => {
+                // A struct without pointers, so that it never nests deeper.
+                let size = StructSize {
+                    pointers: 0,
+                    ..self.struct_size()
+                };
+                self.fill_struct(builder.init_struct(size), size, 0);
+            }
+            10 => {
+                let size = self.struct_size();
+                self.fill_struct(builder.init_struct(size), size, depth - 1);
+            }
+            11 => {
+                let len = self.length();
+                let mut list = builder.init_list(ElementSize::Pointer, len);
+                for idx in 0..len {
+                    self.fill_pointer(list.reborrow().get_pointer_element(idx), depth - 1);
+                }
+            }
+            _ => {
+                let size = self.struct_size();
+                let len = self.length();
+                let mut list = builder.init_struct_list(len, size);
+                for idx in 0..len {
+                    self.fill_struct(list.reborrow().get_struct_element(idx), size, depth - 1);
+                }
+            }
+        }
+    }
+
+    fn fill_list<T: PrimitiveElement + Arbitrary>(
+        &mut self,
+        builder: PointerBuilder<'_>,
+        element_size: ElementSize,
+    ) {
+        let len = self.length();
+        let list = builder.init_list(element_size, len);
+        for idx in 0..len {
+            T::set(&list, idx, T::arbitrary(self.g));
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! End-to-end throughput measurements of the main paths through `layout` and `serialize`:
+//! building a representative message, writing it out plain and packed, reading it back, deep
+//! copying it, and canonicalizing it. The `end_to_end` bench prints the numbers, and the
+//! `bench_baseline` test compares them against committed baselines to catch large regressions.
+//! Benchmarks of narrower paths can build on [`Fixture`] too.
+//!
+//! Only available with the `bench` feature.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+
+use crate::message::{self, HeapAllocator, ReaderOptions};
+use crate::schema_capnp::{code_generator_request, field, node, type_};
+use crate::{serialize, serialize_packed, Result};
+
+/// A xorshift64* generator, so that a given seed always produces the same message.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // The state must not be zero.
+        Self(seed | 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Returns a number in `0..bound`.
+    pub fn below(&mut self, bound: u32) -> u32 {
+        (self.next_u64() % u64::from(bound)) as u32
+    }
+}
+
+/// The size of the message built by [`build_message()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shape {
+    pub seed: u64,
+
+    /// The number of struct nodes in the request.
+    pub nodes: u32,
+
+    /// The number of fields of each node.
+    pub fields_per_node: u32,
+}
+
+impl Shape {
+    /// The shape used by the `end_to_end` bench and the `bench_baseline` test, which comes to
+    /// about 35,000 words.
+    pub const STANDARD: Self = Self {
+        seed: 0x5eed,
+        nodes: 200,
+        fields_per_node: 12,
+    };
+}
+
+const WORDS: [&str; 8] = [
+    "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel",
+];
+
+fn name(rng: &mut Rng) -> std::string::String {
+    let mut name = std::string::String::new();
+    for _ in 0..1 + rng.below(3) {
+        name.push_str(WORDS[rng.below(WORDS.len() as u32) as usize]);
+    }
+    name
+}
+
+/// Builds a `CodeGeneratorRequest` of the given shape, which has three levels of structs: the
+/// request, its nodes, and their fields, with text, lists, and unions along the way.
+pub fn build_message(shape: Shape) -> message::Builder<HeapAllocator> {
+    let mut rng = Rng::new(shape.seed);
+    let mut message = message::Builder::new_default();
+    let request: code_generator_request::Builder = message.init_root();
+    let mut nodes = request.init_nodes(shape.nodes);
+    for node_idx in 0..shape.nodes {
+        let mut node = nodes.reborrow().get(node_idx);
+        node.set_id(rng.next_u64());
+        node.set_scope_id(rng.next_u64());
+        let display_name = format!("bench.capnp:{}", name(&mut rng));
+        node.set_display_name_prefix_length(12);
+        node.set_display_name(display_name.as_str().into());
+        let mut nested = node.reborrow().init_nested_nodes(rng.below(3));
+        for idx in 0..nested.len() {
+            let mut nested = nested.reborrow().get(idx);
+            nested.set_id(rng.next_u64());
+            nested.set_name(name(&mut rng).as_str().into());
+        }
+        let mut struct_ = node.init_struct();
+        struct_.set_data_word_count(1 + rng.below(4) as u16);
+        struct_.set_pointer_count(rng.below(4) as u16);
+        let mut fields = struct_.init_fields(shape.fields_per_node);
+        for field_idx in 0..shape.fields_per_node {
+            let mut field = fields.reborrow().get(field_idx);
+            field.set_name(name(&mut rng).as_str().into());
+            field.set_code_order(field_idx as u16);
+            field.set_discriminant_value(field::NO_DISCRIMINANT);
+            let mut slot = field.init_slot();
+            slot.set_offset(rng.below(64));
+            let mut type_ = slot.init_type();
+            match rng.below(3) {
+                0 => type_.set_uint32(()),
+                1 => type_.set_text(()),
+                _ => type_.init_struct().set_type_id(rng.next_u64()),
+            }
+        }
+    }
+    message
+}
+
+/// Reads every value in a message built by [`build_message()`] and combines them into a checksum,
+/// which is the same however the message was encoded.
+pub fn traverse(request: code_generator_request::Reader<'_>) -> Result<u64> {
+    let mut sum = 0u64;
+    let mut add = |value: u64| sum = sum.rotate_left(5) ^ value;
+    for node in request.get_nodes()? {
+        add(node.get_id());
+        add(node.get_scope_id());
+        add(node.get_display_name()?.as_bytes().len() as u64);
+        for nested in node.get_nested_nodes()? {
+            add(nested.get_id());
+            add(nested.get_name()?.as_bytes().len() as u64);
+        }
+        let node::Struct(struct_) = node.which()? else {
+            continue;
+        };
+        add(u64::from(struct_.get_data_word_count()));
+        for field in struct_.get_fields()? {
+            add(field.get_name()?.as_bytes().len() as u64);
+            add(u64::from(field.get_code_order()));
+            let field::Slot(slot) = field.which()? else {
+                continue;
+            };
+            add(u64::from(slot.get_offset()));
+            match slot.get_type()?.which()? {
+                type_::Struct(target) => add(target.get_type_id()),
+                _ => add(1),
+            }
+        }
+    }
+    Ok(sum)
+}
+
+/// A path through the library to measure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scenario {
+    /// Builds the message.
+    Build,
+
+    /// Writes the message to a buffer.
+    Serialize,
+
+    /// Writes the message to a buffer, packed.
+    SerializePacked,
+
+    /// Reads the message from a buffer and traverses it.
+    Read,
+
+    /// Reads the packed message from a buffer and traverses it.
+    ReadPacked,
+
+    /// Copies the message into a new one.
+    DeepCopy,
+
+    /// Canonicalizes the message.
+    Canonicalize,
+}
+
+impl Scenario {
+    pub const ALL: [Self; 7] = [
+        Self::Build,
+        Self::Serialize,
+        Self::SerializePacked,
+        Self::Read,
+        Self::ReadPacked,
+        Self::DeepCopy,
+        Self::Canonicalize,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Build => "build",
+            Self::Serialize => "serialize",
+            Self::SerializePacked => "serialize packed",
+            Self::Read => "read",
+            Self::ReadPacked => "read packed",
+            Self::DeepCopy => "deep copy",
+            Self::Canonicalize => "canonicalize",
+        }
+    }
+}
+
+/// A message built by [`build_message()`], along with its encodings.
+pub struct Fixture {
+    pub shape: Shape,
+    pub message: message::Builder<HeapAllocator>,
+    pub plain: Vec<u8>,
+    pub packed: Vec<u8>,
+}
+
+impl Fixture {
+    pub fn new(shape: Shape) -> Self {
+        let message = build_message(shape);
+        let plain = serialize::write_message_to_words(&message);
+        let mut packed = Vec::new();
+        serialize_packed::write_message(&mut packed, &message).expect("writing to a Vec");
+        Self {
+            shape,
+            message,
+            plain,
+            packed,
+        }
+    }
+
+    /// The size of the message, in words.
+    pub fn words(&self) -> u64 {
+        self.plain.len() as u64 / 8
+    }
+
+    /// Reads the message back in the plain encoding.
+    pub fn reader(&self) -> Result<message::Reader<serialize::OwnedSegments>> {
+        serialize::read_message(&self.plain[..], ReaderOptions::new())
+    }
+
+    /// Runs the scenario once, returning a checksum of what it produced.
+    pub fn run(&self, scenario: Scenario) -> Result<u64> {
+        match scenario {
+            Scenario::Build => Ok(build_message(self.shape).get_segments_for_output().len() as u64),
+            Scenario::Serialize => {
+                Ok(serialize::write_message_to_words(&self.message).len() as u64)
+            }
+            Scenario::SerializePacked => {
+                let mut packed = Vec::with_capacity(self.packed.len());
+                serialize_packed::write_message(&mut packed, &self.message)?;
+                Ok(packed.len() as u64)
+            }
+            Scenario::Read => traverse(self.reader()?.get_root()?),
+            Scenario::ReadPacked => {
+                let reader =
+                    serialize_packed::read_message(&self.packed[..], ReaderOptions::new())?;
+                traverse(reader.get_root()?)
+            }
+            Scenario::DeepCopy => {
+                let reader = self.reader()?;
+                let mut copy = message::Builder::new_default();
+                copy.set_root(reader.get_root::<code_generator_request::Reader>()?)?;
+                Ok(copy.get_segments_for_output().len() as u64)
+            }
+            Scenario::Canonicalize => Ok(self.reader()?.canonicalize()?.len() as u64),
+        }
+    }
+
+    /// Measures the scenario, after running it once to warm up. The time is split into
+    /// `batches` batches of at least one run each, and the fastest batch is reported, which is
+    /// less sensitive to interference from the rest of the system than the average.
+    pub fn measure(&self, scenario: Scenario, time: Duration, batches: u32) -> Result<Measurement> {
+        black_box(self.run(scenario)?);
+        let batch_time = time / batches.max(1);
+        let mut best: Option<Measurement> = None;
+        for _ in 0..batches.max(1) {
+            let start = Instant::now();
+            let mut iterations = 0;
+            while iterations == 0 || start.elapsed() < batch_time {
+                black_box(self.run(scenario)?);
+                iterations += 1;
+            }
+            let measurement = Measurement {
+                scenario,
+                words: self.words(),
+                iterations,
+                elapsed: start.elapsed(),
+            };
+            match best {
+                Some(best) if best.words_per_sec() >= measurement.words_per_sec() => {}
+                _ => best = Some(measurement),
+            }
+        }
+        Ok(best.expect("at least one batch"))
+    }
+}
+
+/// The outcome of [`Fixture::measure()`].
+#[derive(Clone, Copy, Debug)]
+pub struct Measurement {
+    pub scenario: Scenario,
+
+    /// The size of the message, in words.
+    pub words: u64,
+    pub iterations: u32,
+    pub elapsed: Duration,
+}
+
+impl Measurement {
+    /// The throughput, in words of message per second.
+    pub fn words_per_sec(&self) -> f64 {
+        (self.words * u64::from(self.iterations)) as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+impl core::fmt::Display for Measurement {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{:>16}: {:>12.0} words/s ({} runs over {} words in {:?})",
+            self.scenario.name(),
+            self.words_per_sec(),
+            self.iterations,
+            self.words,
+            self.elapsed
+        )
+    }
+}
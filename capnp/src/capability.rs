@@ -44,6 +44,17 @@ use crate::traits::{Owned, Pipelined};
 #[cfg(feature = "alloc")]
 use crate::{Error, MessageSize};
 
+/// The capabilities of a message, indexed by the capability pointers in it. A message does not
+/// own its table; attach one to the root of a reader with [`Imbue::imbue()`], after which
+/// reading a capability pointer returns a new reference to the hook at its index. Attach one to
+/// the root of a builder with [`ImbueMut::imbue_mut()`], after which setting a capability pushes
+/// its hook onto the table, for the transport to take when it sends the message.
+///
+/// [`Imbue::imbue()`]: crate::traits::Imbue::imbue
+/// [`ImbueMut::imbue_mut()`]: crate::traits::ImbueMut::imbue_mut
+#[cfg(feature = "alloc")]
+pub use crate::private::layout::CapTable;
+
 /// A computation that might eventually resolve to a value of type `T` or to an error
 ///  of type `E`. Dropping the promise cancels the computation.
 #[cfg(feature = "alloc")]
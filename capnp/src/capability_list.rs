@@ -182,6 +182,8 @@ where
         }
     }
 
+    /// Sets the element at position `index`. Panics if `index` is greater than or
+    /// equal to `len()`.
     pub fn set(&mut self, index: u32, value: Box<dyn ClientHook>) {
         assert!(index < self.len());
         self.builder
@@ -189,6 +191,17 @@ where
             .get_pointer_element(index)
             .set_capability(value);
     }
+
+    /// Sets the element at position `index`. Returns `None` if `index`
+    /// is greater than or equal to `len()`.
+    pub fn try_set(&mut self, index: u32, value: Box<dyn ClientHook>) -> Option<()> {
+        if index < self.len() {
+            self.set(index, value);
+            Some(())
+        } else {
+            None
+        }
+    }
 }
 
 impl<'a, T> Builder<'a, T>
@@ -213,6 +226,12 @@ where
             builder: builder.init_list(Pointer, size),
         }
     }
+    fn try_init_pointer(builder: PointerBuilder<'a>, size: u32) -> Result<Builder<'a, T>> {
+        Ok(Builder {
+            marker: PhantomData,
+            builder: builder.try_init_list(Pointer, size)?,
+        })
+    }
     fn get_from_pointer(
         builder: PointerBuilder<'a>,
         default: Option<&'a [crate::Word]>,
@@ -298,9 +317,6 @@ impl<'a, T: FromClientHook> From<Builder<'a, T>> for crate::dynamic_value::Build
 
 impl<'a, T: FromClientHook> core::fmt::Debug for Reader<'a, T> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        core::fmt::Debug::fmt(
-            &::core::convert::Into::<crate::dynamic_value::Reader<'_>>::into(self.reborrow()),
-            f,
-        )
+        crate::stringify::print_pointer_list_summary("capability_list::Reader", self.len(), f)
     }
 }
@@ -20,8 +20,11 @@
 // THE SOFTWARE.
 
 //! Sequence of bytes.
+//!
+//! As with text, a reader of unset data and one of empty data are the same; use the generated
+//! `has_*()` method of the field, or `any_pointer::Reader::is_null()`, to tell them apart.
 
-use crate::private::layout::{PointerBuilder, PointerReader};
+use crate::private::layout::{BlobReservation, PointerBuilder, PointerReader};
 use crate::Result;
 
 #[derive(Copy, Clone)]
@@ -38,8 +41,86 @@ impl crate::introspect::Introspect for Owned {
     }
 }
 
+/// Formats data for `Debug` output as hex, in the form that dynamic values print data in, but only
+/// its first 64 bytes, followed by its length if it is longer. `Reader` is a plain byte slice,
+/// whose own `Debug` output lists every byte.
+#[derive(Clone, Copy)]
+pub struct Hex<'a>(pub Reader<'a>);
+
+impl core::fmt::Debug for Hex<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let head = &self.0[..self.0.len().min(crate::stringify::BLOB_HEAD_LEN)];
+        f.write_str("0x\"")?;
+        for byte in head {
+            write!(f, "{byte:02x}")?;
+        }
+        f.write_str("\"")?;
+        if head.len() < self.0.len() {
+            write!(f, ".. ({} bytes)", self.0.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// Data is read as a plain slice, so any `&[u8]`, whether or not it points into a message, is
+/// already a `data::Reader`.
 pub type Reader<'a> = &'a [u8];
 
+/// The most bytes that `copy_to()` hands to the writer at once.
+pub const COPY_CHUNK_LEN: usize = 1 << 20;
+
+/// Returns a cursor over `data` that reads it through `io::Read`, and `std::io::Read` when `std`
+/// is enabled, for streaming a blob without treating it as one slice. A blob is contiguous in
+/// the message today, but code that goes through this cursor will keep working if blobs are
+/// ever fetched in pieces.
+pub fn reader(data: Reader<'_>) -> BlobReader<'_> {
+    BlobReader { remaining: data }
+}
+
+/// Writes all of `data` to `write`, in chunks of at most [`COPY_CHUNK_LEN`] bytes, and returns
+/// the number of bytes written. `flush()` is not called on the writer.
+pub fn copy_to<W: crate::io::Write>(data: Reader<'_>, mut write: W) -> Result<u64> {
+    for chunk in data.chunks(COPY_CHUNK_LEN) {
+        write.write_all(chunk)?;
+    }
+    Ok(data.len() as u64)
+}
+
+/// A cursor over the bytes of a blob, from [`reader()`] or `text::Reader::reader()`.
+#[derive(Clone, Copy)]
+pub struct BlobReader<'a> {
+    remaining: &'a [u8],
+}
+
+impl BlobReader<'_> {
+    /// The number of bytes left to read.
+    pub fn remaining(&self) -> usize {
+        self.remaining.len()
+    }
+
+    fn read_into(&mut self, buf: &mut [u8]) -> usize {
+        let n = buf.len().min(self.remaining.len());
+        let (head, tail) = self.remaining.split_at(n);
+        buf[..n].copy_from_slice(head);
+        self.remaining = tail;
+        n
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::io::Read for BlobReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        Ok(self.read_into(buf))
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl crate::io::Read for BlobReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        Ok(self.read_into(buf))
+    }
+}
+
 pub(crate) unsafe fn reader_from_raw_parts<'a>(p: *const u8, len: u32) -> Reader<'a> {
     ::core::slice::from_raw_parts(p, len as usize)
 }
@@ -63,6 +144,9 @@ impl<'a> crate::traits::FromPointerBuilder<'a> for Builder<'a> {
     fn init_pointer(builder: PointerBuilder<'a>, size: u32) -> Builder<'a> {
         builder.init_data(size)
     }
+    fn try_init_pointer(builder: PointerBuilder<'a>, size: u32) -> Result<Builder<'a>> {
+        builder.try_init_data(size)
+    }
     fn get_from_pointer(
         builder: PointerBuilder<'a>,
         default: Option<&'a [crate::Word]>,
@@ -71,14 +155,54 @@ impl<'a> crate::traits::FromPointerBuilder<'a> for Builder<'a> {
     }
 }
 
+/// Initializes `builder` as a data blob of up to `max_len` bytes, to be truncated to its actual
+/// length once that is known. Generated code offers the same for each data field as
+/// `reserve_<field>()`. Fails with `BlobTooLong` if `max_len` bytes do not fit in a list.
+pub fn reserve(builder: crate::any_pointer::Builder<'_>, max_len: u32) -> Result<Reservation<'_>> {
+    builder.reserve_data(max_len)
+}
+
+/// Space for a blob whose length is only known once it has been written, obtained from
+/// `reserve()`.
+///
+/// If the reservation is dropped without calling `finish()`, the blob keeps its full reserved
+/// length, but can still be truncated later through
+/// `any_pointer::Builder::get_data_reservation()`.
+pub struct Reservation<'a> {
+    inner: BlobReservation<'a>,
+}
+
+impl<'a> Reservation<'a> {
+    pub(crate) fn new(inner: BlobReservation<'a>) -> Self {
+        Self { inner }
+    }
+
+    /// The number of bytes reserved.
+    pub fn capacity(&self) -> u32 {
+        self.inner.capacity()
+    }
+
+    /// All of the reserved bytes, which start out zeroed.
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self.inner.as_bytes_mut()
+    }
+
+    /// Truncates the blob to its first `len` bytes and zeroes the rest. If nothing else has been
+    /// allocated since the reservation was made, the unused space is given back to the message.
+    ///
+    /// Panics if `len` is greater than the capacity.
+    pub fn finish(self, len: u32) -> Builder<'a> {
+        self.inner.finish(len)
+    }
+}
+
 impl<'a> crate::traits::SetPointerBuilder for Reader<'a> {
     fn set_pointer_builder<'b>(
         mut pointer: PointerBuilder<'b>,
         value: Reader<'a>,
         _canonicalize: bool,
     ) -> Result<()> {
-        pointer.set_data(value);
-        Ok(())
+        pointer.try_set_data(value)
     }
 }
 
@@ -134,6 +134,8 @@ impl<'a> Builder<'a> {
         }
     }
 
+    /// Sets the element at position `index`. Panics if `index` is greater than or
+    /// equal to `len()`, or if `value` is too long to fit in a list.
     pub fn set(&mut self, index: u32, value: crate::data::Reader) {
         assert!(index < self.len());
         self.builder
@@ -142,6 +144,22 @@ impl<'a> Builder<'a> {
             .set_data(value);
     }
 
+    /// Sets the element at position `index`. Returns `None` if `index`
+    /// is greater than or equal to `len()`, and an error if `value` is
+    /// too long to fit in a list.
+    pub fn try_set(&mut self, index: u32, value: crate::data::Reader) -> Option<Result<()>> {
+        if index < self.len() {
+            Some(
+                self.builder
+                    .reborrow()
+                    .get_pointer_element(index)
+                    .try_set_data(value),
+            )
+        } else {
+            None
+        }
+    }
+
     pub fn reborrow(&mut self) -> Builder<'_> {
         Builder {
             builder: self.builder.reborrow(),
@@ -156,6 +174,12 @@ impl<'a> FromPointerBuilder<'a> for Builder<'a> {
         }
     }
 
+    fn try_init_pointer(builder: PointerBuilder<'a>, size: u32) -> Result<Builder<'a>> {
+        Ok(Builder {
+            builder: builder.try_init_list(Pointer, size)?,
+        })
+    }
+
     fn get_from_pointer(
         builder: PointerBuilder<'a>,
         default: Option<&'a [crate::Word]>,
@@ -225,9 +249,6 @@ impl<'a> From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
 
 impl<'a> core::fmt::Debug for Reader<'a> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        core::fmt::Debug::fmt(
-            &::core::convert::Into::<crate::dynamic_value::Reader<'_>>::into(*self),
-            f,
-        )
+        crate::stringify::print_pointer_list_summary("data_list::Reader", self.len(), f)
     }
 }
@@ -574,13 +574,11 @@ impl<'a> Builder<'a> {
                     }
                     (TypeVariant::Text, dynamic_value::Reader::Text(tv), _) => {
                         let mut p = self.builder.reborrow().get_pointer_field(offset);
-                        p.set_text(tv);
-                        Ok(())
+                        p.try_set_text(tv)
                     }
                     (TypeVariant::Data, dynamic_value::Reader::Data(v), _) => {
                         let mut p = self.builder.reborrow().get_pointer_field(offset);
-                        p.set_data(v);
-                        Ok(())
+                        p.try_set_data(v)
                     }
                     (TypeVariant::List(_), dynamic_value::Reader::List(l), _) => {
                         let mut p = self.builder.reborrow().get_pointer_field(offset);
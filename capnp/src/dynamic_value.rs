@@ -1,6 +1,7 @@
 //! Dynamically typed values.
 
 use crate::introspect::{self, TypeVariant};
+use crate::private::arena::{LimitedReaderArena, ReaderArena};
 use crate::schema_capnp::value;
 use crate::Result;
 use crate::{dynamic_list, dynamic_struct};
@@ -65,11 +66,65 @@ impl<'a> Reader<'a> {
         }
     }
 
+    /// Gets the arena that this value reads from as it is traversed, if any.
+    pub(crate) fn arena(&self) -> Option<&'a dyn ReaderArena> {
+        match self {
+            Reader::Struct(s) => Some(s.reader.arena()),
+            Reader::List(l) => Some(l.reader.arena()),
+            Reader::AnyPointer(a) => Some(a.reader.arena()),
+            _ => None,
+        }
+    }
+
+    /// Returns a copy of this value that reads through `arena`, which must wrap the value's
+    /// arena.
+    pub(crate) fn with_limits<'b>(self, arena: &'b LimitedReaderArena<'a>) -> Reader<'b> {
+        match self {
+            Reader::Struct(s) => Reader::Struct(dynamic_struct::Reader::new(
+                s.reader.with_limits(arena),
+                s.get_schema(),
+            )),
+            Reader::List(l) => Reader::List(dynamic_list::Reader::new(
+                l.reader.with_limits(arena),
+                l.element_type(),
+            )),
+            Reader::AnyPointer(a) => {
+                Reader::AnyPointer(crate::any_pointer::Reader::new(a.reader.with_limits(arena)))
+            }
+            other => other,
+        }
+    }
+
     /// Downcasts the `Reader` into a more specific type. Panics if the
     /// expected type does not match the value.
     pub fn downcast<T: DowncastReader<'a>>(self) -> T {
         T::downcast_reader(self)
     }
+
+    /// Checks that every Text value reachable from this value is valid UTF-8,
+    /// returning `ErrorKind::TextContainsNonUtf8Data` on the first one that is not.
+    /// AnyPointer fields are not traversed, as their type is unknown.
+    pub fn validate_utf8(self) -> Result<()> {
+        match self {
+            Reader::Text(t) => {
+                t.to_str()?;
+            }
+            Reader::List(list) => {
+                for value in list.iter() {
+                    value?.validate_utf8()?;
+                }
+            }
+            Reader::Struct(st) => {
+                for field in st.get_schema().get_fields()? {
+                    if st.has(field)? {
+                        st.get(field)?.validate_utf8()?;
+                    }
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
 }
 
 impl<'a> From<()> for Reader<'a> {
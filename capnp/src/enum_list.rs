@@ -119,6 +119,16 @@ impl<'a, T: TryFrom<u16, Error = NotInSchema>> Reader<'a, T> {
             None
         }
     }
+
+    /// Like `get()`, but a value that is not in the schema is returned as an `Error` that names
+    /// `T` and `index`, for callers that pass it on with `?`.
+    pub fn get_with_context(&self, index: u32) -> Result<T> {
+        self.get(index).map_err(|e| {
+            let mut error = crate::Error::from_not_in_schema(e, core::any::type_name::<T>());
+            write!(error, ", element {index} of an enum list");
+            error
+        })
+    }
 }
 
 impl<'a, T> crate::traits::IntoInternalListReader<'a> for Reader<'a, T>
@@ -151,10 +161,23 @@ impl<'a, T: Into<u16> + TryFrom<u16, Error = NotInSchema>> Builder<'a, T> {
         }
     }
 
+    /// Sets the element at position `index`. Panics if `index` is greater than or
+    /// equal to `len()`.
     pub fn set(&mut self, index: u32, value: T) {
         assert!(index < self.len());
         PrimitiveElement::set(&self.builder, index, value.into());
     }
+
+    /// Sets the element at position `index`. Returns `None` if `index`
+    /// is greater than or equal to `len()`.
+    pub fn try_set(&mut self, index: u32, value: T) -> Option<()> {
+        if index < self.len() {
+            PrimitiveElement::set(&self.builder, index, value.into());
+            Some(())
+        } else {
+            None
+        }
+    }
 }
 
 impl<'a, T: TryFrom<u16, Error = NotInSchema>> FromPointerBuilder<'a> for Builder<'a, T> {
@@ -164,6 +187,12 @@ impl<'a, T: TryFrom<u16, Error = NotInSchema>> FromPointerBuilder<'a> for Builde
             marker: PhantomData,
         }
     }
+    fn try_init_pointer(builder: PointerBuilder<'a>, size: u32) -> Result<Builder<'a, T>> {
+        Ok(Builder {
+            builder: builder.try_init_list(TwoBytes, size)?,
+            marker: PhantomData,
+        })
+    }
     fn get_from_pointer(
         builder: PointerBuilder<'a>,
         default: Option<&'a [crate::Word]>,
@@ -248,8 +277,9 @@ impl<'a, T: Copy + TryFrom<u16, Error = NotInSchema> + crate::introspect::Intros
     core::fmt::Debug for Reader<'a, T>
 {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        core::fmt::Debug::fmt(
-            &::core::convert::Into::<crate::dynamic_value::Reader<'_>>::into(*self),
+        crate::stringify::print_list_summary(
+            "enum_list::Reader",
+            crate::dynamic_list::Reader::new(self.reader, T::introspect()),
             f,
         )
     }
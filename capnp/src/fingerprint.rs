@@ -0,0 +1,133 @@
+//! A fast, non-cryptographic hash of the value a pointer points to, for noticing when successive
+//! messages hold the same value.
+
+use crate::any_pointer;
+use crate::private::layout::ElementSize;
+use crate::Result;
+
+// The primes of XXH64, whose per-word round and final avalanche `Hasher` follows.
+const PRIME_1: u64 = 0x9E37_79B1_85EB_CA87;
+const PRIME_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const PRIME_3: u64 = 0x1656_67B1_9E37_79F9;
+const PRIME_4: u64 = 0x85EB_CA77_C2B2_AE63;
+const PRIME_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+/// Accumulates the encoding of the value that `PointerReader::fingerprint()` walks. Each pointer
+/// starts with a tag for its kind, so that a value of one kind never hashes the same words as a
+/// value of another.
+pub(crate) struct Hasher {
+    state: u64,
+    word_count: u64,
+}
+
+impl Hasher {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: PRIME_5,
+            word_count: 0,
+        }
+    }
+
+    fn word(&mut self, word: u64) {
+        let round = word
+            .wrapping_mul(PRIME_2)
+            .rotate_left(31)
+            .wrapping_mul(PRIME_1);
+        self.state = (self.state ^ round)
+            .rotate_left(27)
+            .wrapping_mul(PRIME_1)
+            .wrapping_add(PRIME_4);
+        self.word_count += 1;
+    }
+
+    /// Hashes `bytes` a word at a time, padding the last word with zeros.
+    fn bytes(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.word(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut last = [0; 8];
+            last[..rest.len()].copy_from_slice(rest);
+            self.word(u64::from_le_bytes(last));
+        }
+    }
+
+    pub(crate) fn null(&mut self) {
+        self.word(0);
+    }
+
+    pub(crate) fn struct_pointer(&mut self) {
+        self.word(1);
+    }
+
+    /// Hashes the data section of a struct, and the number of its pointers that follow. Trailing
+    /// zero words of `data` are left out, as canonicalization truncates them; the caller likewise
+    /// leaves out trailing null pointers.
+    pub(crate) fn struct_sections(&mut self, data: &[u8], pointer_count: u16) {
+        let data_words = data
+            .chunks(8)
+            .rposition(|word| word != [0; 8])
+            .map_or(0, |last| last + 1);
+        self.word(data_words as u64);
+        self.bytes(&data[..data_words * 8]);
+        self.word(u64::from(pointer_count));
+    }
+
+    pub(crate) fn list(&mut self, element_size: ElementSize, element_count: u32) {
+        self.word(2);
+        self.word(element_size as u64 | (u64::from(element_count) << 8));
+    }
+
+    /// Hashes the contents of a list of `element_count` elements that are not pointers.
+    pub(crate) fn list_data(
+        &mut self,
+        bytes: &[u8],
+        element_size: ElementSize,
+        element_count: u32,
+    ) {
+        match bytes.split_last() {
+            // Canonicalization clears the bits past the end of a list of bits.
+            Some((&last, whole)) if element_size == ElementSize::Bit && element_count % 8 != 0 => {
+                self.bytes(whole);
+                self.word(u64::from(last & ((1 << (element_count % 8)) - 1)));
+            }
+            _ => self.bytes(bytes),
+        }
+    }
+
+    pub(crate) fn capability(&mut self, index: u32) {
+        self.word(3);
+        self.word(u64::from(index));
+    }
+
+    fn finish(&self) -> u64 {
+        let mut hash = self.state.wrapping_add(self.word_count.wrapping_mul(8));
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(PRIME_2);
+        hash ^= hash >> 29;
+        hash = hash.wrapping_mul(PRIME_3);
+        hash ^ (hash >> 32)
+    }
+}
+
+/// Returns a 64-bit hash of the value that `root` points to.
+///
+/// The hash covers the value as [canonicalization](crate::message::Reader::canonicalize) would
+/// write it, so values that canonicalize to the same words have the same fingerprint, however
+/// they are laid out: trailing zero data and null pointers of structs, their positions in the
+/// message, and the bits past the end of a list of bits do not matter. A capability is hashed
+/// by its cap table index, as canonicalization does not allow them. Unlike canonicalization, no
+/// copy is made, so this is much cheaper, and it is meant for skipping work when a value is
+/// unchanged, not for telling values apart securely: it is not a cryptographic hash.
+///
+/// Fingerprints are stable between releases with the same major version of this crate, but
+/// other implementations of Cap'n Proto do not compute them.
+///
+/// The traversal counts against the message's traversal and nesting limits, like any other read.
+pub fn fingerprint(root: any_pointer::Reader<'_>) -> Result<u64> {
+    let mut hasher = Hasher::new();
+    root.reader.fingerprint(&mut hasher)?;
+    Ok(hasher.finish())
+}
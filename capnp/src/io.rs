@@ -42,11 +42,27 @@ pub trait Write {
     fn write_all(&mut self, buf: &[u8]) -> Result<()>;
 }
 
+/// A `Write` that can take several buffers at once, like `std::io::Write::write_vectored()`.
+pub trait VectoredWrite: Write {
+    /// Writes all of `bufs`, in order. Writers that support vectored writes should hand over
+    /// as many of them per call as they can; the default writes them one at a time.
+    fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<()> {
+        for buf in bufs {
+            self.write_all(buf)?;
+        }
+        Ok(())
+    }
+}
+
 /// Blanket impls for when `std` is enabled.
 #[cfg(feature = "std")]
 mod std_impls {
-    use crate::io::{BufRead, Read, Write};
+    use crate::io::{BufRead, Read, VectoredWrite, Write};
     use crate::Result;
+    use std::io::IoSlice;
+
+    /// How many buffers `write_all_vectored()` passes to `write_vectored()` at most.
+    const MAX_IO_SLICES: usize = 64;
 
     impl<R> Read for R
     where
@@ -84,12 +100,62 @@ mod std_impls {
             Ok(())
         }
     }
+
+    impl<W> VectoredWrite for W
+    where
+        W: std::io::Write,
+    {
+        fn write_all_vectored(&mut self, mut bufs: &[&[u8]]) -> Result<()> {
+            // How much of `bufs[0]` has been written.
+            let mut written = 0;
+            loop {
+                while bufs.first().is_some_and(|first| first.len() == written) {
+                    bufs = &bufs[1..];
+                    written = 0;
+                }
+                let Some((first, rest)) = bufs.split_first() else {
+                    return Ok(());
+                };
+                let mut slices = [IoSlice::new(&[]); MAX_IO_SLICES];
+                slices[0] = IoSlice::new(&first[written..]);
+                let slice_count = 1 + rest.len().min(MAX_IO_SLICES - 1);
+                for (slice, buf) in slices[1..slice_count].iter_mut().zip(rest) {
+                    *slice = IoSlice::new(buf);
+                }
+                match std::io::Write::write_vectored(self, &slices[..slice_count]) {
+                    Ok(0) => return Err(std::io::Error::from(std::io::ErrorKind::WriteZero).into()),
+                    Ok(n) if n > slices[..slice_count].iter().map(|s| s.len()).sum() => {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "write_vectored() reported more bytes than it was given",
+                        )
+                        .into());
+                    }
+                    Ok(mut n) => {
+                        while n > 0 {
+                            let left = bufs[0].len() - written;
+                            if n < left {
+                                written += n;
+                                n = 0;
+                            } else {
+                                n -= left;
+                                bufs = &bufs[1..];
+                                written = 0;
+                            }
+                        }
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+    }
 }
 
 /// Blanket impls for when `embedded-io` is enabled and `std` is not.
 #[cfg(all(feature = "embedded-io", not(feature = "std")))]
 mod embedded_io_impls {
-    use crate::io::{BufRead, Read, Write};
+    use crate::io::{BufRead, Read, VectoredWrite, Write};
     use crate::Result;
     use embedded_io::Error;
 
@@ -101,6 +167,8 @@ mod embedded_io_impls {
         }
     }
 
+    impl<W: embedded_io::Write> VectoredWrite for W {}
+
     impl<R: embedded_io::Read> Read for R {
         fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
             embedded_io::Read::read(self, buf).map_err(|e| crate::Error::from_kind(e.kind().into()))
@@ -122,7 +190,7 @@ mod embedded_io_impls {
 /// Fallback impls, for when neither `std` nor `embedded-io` is enabled.
 #[cfg(not(any(feature = "std", feature = "embedded-io")))]
 mod no_std_impls {
-    use crate::io::{BufRead, Read, Write};
+    use crate::io::{BufRead, Read, VectoredWrite, Write};
     use crate::{Error, ErrorKind, Result};
 
     impl<'a> Write for &'a mut [u8] {
@@ -138,6 +206,8 @@ mod no_std_impls {
         }
     }
 
+    impl<'a> VectoredWrite for &'a mut [u8] {}
+
     #[cfg(feature = "alloc")]
     impl Write for alloc::vec::Vec<u8> {
         fn write_all(&mut self, buf: &[u8]) -> Result<()> {
@@ -146,6 +216,9 @@ mod no_std_impls {
         }
     }
 
+    #[cfg(feature = "alloc")]
+    impl VectoredWrite for alloc::vec::Vec<u8> {}
+
     impl<W: ?Sized> Write for &mut W
     where
         W: Write,
@@ -155,6 +228,15 @@ mod no_std_impls {
         }
     }
 
+    impl<W: ?Sized> VectoredWrite for &mut W
+    where
+        W: VectoredWrite,
+    {
+        fn write_all_vectored(&mut self, bufs: &[&[u8]]) -> Result<()> {
+            (**self).write_all_vectored(bufs)
+        }
+    }
+
     impl<'a> Read for &'a [u8] {
         fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
             let amt = core::cmp::min(buf.len(), self.len());
@@ -33,12 +33,27 @@
 #[macro_use]
 extern crate alloc;
 
+/// Runs the given statements only if the `tracing-hooks` feature is enabled.
+/// Used to report events to the hook in the `trace` module.
+macro_rules! decode_trace {
+    ($($body:tt)*) => {
+        #[cfg(feature = "tracing-hooks")]
+        {
+            $($body)*
+        }
+    };
+}
+
 /// Code generated from
 /// [schema.capnp](https://github.com/capnproto/capnproto/blob/master/c%2B%2B/src/capnp/schema.capnp).
 pub mod schema_capnp;
 
 pub mod any_pointer;
 pub mod any_pointer_list;
+#[cfg(all(feature = "alloc", any(feature = "quickcheck", test)))]
+pub mod arbitrary;
+#[cfg(feature = "bench")]
+pub mod bench;
 pub mod capability;
 pub mod capability_list;
 pub mod constant;
@@ -48,10 +63,15 @@ pub mod dynamic_list;
 pub mod dynamic_struct;
 pub mod dynamic_value;
 pub mod enum_list;
+mod fingerprint;
 pub mod introspect;
 pub mod io;
 pub mod list_list;
 pub mod message;
+#[cfg(feature = "alloc")]
+pub mod overlap;
+#[cfg(feature = "alloc")]
+pub mod policy;
 pub mod primitive_list;
 pub mod private;
 pub mod raw;
@@ -62,8 +82,16 @@ pub(crate) mod stringify;
 pub mod struct_list;
 pub mod text;
 pub mod text_list;
+#[cfg(feature = "tracing-hooks")]
+pub mod trace;
 pub mod traits;
 
+pub use fingerprint::fingerprint;
+#[cfg(feature = "alloc")]
+pub use overlap::{check_no_overlap, OverlapReport};
+#[cfg(feature = "tracing-hooks")]
+pub use trace::{clear_decode_trace_hook, set_decode_trace_hook, DecodeCheck, DecodeEvent};
+
 #[cfg(feature = "alloc")]
 use alloc::string::String;
 #[cfg(feature = "alloc")]
@@ -138,6 +166,42 @@ impl core::ops::AddAssign for MessageSize {
     }
 }
 
+/// Returns the cap table indices of the capabilities reachable from `root`, without duplicates,
+/// in the order they are first encountered. Entries of the cap table that are not in the result
+/// are unreachable, and need not be sent along with the message.
+///
+/// The traversal counts against the message's traversal and nesting limits, like any other read.
+#[cfg(feature = "alloc")]
+pub fn cap_walk(root: any_pointer::Reader<'_>) -> Result<Vec<u32>> {
+    let mut seen = alloc::collections::BTreeSet::new();
+    let mut result = Vec::new();
+    root.visit_capabilities(&mut |index| {
+        if seen.insert(index) {
+            result.push(index);
+        }
+    })?;
+    Ok(result)
+}
+
+/// A null pointer, through which every type reads as its default value.
+static NULL_POINTER: Word = word(0, 0, 0, 0, 0, 0, 0, 0);
+
+/// Returns a reader of the default value of `T`: a struct whose fields all have their default
+/// values, or an empty list, text, or data. This is useful for passing a value to code that
+/// expects a reader when there is no message to read it from. The reader borrows from a static
+/// null pointer, so no allocation happens.
+///
+/// Panics if `T` is an interface type, since a capability has no default value.
+pub fn default_value<T: traits::Owned>() -> T::Reader<'static> {
+    any_pointer::Reader::new(unsafe {
+        private::layout::PointerReader::get_root_unchecked(
+            &NULL_POINTER as *const Word as *const u8,
+        )
+    })
+    .get_as()
+    .expect("type has no default value")
+}
+
 /// An enum value or union discriminant that was not found among those defined in a schema.
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub struct NotInSchema(pub u16);
@@ -173,7 +237,9 @@ pub struct Error {
     /// should read only this field in making its decision.
     pub kind: ErrorKind,
 
-    /// Extra context about error
+    /// Extra context about error. The reasons for common failures, such as exceeding a read limit
+    /// or an out-of-bounds pointer, are described by `kind` alone, so that this is left empty and
+    /// creating those errors does not allocate.
     #[cfg(feature = "alloc")]
     pub extra: String,
 
@@ -224,12 +290,24 @@ pub enum ErrorKind {
     /// approach based on other methods.
     Unimplemented,
 
+    /// Text or data of {len} bytes is too long to fit in a list, which has at most 2**29 - 1 elements
+    BlobTooLong(usize),
+
     /// Buffer is not large enough
     BufferNotLargeEnough,
 
+    /// Cannot compare capabilities by value, as only their capability table indices are in the message
+    CannotCompareCapabilities,
+
+    /// Cannot copy a capability into a message that has no capability table. Call imbue_mut() on the destination before copying capabilities into it.
+    CannotCopyACapabilityWithoutACapTable,
+
     /// Cannot create a canonical message with a capability
     CannotCreateACanonicalMessageWithACapability,
 
+    /// Cannot read a capability from a message that has no capability table. Call imbue() on the message's root before reading capabilities from it.
+    CannotReadACapabilityWithoutACapTable,
+
     /// Cannot set AnyPointer field to a primitive value
     CannotSetAnyPointerFieldToAPrimitiveValue,
 
@@ -392,6 +470,9 @@ pub enum ErrorKind {
     /// Read limit exceeded
     ReadLimitExceeded,
 
+    /// Segment table is followed by zero words of padding that it does not need.
+    SegmentTableFollowedByPadding,
+
     /// setting dynamic capabilities is unsupported
     SettingDynamicCapabilitiesIsUnsupported,
 
@@ -454,6 +535,14 @@ impl Error {
         };
     }
 
+    /// Like the `From<NotInSchema>` conversion, but names in `extra` the enum or union,
+    /// `type_name`, that the value was read as.
+    pub fn from_not_in_schema(e: NotInSchema, type_name: &'static str) -> Self {
+        let mut error = Self::from_kind(ErrorKind::EnumValueOrUnionDiscriminantNotPresent(e));
+        write!(error, "reading {type_name}");
+        error
+    }
+
     #[cfg(feature = "alloc")]
     pub fn from_kind_context(kind: ErrorKind, context: String) -> Self {
         Self {
@@ -572,17 +661,21 @@ impl core::fmt::Display for ErrorKind {
             Self::Overloaded => write!(fmt, "Overloaded"),
             Self::Disconnected => write!(fmt, "Disconnected"),
             Self::Unimplemented => write!(fmt, "Unimplemented"),
+            Self::BlobTooLong(len) => write!(fmt, "Text or data of {len} bytes is too long to fit in a list, which has at most 2**29 - 1 elements"),
             Self::BufferNotLargeEnough => write!(fmt, "buffer is not large enough"),
             Self::ExistingListPointerIsNotByteSized => write!(fmt, "Called get_writable_{{data|text}}_pointer() but existing list pointer is not byte-sized."),
             Self::ExistingPointerIsNotAList => write!(fmt, "Called get_writable_{{data|text|list|struct_list}}_pointer() but existing pointer is not a list."),
+            Self::CannotCompareCapabilities => write!(fmt, "Cannot compare capabilities by value, as only their capability table indices are in the message"),
+            Self::CannotCopyACapabilityWithoutACapTable => write!(fmt, "Cannot copy a capability into a message that has no capability table. Call imbue_mut() on the destination before copying capabilities into it."),
             Self::CannotCreateACanonicalMessageWithACapability => write!(fmt, "Cannot create a canonical message with a capability"),
             Self::FourByteLengthTooBigForUSize => write!(fmt, "Cannot represent 4 byte length as `usize`. This may indicate that you are running on 8 or 16 bit platform or message is too large."),
             Self::FourByteSegmentLengthTooBigForUSize => write!(fmt, "Cannot represent 4 byte segment length as usize. This may indicate that you are running on 8 or 16 bit platform or segment is too large"),
+            Self::CannotReadACapabilityWithoutACapTable => write!(fmt, "Cannot read a capability from a message that has no capability table. Call imbue() on the message's root before reading capabilities from it."),
             Self::CannotSetAnyPointerFieldToAPrimitiveValue => write!(fmt, "cannot set AnyPointer field to a primitive value"),
             Self::CantHandleNonStructInlineComposite => write!(fmt, "Don't know how to handle non-STRUCT inline composite."),
             Self::EmptyBuffer => write!(fmt, "empty buffer"),
             Self::EmptySlice => write!(fmt, "empty slice"),
-            Self::EnumValueOrUnionDiscriminantNotPresent(val) => write!(fmt, "Enum value or union discriminant {val} was not present in schema"),
+            Self::EnumValueOrUnionDiscriminantNotPresent(NotInSchema(val)) => write!(fmt, "Enum value or union discriminant {val} was not present in schema"),
             Self::ExistingListValueIsIncompatibleWithExpectedType => write!(fmt, "Existing list value is incompatible with expected type."),
             Self::ExpectedAListOrBlob => write!(fmt, "Expected a list or blob."),
             Self::ExpectedAPointerListButGotAListOfDataOnlyStructs => write!(fmt, "Expected a pointer list, but got a list of data-only structs"),
@@ -628,6 +721,7 @@ impl core::fmt::Display for ErrorKind {
             Self::PrematureEndOfFile => write!(fmt, "Premature end of file"),
             Self::PrematureEndOfPackedInput => write!(fmt, "Premature end of packed input."),
             Self::ReadLimitExceeded => write!(fmt, "Read limit exceeded"),
+            Self::SegmentTableFollowedByPadding => write!(fmt, "Segment table is followed by zero words of padding that it does not need. Set ReaderOptions::tolerate_legacy_padding or ReaderOptions::lenient_segment_table to read the message."),
             Self::SettingDynamicCapabilitiesIsUnsupported => write!(fmt, "setting dynamic capabilities is unsupported"),
             Self::StructReaderHadBitwidthOtherThan1 => write!(fmt, "struct reader had bitwidth other than 1"),
             Self::TextBlobMissingNULTerminator => write!(fmt, "Text blob missing NUL terminator."),
@@ -699,6 +793,111 @@ impl<'a> core::ops::Deref for OutputSegments<'a> {
     }
 }
 
+/// Prints the number of segments, and the length of each in words, but not their contents.
+impl core::fmt::Debug for OutputSegments<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        struct Lengths<'b>(&'b [&'b [u8]]);
+        impl core::fmt::Debug for Lengths<'_> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_list()
+                    .entries(
+                        self.0
+                            .iter()
+                            .map(|s| s.len() / private::units::BYTES_PER_WORD),
+                    )
+                    .finish()
+            }
+        }
+        f.debug_struct("OutputSegments")
+            .field("len", &self.len())
+            .field("words", &Lengths(self))
+            .finish()
+    }
+}
+
+impl<'a> OutputSegments<'a> {
+    /// Returns the total length of all segments, in words.
+    pub fn total_words(&self) -> usize {
+        self.iter()
+            .map(|s| s.len() / private::units::BYTES_PER_WORD)
+            .sum()
+    }
+
+    /// Writes the segments, preceded by a segment table, to `write`. The output
+    /// is the same as that of [`serialize::write_message()`].
+    ///
+    /// `flush()` will not be called on the writer.
+    pub fn write_to<W: io::Write>(&self, write: W) -> Result<()> {
+        serialize::write_message_segments(write, self)
+    }
+
+    /// Copies the segments into memory that the result owns, so that they can outlive the
+    /// builder that they were borrowed from, e.g. to queue them for sending while the builder is
+    /// reused or dropped. All segments go into a single buffer, however many there are. Write
+    /// the copy with `serialize::write_message_segments()` or
+    /// `serialize_packed::write_message_segments()`, or read it with `message::Reader::new()`.
+    #[cfg(feature = "alloc")]
+    pub fn to_owned_segments(&self) -> serialize::OwnedSegments {
+        let mut lengths = serialize::SegmentLengthsBuilder::with_capacity(self.len());
+        for segment in self {
+            lengths.push_segment(segment.len() / private::units::BYTES_PER_WORD);
+        }
+        let mut owned = lengths.into_owned_segments();
+        let mut start = 0;
+        for segment in self {
+            owned[start..start + segment.len()].copy_from_slice(segment);
+            start += segment.len();
+        }
+        owned
+    }
+}
+
+impl<'a> core::iter::IntoIterator for OutputSegments<'a> {
+    type Item = &'a [u8];
+    type IntoIter = OutputSegmentsIntoIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        OutputSegmentsIntoIter {
+            segments: self,
+            index: 0,
+        }
+    }
+}
+
+impl<'a, 'b> core::iter::IntoIterator for &'b OutputSegments<'a> {
+    type Item = &'a [u8];
+    type IntoIter = core::iter::Copied<core::slice::Iter<'b, &'a [u8]>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter().copied()
+    }
+}
+
+/// Iterator over the segments of an [`OutputSegments`], returned by `into_iter()`.
+pub struct OutputSegmentsIntoIter<'a> {
+    segments: OutputSegments<'a>,
+    index: usize,
+}
+
+impl<'a> core::iter::Iterator for OutputSegmentsIntoIter<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        let segment = self.segments.get(self.index).copied();
+        if segment.is_some() {
+            self.index += 1;
+        }
+        segment
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.segments.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a> core::iter::ExactSizeIterator for OutputSegmentsIntoIter<'a> {}
+
 impl<'s> message::ReaderSegments for OutputSegments<'s> {
     fn get_segment(&self, id: u32) -> Option<&[u8]> {
         match self {
@@ -119,17 +119,18 @@ where
     /// equal to `len()`.
     pub fn get(self, index: u32) -> Result<T::Reader<'a>> {
         assert!(index < self.len());
-        FromPointerReader::get_from_pointer(&self.reader.get_pointer_element(index), None)
+        FromPointerReader::get_from_pointer(&self.reader.get_list_list_element(index)?, None)
     }
 
     /// Gets the element at position `index`. Returns `None` if `index`
     /// is greater than or equal to `len()`.
     pub fn try_get(self, index: u32) -> Option<Result<T::Reader<'a>>> {
         if index < self.len() {
-            Some(FromPointerReader::get_from_pointer(
-                &self.reader.get_pointer_element(index),
-                None,
-            ))
+            Some(
+                self.reader
+                    .get_list_list_element(index)
+                    .and_then(|element| FromPointerReader::get_from_pointer(&element, None)),
+            )
         } else {
             None
         }
@@ -177,9 +178,25 @@ impl<'a, T> Builder<'a, T>
 where
     T: crate::traits::Owned,
 {
+    /// Initializes the element at position `index` to a list of `size` elements. Panics if
+    /// `index` is greater than or equal to `len()`.
     pub fn init(self, index: u32, size: u32) -> T::Builder<'a> {
+        assert!(index < self.len());
         FromPointerBuilder::init_pointer(self.builder.get_pointer_element(index), size)
     }
+
+    /// Initializes the element at position `index` to a list of `size` elements. Returns
+    /// `None` if `index` is greater than or equal to `len()`.
+    pub fn try_init(self, index: u32, size: u32) -> Option<T::Builder<'a>> {
+        if index < self.len() {
+            Some(FromPointerBuilder::init_pointer(
+                self.builder.get_pointer_element(index),
+                size,
+            ))
+        } else {
+            None
+        }
+    }
 }
 
 impl<'a, T> Builder<'a, T>
@@ -204,6 +221,12 @@ where
             builder: builder.init_list(Pointer, size),
         }
     }
+    fn try_init_pointer(builder: PointerBuilder<'a>, size: u32) -> Result<Builder<'a, T>> {
+        Ok(Builder {
+            marker: ::core::marker::PhantomData,
+            builder: builder.try_init_list(Pointer, size)?,
+        })
+    }
     fn get_from_pointer(
         builder: PointerBuilder<'a>,
         default: Option<&'a [crate::Word]>,
@@ -239,6 +262,8 @@ where
         }
     }
 
+    /// Sets the element at position `index` to a copy of `value`. Panics if `index` is
+    /// greater than or equal to `len()`.
     pub fn set<'b>(&mut self, index: u32, value: T::Reader<'a>) -> Result<()>
     where
         T::Reader<'a>: crate::traits::IntoInternalListReader<'b>,
@@ -250,6 +275,19 @@ where
             .get_pointer_element(index)
             .set_list(&value.into_internal_list_reader(), false)
     }
+
+    /// Sets the element at position `index` to a copy of `value`. Returns `None` if `index`
+    /// is greater than or equal to `len()`.
+    pub fn try_set<'b>(&mut self, index: u32, value: T::Reader<'a>) -> Option<Result<()>>
+    where
+        T::Reader<'a>: crate::traits::IntoInternalListReader<'b>,
+    {
+        if index < self.len() {
+            Some(self.set(index, value))
+        } else {
+            None
+        }
+    }
 }
 
 impl<'a, T> crate::traits::SetPointerBuilder for Reader<'a, T>
@@ -297,9 +335,6 @@ impl<'a, T: crate::traits::Owned> From<Builder<'a, T>> for crate::dynamic_value:
 
 impl<'a, T: crate::traits::Owned> core::fmt::Debug for Reader<'a, T> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        core::fmt::Debug::fmt(
-            &::core::convert::Into::<crate::dynamic_value::Reader<'_>>::into(*self),
-            f,
-        )
+        crate::stringify::print_pointer_list_summary("list_list::Reader", self.len(), f)
     }
 }
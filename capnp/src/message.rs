@@ -74,16 +74,22 @@ use core::convert::From;
 
 use crate::any_pointer;
 use crate::private::arena::{BuilderArena, BuilderArenaImpl};
-use crate::private::arena::{ReaderArena, ReaderArenaImpl};
+use crate::private::arena::{LimitedReaderArena, ReaderArena, ReaderArenaImpl};
 use crate::private::layout;
+#[cfg(feature = "alloc")]
+use crate::private::read_limiter::SharedLimiter;
 use crate::private::units::BYTES_PER_WORD;
+#[cfg(any(feature = "alloc", feature = "sync_reader"))]
+use crate::traits::IntoInternalListReader;
+#[cfg(feature = "alloc")]
+use crate::traits::OwnedStruct;
 use crate::traits::{FromPointerBuilder, SetPointerBuilder};
 use crate::traits::{FromPointerReader, Owned};
 use crate::OutputSegments;
 use crate::Result;
 
 /// Options controlling how data is read.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ReaderOptions {
     /// Limits how many total (8-byte) words of data are allowed to be traversed. Traversal is counted
     /// when a new struct or list builder is obtained, e.g. from a get() accessor. This means that
@@ -113,11 +119,102 @@ pub struct ReaderOptions {
     /// being very large. The default limit of 64 is probably low enough to prevent any chance of
     /// stack overflow, yet high enough that it is never a problem in practice.
     pub nesting_limit: i32,
+
+    /// Limits how many lists of lists may be nested, e.g. in a `List(List(List(Int32)))`.
+    /// Each `list_list::Reader::get()` counts against this limit, as well as against
+    /// `nesting_limit`, including when lists of lists are reached through structs. This allows
+    /// a high `nesting_limit` for deep trees of structs while keeping a tight bound on the
+    /// nesting of lists, which is what recursive code tends to recurse over unboundedly.
+    ///
+    /// A limit of `None`, which is the default, means that only `nesting_limit` applies.
+    pub list_nesting_limit: Option<i32>,
+
+    /// Some early implementations wrote an extra zero word after the segment table of a message
+    /// with an odd number of segments, where the table needs no padding. By default, a flat
+    /// buffer whose segment table is followed by zero words that, if skipped, would make the
+    /// buffer end exactly at the end of the message is rejected with a
+    /// `SegmentTableFollowedByPadding` error, rather than read from the wrong offset. If this is
+    /// true, such words are skipped instead, however many there are. Any other inconsistency
+    /// between the table and the buffer is still rejected.
+    ///
+    /// This only affects `serialize::read_message_from_flat_slice()` and
+    /// `serialize::BufferSegments::new()`, because the total length of the message must be known
+    /// in advance to tell the padding apart from the start of the first segment. With the
+    /// `tracing-hooks` feature, each message whose padding is skipped is reported to the decode
+    /// trace hook as `DecodeCheck::LegacySegmentTablePadding`.
+    pub lenient_segment_table: bool,
+
+    /// A narrower form of `lenient_segment_table`, for messages from a producer known to write
+    /// one zero word after the segment table whenever the number of segments is odd. If this is
+    /// true, that word is skipped if, and only if, the buffer is then exactly as long as the table
+    /// says the message is. Any other padding is rejected as it is by default. With the
+    /// `tracing-hooks` feature, each skipped word is reported to the decode trace hook as
+    /// `DecodeCheck::LegacySegmentTablePadding`.
+    pub tolerate_legacy_padding: bool,
+
+    /// If true, the functions in `serialize` and `serialize_packed` that read a message check
+    /// that it is [canonical](https://capnproto.org/encoding.html#canonicalization) before
+    /// returning it, and return a `Failed` error naming the first violation if it is not.
+    /// Useful when every reader of a message must see exactly the same bytes, as when the
+    /// message is hashed or signed. The check takes time linear in the size of the message,
+    /// and does not count against `traversal_limit_in_words`.
+    pub require_canonical: bool,
+
+    /// Limits how many pointer dereferences are allowed during traversal, counting each struct,
+    /// list, or blob reached through a pointer, and each far pointer landing pad. Once the limit
+    /// is reached, an `Overloaded` error is reported.
+    ///
+    /// The word limit alone does not bound the cost of traversing a message that is dense in
+    /// pointers to tiny or empty objects, since each of those counts for few or no words. Counting
+    /// dereferences bounds the work done on such messages in a way that does not depend on the
+    /// speed of the machine, which makes it a portable substitute for a wall-clock deadline.
+    ///
+    /// A limit of `None`, which is the default, means that no limit is enforced.
+    pub traversal_limit_in_ops: Option<u64>,
+
+    /// Limits how many distinct capabilities a message may refer to. Capability pointers are
+    /// checked when they are dereferenced: once the limit's worth of distinct cap table indices
+    /// has been seen, dereferencing any other index fails with an `Overloaded` error. Imbuing a
+    /// reader of the message with a cap table of more entries than the limit makes every later
+    /// capability dereference fail the same way, as `imbue()` itself cannot fail.
+    /// [`Reader::cap_count_seen()`] reports how many distinct capabilities have been
+    /// dereferenced so far, e.g. for metering in an RPC system.
+    ///
+    /// A limit of `None`, which is the default, means that no limit is enforced. The indices
+    /// seen are kept in a set that grows as they are dereferenced. With the `sync_reader`
+    /// feature but without `std`, there is no set, and every dereference counts, even of a
+    /// capability that has been dereferenced before.
+    pub max_caps: Option<u32>,
+
+    /// Messages whose segment table declares this many segments or more are rejected with an
+    /// `InvalidNumberOfSegments` error as soon as the first word of the table has been read,
+    /// before anything is allocated for the rest of the table. Without it, an eight-byte prefix
+    /// could make the reader allocate a table for four billion segments. The default is
+    /// [`SEGMENTS_COUNT_LIMIT`](crate::serialize::SEGMENTS_COUNT_LIMIT); raise it to read
+    /// messages built with many small segments.
+    pub segments_limit: usize,
+
+    /// Messages whose segment table declares more than this many words, across all segments,
+    /// are rejected with an `Overloaded` error as soon as the table has been read, before
+    /// anything is allocated for the segments. `traversal_limit_in_words` rejects messages too
+    /// large to traverse in the same way, with a `MessageTooLarge` error, but this applies even
+    /// when traversal is not limited, as with [`PERMISSIVE`](Self::PERMISSIVE).
+    ///
+    /// A limit of `None`, which is the default, means that only the traversal limit applies.
+    pub total_size_limit_in_words: Option<usize>,
 }
 
 pub const DEFAULT_READER_OPTIONS: ReaderOptions = ReaderOptions {
     traversal_limit_in_words: Some(8 * 1024 * 1024),
     nesting_limit: 64,
+    list_nesting_limit: None,
+    lenient_segment_table: false,
+    tolerate_legacy_padding: false,
+    require_canonical: false,
+    traversal_limit_in_ops: None,
+    max_caps: None,
+    segments_limit: crate::serialize::SEGMENTS_COUNT_LIMIT,
+    total_size_limit_in_words: None,
 };
 
 impl Default for ReaderOptions {
@@ -127,6 +224,23 @@ impl Default for ReaderOptions {
 }
 
 impl ReaderOptions {
+    /// Options for untrusted input that is expected to be small, such as a handshake: at most
+    /// 1 MiB may be traversed, with structs nested at most 16 deep.
+    pub const STRICT: Self = Self {
+        traversal_limit_in_words: Some(1024 * 1024 / 8),
+        nesting_limit: 16,
+        ..DEFAULT_READER_OPTIONS
+    };
+
+    /// Options for trusted input of any size: there is no traversal limit. The nesting limit
+    /// stays at its default, since it guards against stack overflow in code that recurses over
+    /// the message, and segment tables are checked as strictly as by default; set
+    /// `lenient_segment_table` as well to read messages with padded tables.
+    pub const PERMISSIVE: Self = Self {
+        traversal_limit_in_words: None,
+        ..DEFAULT_READER_OPTIONS
+    };
+
     pub fn new() -> Self {
         DEFAULT_READER_OPTIONS
     }
@@ -136,10 +250,135 @@ impl ReaderOptions {
         self
     }
 
+    pub fn list_nesting_limit(&mut self, value: Option<i32>) -> &mut Self {
+        self.list_nesting_limit = value;
+        self
+    }
+
     pub fn traversal_limit_in_words(&mut self, value: Option<usize>) -> &mut Self {
         self.traversal_limit_in_words = value;
         self
     }
+
+    pub fn lenient_segment_table(&mut self, value: bool) -> &mut Self {
+        self.lenient_segment_table = value;
+        self
+    }
+
+    pub fn tolerate_legacy_padding(&mut self, value: bool) -> &mut Self {
+        self.tolerate_legacy_padding = value;
+        self
+    }
+
+    pub fn require_canonical(&mut self, value: bool) -> &mut Self {
+        self.require_canonical = value;
+        self
+    }
+
+    pub fn traversal_limit_in_ops(&mut self, value: Option<u64>) -> &mut Self {
+        self.traversal_limit_in_ops = value;
+        self
+    }
+
+    pub fn max_caps(&mut self, value: Option<u32>) -> &mut Self {
+        self.max_caps = value;
+        self
+    }
+
+    pub fn segments_limit(&mut self, value: usize) -> &mut Self {
+        self.segments_limit = value;
+        self
+    }
+
+    pub fn total_size_limit_in_words(&mut self, value: Option<usize>) -> &mut Self {
+        self.total_size_limit_in_words = value;
+        self
+    }
+
+    /// Sets both `traversal_limit_in_words` and `traversal_limit_in_ops`. Note that, for
+    /// compatibility, exceeding the word limit of a reader is reported as `ReadLimitExceeded`
+    /// rather than `Overloaded`.
+    pub fn traversal_limits(&mut self, limits: TraversalLimits) -> &mut Self {
+        self.traversal_limit_in_words = Some(usize::try_from(limits.words).unwrap_or(usize::MAX));
+        self.traversal_limit_in_ops = Some(limits.max_ops);
+        self
+    }
+}
+
+/// A budget for a traversal: at most `words` words may be read, with at most `max_ops` pointer
+/// dereferences. See `ReaderOptions::traversal_limit_in_ops` for what counts as a dereference.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TraversalLimits {
+    pub words: u64,
+    pub max_ops: u64,
+}
+
+/// A traversal budget that several operations, and several readers, draw from together, so that
+/// many calls that each stay within their own limits cannot add up to unbounded work. Attach it to
+/// readers with [`Reader::set_shared_budget()`]: from then on, everything read through them,
+/// including by copies, canonicalization and checks, is charged to the budget. As with a reader's
+/// own limits, running out of words fails with `ReadLimitExceeded`, and running out of pointer
+/// dereferences with `Overloaded`.
+///
+/// The budget is shared through an `Arc`, and is counted with atomics whether or not the
+/// `sync_reader` feature is enabled, so readers on different threads can share it.
+#[cfg(feature = "alloc")]
+pub struct SharedReadBudget {
+    limiter: SharedLimiter,
+}
+
+#[cfg(feature = "alloc")]
+impl SharedReadBudget {
+    pub fn new(limits: TraversalLimits) -> Self {
+        Self {
+            limiter: SharedLimiter::new(limits.words, limits.max_ops),
+        }
+    }
+
+    /// How much of the budget is left.
+    pub fn remaining(&self) -> TraversalLimits {
+        let (words, max_ops) = self.limiter.remaining();
+        TraversalLimits { words, max_ops }
+    }
+
+    /// Adds `limits` to what is left of the budget, e.g. periodically on a long-lived connection.
+    pub fn refill(&self, limits: TraversalLimits) {
+        self.limiter.refill(limits.words, limits.max_ops);
+    }
+
+    pub(crate) fn limiter(&self) -> &SharedLimiter {
+        &self.limiter
+    }
+}
+
+/// Options controlling how values are deep-copied into a message, as in [`Builder::set_root_with_options()`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CopyOptions {
+    /// If true, the copy fails with `ErrorKind::TextContainsNonUtf8Data` if the value contains
+    /// a Text field that is not valid UTF-8. Otherwise, which is the default, Text is copied
+    /// byte-for-byte regardless of its content, like any other blob.
+    pub validate_utf8: bool,
+
+    /// If set, reading the value to be copied, including any UTF-8 validation, fails with
+    /// `ErrorKind::Overloaded` once it exceeds these limits. The limits apply on top of those of
+    /// the message that the value belongs to, if any.
+    pub traversal_limits: Option<TraversalLimits>,
+}
+
+impl CopyOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn validate_utf8(&mut self, value: bool) -> &mut Self {
+        self.validate_utf8 = value;
+        self
+    }
+
+    pub fn traversal_limits(&mut self, value: Option<TraversalLimits>) -> &mut Self {
+        self.traversal_limits = value;
+        self
+    }
 }
 
 /// An object that manages the buffers underlying a Cap'n Proto message reader.
@@ -153,6 +392,24 @@ pub trait ReaderSegments {
     /// object is dropped. In safe Rust, it should not be possible to violate this requirement.
     fn get_segment(&self, idx: u32) -> Option<&[u8]>;
 
+    /// Gets the segment with index `idx`, fetching it first if `get_segment()` does not have it
+    /// yet. This is called when a far pointer into a segment that `get_segment()` returns `None`
+    /// for is followed, so an implementation that keeps segments elsewhere, such as in a block
+    /// store, can load each one the first time the message refers to it. Once this has
+    /// succeeded, `get_segment(idx)` must return the same slice. Segment 0, which holds the root
+    /// pointer, is never fetched this way.
+    ///
+    /// Return an `InvalidSegmentId` error if there is no such segment. Any other error is
+    /// reported to the reader as `Disconnected`, so that its caller can fetch the segment some
+    /// other way and retry. The default implementation does not fetch anything.
+    ///
+    /// An implementation that fetches segments should also override `len()`, which must count
+    /// the segments that have not been fetched yet.
+    fn get_segment_or_fetch(&self, idx: u32) -> Result<&[u8]> {
+        self.get_segment(idx)
+            .ok_or_else(|| crate::Error::from_kind(crate::ErrorKind::InvalidSegmentId(idx)))
+    }
+
     /// Gets the number of segments.
     fn len(&self) -> usize {
         for i in 0.. {
@@ -168,6 +425,8 @@ pub trait ReaderSegments {
     }
 
     #[cfg(feature = "alloc")]
+    // The reader is handed back unchanged on failure, so it isn't boxed.
+    #[allow(clippy::result_large_err)]
     fn reader_into_owned(
         reader: Reader<Self>,
     ) -> core::result::Result<Reader<crate::serialize::OwnedSegments>, Reader<Self>>
@@ -180,17 +439,31 @@ pub trait ReaderSegments {
 
 impl<S> ReaderSegments for &S
 where
-    S: ReaderSegments,
+    S: ReaderSegments + ?Sized,
 {
     fn get_segment(&self, idx: u32) -> Option<&[u8]> {
         (**self).get_segment(idx)
     }
 
+    fn get_segment_or_fetch(&self, idx: u32) -> Result<&[u8]> {
+        (**self).get_segment_or_fetch(idx)
+    }
+
     fn len(&self) -> usize {
         (**self).len()
     }
 }
 
+/// Returns the first way in which segment 0 of `arena`, taken as a whole message, is not
+/// canonical, if any. Does not look at any other segments.
+pub(crate) fn first_segment_canonical_violation(
+    arena: &dyn ReaderArena,
+) -> Result<Option<layout::NonCanonical>> {
+    let (segment_start, _) = arena.get_segment(0)?;
+    layout::PointerReader::get_root(arena, 0, segment_start, arena.nesting_limit())?
+        .canonical_violation()
+}
+
 /// An array of segments.
 pub struct SegmentArray<'a> {
     segments: &'a [&'a [u8]],
@@ -222,6 +495,24 @@ impl<'b> ReaderSegments for [&'b [u8]] {
     }
 }
 
+/// Segments in memory that this crate did not allocate, such as a region shared with another
+/// process or a memory-mapped file, can be read in place with `Reader::new(&segments[..], options)`,
+/// where segment 0 holds the root pointer. The reader borrows the segments, so the memory cannot
+/// be released while it is in use. As with any reader, the segments are checked only as they are
+/// read. Whoever makes the slices must ensure that nothing changes the memory while they exist.
+/// `serialize::read_message_from_segments()` also checks the number and total size of the
+/// segments against the options up front.
+impl ReaderSegments for [&[crate::Word]] {
+    fn get_segment(&self, id: u32) -> Option<&[u8]> {
+        self.get(id as usize)
+            .map(|segment| crate::Word::words_to_bytes(segment))
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
 /// A container used to read a message.
 pub struct Reader<S>
 where
@@ -230,6 +521,21 @@ where
     arena: ReaderArenaImpl<S>,
 }
 
+/// The single word of a message whose root pointer is null. Any type read from the root of such a
+/// message has its default value.
+pub static NULL_MESSAGE_WORDS: [crate::Word; 1] = [crate::word(0, 0, 0, 0, 0, 0, 0, 0)];
+
+static NULL_MESSAGE_SEGMENTS: [&[crate::Word]; 1] = [&NULL_MESSAGE_WORDS];
+
+impl Reader<&'static [&'static [crate::Word]]> {
+    /// Returns a reader of [`NULL_MESSAGE_WORDS`], whose `get_root()` returns the default value
+    /// of the requested type, e.g. to pass an absent message down the same path as a present
+    /// one. This does not allocate.
+    pub fn empty() -> Self {
+        Self::new(&NULL_MESSAGE_SEGMENTS[..], ReaderOptions::new())
+    }
+}
+
 impl<S> Reader<S>
 where
     S: ReaderSegments,
@@ -251,57 +557,203 @@ where
         Ok(any_pointer::Reader::new(pointer_reader))
     }
 
-    /// Gets the root of the message, interpreting it as the given type.
+    /// Gets the root of the message, interpreting it as the given type. When the type is not known
+    /// yet, get an [`any_pointer::Reader`], whose `get_as()` interprets it later.
     pub fn get_root<'a, T: FromPointerReader<'a>>(&'a self) -> Result<T> {
         self.get_root_internal()?.get_as()
     }
 
+    /// Gets the root of the message, to be read under the tighter limits of `options`. See
+    /// [`any_pointer::Reader::with_options()`].
+    pub fn get_root_with_options(
+        &self,
+        options: &ReaderOptions,
+    ) -> Result<any_pointer::LimitedReader<'_>> {
+        Ok(self.get_root_internal()?.with_options(options))
+    }
+
+    /// Gets the pointer at word `word_offset` of segment `segment_id`, treating it as the root of
+    /// a value. This allows a single message to hold several independent values whose locations
+    /// are recorded out of band, e.g. in an index stored alongside the message.
+    ///
+    /// **The location must come from a trusted index.** The pointer and everything it points to
+    /// are bounds-checked and counted against the traversal limit as usual, but nothing checks
+    /// that the location actually holds a pointer. Words of some other value will be
+    /// misinterpreted, and several locations may share data, which the traversal limit only
+    /// partially protects against.
+    pub fn get_pointer_at(
+        &self,
+        segment_id: u32,
+        word_offset: u32,
+    ) -> Result<any_pointer::Reader<'_>> {
+        let (segment_start, seg_len) = self.arena.get_segment(segment_id)?;
+        let offset = match (word_offset as usize).checked_mul(BYTES_PER_WORD) {
+            Some(offset) if word_offset < seg_len => offset,
+            _ => {
+                return Err(crate::Error::from_kind(
+                    crate::ErrorKind::MessageContainsOutOfBoundsPointer,
+                ))
+            }
+        };
+        // `PointerReader::get_root()` checks that the pointer word is within the segment.
+        let location = segment_start.wrapping_add(offset);
+        let pointer_reader = layout::PointerReader::get_root(
+            &self.arena,
+            segment_id,
+            location,
+            self.arena.nesting_limit(),
+        )?;
+        Ok(any_pointer::Reader::new(pointer_reader))
+    }
+
+    /// Returns how many distinct capabilities have been dereferenced from this message so far.
+    /// This is only tracked when [`ReaderOptions::max_caps`] is set, and is zero otherwise.
+    #[cfg(feature = "alloc")]
+    pub fn cap_count_seen(&self) -> u32 {
+        self.arena.cap_count_seen()
+    }
+
+    /// Returns the options that this reader was created with, e.g. to read a value extracted
+    /// from the message under the same limits. The traversal limits here are as configured;
+    /// see [`remaining_traversal_limit_in_words()`](Self::remaining_traversal_limit_in_words)
+    /// and [`remaining_traversal_limit_in_ops()`](Self::remaining_traversal_limit_in_ops) for
+    /// how much of them is left.
+    pub fn options(&self) -> &ReaderOptions {
+        self.arena.options()
+    }
+
+    /// Returns how many more words may be traversed before reads fail with
+    /// `ReadLimitExceeded`, or `None` if [`ReaderOptions::traversal_limit_in_words`] is `None`.
+    /// This starts at the configured limit and goes down as the message is read.
+    pub fn remaining_traversal_limit_in_words(&self) -> Option<usize> {
+        self.arena.remaining_traversal_limit_in_words()
+    }
+
+    /// Returns how many more pointers may be dereferenced before reads fail with `Overloaded`,
+    /// or `None` if [`ReaderOptions::traversal_limit_in_ops`] is `None`. This starts at the
+    /// configured limit and goes down as the message is read.
+    pub fn remaining_traversal_limit_in_ops(&self) -> Option<u64> {
+        self.arena.remaining_traversal_limit_in_ops()
+    }
+
+    /// Restores the traversal limits to those of the options, as if nothing had been read yet.
+    /// This is for trusted data that is consulted repeatedly, such as a large memory-mapped file,
+    /// which would otherwise exhaust the limits after enough lookups. For data that needs no
+    /// limit at all, create the reader with a `traversal_limit_in_words` of `None` instead, as
+    /// [`ReaderOptions::PERMISSIVE`] has. A budget set with
+    /// [`set_shared_budget()`](Self::set_shared_budget) is not affected; refill it instead.
+    pub fn reset_traversal_limits(&self) {
+        self.arena.reset_traversal_limits()
+    }
+
+    /// Adds `limits` to what is left of the traversal limits, e.g. before each batch of lookups
+    /// in long-lived trusted data. Limits that the options leave unset stay unlimited. As with
+    /// [`reset_traversal_limits()`](Self::reset_traversal_limits), a shared budget is not
+    /// affected.
+    pub fn refill_traversal_limits(&self, limits: TraversalLimits) {
+        self.arena.refill_traversal_limits(limits)
+    }
+
+    /// Charges everything read from this message from now on to `budget`, instead of to the
+    /// traversal limits of its options, which no longer apply. The remaining traversal limits
+    /// reported by the reader are then those of the budget.
+    #[cfg(feature = "alloc")]
+    pub fn set_shared_budget(&mut self, budget: alloc::sync::Arc<SharedReadBudget>) {
+        self.arena.set_shared_budget(budget)
+    }
+
+    /// The segments that the message is read from.
+    pub fn segments(&self) -> &S {
+        self.arena.segments()
+    }
+
+    /// Borrows the message's segments in the form that `Builder::get_segments_for_output()`
+    /// returns, for forwarding them without a copy into a builder, e.g. with
+    /// `OutputSegments::write_to()` or `serialize::write_message_segments()`. The segments are
+    /// exactly those that the reader was given, in the same order, so a message read from an
+    /// unpacked stream is written out again byte for byte. Nothing is validated or traversed,
+    /// and the traversal limits are not charged.
+    ///
+    /// Segments that have not been fetched yet are fetched with
+    /// `ReaderSegments::get_segment_or_fetch()`, and its error is returned if one cannot be.
+    #[cfg(feature = "alloc")]
+    pub fn as_output_segments(&self) -> Result<OutputSegments<'_>> {
+        let segments = self.segments();
+        if segments.len() == 1 {
+            return Ok(OutputSegments::SingleSegment([
+                segments.get_segment_or_fetch(0)?
+            ]));
+        }
+        let all = (0..segments.len() as u32)
+            .map(|id| segments.get_segment_or_fetch(id))
+            .collect::<Result<_>>()?;
+        Ok(OutputSegments::MultiSegment(all))
+    }
+
     pub fn into_segments(self) -> S {
         self.arena.into_segments()
     }
 
     /// Checks whether the message is [canonical](https://capnproto.org/encoding.html#canonicalization).
     pub fn is_canonical(&self) -> Result<bool> {
-        let (segment_start, seg_len) = self.arena.get_segment(0)?;
+        Ok(self.canonical_violation()?.is_none())
+    }
 
+    /// Returns the first way in which the message is not canonical, if any.
+    fn canonical_violation(&self) -> Result<Option<layout::NonCanonical>> {
         if self.arena.get_segment(1).is_ok() {
             // TODO(cleanup, apibump): should there be a nicer way to ask the arena how many
             // segments there are?
 
             // There is more than one segment, so the message cannot be canonical.
-            return Ok(false);
+            return Ok(Some(layout::NonCanonical::MultipleSegments));
         }
+        first_segment_canonical_violation(&self.arena)
+    }
 
-        let pointer_reader = layout::PointerReader::get_root(
-            &self.arena,
-            0,
-            segment_start,
-            self.arena.nesting_limit(),
-        )?;
-        let read_head = ::core::cell::Cell::new(unsafe { segment_start.add(BYTES_PER_WORD) });
-        let root_is_canonical = pointer_reader.is_canonical(&read_head)?;
-        let all_words_consumed = (read_head.get() as usize - segment_start as usize)
-            / BYTES_PER_WORD
-            == seg_len as usize;
-        Ok(root_is_canonical && all_words_consumed)
+    /// Like `new()`, but if `options.require_canonical` is set, first checks that the message
+    /// is canonical, returning a `Failed` error describing the first violation if it is not.
+    pub(crate) fn new_checked(segments: S, options: ReaderOptions) -> Result<Self> {
+        let reader = Self::new(segments, options);
+        if !options.require_canonical {
+            return Ok(reader);
+        }
+        if let Some(violation) = reader.canonical_violation()? {
+            let mut error = crate::Error::from_kind(crate::ErrorKind::Failed);
+            write!(
+                error,
+                "message is not canonical: {}",
+                violation.description()
+            );
+            return Err(error);
+        }
+        // Start over, so that the check does not count against the traversal limit.
+        Ok(Self::new(reader.into_segments(), options))
     }
 
     /// Gets the [canonical](https://capnproto.org/encoding.html#canonicalization) form
     /// of this message. Works by copying the message twice. For a canonicalization
     /// method that only requires one copy, see `message::Builder::set_root_canonical()`.
+    ///
+    /// The result is a single segment, without a segment table, whose words depend only on the
+    /// value of the message, so it can serve as a key for deduplication, and two messages are
+    /// equal if their canonical forms are. A message that holds capabilities has no canonical
+    /// form, and fails with `CannotCreateACanonicalMessageWithACapability`. If the message may
+    /// already be canonical, `is_canonical()` tells whether its own first segment can be used
+    /// instead.
     #[cfg(feature = "alloc")]
     pub fn canonicalize(&self) -> Result<Vec<crate::Word>> {
+        canonicalize_root(self.get_root_internal()?)
+    }
+
+    /// Like `canonicalize()`, but fails with `ErrorKind::Overloaded` once the canonicalization
+    /// exceeds `limits`. The limits apply on top of the reader's own limits, and cover the
+    /// whole operation, which reads the message twice.
+    #[cfg(feature = "alloc")]
+    pub fn canonicalize_with_limits(&self, limits: TraversalLimits) -> Result<Vec<crate::Word>> {
+        let arena = LimitedReaderArena::new(&self.arena, limits);
         let root = self.get_root_internal()?;
-        let size = root.target_size()?.word_count + 1;
-        let mut message = Builder::new(HeapAllocator::new().first_segment_words(size as u32));
-        message.set_root_canonical(root)?;
-        let output_segments = message.get_segments_for_output();
-        assert_eq!(1, output_segments.len());
-        let output = output_segments[0];
-        assert!((output.len() / BYTES_PER_WORD) as u64 <= size);
-        let mut result = crate::Word::allocate_zeroed_vec(output.len() / BYTES_PER_WORD);
-        crate::Word::words_to_bytes_mut(&mut result[..]).copy_from_slice(output);
-        Ok(result)
+        canonicalize_root(any_pointer::Reader::new(root.reader.with_limits(&arena)))
     }
 
     pub fn into_typed<T: Owned>(self) -> TypedReader<S, T> {
@@ -309,6 +761,55 @@ where
     }
 }
 
+#[cfg(feature = "sync_reader")]
+impl<S> Reader<S>
+where
+    S: ReaderSegments + Sync,
+{
+    /// Wraps `list`, which must have been read from this message, so that it can be sent to
+    /// another thread, for example after splitting it with `struct_list::Reader::par_chunks()`.
+    /// Reads on every thread are charged against this message's traversal limit, exactly as if
+    /// they had all been made on one thread.
+    ///
+    /// Fails if `list` was read from some other message, or has a capability table.
+    pub fn sendable_struct_list<'a, T: crate::traits::OwnedStruct>(
+        &'a self,
+        list: crate::struct_list::Reader<'a, T>,
+    ) -> Result<crate::struct_list::SendReader<'a, T>> {
+        let reader = list.into_internal_list_reader();
+        let arena: &dyn ReaderArena = &self.arena;
+        if !core::ptr::addr_eq(reader.arena(), arena) {
+            let mut error = crate::Error::from_kind(crate::ErrorKind::Failed);
+            write!(error, "struct list was not read from this message");
+            return Err(error);
+        }
+        if reader.has_cap_table() {
+            let mut error = crate::Error::from_kind(crate::ErrorKind::Failed);
+            write!(
+                error,
+                "struct list with a capability table cannot be sent to another thread"
+            );
+            return Err(error);
+        }
+        // Safety: with the "sync_reader" feature and `Sync` segments, `self.arena` is `Sync`.
+        Ok(unsafe { crate::struct_list::SendReader::new_unchecked(list) })
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn canonicalize_root(root: any_pointer::Reader) -> Result<Vec<crate::Word>> {
+    let size = root.target_size()?.word_count + 1;
+    let mut message = Builder::new(HeapAllocator::new().first_segment_words(size as u32));
+    message.set_root_canonical(root)?;
+    let output_segments = message.get_segments_for_output();
+    assert_eq!(1, output_segments.len());
+    let output = output_segments[0];
+    assert!((output.len() / BYTES_PER_WORD) as u64 <= size);
+    let mut result = crate::Word::allocate_zeroed_vec(output.len() / BYTES_PER_WORD);
+    crate::Word::words_to_bytes_mut(&mut result[..]).copy_from_slice(output);
+    Ok(result)
+}
+
 /// A message reader whose value is known to be of type `T`.
 /// Please see [module documentation](self) for more info about reader type specialization.
 pub struct TypedReader<S, T>
@@ -339,6 +840,144 @@ where
     pub fn into_inner(self) -> Reader<S> {
         self.message
     }
+
+    /// Returns the options that the underlying reader was created with. See
+    /// [`Reader::options()`].
+    pub fn options(&self) -> &ReaderOptions {
+        self.message.options()
+    }
+
+    /// Converts this reader into an iterator over a struct list reachable from its root.
+    ///
+    /// `get_list` selects the list from the message, and `map` is applied to each element to
+    /// extract an owned value. Because the returned iterator owns the message, it can be returned
+    /// from the function that read the message, which is not possible with `struct_list::Reader::iter()`.
+    ///
+    /// ```ignore
+    /// fn file_names(
+    ///     message: TypedReader<OwnedSegments, code_generator_request::Owned>,
+    /// ) -> capnp::Result<impl Iterator<Item = capnp::Result<String>>> {
+    ///     message.into_struct_list_iter(
+    ///         |message| message.get()?.get_requested_files(),
+    ///         |file| Ok(file.get_filename()?.to_string()?),
+    ///     )
+    /// }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn into_struct_list_iter<E, G, F, U>(
+        self,
+        get_list: G,
+        map: F,
+    ) -> Result<OwnedStructListIter<S, T, E, F>>
+    where
+        E: OwnedStruct,
+        G: for<'b> FnOnce(&'b Self) -> Result<crate::struct_list::Reader<'b, E>>,
+        F: for<'b> FnMut(E::Reader<'b>) -> U,
+    {
+        // The message is moved to the heap so that it has a stable address for as long as the
+        // iterator is alive. `OwnedStructListIter` never exposes the 'static lifetime.
+        let message = alloc::boxed::Box::into_raw(alloc::boxed::Box::new(self));
+        let list = match get_list(unsafe { &*message }) {
+            Ok(list) => list.into_internal_list_reader(),
+            Err(e) => {
+                drop(unsafe { alloc::boxed::Box::from_raw(message) });
+                return Err(e);
+            }
+        };
+        let list: layout::ListReader<'static> = unsafe { core::mem::transmute(list) };
+        Ok(OwnedStructListIter {
+            len: list.len(),
+            list,
+            index: 0,
+            map,
+            message,
+            marker: ::core::marker::PhantomData,
+        })
+    }
+}
+
+/// An iterator over a struct list that owns the message containing the list.
+/// Constructed with [TypedReader::into_struct_list_iter].
+#[cfg(feature = "alloc")]
+pub struct OwnedStructListIter<S, T, E, F>
+where
+    S: ReaderSegments,
+    T: Owned,
+    E: OwnedStruct,
+{
+    // Points into `*message`.
+    list: layout::ListReader<'static>,
+    index: u32,
+    len: u32,
+    map: F,
+    message: *mut TypedReader<S, T>,
+    marker: ::core::marker::PhantomData<E>,
+}
+
+#[cfg(feature = "alloc")]
+impl<S, T, E, F> OwnedStructListIter<S, T, E, F>
+where
+    S: ReaderSegments,
+    T: Owned,
+    E: OwnedStruct,
+{
+    /// Gives back the message, discarding the iteration state.
+    pub fn into_inner(self) -> TypedReader<S, T> {
+        let mut this = core::mem::ManuallyDrop::new(self);
+        unsafe {
+            core::ptr::drop_in_place(&mut this.map);
+            *alloc::boxed::Box::from_raw(this.message)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S, T, E, F> Drop for OwnedStructListIter<S, T, E, F>
+where
+    S: ReaderSegments,
+    T: Owned,
+    E: OwnedStruct,
+{
+    fn drop(&mut self) {
+        drop(unsafe { alloc::boxed::Box::from_raw(self.message) });
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S, T, E, F, U> Iterator for OwnedStructListIter<S, T, E, F>
+where
+    S: ReaderSegments,
+    T: Owned,
+    E: OwnedStruct,
+    F: for<'b> FnMut(E::Reader<'b>) -> U,
+{
+    type Item = U;
+
+    fn next(&mut self) -> Option<U> {
+        if self.index < self.len {
+            let list: layout::ListReader<'_> = self.list;
+            let element = list.get_struct_element(self.index);
+            self.index += 1;
+            Some((self.map)(element.into()))
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.len - self.index) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S, T, E, F, U> ExactSizeIterator for OwnedStructListIter<S, T, E, F>
+where
+    S: ReaderSegments,
+    T: Owned,
+    E: OwnedStruct,
+    F: for<'b> FnMut(E::Reader<'b>) -> U,
+{
 }
 
 impl<S, T> From<Reader<S>> for TypedReader<S, T>
@@ -380,7 +1019,12 @@ where
 ///
 /// # Safety
 /// Implementions must ensure all of the following:
-///   1. The memory returned by `allocate_segment` is initialized to all zeroes.
+///   1. The memory returned by `allocate_segment` is initialized to all zeroes, on every call,
+///      including when it is memory that an earlier segment used. Builders rely on this: they
+///      leave fields and list elements that are never set as they find them, and a reader sees
+///      whatever is there. An allocator that reuses segments only needs to re-zero the first
+///      `words_used` words of each one passed to `deallocate_segment()`, since the rest are
+///      still zero.
 ///   2. The memory returned by `allocate_segment` is valid until `deallocate_segment()`
 ///      is called on it.
 ///   3. The allocated memory does not overlap with other allocated memory.
@@ -395,16 +1039,35 @@ pub unsafe trait Allocator {
     /// previous segment.
     fn allocate_segment(&mut self, minimum_size: u32) -> (*mut u8, u32);
 
+    /// Like `allocate_segment()`, but returns `None` if the allocator has no room for a segment of
+    /// `minimum_size` words. Builders allocate every segment through this method, so an operation
+    /// that needs the segment fails with `Overloaded` if it returns `None`, or panics if it does
+    /// not return a `Result`. The default calls `allocate_segment()`, for an allocator that never
+    /// runs out.
+    fn try_allocate_segment(&mut self, minimum_size: u32) -> Option<(*mut u8, u32)> {
+        Some(self.allocate_segment(minimum_size))
+    }
+
+    /// Like `try_allocate_segment()`, but for a segment that is to take the place of all of the
+    /// current ones once the message has been copied into it, as `Builder::shrink_to()` does.
+    /// An allocator that grows each segment by the size of the last should size this one by
+    /// `minimum_size` alone, since the message is about to shrink. The default calls
+    /// `try_allocate_segment()`.
+    fn allocate_replacement_segment(&mut self, minimum_size: u32) -> Option<(*mut u8, u32)> {
+        self.try_allocate_segment(minimum_size)
+    }
+
     /// Indicates that a segment, previously allocated via allocate_segment(), is no longer in use.
     /// `word_size` is the length of the segment in words, as returned from `allocate_segment()`.
     /// `words_used` is always less than or equal to `word_size`, and indicates how many
     /// words (contiguous from the start of the segment) were possibly written with non-zero values.
+    /// The words after them are still zero.
     ///
     /// # Safety
     /// Callers must only call this method on a pointer that has previously been been returned
-    /// from `allocate_segment()`, and only once on each such segment. `word_size` must
-    /// equal the word size returned from `allocate_segment()`, and `words_used` must be at
-    /// most `word_size`.
+    /// from `allocate_segment()` or `allocate_replacement_segment()`, and only once on each such
+    /// segment. `word_size` must equal the word size returned with it, and `words_used` must be
+    /// at most `word_size`.
     unsafe fn deallocate_segment(&mut self, ptr: *mut u8, word_size: u32, words_used: u32);
 }
 
@@ -439,32 +1102,94 @@ where
     }
 
     fn get_root_internal(&mut self) -> any_pointer::Builder<'_> {
+        self.try_get_root_internal().expect("allocate root pointer")
+    }
+
+    fn try_get_root_internal(&mut self) -> Result<any_pointer::Builder<'_>> {
         if self.arena.is_empty() {
-            self.arena
-                .allocate_segment(1)
-                .expect("allocate root pointer");
+            self.arena.allocate_segment(1)?;
             self.arena.allocate(0, 1).expect("allocate root pointer");
         }
         let (seg_start, _seg_len) = self.arena.get_segment_mut(0);
         let location: *mut u8 = seg_start;
         let Self { arena } = self;
 
-        any_pointer::Builder::new(layout::PointerBuilder::get_root(arena, 0, location))
+        Ok(any_pointer::Builder::new(layout::PointerBuilder::get_root(
+            arena, 0, location,
+        )))
+    }
+
+    /// Gets the pointer at word `word_offset` of segment `segment_id`, for patching in place a
+    /// value stored at a location recorded out of band. This is the counterpart of
+    /// `Reader::get_pointer_at()`. Returns an error if the location is not within the allocated
+    /// part of the segment.
+    ///
+    /// # Safety
+    /// **The location must come from a trusted index**, and must hold a pointer that was written
+    /// by this builder. Unlike the reader side, values reached through a builder are not
+    /// bounds-checked, so following words that are not such a pointer can read or overwrite
+    /// memory outside of the message.
+    pub unsafe fn get_pointer_at_mut(
+        &mut self,
+        segment_id: u32,
+        word_offset: u32,
+    ) -> Result<any_pointer::Builder<'_>> {
+        let (_, seg_len) = self.arena.get_segment(segment_id)?;
+        let offset = match (word_offset as usize).checked_mul(BYTES_PER_WORD) {
+            Some(offset) if word_offset < seg_len => offset,
+            _ => {
+                return Err(crate::Error::from_kind(
+                    crate::ErrorKind::MessageContainsOutOfBoundsPointer,
+                ))
+            }
+        };
+        let (seg_start, _seg_len) = self.arena.get_segment_mut(segment_id);
+        let location = unsafe { seg_start.add(offset) };
+        let Self { arena } = self;
+        Ok(any_pointer::Builder::new(layout::PointerBuilder::get_root(
+            arena, segment_id, location,
+        )))
     }
 
-    /// Initializes the root as a value of the given type.
+    /// Initializes the root as a value of the given type. Any value that the root already has is
+    /// zeroed, but its space stays allocated in the message; use `get_root()` to go on building
+    /// an existing root.
+    ///
+    /// Code that does not know the type of the root, such as an envelope around an arbitrary
+    /// payload, can initialize it as an [`any_pointer::Builder`], which clears the root and
+    /// leaves it null, and hand that to code that does, which sets it with `init_as()` or
+    /// `set_as()`.
+    ///
+    /// Panics with `Overloaded` if the allocator has no room for the value, as can happen with a
+    /// `SingleSegmentAllocator`. See `try_init_root()`.
     pub fn init_root<'a, T: FromPointerBuilder<'a>>(&'a mut self) -> T {
         let root = self.get_root_internal();
         root.init_as()
     }
 
+    /// Like `init_root()`, but fails with `Overloaded` instead of panicking if the allocator has
+    /// no room for the value.
+    pub fn try_init_root<'a, T: FromPointerBuilder<'a>>(&'a mut self) -> Result<T> {
+        let root = self.try_get_root_internal()?;
+        root.try_init_as()
+    }
+
     /// Initializes the root as a value of the given list type, with the given length.
     pub fn initn_root<'a, T: FromPointerBuilder<'a>>(&'a mut self, length: u32) -> T {
         let root = self.get_root_internal();
         root.initn_as(length)
     }
 
-    /// Gets the root, interpreting it as the given type.
+    /// Like `initn_root()`, but fails with `Overloaded` instead of panicking if the allocator has
+    /// no room for the value.
+    pub fn try_initn_root<'a, T: FromPointerBuilder<'a>>(&'a mut self, length: u32) -> Result<T> {
+        let root = self.try_get_root_internal()?;
+        root.try_initn_as(length)
+    }
+
+    /// Gets the root, interpreting it as the given type, so that it can be built further. A root
+    /// that has not been set is initialized to the type's default value, an empty struct for a
+    /// struct type, and one that has been set is left as it is.
     pub fn get_root<'a, T: FromPointerBuilder<'a>>(&'a mut self) -> Result<T> {
         let root = self.get_root_internal();
         root.get_as()
@@ -486,12 +1211,55 @@ where
         }
     }
 
-    /// Sets the root to a deep copy of the given value.
+    /// Sets the root to a deep copy of the given value, which may be a struct, list, text, data or
+    /// any-pointer reader from any message. Far pointers in `value` are followed, and the copy is
+    /// laid out afresh in this message. Its capabilities are extracted from the cap table that
+    /// `value` was imbued with, but the root of a `Builder` has no cap table to inject them
+    /// into, so a value with capabilities fails with `CannotCopyACapabilityWithoutACapTable`.
+    /// To copy one, initialize the root as an `any_pointer::Builder`, `imbue_mut()` it with a
+    /// cap table, and `set_as()` the value.
     pub fn set_root<From: SetPointerBuilder>(&mut self, value: From) -> Result<()> {
         let mut root = self.get_root_internal();
         root.set_as(value)
     }
 
+    /// Like `set_root()`, but with the given `CopyOptions`. With `validate_utf8` set, `value`
+    /// is checked before anything is copied, so the root is left untouched on failure.
+    pub fn set_root_with_options<'v, From>(
+        &mut self,
+        value: From,
+        options: CopyOptions,
+    ) -> Result<()>
+    where
+        From: SetPointerBuilder + Into<crate::dynamic_value::Reader<'v>> + Clone,
+    {
+        if let Some(limits) = options.traversal_limits {
+            if let Some(source) = value.clone().into().arena() {
+                let arena = LimitedReaderArena::new(source, limits);
+                let value = value.into().with_limits(&arena);
+                if options.validate_utf8 {
+                    value.validate_utf8()?;
+                }
+                return match value {
+                    crate::dynamic_value::Reader::Struct(s) => self.set_root(s),
+                    crate::dynamic_value::Reader::List(l) => self.set_root(l),
+                    crate::dynamic_value::Reader::AnyPointer(a) => self.set_root(a),
+                    // Only pointer values have an arena, but `into()` need not return the same
+                    // kind of value twice.
+                    _ => {
+                        let mut error = crate::Error::from_kind(crate::ErrorKind::Failed);
+                        write!(error, "value did not convert to a pointer value");
+                        Err(error)
+                    }
+                };
+            }
+        }
+        if options.validate_utf8 {
+            value.clone().into().validate_utf8()?;
+        }
+        self.set_root(value)
+    }
+
     /// Sets the root to a canonicalized version of `value`. If this was the first action taken
     /// on this `Builder`, then a subsequent call to `get_segments_for_output()` should return
     /// a single segment, containing the full canonicalized message.
@@ -509,18 +1277,141 @@ where
         Ok(())
     }
 
+    /// Sets the root to a copy of the canonical single-segment message in `words`, such as one
+    /// produced by `Reader::canonicalize()` and kept since. See
+    /// [`any_pointer::Builder::set_from_canonical_words()`]: the words are checked for
+    /// canonicality and then copied as a block, adjusting only the root pointer, and a `Failed`
+    /// error is returned, leaving the root as it was, if they are not canonical.
+    pub fn set_root_from_canonical_words(&mut self, words: &[crate::Word]) -> Result<()> {
+        self.get_root_internal().set_from_canonical_words(words)
+    }
+
     pub fn get_segments_for_output(&self) -> OutputSegments {
         self.arena.get_segments_for_output()
     }
 
+    /// Overwrites every allocated word of every segment with zeroes, using writes that the compiler
+    /// cannot elide. Call this before dropping or reusing a builder that held secrets such as key
+    /// material. Afterwards the root is null; the segments remain allocated, and the builder
+    /// remains usable.
+    pub fn scrub(&mut self) {
+        self.arena.scrub_segments();
+    }
+
+    /// The number of words allocated for the message across all of its segments. This counts
+    /// everything that `get_segments_for_output()` returns, including space left behind by
+    /// overwritten values and the landing pads of far pointers, so it is at least the
+    /// `word_count` of the root's `target_size()`, plus one word for the root pointer. The unused
+    /// capacity of the segments is not counted.
+    ///
+    /// It makes a good first segment size for the next builder of a similar message: built with
+    /// `HeapAllocator::new().first_segment_words()` of this size, a message built in the same way
+    /// fits in its first segment, as long as the size is within the maximum segment size.
+    pub fn size_in_words(&self) -> usize {
+        self.arena.size_in_words()
+    }
+
+    /// The number of segments the message has been given by its allocator. This is zero until
+    /// something is written, even the root pointer, and more than one once the message has
+    /// outgrown its first segment. Like `size_in_words()`, it does not traverse the message.
+    pub fn segment_count(&self) -> usize {
+        self.arena.segment_count()
+    }
+
+    /// Empties the message so that the builder can be reused for another one. Every segment but
+    /// the first is returned to the allocator, and the used part of the first is zeroed, so a
+    /// next message that fits in the first segment is built without allocating. Afterwards
+    /// `get_segments_for_output()` returns what it does for a new builder: a single segment
+    /// holding a null root. A cap table that the message was imbued with is kept outside of the
+    /// builder, and needs to be cleared separately.
+    pub fn clear(&mut self) {
+        self.arena.clear();
+    }
+
+    /// Releases memory that is not needed to hold the message's current content, such as space
+    /// left behind by values that were overwritten, or segments that were grown for content that
+    /// is no longer reachable from the root. Equivalent to `shrink_to(0)`.
+    ///
+    /// The content is rewritten into a single segment, so the small segments that a builder with
+    /// a small first segment grows into, each of which costs a segment table entry and far
+    /// pointers on the wire, are coalesced. Only content larger than the maximum segment size
+    /// still needs more than one segment.
+    #[cfg(feature = "alloc")]
+    pub fn shrink_to_fit(&mut self) -> Result<()> {
+        self.shrink_to(0)
+    }
+
+    /// Like `shrink_to_fit()`, but keeps a first segment of at least `min_first_segment_words`
+    /// words, so that subsequent writes do not immediately need a new allocation.
+    ///
+    /// The content is copied into a newly-allocated first segment, and only then are the old
+    /// segments returned to the allocator. If allocating or copying fails, the new segments are
+    /// returned instead, and the message is left as it was. Both copies exist at once, so this
+    /// fails with `Overloaded` for an allocator without room for the second, such as a
+    /// `SingleSegmentAllocator`. Capability pointers keep their indices, so a cap table that the
+    /// message was imbued with still applies.
+    #[cfg(feature = "alloc")]
+    pub fn shrink_to(&mut self, min_first_segment_words: u32) -> Result<()> {
+        if self.arena.is_empty() {
+            return Ok(());
+        }
+        let root: any_pointer::Reader = self.get_root_as_reader()?;
+        // Add one for the root pointer.
+        let words = (root.target_size()?.word_count + 1).min(MAX_SEGMENT_WORDS as u64) as u32;
+
+        let needed = words.max(min_first_segment_words);
+
+        // The copy is allocated by this message's allocator, which the old segments do not need
+        // in the meantime, since they are only read.
+        let allocator = self
+            .arena
+            .take_allocator()
+            .expect("builder has an allocator");
+        let mut copy = Builder::new(allocator);
+        let result = copy
+            .arena
+            .allocate_replacement_segment(needed)
+            .and_then(|()| {
+                copy.arena.allocate(0, 1).expect("allocate root pointer");
+                let root: any_pointer::Reader = self.get_root_as_reader()?;
+                copy.get_root_internal().set_keeping_cap_indices(root)
+            });
+        match result {
+            Ok(()) => {
+                self.arena.replace_segments(copy.arena);
+                Ok(())
+            }
+            Err(error) => {
+                self.arena.restore_allocator(copy.into_allocator());
+                Err(error)
+            }
+        }
+    }
+
+    /// Turns the builder into a reader of the message, without copying its segments. The
+    /// reader has no limits, since the message was built here; use
+    /// `into_reader_with_options()` to read it as untrusted input.
     pub fn into_reader(self) -> Reader<Self> {
-        Reader::new(
-            self,
-            ReaderOptions {
-                traversal_limit_in_words: None,
-                nesting_limit: i32::MAX,
-            },
-        )
+        self.into_reader_with_options(ReaderOptions {
+            traversal_limit_in_words: None,
+            nesting_limit: i32::MAX,
+            list_nesting_limit: None,
+            lenient_segment_table: false,
+            tolerate_legacy_padding: false,
+            require_canonical: false,
+            traversal_limit_in_ops: None,
+            max_caps: None,
+            segments_limit: usize::MAX,
+            total_size_limit_in_words: None,
+        })
+    }
+
+    /// Like `into_reader()`, but the reader enforces `options`, as one reading the message from
+    /// its serialized segments would. The message holds capabilities as indices into a
+    /// capability table kept outside of it, so the table that the builder was imbued with can
+    /// be imbued into the roots that the reader returns.
+    pub fn into_reader_with_options(self, options: ReaderOptions) -> Reader<Self> {
+        Reader::new(self, options)
     }
 
     pub fn into_typed<T: Owned>(self) -> TypedBuilder<T, A> {
@@ -616,6 +1507,14 @@ where
         self.message.initn_root(length)
     }
 
+    pub fn try_init_root(&mut self) -> Result<T::Builder<'_>> {
+        self.message.try_init_root()
+    }
+
+    pub fn try_initn_root(&mut self, length: u32) -> Result<T::Builder<'_>> {
+        self.message.try_initn_root(length)
+    }
+
     pub fn get_root(&mut self) -> Result<T::Builder<'_>> {
         self.message.get_root()
     }
@@ -643,6 +1542,10 @@ where
     pub fn into_reader(self) -> TypedReader<Builder<A>, T> {
         TypedReader::new(self.message.into_reader())
     }
+
+    pub fn into_reader_with_options(self, options: ReaderOptions) -> TypedReader<Builder<A>, T> {
+        TypedReader::new(self.message.into_reader_with_options(options))
+    }
 }
 
 impl<T, A> From<Builder<A>> for TypedBuilder<T, A>
@@ -669,7 +1572,7 @@ pub struct HeapAllocator {
     max_segment_words: u32,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AllocationStrategy {
     /// Allocates the same number of words for each segment, to the extent possible.
     /// This strategy is primarily useful for testing cross-segment pointers.
@@ -680,6 +1583,10 @@ pub enum AllocationStrategy {
 }
 
 pub const SUGGESTED_FIRST_SEGMENT_WORDS: u32 = 1024;
+
+// The largest segment that a HeapAllocator will allocate by default.
+#[cfg(feature = "alloc")]
+const MAX_SEGMENT_WORDS: u32 = 1 << 29;
 pub const SUGGESTED_ALLOCATION_STRATEGY: AllocationStrategy = AllocationStrategy::GrowHeuristically;
 
 #[cfg(feature = "alloc")]
@@ -688,7 +1595,7 @@ impl Default for HeapAllocator {
         Self {
             next_size: SUGGESTED_FIRST_SEGMENT_WORDS,
             allocation_strategy: SUGGESTED_ALLOCATION_STRATEGY,
-            max_segment_words: 1 << 29,
+            max_segment_words: MAX_SEGMENT_WORDS,
         }
     }
 }
@@ -720,16 +1627,22 @@ impl HeapAllocator {
     }
 }
 
+// Allocates `size` zeroed words, in the way that `HeapAllocator::deallocate_segment()` expects.
+#[cfg(feature = "alloc")]
+fn allocate_zeroed_words(size: u32) -> *mut u8 {
+    let layout = alloc::alloc::Layout::from_size_align(size as usize * BYTES_PER_WORD, 8).unwrap();
+    let ptr = unsafe { alloc::alloc::alloc_zeroed(layout) };
+    if ptr.is_null() {
+        alloc::alloc::handle_alloc_error(layout);
+    }
+    ptr
+}
+
 #[cfg(feature = "alloc")]
 unsafe impl Allocator for HeapAllocator {
     fn allocate_segment(&mut self, minimum_size: u32) -> (*mut u8, u32) {
         let size = core::cmp::max(minimum_size, self.next_size);
-        let layout =
-            alloc::alloc::Layout::from_size_align(size as usize * BYTES_PER_WORD, 8).unwrap();
-        let ptr = unsafe { alloc::alloc::alloc_zeroed(layout) };
-        if ptr.is_null() {
-            alloc::alloc::handle_alloc_error(layout);
-        }
+        let ptr = allocate_zeroed_words(size);
         match self.allocation_strategy {
             AllocationStrategy::GrowHeuristically => {
                 if size < self.max_segment_words - self.next_size {
@@ -743,6 +1656,13 @@ unsafe impl Allocator for HeapAllocator {
         (ptr, size)
     }
 
+    /// Allocates the larger of `minimum_size` and `SUGGESTED_FIRST_SEGMENT_WORDS`, the size that
+    /// the next segment goes back to once the current ones are deallocated.
+    fn allocate_replacement_segment(&mut self, minimum_size: u32) -> Option<(*mut u8, u32)> {
+        let size = core::cmp::max(minimum_size, SUGGESTED_FIRST_SEGMENT_WORDS);
+        Some((allocate_zeroed_words(size), size))
+    }
+
     unsafe fn deallocate_segment(&mut self, ptr: *mut u8, word_size: u32, _words_used: u32) {
         unsafe {
             alloc::alloc::dealloc(
@@ -787,6 +1707,247 @@ impl Builder<HeapAllocator> {
     pub fn new_default() -> Self {
         Default::default()
     }
+
+    /// Captures the state of this builder, so that building can later be resumed with `thaw()`,
+    /// for example after the process has been restarted.
+    pub fn freeze(&self) -> BuilderSnapshot {
+        let allocator = self.arena.allocator();
+        let mut segments = Vec::new();
+        if !self.arena.is_empty() {
+            for segment in &*self.get_segments_for_output() {
+                let mut words = crate::Word::allocate_zeroed_vec(segment.len() / BYTES_PER_WORD);
+                crate::Word::words_to_bytes_mut(&mut words).copy_from_slice(segment);
+                segments.push(words);
+            }
+        }
+        BuilderSnapshot {
+            segments,
+            capacities: self.arena.segment_capacities(),
+            next_segment_words: allocator.next_size,
+            allocation_strategy: allocator.allocation_strategy,
+            max_segment_words: allocator.max_segment_words,
+        }
+    }
+
+    /// Reconstructs a builder from a snapshot taken by `freeze()`. The builder allocates from
+    /// the same segments, at the same offsets, as the frozen builder would have, so that
+    /// building the rest of a message after thawing produces the same bytes as building all of
+    /// it at once.
+    ///
+    /// Returns an error if the snapshot is inconsistent, if any pointer reachable from the root
+    /// refers to words beyond the watermark of its segment, or if any two objects overlap.
+    pub fn thaw(snapshot: BuilderSnapshot) -> Result<Self> {
+        snapshot.validate()?;
+        let mut message = Self::new(HeapAllocator {
+            next_size: snapshot.next_segment_words,
+            allocation_strategy: snapshot.allocation_strategy,
+            max_segment_words: snapshot.max_segment_words,
+        });
+        for (words, &capacity) in snapshot.segments.iter().zip(&snapshot.capacities) {
+            let ptr = allocate_zeroed_words(capacity);
+            let bytes = crate::Word::words_to_bytes(words);
+            unsafe {
+                core::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+                message
+                    .arena
+                    .push_segment(ptr, capacity, words.len() as u32);
+            }
+        }
+        Ok(message)
+    }
+}
+
+/// Builds a message whose root is the text `value`, in a single segment of exactly the size
+/// that it needs. Read it back with [`read_single_text()`].
+///
+/// Returns a `BlobTooLong` error if `value` does not fit in a list.
+#[cfg(feature = "alloc")]
+pub fn single_text(value: &str) -> Result<Builder<HeapAllocator>> {
+    // Text is followed by a NUL terminator.
+    single_blob(value.len() + 1, |message| message.set_root(value))
+}
+
+/// Builds a message whose root is `value`, in a single segment of exactly the size that it
+/// needs. Read it back with [`read_single_data()`].
+///
+/// Returns a `BlobTooLong` error if `value` does not fit in a list.
+#[cfg(feature = "alloc")]
+pub fn single_data(value: &[u8]) -> Result<Builder<HeapAllocator>> {
+    single_blob(value.len(), |message| message.set_root(value))
+}
+
+#[cfg(feature = "alloc")]
+fn single_blob(
+    byte_count: usize,
+    set_root: impl FnOnce(&mut Builder<HeapAllocator>) -> Result<()>,
+) -> Result<Builder<HeapAllocator>> {
+    // The root pointer, followed by the blob.
+    let words = 1 + byte_count.div_ceil(BYTES_PER_WORD);
+    let words =
+        u32::try_from(words).map_or(MAX_SEGMENT_WORDS, |words| words.min(MAX_SEGMENT_WORDS));
+    let mut message = Builder::new(HeapAllocator::new().first_segment_words(words));
+    set_root(&mut message)?;
+    Ok(message)
+}
+
+/// Reads the text at the root of `bytes`, which must hold exactly one message in the standard
+/// serialization format, such as one built by [`single_text()`] and written with
+/// `serialize::write_message()`. The text is borrowed from `bytes`, without copying.
+///
+/// A null root reads as empty text. It is an error for the root to be anything other than text,
+/// for the text not to be valid UTF-8, or for anything to follow the message in `bytes`.
+///
+/// ALIGNMENT: Without the "unaligned" feature, `bytes` that are not 8-byte aligned are copied
+/// before being read, or rejected if the "alloc" feature is also disabled.
+pub fn read_single_text(bytes: &[u8], options: ReaderOptions) -> Result<&str> {
+    let text = read_single_blob(bytes, options, |root| {
+        Ok(root.get_as::<crate::text::Reader>()?.as_bytes())
+    })?;
+    Ok(core::str::from_utf8(text)?)
+}
+
+/// Reads the data at the root of `bytes`, which must hold exactly one message in the standard
+/// serialization format, such as one built by [`single_data()`] and written with
+/// `serialize::write_message()`. The data is borrowed from `bytes`, without copying.
+///
+/// A null root reads as empty data. It is an error for the root to be anything other than data,
+/// or for anything to follow the message in `bytes`. See [`read_single_text()`] for the
+/// alignment requirements on `bytes`.
+pub fn read_single_data(bytes: &[u8], options: ReaderOptions) -> Result<&[u8]> {
+    read_single_blob(bytes, options, |root| root.get_as::<crate::data::Reader>())
+}
+
+fn read_single_blob(
+    bytes: &[u8],
+    options: ReaderOptions,
+    get: for<'b> fn(any_pointer::Reader<'b>) -> Result<&'b [u8]>,
+) -> Result<&[u8]> {
+    #[cfg(all(feature = "alloc", not(feature = "unaligned")))]
+    if bytes.as_ptr() as usize % BYTES_PER_WORD != 0 {
+        // The blob is at the same offset in the copy as in `bytes`.
+        let mut words = crate::Word::allocate_zeroed_vec(bytes.len().div_ceil(BYTES_PER_WORD));
+        let copy = &mut crate::Word::words_to_bytes_mut(&mut words)[..bytes.len()];
+        copy.copy_from_slice(bytes);
+        let range = single_blob_range(copy, options, get)?;
+        return Ok(&bytes[range]);
+    }
+    let range = single_blob_range(bytes, options, get)?;
+    Ok(&bytes[range])
+}
+
+/// Returns where in `bytes` the blob at the root of the message is.
+fn single_blob_range(
+    bytes: &[u8],
+    options: ReaderOptions,
+    get: for<'b> fn(any_pointer::Reader<'b>) -> Result<&'b [u8]>,
+) -> Result<core::ops::Range<usize>> {
+    let mut rest = bytes;
+    let message = crate::serialize::read_message_from_flat_slice_no_alloc(&mut rest, options)?;
+    if !rest.is_empty() {
+        let mut error = crate::Error::from_kind(crate::ErrorKind::Failed);
+        write!(error, "{} bytes follow the message", rest.len());
+        return Err(error);
+    }
+    let blob = get(message.get_root()?)?;
+    if blob.is_empty() {
+        // An empty blob may not point into `bytes` at all.
+        return Ok(0..0);
+    }
+    let start = blob.as_ptr() as usize - bytes.as_ptr() as usize;
+    Ok(start..start + blob.len())
+}
+
+/// The state of a `message::Builder<HeapAllocator>`, as captured by `Builder::freeze()`. All
+/// fields are plain data, so the caller can persist a snapshot in whatever format suits it.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BuilderSnapshot {
+    /// The allocated words of each segment. The length of each is the segment's watermark: the
+    /// offset at which the next allocation in the segment will start.
+    pub segments: Vec<Vec<crate::Word>>,
+
+    /// The capacity in words of each segment.
+    pub capacities: Vec<u32>,
+
+    /// The minimum size in words of the next segment that the allocator will allocate.
+    pub next_segment_words: u32,
+
+    /// The allocator's strategy for sizing new segments.
+    pub allocation_strategy: AllocationStrategy,
+
+    /// The allocator's maximum segment size in words.
+    pub max_segment_words: u32,
+}
+
+#[cfg(feature = "alloc")]
+fn invalid_snapshot(args: core::fmt::Arguments<'_>) -> crate::Error {
+    let mut error = crate::Error::from_kind(crate::ErrorKind::Failed);
+    error.write_fmt(format_args!("invalid builder snapshot: {args}"));
+    error
+}
+
+#[cfg(feature = "alloc")]
+impl BuilderSnapshot {
+    fn validate(&self) -> Result<()> {
+        if self.segments.len() != self.capacities.len() {
+            return Err(invalid_snapshot(format_args!(
+                "snapshot has {} segments but {} capacities",
+                self.segments.len(),
+                self.capacities.len()
+            )));
+        }
+        if self.next_segment_words > self.max_segment_words {
+            return Err(invalid_snapshot(format_args!(
+                "next segment size {} exceeds the maximum segment size {}",
+                self.next_segment_words, self.max_segment_words
+            )));
+        }
+        for (id, (words, &capacity)) in self.segments.iter().zip(&self.capacities).enumerate() {
+            if capacity == 0 {
+                return Err(invalid_snapshot(format_args!(
+                    "segment {id} has no capacity"
+                )));
+            }
+            if words.len() > capacity as usize {
+                return Err(invalid_snapshot(format_args!(
+                    "segment {id} has watermark {} beyond its capacity {capacity}",
+                    words.len()
+                )));
+            }
+        }
+        match self.segments.first() {
+            None => return Ok(()),
+            Some(first) if first.is_empty() => {
+                return Err(invalid_snapshot(format_args!(
+                    "segment 0 has no room for the root pointer"
+                )));
+            }
+            Some(_) => {}
+        }
+
+        // Walk the message as a reader, which checks every reachable pointer against the
+        // watermark of the segment it points into. The thawed builder will trust those pointers,
+        // so objects that overlap, or are reachable more than once, are rejected too. Each object
+        // is walked once, so the traversal limit cannot be hit by a valid snapshot.
+        let segments: Vec<&[u8]> = self
+            .segments
+            .iter()
+            .map(|words| crate::Word::words_to_bytes(words))
+            .collect();
+        let total_words = self.segments.iter().map(Vec::len).sum();
+        let reader = Reader::new(
+            SegmentArray::new(&segments),
+            ReaderOptions {
+                traversal_limit_in_words: Some(total_words),
+                nesting_limit: i32::MAX,
+                list_nesting_limit: None,
+                segments_limit: usize::MAX,
+                ..Default::default()
+            },
+        );
+        crate::overlap::reject_overlap(reader.get_root()?)?;
+        Ok(())
+    }
 }
 
 #[cfg(feature = "alloc")]
@@ -844,6 +2005,13 @@ impl<'a> ScratchSpaceHeapAllocator<'a> {
         }
     }
 
+    /// Like `new()`, but takes the scratch space as words, which are always aligned. A message
+    /// that fits in `scratch_space` is built without any heap allocation, and one that outgrows
+    /// it continues in segments on the heap.
+    pub fn from_words(scratch_space: &'a mut [crate::Word]) -> ScratchSpaceHeapAllocator<'a> {
+        Self::new(crate::Word::words_to_bytes_mut(scratch_space))
+    }
+
     /// Sets the size of the second segment in words, where 1 word = 8 bytes.
     /// (The first segment is the scratch space passed to `ScratchSpaceHeapAllocator::new()`.
     pub fn second_segment_words(self, value: u32) -> ScratchSpaceHeapAllocator<'a> {
@@ -865,7 +2033,7 @@ impl<'a> ScratchSpaceHeapAllocator<'a> {
 #[cfg(feature = "alloc")]
 unsafe impl<'a> Allocator for ScratchSpaceHeapAllocator<'a> {
     fn allocate_segment(&mut self, minimum_size: u32) -> (*mut u8, u32) {
-        if (minimum_size as usize) < (self.scratch_space.len() / BYTES_PER_WORD)
+        if (minimum_size as usize) <= (self.scratch_space.len() / BYTES_PER_WORD)
             && !self.scratch_space_allocated
         {
             self.scratch_space_allocated = true;
@@ -878,6 +2046,16 @@ unsafe impl<'a> Allocator for ScratchSpaceHeapAllocator<'a> {
         }
     }
 
+    fn allocate_replacement_segment(&mut self, minimum_size: u32) -> Option<(*mut u8, u32)> {
+        if (minimum_size as usize) <= (self.scratch_space.len() / BYTES_PER_WORD)
+            && !self.scratch_space_allocated
+        {
+            Some(self.allocate_segment(minimum_size))
+        } else {
+            self.allocator.allocate_replacement_segment(minimum_size)
+        }
+    }
+
     unsafe fn deallocate_segment(&mut self, ptr: *mut u8, word_size: u32, words_used: u32) {
         let seg_ptr = self.scratch_space.as_mut_ptr();
         if ptr == seg_ptr {
@@ -899,7 +2077,10 @@ unsafe impl<'a> Allocator for ScratchSpaceHeapAllocator<'a> {
 }
 
 /// An Allocator whose first and only segment is a backed by a user-provided buffer.
-/// If the segment fills up, subsequent allocations trigger panics.
+/// If the segment fills up, builder operations that return a `Result`, such as
+/// `Builder::set_root()`, fail with `Overloaded`, and the others, such as `init_root()`, panic.
+/// If no part of a value fits, the message is left as it was. Otherwise the part that fit stays
+/// in the message, which is still valid.
 ///
 /// The main purpose of this struct is to be used in situations where heap allocation
 /// is not available.
@@ -979,6 +2160,86 @@ unsafe impl<'a> Allocator for SingleSegmentAllocator<'a> {
             self.segment_allocated = false;
         }
     }
+
+    fn try_allocate_segment(&mut self, minimum_size: u32) -> Option<(*mut u8, u32)> {
+        if (minimum_size as usize) > self.segment.len() / BYTES_PER_WORD || self.segment_allocated {
+            None
+        } else {
+            Some(self.allocate_segment(minimum_size))
+        }
+    }
+}
+
+/// The allocator of a builder opened over an existing serialized message by
+/// `Builder::from_flat_slice_mut()`. The message's segments are all full, and it has no others
+/// to give out, so an operation that needs to allocate fails with `Overloaded`, or panics if it
+/// does not return a `Result`, as it would with a full `SingleSegmentAllocator`.
+#[cfg(feature = "alloc")]
+pub struct InPlaceAllocator<'a> {
+    marker: core::marker::PhantomData<&'a mut [u8]>,
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<'a> Allocator for InPlaceAllocator<'a> {
+    fn allocate_segment(&mut self, minimum_size: u32) -> (*mut u8, u32) {
+        // Builders only allocate through try_allocate_segment().
+        panic!(
+            "Tried to allocate {minimum_size} words in a message opened in place, \
+                which has no free space."
+        )
+    }
+
+    fn try_allocate_segment(&mut self, _minimum_size: u32) -> Option<(*mut u8, u32)> {
+        None
+    }
+
+    unsafe fn deallocate_segment(&mut self, _ptr: *mut u8, _word_size: u32, _words_used: u32) {
+        // The segments belong to the caller's buffer, which must be left as it is.
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Builder<InPlaceAllocator<'a>> {
+    /// Opens the serialized message at the start of `buffer`, including its segment table, as
+    /// a builder that modifies it in place. Primitive fields can be set, and pointers cleared,
+    /// without touching any other bytes of `buffer`. There is no free space, so anything that
+    /// needs to allocate and returns a `Result`, such as `try_set_text()` or setting a struct
+    /// field, fails with `Overloaded`. The infallible operations that allocate, such as
+    /// initializing a struct or `set_text()`, panic with `Overloaded`, so they should not be used
+    /// on such a builder.
+    ///
+    /// The message comes from outside, and builders trust the pointers they follow, so before
+    /// opening it, this reads the whole message as a `Reader` with `options` would, and returns
+    /// that reader's error if any reachable pointer is invalid. It also fails if any two objects
+    /// overlap, or if any object is reachable by more than one pointer, since setting a field of
+    /// one could then change a pointer of the other. Each object is read once, so the traversal
+    /// limit must allow for the whole message.
+    ///
+    /// ALIGNMENT: If the "unaligned" feature is enabled, then there are no alignment requirements
+    /// on `buffer`. Otherwise, `buffer` must be 8-byte aligned.
+    pub fn from_flat_slice_mut(buffer: &'a mut [u8], options: ReaderOptions) -> Result<Self> {
+        let mut segment_ranges = Vec::new();
+        {
+            let reader = crate::serialize::read_message_from_flat_slice(&mut &*buffer, options)?;
+            crate::overlap::reject_overlap(reader.get_root()?)?;
+            let segments = reader.into_segments();
+            for id in 0..segments.len() as u32 {
+                let segment = segments.get_segment(id).expect("segment in table");
+                let start = segment.as_ptr() as usize - buffer.as_ptr() as usize;
+                segment_ranges.push((start, (segment.len() / BYTES_PER_WORD) as u32));
+            }
+        }
+
+        let mut message = Self::new(InPlaceAllocator {
+            marker: core::marker::PhantomData,
+        });
+        let base = buffer.as_mut_ptr();
+        for (start, words) in segment_ranges {
+            // The segment is full, so the allocator never has to zero, or free, any of it.
+            unsafe { message.arena.push_segment(base.add(start), words, words) };
+        }
+        Ok(message)
+    }
 }
 
 #[cfg(feature = "alloc")]
@@ -990,6 +2251,14 @@ where
         (*self).allocate_segment(minimum_size)
     }
 
+    fn try_allocate_segment(&mut self, minimum_size: u32) -> Option<(*mut u8, u32)> {
+        (*self).try_allocate_segment(minimum_size)
+    }
+
+    fn allocate_replacement_segment(&mut self, minimum_size: u32) -> Option<(*mut u8, u32)> {
+        (*self).allocate_replacement_segment(minimum_size)
+    }
+
     unsafe fn deallocate_segment(&mut self, ptr: *mut u8, word_size: u32, words_used: u32) {
         (*self).deallocate_segment(ptr, word_size, words_used)
     }
@@ -0,0 +1,200 @@
+//! Diagnostics for messages whose objects overlap one another, or that have words which no
+//! pointer reaches.
+//!
+//! A well-formed message built by this library never has overlapping objects, but nothing in the
+//! encoding prevents a hand-crafted message from pointing two pointers at the same words, or a
+//! pointer back at one of its ancestors. Readers tolerate such messages, subject to the traversal
+//! limit, but code that assumes each object has a single owner (e.g. in-place mutation of a
+//! received message) may not.
+
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+use crate::any_pointer;
+use crate::private::layout::ObjectVisitor;
+pub use crate::private::layout::PathStep;
+use crate::{Error, ErrorKind, Result};
+
+/// The sequence of steps leading from the root to a pointer. An empty path denotes the root
+/// pointer itself.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PointerPath(pub Vec<PathStep>);
+
+impl core::fmt::Display for PointerPath {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        fmt.write_str("root")?;
+        for step in &self.0 {
+            match step {
+                PathStep::Pointer(index) => write!(fmt, ".{index}")?,
+                PathStep::Element(index) => write!(fmt, "[{index}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A run of words claimed by two different pointers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Overlap {
+    pub segment_id: u32,
+    pub word_offset: u32,
+    pub word_count: u32,
+
+    /// The pointer to the object that starts first, or that was reached first if both start at
+    /// the same word.
+    pub first: PointerPath,
+
+    /// The pointer to the other object.
+    pub second: PointerPath,
+}
+
+/// A run of words that is not reachable from the root.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gap {
+    pub segment_id: u32,
+    pub word_offset: u32,
+    pub word_count: u32,
+}
+
+/// The result of `check_no_overlap()`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OverlapReport {
+    /// Overlapping objects, ordered by segment and word offset.
+    pub overlaps: Vec<Overlap>,
+
+    /// Unreachable words, ordered by segment and word offset.
+    pub gaps: Vec<Gap>,
+}
+
+impl OverlapReport {
+    /// Returns true if no objects overlap and every word is reachable.
+    pub fn is_clean(&self) -> bool {
+        self.overlaps.is_empty() && self.gaps.is_empty()
+    }
+}
+
+struct Occupied {
+    segment_id: u32,
+    start: u32,
+    end: u32,
+    path: PointerPath,
+}
+
+#[derive(Default)]
+struct Collector {
+    segments: Vec<u32>,
+    path: Vec<PathStep>,
+    ranges: Vec<Occupied>,
+    visited: BTreeSet<(u32, u32)>,
+}
+
+impl ObjectVisitor for Collector {
+    fn segment(&mut self, _segment_id: u32, word_count: u32) {
+        self.segments.push(word_count);
+    }
+
+    fn occupied(&mut self, segment_id: u32, word_offset: u32, word_count: u32) -> bool {
+        if word_count == 0 {
+            return true;
+        }
+        self.ranges.push(Occupied {
+            segment_id,
+            start: word_offset,
+            end: word_offset + word_count,
+            path: PointerPath(self.path.clone()),
+        });
+        // An object that has already been walked is not walked again, so that cycles terminate
+        // and shared subtrees are reported only once.
+        self.visited.insert((segment_id, word_offset))
+    }
+
+    fn enter(&mut self, step: PathStep) {
+        self.path.push(step);
+    }
+
+    fn leave(&mut self) {
+        self.path.pop();
+    }
+}
+
+impl Collector {
+    fn into_report(mut self) -> OverlapReport {
+        self.ranges
+            .sort_by_key(|range| (range.segment_id, range.start, range.end));
+        let mut report = OverlapReport::default();
+        let mut ranges = self.ranges.iter().peekable();
+        for (segment_id, &segment_len) in self.segments.iter().enumerate() {
+            let segment_id = segment_id as u32;
+            let mut cursor = 0;
+            // The range that reaches furthest so far in this segment.
+            let mut furthest: Option<&Occupied> = None;
+            while let Some(range) = ranges.next_if(|range| range.segment_id == segment_id) {
+                if range.start > cursor {
+                    report.gaps.push(Gap {
+                        segment_id,
+                        word_offset: cursor,
+                        word_count: range.start - cursor,
+                    });
+                }
+                if let Some(previous) = furthest.filter(|previous| range.start < previous.end) {
+                    report.overlaps.push(Overlap {
+                        segment_id,
+                        word_offset: range.start,
+                        word_count: range.end.min(previous.end) - range.start,
+                        first: previous.path.clone(),
+                        second: range.path.clone(),
+                    });
+                }
+                if range.end > cursor {
+                    cursor = range.end;
+                    furthest = Some(range);
+                }
+            }
+            if segment_len > cursor {
+                report.gaps.push(Gap {
+                    segment_id,
+                    word_offset: cursor,
+                    word_count: segment_len - cursor,
+                });
+            }
+        }
+        report
+    }
+}
+
+/// Walks the message from `root`, recording the words occupied by each object, and reports any
+/// objects that overlap and any words that are not reachable.
+///
+/// Objects are compared by location, so a pointer back at one of its ancestors shows up as an
+/// overlap rather than sending the walk around in circles. Each object is walked at most once.
+/// Far pointer landing pads and the root pointer itself count as occupied, so for the root of a
+/// well-formed message the report is clean. For any other pointer, the words outside its subtree
+/// are reported as gaps.
+///
+/// The walk counts against the message's traversal and nesting limits, like any other read.
+pub fn check_no_overlap(root: any_pointer::Reader<'_>) -> Result<OverlapReport> {
+    let mut collector = Collector::default();
+    root.reader.walk_objects(&mut collector)?;
+    Ok(collector.into_report())
+}
+
+/// Fails if any two objects reachable from `root` overlap, or if one of them is reachable by more
+/// than one pointer. Builders follow the pointers of their message without checking them, so this
+/// must pass before a builder is opened over bytes from outside: otherwise setting a primitive
+/// field whose data overlaps a pointer could point that pointer anywhere.
+///
+/// Like `check_no_overlap()`, this validates every reachable pointer as a reader would, against
+/// the message's traversal and nesting limits.
+pub(crate) fn reject_overlap(root: any_pointer::Reader<'_>) -> Result<()> {
+    let report = check_no_overlap(root)?;
+    let Some(overlap) = report.overlaps.first() else {
+        return Ok(());
+    };
+    let mut error = Error::from_kind(ErrorKind::Failed);
+    write!(
+        error,
+        "objects at {} and {} overlap at word {} of segment {}",
+        overlap.first, overlap.second, overlap.word_offset, overlap.segment_id
+    );
+    Err(error)
+}
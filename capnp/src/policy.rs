@@ -0,0 +1,297 @@
+//! Validation of messages against structural policies, such as a limit on the length of lists,
+//! for enforcing rules on untrusted input before handing it to the rest of a program.
+//!
+//! The encoding alone cannot tell text from data, so text is only checked where the type of the
+//! message is given by [`Validator::validate_as()`].
+
+use alloc::vec::Vec;
+
+use crate::any_pointer;
+use crate::introspect::{Type, TypeVariant};
+use crate::overlap::{PathStep, PointerPath};
+use crate::private::layout::{ElementSize, ObjectVisitor};
+use crate::schema::StructSchema;
+use crate::schema_capnp::field;
+
+/// The rules enforced by a [`Validator`]. The default policy allows everything.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Policy {
+    /// The largest number of elements allowed in a list, including lists of bytes.
+    pub max_list_elements: Option<u32>,
+
+    /// If true, text must be valid UTF-8. Only text fields of a known type are checked; see
+    /// [`Validator::validate_as()`].
+    pub require_utf8_text: bool,
+
+    /// The largest number of pointers that may be followed from the root to reach an object,
+    /// counting the root pointer, in the same sense as `ReaderOptions::nesting_limit`. Objects
+    /// nested more deeply are reported without being walked.
+    pub max_nesting: Option<u32>,
+
+    /// If false, capability pointers are not allowed.
+    pub allow_capabilities: bool,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            max_list_elements: None,
+            require_utf8_text: false,
+            max_nesting: None,
+            allow_capabilities: true,
+        }
+    }
+}
+
+impl Policy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_list_elements(&mut self, value: Option<u32>) -> &mut Self {
+        self.max_list_elements = value;
+        self
+    }
+
+    pub fn require_utf8_text(&mut self, value: bool) -> &mut Self {
+        self.require_utf8_text = value;
+        self
+    }
+
+    pub fn max_nesting(&mut self, value: Option<u32>) -> &mut Self {
+        self.max_nesting = value;
+        self
+    }
+
+    pub fn allow_capabilities(&mut self, value: bool) -> &mut Self {
+        self.allow_capabilities = value;
+        self
+    }
+}
+
+/// What is wrong at the location of a [`Violation`], along with the measured value.
+#[derive(Clone, Debug)]
+pub enum ViolationKind {
+    /// A list has more elements than `Policy::max_list_elements`.
+    ListTooLong { elements: u32 },
+
+    /// Text is not valid UTF-8. Its first `valid_up_to` bytes are.
+    TextNotUtf8 { valid_up_to: usize },
+
+    /// An object is nested more deeply than `Policy::max_nesting`.
+    TooDeeplyNested { depth: u32 },
+
+    /// A capability pointer was found, but `Policy::allow_capabilities` is false.
+    Capability { index: u32 },
+
+    /// The message could not be walked any further. Nothing beyond this point was validated.
+    Malformed(crate::Error),
+}
+
+/// A breach of a [`Policy`].
+#[derive(Clone, Debug)]
+pub struct Violation {
+    /// The pointer to the offending object, or the capability pointer.
+    pub path: PointerPath,
+    pub kind: ViolationKind,
+}
+
+impl core::fmt::Display for Violation {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{}: ", self.path)?;
+        match &self.kind {
+            ViolationKind::ListTooLong { elements } => write!(fmt, "list has {elements} elements"),
+            ViolationKind::TextNotUtf8 { valid_up_to } => {
+                write!(fmt, "text is not UTF-8 after byte {valid_up_to}")
+            }
+            ViolationKind::TooDeeplyNested { depth } => {
+                write!(fmt, "object is nested {depth} deep")
+            }
+            ViolationKind::Capability { index } => write!(fmt, "capability {index}"),
+            ViolationKind::Malformed(error) => write!(fmt, "{error}"),
+        }
+    }
+}
+
+/// Checks messages against a [`Policy`].
+#[derive(Clone, Debug)]
+pub struct Validator {
+    policy: Policy,
+}
+
+impl Validator {
+    pub fn new(policy: Policy) -> Self {
+        Self { policy }
+    }
+
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    /// Walks the message from `root` and returns every violation of the policy, in the order
+    /// they are encountered.
+    ///
+    /// The walk counts against the message's traversal and nesting limits, like any other read.
+    /// Shared objects are walked once for each pointer to them, as a reader would. Without a
+    /// type for `root`, no text is checked against `Policy::require_utf8_text`.
+    pub fn validate(&self, root: any_pointer::Reader<'_>) -> Result<(), Vec<Violation>> {
+        self.validate_typed(root, None)
+    }
+
+    /// Like `validate()`, but with `root` known to be of type `ty`, such as
+    /// `T::introspect()` for a generated `T::Owned`. Text is found through the schema, so only
+    /// the text fields and text list elements of the type are checked against
+    /// `Policy::require_utf8_text`. A pointer shared by union members of different types, and
+    /// anything under an `AnyPointer`, is not checked.
+    pub fn validate_as(
+        &self,
+        root: any_pointer::Reader<'_>,
+        ty: Type,
+    ) -> Result<(), Vec<Violation>> {
+        self.validate_typed(root, Some(ty))
+    }
+
+    fn validate_typed(
+        &self,
+        root: any_pointer::Reader<'_>,
+        ty: Option<Type>,
+    ) -> Result<(), Vec<Violation>> {
+        let mut checker = Checker {
+            policy: &self.policy,
+            path: Vec::new(),
+            types: alloc::vec![ty],
+            depth: 1,
+            violations: Vec::new(),
+        };
+        if let Err(error) = root.reader.walk_objects(&mut checker) {
+            checker.report(ViolationKind::Malformed(error));
+        }
+        if checker.violations.is_empty() {
+            Ok(())
+        } else {
+            Err(checker.violations)
+        }
+    }
+}
+
+struct Checker<'a> {
+    policy: &'a Policy,
+    path: Vec<PathStep>,
+
+    /// The type of the object at the end of each prefix of `path`, where it is known.
+    types: Vec<Option<Type>>,
+
+    /// The number of pointers followed to reach the current position, counting the root pointer.
+    depth: u32,
+    violations: Vec<Violation>,
+}
+
+impl Checker<'_> {
+    fn report(&mut self, kind: ViolationKind) {
+        self.violations.push(Violation {
+            path: PointerPath(self.path.clone()),
+            kind,
+        });
+    }
+
+    /// The type of the object reached by taking `step` from the current one.
+    fn step_type(&self, step: PathStep) -> Option<Type> {
+        let current = (*self.types.last()?)?;
+        match (current.which(), step) {
+            (TypeVariant::Struct(schema), PathStep::Pointer(index)) => {
+                pointer_field_type(schema.into(), index)
+            }
+            (TypeVariant::List(element), PathStep::Pointer(_)) if element.is_pointer_type() => {
+                Some(element)
+            }
+            (TypeVariant::List(element), PathStep::Element(_)) => Some(element),
+            _ => None,
+        }
+    }
+}
+
+/// The type of the field of `schema`, or of one of its groups, that is stored in the pointer at
+/// `index`, or None if there is no such field, or union members of different types share it.
+fn pointer_field_type(schema: StructSchema, index: u32) -> Option<Type> {
+    let mut found = None;
+    for field in schema.get_fields().ok()? {
+        let ty = field.get_type();
+        let candidate = match field.get_proto().which().ok()? {
+            field::Slot(slot) if ty.is_pointer_type() && slot.get_offset() == index => Some(ty),
+            field::Slot(_) => None,
+            field::Group(_) => match ty.which() {
+                TypeVariant::Struct(group) => pointer_field_type(group.into(), index),
+                _ => None,
+            },
+        };
+        match (found, candidate) {
+            (Some(found), Some(candidate)) if found != candidate => return None,
+            (None, Some(_)) => found = candidate,
+            _ => {}
+        }
+    }
+    found
+}
+
+impl ObjectVisitor for Checker<'_> {
+    fn segment(&mut self, _segment_id: u32, _word_count: u32) {}
+
+    fn occupied(&mut self, _segment_id: u32, _word_offset: u32, _word_count: u32) -> bool {
+        let Some(max) = self.policy.max_nesting else {
+            return true;
+        };
+        if self.depth <= max {
+            return true;
+        }
+        // A far pointer's landing pad is reported at the same path as its object.
+        let reported = self.violations.last().is_some_and(|last| {
+            matches!(last.kind, ViolationKind::TooDeeplyNested { .. }) && last.path.0 == self.path
+        });
+        if !reported {
+            self.report(ViolationKind::TooDeeplyNested { depth: self.depth });
+        }
+        false
+    }
+
+    fn enter(&mut self, step: PathStep) {
+        if let PathStep::Pointer(_) = step {
+            self.depth += 1;
+        }
+        self.types.push(self.step_type(step));
+        self.path.push(step);
+    }
+
+    fn leave(&mut self) {
+        self.types.pop();
+        if let Some(PathStep::Pointer(_)) = self.path.pop() {
+            self.depth -= 1;
+        }
+    }
+
+    fn list(&mut self, _element_size: ElementSize, element_count: u32, bytes: Option<&[u8]>) {
+        if let Some(max) = self.policy.max_list_elements {
+            if element_count > max {
+                self.report(ViolationKind::ListTooLong {
+                    elements: element_count,
+                });
+            }
+        }
+        let is_text = matches!(
+            self.types.last(),
+            Some(Some(ty)) if matches!(ty.which(), TypeVariant::Text)
+        );
+        if let Some([text @ .., 0]) = bytes.filter(|_| is_text && self.policy.require_utf8_text) {
+            if let Err(error) = core::str::from_utf8(text) {
+                self.report(ViolationKind::TextNotUtf8 {
+                    valid_up_to: error.valid_up_to(),
+                });
+            }
+        }
+    }
+
+    fn capability(&mut self, index: u32) {
+        if !self.policy.allow_capabilities {
+            self.report(ViolationKind::Capability { index });
+        }
+    }
+}
@@ -200,11 +200,24 @@ where
         }
     }
 
+    /// Sets the `T` at position `index`. Panics if `index` is greater than or
+    /// equal to `len()`.
     pub fn set(&mut self, index: u32, value: T) {
         assert!(index < self.len());
         PrimitiveElement::set(&self.builder, index, value);
     }
 
+    /// Sets the `T` at position `index`. Returns `None` if `index`
+    /// is greater than or equal to `len()`.
+    pub fn try_set(&mut self, index: u32, value: T) -> Option<()> {
+        if index < self.len() {
+            PrimitiveElement::set(&self.builder, index, value);
+            Some(())
+        } else {
+            None
+        }
+    }
+
     const _CHECK_SLICE: () = check_slice_supported::<T>();
 
     /// Attempts to return a view of the list as a native Rust slice.
@@ -245,6 +258,12 @@ impl<'a, T: PrimitiveElement> FromPointerBuilder<'a> for Builder<'a, T> {
             marker: marker::PhantomData,
         }
     }
+    fn try_init_pointer(builder: PointerBuilder<'a>, size: u32) -> Result<Builder<'a, T>> {
+        Ok(Builder {
+            builder: builder.try_init_list(T::element_size(), size)?,
+            marker: marker::PhantomData,
+        })
+    }
     fn get_from_pointer(
         builder: PointerBuilder<'a>,
         default: Option<&'a [crate::Word]>,
@@ -280,6 +299,58 @@ impl<'a, T: PrimitiveElement> Builder<'a, T> {
             builder: self.builder.reborrow(),
         }
     }
+
+    /// Returns a cursor that writes the elements in order, starting at the first. This is
+    /// cheaper than calling `set()` for each index when filling in a long list, as the cursor
+    /// steps from one element to the next instead of working out where each index is.
+    pub fn cursor(&mut self) -> ListCursor<'_, T> {
+        ListCursor {
+            marker: marker::PhantomData,
+            step: u64::from(self.builder.get_step_size_in_bits()),
+            builder: self.builder.reborrow(),
+            next: 0,
+            bit_offset: 0,
+        }
+    }
+}
+
+/// Writes the elements of a primitive list builder in order. Returned by `Builder::cursor()`.
+pub struct ListCursor<'a, T>
+where
+    T: PrimitiveElement,
+{
+    marker: marker::PhantomData<T>,
+    builder: ListBuilder<'a>,
+    next: u32,
+    /// Where the element at `next` starts, in bits from the start of the list.
+    bit_offset: u64,
+    step: u64,
+}
+
+impl<T: PrimitiveElement> ListCursor<'_, T> {
+    /// Sets the next element to `value`. Returns `None`, without writing anything, if every
+    /// element has already been written.
+    #[inline]
+    pub fn write_next(&mut self, value: T) -> Option<()> {
+        if self.next < self.builder.len() {
+            PrimitiveElement::set_at_bit_offset(&self.builder, self.bit_offset, value);
+            self.next += 1;
+            self.bit_offset += self.step;
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// The number of elements that have not been written yet.
+    pub fn remaining(&self) -> u32 {
+        self.builder.len() - self.next
+    }
+
+    /// The index of the element that `write_next()` will set.
+    pub fn position(&self) -> u32 {
+        self.next
+    }
 }
 
 impl<'a, T> crate::traits::SetPointerBuilder for Reader<'a, T>
@@ -318,6 +389,16 @@ impl<'a, T: PrimitiveElement + crate::introspect::Introspect> From<Reader<'a, T>
     }
 }
 
+impl<'a, T: PrimitiveElement + crate::introspect::Introspect> core::fmt::Debug for Reader<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::stringify::print_list_summary(
+            "primitive_list::Reader",
+            crate::dynamic_list::Reader::new(self.reader, T::introspect()),
+            f,
+        )
+    }
+}
+
 impl<'a, T: PrimitiveElement + crate::introspect::Introspect> From<Builder<'a, T>>
     for crate::dynamic_value::Builder<'a>
 {
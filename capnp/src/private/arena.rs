@@ -25,7 +25,10 @@ use smallvec::SmallVec;
 use crate::message;
 use crate::message::Allocator;
 use crate::message::ReaderSegments;
+#[cfg(feature = "alloc")]
+use crate::private::read_limiter::CapLimiter;
 use crate::private::read_limiter::ReadLimiter;
+use crate::private::scrub::scrub_bytes;
 use crate::private::units::*;
 use crate::OutputSegments;
 use crate::{Error, ErrorKind, Result};
@@ -36,6 +39,12 @@ pub trait ReaderArena {
     // return pointer to start of segment, and number of words in that segment
     fn get_segment(&self, id: u32) -> Result<(*const u8, u32)>;
 
+    /// Like `get_segment()`, for a segment reached through a far pointer, which may have to be
+    /// fetched first. See `ReaderSegments::get_segment_or_fetch()`.
+    fn get_far_segment(&self, id: u32) -> Result<(*const u8, u32)> {
+        self.get_segment(id)
+    }
+
     unsafe fn check_offset(
         &self,
         segment_id: u32,
@@ -47,6 +56,19 @@ pub trait ReaderArena {
 
     fn nesting_limit(&self) -> i32;
 
+    /// The initial value of `ReaderOptions::list_nesting_limit`, with `i32::MAX` for no limit.
+    fn list_nesting_limit(&self) -> i32 {
+        i32::MAX
+    }
+
+    /// Called each time a capability pointer with the given cap table index is dereferenced.
+    #[cfg(feature = "alloc")]
+    fn can_use_cap(&self, index: u32) -> Result<()>;
+
+    /// Called each time a reader of this arena is imbued with a cap table of `len` entries.
+    #[cfg(feature = "alloc")]
+    fn imbue_cap_table(&self, len: usize);
+
     // TODO(apibump): Consider putting extract_cap(), inject_cap(), drop_cap() here
     //   and on message::Reader. Then we could get rid of Imbue and ImbueMut, and
     //   layout::StructReader, layout::ListReader, etc. could drop their `cap_table` fields.
@@ -66,7 +88,11 @@ impl core::fmt::Debug for dyn ReaderArena + '_ {
 pub struct ReaderArenaImpl<S> {
     segments: S,
     read_limiter: ReadLimiter,
-    nesting_limit: i32,
+    #[cfg(feature = "alloc")]
+    shared_budget: Option<alloc::sync::Arc<message::SharedReadBudget>>,
+    #[cfg(feature = "alloc")]
+    cap_limiter: CapLimiter,
+    options: message::ReaderOptions,
 }
 
 #[cfg(feature = "sync_reader")]
@@ -82,12 +108,95 @@ where
     S: ReaderSegments,
 {
     pub fn new(segments: S, options: message::ReaderOptions) -> Self {
-        let limiter = ReadLimiter::new(options.traversal_limit_in_words);
+        let limiter = ReadLimiter::new(
+            options.traversal_limit_in_words,
+            options.traversal_limit_in_ops,
+        );
         Self {
             segments,
             read_limiter: limiter,
-            nesting_limit: options.nesting_limit,
+            #[cfg(feature = "alloc")]
+            shared_budget: None,
+            #[cfg(feature = "alloc")]
+            cap_limiter: CapLimiter::new(options.max_caps),
+            options,
+        }
+    }
+
+    /// The options that the arena was created with.
+    pub fn options(&self) -> &message::ReaderOptions {
+        &self.options
+    }
+
+    /// Charges all further reads to `budget`, instead of to the limits in the options.
+    #[cfg(feature = "alloc")]
+    pub fn set_shared_budget(&mut self, budget: alloc::sync::Arc<message::SharedReadBudget>) {
+        self.shared_budget = Some(budget);
+    }
+
+    #[inline]
+    fn can_read(&self, amount: usize) -> Result<()> {
+        #[cfg(feature = "alloc")]
+        if let Some(budget) = &self.shared_budget {
+            return budget.limiter().can_read(amount);
+        }
+        self.read_limiter.can_read(amount)
+    }
+
+    #[inline]
+    fn can_dereference(&self) -> Result<()> {
+        #[cfg(feature = "alloc")]
+        if let Some(budget) = &self.shared_budget {
+            return budget.limiter().can_dereference();
+        }
+        // Without a limit there is nothing to count.
+        if self.options.traversal_limit_in_ops.is_none() {
+            return Ok(());
         }
+        self.read_limiter.can_dereference()
+    }
+
+    /// The number of words that may still be read before the traversal limit is reached, or
+    /// `None` if there is no limit.
+    pub fn remaining_traversal_limit_in_words(&self) -> Option<usize> {
+        #[cfg(feature = "alloc")]
+        if let Some(budget) = &self.shared_budget {
+            return Some(budget.remaining().words as usize);
+        }
+        self.read_limiter.remaining_words()
+    }
+
+    /// The number of pointer dereferences still allowed, or `None` if there is no limit.
+    pub fn remaining_traversal_limit_in_ops(&self) -> Option<u64> {
+        #[cfg(feature = "alloc")]
+        if let Some(budget) = &self.shared_budget {
+            return Some(budget.remaining().max_ops);
+        }
+        self.read_limiter.remaining_ops()
+    }
+
+    /// Restores the traversal limits of the options, as if nothing had been read.
+    pub fn reset_traversal_limits(&self) {
+        self.read_limiter.reset(
+            self.options.traversal_limit_in_words,
+            self.options.traversal_limit_in_ops,
+        );
+    }
+
+    /// Adds to what is left of the traversal limits of the options.
+    pub fn refill_traversal_limits(&self, limits: message::TraversalLimits) {
+        self.read_limiter.refill(limits.words, limits.max_ops);
+    }
+
+    /// The number of distinct capabilities dereferenced so far. Always zero if there is no limit
+    /// on capabilities.
+    #[cfg(feature = "alloc")]
+    pub fn cap_count_seen(&self) -> u32 {
+        self.cap_limiter.count()
+    }
+
+    pub fn segments(&self) -> &S {
+        &self.segments
     }
 
     pub fn into_segments(self) -> S {
@@ -95,23 +204,41 @@ where
     }
 }
 
+/// Returns the start and length in words of `seg`, checking that it is aligned.
+fn segment_bounds(seg: &[u8]) -> Result<(*const u8, u32)> {
+    #[cfg(not(feature = "unaligned"))]
+    {
+        if seg.as_ptr() as usize % BYTES_PER_WORD != 0 {
+            return Err(Error::from_kind(ErrorKind::UnalignedSegment));
+        }
+    }
+
+    Ok((seg.as_ptr(), (seg.len() / BYTES_PER_WORD) as u32))
+}
+
 impl<S> ReaderArena for ReaderArenaImpl<S>
 where
     S: ReaderSegments,
 {
     fn get_segment(&self, id: u32) -> Result<(*const u8, u32)> {
         match self.segments.get_segment(id) {
-            Some(seg) => {
-                #[cfg(not(feature = "unaligned"))]
-                {
-                    if seg.as_ptr() as usize % BYTES_PER_WORD != 0 {
-                        return Err(Error::from_kind(ErrorKind::UnalignedSegment));
-                    }
-                }
+            Some(seg) => segment_bounds(seg),
+            None => Err(Error::from_kind(ErrorKind::InvalidSegmentId(id))),
+        }
+    }
 
-                Ok((seg.as_ptr(), (seg.len() / BYTES_PER_WORD) as u32))
+    fn get_far_segment(&self, id: u32) -> Result<(*const u8, u32)> {
+        if let Some(seg) = self.segments.get_segment(id) {
+            return segment_bounds(seg);
+        }
+        match self.segments.get_segment_or_fetch(id) {
+            Ok(seg) => segment_bounds(seg),
+            Err(e) if matches!(e.kind, ErrorKind::InvalidSegmentId(_)) => Err(e),
+            Err(e) => {
+                let mut error = Error::from_kind(ErrorKind::Disconnected);
+                write!(error, "failed to fetch segment {id}: {e}");
+                Err(error)
             }
-            None => Err(Error::from_kind(ErrorKind::InvalidSegmentId(id))),
         }
     }
 
@@ -148,24 +275,145 @@ where
                 ErrorKind::MessageContainsOutOfBoundsPointer,
             ))
         } else {
-            self.read_limiter.can_read(size_in_words)
+            self.can_dereference()?;
+            self.can_read(size_in_words)
         }
     }
 
     fn amplified_read(&self, virtual_amount: u64) -> Result<()> {
-        self.read_limiter.can_read(virtual_amount as usize)
+        self.can_read(virtual_amount as usize)
     }
 
     fn nesting_limit(&self) -> i32 {
-        self.nesting_limit
+        self.options.nesting_limit
+    }
+
+    fn list_nesting_limit(&self) -> i32 {
+        self.options.list_nesting_limit.unwrap_or(i32::MAX)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn can_use_cap(&self, index: u32) -> Result<()> {
+        self.cap_limiter.can_use_cap(index)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn imbue_cap_table(&self, len: usize) {
+        self.cap_limiter.imbue_cap_table(len)
+    }
+}
+
+/// Wraps another arena, charging reads against a separate budget in addition to the wrapped
+/// arena's own limits. Used to bound the cost of a single operation, such as a copy, whose source
+/// may not have any limits of its own. Exceeding the budget is reported as `Overloaded`.
+pub struct LimitedReaderArena<'a> {
+    inner: &'a dyn ReaderArena,
+    limiter: ReadLimiter,
+    #[cfg(feature = "alloc")]
+    cap_limiter: CapLimiter,
+}
+
+impl<'a> LimitedReaderArena<'a> {
+    pub fn new(inner: &'a dyn ReaderArena, limits: message::TraversalLimits) -> Self {
+        Self {
+            inner,
+            limiter: ReadLimiter::new(
+                Some(usize::try_from(limits.words).unwrap_or(usize::MAX)),
+                Some(limits.max_ops),
+            ),
+            #[cfg(feature = "alloc")]
+            cap_limiter: CapLimiter::new(None),
+        }
+    }
+
+    /// Charges reads against the traversal limits and `max_caps` of `options`, where they are
+    /// set.
+    pub fn with_options(inner: &'a dyn ReaderArena, options: &message::ReaderOptions) -> Self {
+        Self {
+            inner,
+            limiter: ReadLimiter::new(
+                options.traversal_limit_in_words,
+                options.traversal_limit_in_ops,
+            ),
+            #[cfg(feature = "alloc")]
+            cap_limiter: CapLimiter::new(options.max_caps),
+        }
+    }
+
+    fn can_read(&self, amount: usize) -> Result<()> {
+        self.limiter
+            .can_read(amount)
+            .map_err(|_| Error::from_kind(ErrorKind::Overloaded))
+    }
+}
+
+impl ReaderArena for LimitedReaderArena<'_> {
+    fn get_segment(&self, id: u32) -> Result<(*const u8, u32)> {
+        self.inner.get_segment(id)
+    }
+
+    fn get_far_segment(&self, id: u32) -> Result<(*const u8, u32)> {
+        self.inner.get_far_segment(id)
+    }
+
+    unsafe fn check_offset(
+        &self,
+        segment_id: u32,
+        start: *const u8,
+        offset_in_words: i32,
+    ) -> Result<*const u8> {
+        self.inner.check_offset(segment_id, start, offset_in_words)
+    }
+
+    fn contains_interval(&self, id: u32, start: *const u8, size_in_words: usize) -> Result<()> {
+        self.inner.contains_interval(id, start, size_in_words)?;
+        self.limiter.can_dereference()?;
+        self.can_read(size_in_words)
+    }
+
+    fn amplified_read(&self, virtual_amount: u64) -> Result<()> {
+        self.inner.amplified_read(virtual_amount)?;
+        self.can_read(virtual_amount as usize)
+    }
+
+    fn nesting_limit(&self) -> i32 {
+        self.inner.nesting_limit()
+    }
+
+    fn list_nesting_limit(&self) -> i32 {
+        self.inner.list_nesting_limit()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn can_use_cap(&self, index: u32) -> Result<()> {
+        self.inner.can_use_cap(index)?;
+        self.cap_limiter.can_use_cap(index)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn imbue_cap_table(&self, len: usize) {
+        self.inner.imbue_cap_table(len);
+        self.cap_limiter.imbue_cap_table(len)
     }
 }
 
 pub trait BuilderArena: ReaderArena {
     fn allocate(&mut self, segment_id: u32, amount: WordCount32) -> Option<u32>;
-    fn allocate_anywhere(&mut self, amount: u32) -> (SegmentId, u32);
+
+    /// Allocates `amount` words in whichever segment has room for them, allocating a new segment
+    /// if none does. Fails with `Overloaded` if the allocator has no room for one.
+    fn allocate_anywhere(&mut self, amount: u32) -> Result<(SegmentId, u32)>;
     fn get_segment_mut(&mut self, id: u32) -> (*mut u8, u32);
 
+    /// Gives back the last `amount` words of an allocation that ends at word `end` of the
+    /// segment, provided nothing has been allocated after it. Returns false if the words could
+    /// not be given back.
+    fn reclaim(&mut self, segment_id: u32, end: u32, amount: WordCount32) -> bool;
+
+    /// Returns the number of words that segment `id` has room for, in use or not.
+    #[cfg(any(debug_assertions, feature = "paranoid-builder"))]
+    fn segment_capacity(&self, id: u32) -> u32;
+
     fn as_reader(&self) -> &dyn ReaderArena;
 }
 
@@ -201,6 +449,10 @@ impl BuilderSegmentArray {
         }
     }
 
+    fn is_empty(&self) -> bool {
+        self.segment.is_none()
+    }
+
     fn push(&mut self, segment: BuilderSegment) {
         if self.segment.is_some() {
             panic!("multiple segments are not supported in no-alloc mode")
@@ -267,9 +519,35 @@ where
         self.inner.allocate_segment(minimum_size)
     }
 
+    /// Like `allocate_segment()`, but through `Allocator::allocate_replacement_segment()`.
+    #[cfg(feature = "alloc")]
+    pub fn allocate_replacement_segment(&mut self, minimum_size: u32) -> Result<()> {
+        let Some((ptr, capacity)) = self
+            .inner
+            .allocator
+            .as_mut()
+            .expect("arena has an allocator")
+            .allocate_replacement_segment(minimum_size)
+        else {
+            return Err(no_room_for_segment(minimum_size));
+        };
+        self.inner.segments.push(BuilderSegment {
+            ptr,
+            capacity,
+            allocated: 0,
+        });
+        Ok(())
+    }
+
     pub fn get_segments_for_output(&self) -> OutputSegments {
         let reff = &self.inner;
-        if reff.segments.len() == 1 {
+        if reff.segments.is_empty() {
+            // Nothing has been allocated yet, not even the root pointer. Output a single
+            // segment holding a null root pointer so that the message is well-formed.
+            OutputSegments::SingleSegment([crate::Word::words_to_bytes(
+                &crate::message::NULL_MESSAGE_WORDS,
+            )])
+        } else if reff.segments.len() == 1 {
             let seg = &reff.segments[0];
 
             // The user must mutably borrow the `message::Builder` to be able to modify segment memory.
@@ -310,6 +588,91 @@ where
         self.len() == 0
     }
 
+    /// Zeroes the allocated words of every segment, so that the message has a null root.
+    /// The segments stay allocated.
+    pub fn scrub_segments(&mut self) {
+        for idx in 0..self.inner.segments.len() {
+            let seg = &self.inner.segments[idx];
+            unsafe { scrub_bytes(seg.ptr, seg.allocated as usize * BYTES_PER_WORD) };
+        }
+    }
+
+    /// Returns the number of words allocated across all segments.
+    pub fn size_in_words(&self) -> usize {
+        (0..self.inner.segments.len())
+            .map(|idx| self.inner.segments[idx].allocated as usize)
+            .sum()
+    }
+
+    /// Returns the number of segments allocated so far.
+    pub fn segment_count(&self) -> usize {
+        self.inner.segments.len()
+    }
+
+    /// Returns the capacity in words of each segment.
+    #[cfg(feature = "alloc")]
+    pub fn segment_capacities(&self) -> alloc::vec::Vec<u32> {
+        self.inner.segments.iter().map(|seg| seg.capacity).collect()
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn allocator(&self) -> &A {
+        self.inner.allocator.as_ref().unwrap()
+    }
+
+    /// Adds a segment of `capacity` words, of which the first `allocated` are in use, at the end
+    /// of the segment list.
+    ///
+    /// # Safety
+    /// `ptr` must point to `capacity` words that the allocator is able to deallocate, and the
+    /// words after the first `allocated` must be zero.
+    #[cfg(feature = "alloc")]
+    pub unsafe fn push_segment(&mut self, ptr: *mut u8, capacity: u32, allocated: u32) {
+        self.inner.segments.push(BuilderSegment {
+            ptr,
+            capacity,
+            allocated,
+        });
+    }
+
+    /// Deallocates every segment but the first, and zeroes the allocated words of the first,
+    /// leaving a message in which only the root pointer is allocated, and null.
+    pub fn clear(&mut self) {
+        if self.is_empty() {
+            return;
+        }
+        #[cfg(feature = "alloc")]
+        if let Some(a) = &mut self.inner.allocator {
+            for seg in self.inner.segments.drain(1..) {
+                unsafe { a.deallocate_segment(seg.ptr, seg.capacity, seg.allocated) };
+            }
+        }
+        let seg = &mut self.inner.segments[0];
+        unsafe { core::ptr::write_bytes(seg.ptr, 0, seg.allocated as usize * BYTES_PER_WORD) };
+        seg.allocated = 1;
+    }
+
+    /// Moves the allocator out of the arena, which must not allocate until it is given one again
+    /// by `restore_allocator()` or `replace_segments()`. The segments stay readable.
+    #[cfg(feature = "alloc")]
+    pub fn take_allocator(&mut self) -> Option<A> {
+        self.inner.allocator.take()
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn restore_allocator(&mut self, allocator: A) {
+        self.inner.allocator = Some(allocator);
+    }
+
+    /// Takes over the allocator and segments of `other`, and deallocates the current segments
+    /// with that allocator, which must be the one taken from this arena by `take_allocator()`.
+    #[cfg(feature = "alloc")]
+    pub fn replace_segments(&mut self, mut other: Self) {
+        self.inner.allocator = other.inner.allocator.take();
+        self.inner.deallocate_all();
+        self.inner.segments = core::mem::take(&mut other.inner.segments);
+    }
+
     /// Retrieves the underlying `Allocator`, deallocating all currently-allocated
     /// segments.
     pub fn into_allocator(mut self) -> A {
@@ -351,6 +714,24 @@ where
     fn nesting_limit(&self) -> i32 {
         0x7fffffff
     }
+
+    #[cfg(feature = "alloc")]
+    fn can_use_cap(&self, _index: u32) -> Result<()> {
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn imbue_cap_table(&self, _len: usize) {}
+}
+
+/// The error for an allocator that has no room for a new segment of `minimum_size` words.
+fn no_room_for_segment(minimum_size: u32) -> Error {
+    let mut error = Error::from_kind(ErrorKind::Overloaded);
+    write!(
+        error,
+        "the allocator has no room for a segment of {minimum_size} words"
+    );
+    error
 }
 
 impl<A> BuilderArenaImplInner<A>
@@ -360,9 +741,12 @@ where
     /// Allocates a new segment with capacity for at least `minimum_size` words.
     fn allocate_segment(&mut self, minimum_size: WordCount32) -> Result<()> {
         let seg = match &mut self.allocator {
-            Some(a) => a.allocate_segment(minimum_size),
+            Some(a) => a.try_allocate_segment(minimum_size),
             None => unreachable!(),
         };
+        let Some(seg) = seg else {
+            return Err(no_room_for_segment(minimum_size));
+        };
         self.segments.push(BuilderSegment {
             ptr: seg.0,
             capacity: seg.1,
@@ -382,23 +766,33 @@ where
         }
     }
 
-    fn allocate_anywhere(&mut self, amount: u32) -> (SegmentId, u32) {
+    fn reclaim(&mut self, segment_id: u32, end: u32, amount: WordCount32) -> bool {
+        let seg = &mut self.segments[segment_id as usize];
+        if seg.allocated != end || amount > end {
+            false
+        } else {
+            seg.allocated -= amount;
+            true
+        }
+    }
+
+    fn allocate_anywhere(&mut self, amount: u32) -> Result<(SegmentId, u32)> {
         // first try the existing segments, then try allocating a new segment.
         let allocated_len = self.segments.len() as u32;
         for segment_id in 0..allocated_len {
             if let Some(idx) = self.allocate(segment_id, amount) {
-                return (segment_id, idx);
+                return Ok((segment_id, idx));
             }
         }
 
         // Need to allocate a new segment.
 
-        self.allocate_segment(amount).expect("allocate new segment");
-        (
+        self.allocate_segment(amount)?;
+        Ok((
             allocated_len,
             self.allocate(allocated_len, amount)
                 .expect("use freshly-allocated segment"),
-        )
+        ))
     }
 
     fn deallocate_all(&mut self) {
@@ -433,7 +827,7 @@ where
         self.inner.allocate(segment_id, amount)
     }
 
-    fn allocate_anywhere(&mut self, amount: u32) -> (SegmentId, u32) {
+    fn allocate_anywhere(&mut self, amount: u32) -> Result<(SegmentId, u32)> {
         self.inner.allocate_anywhere(amount)
     }
 
@@ -441,6 +835,15 @@ where
         self.inner.get_segment_mut(id)
     }
 
+    fn reclaim(&mut self, segment_id: u32, end: u32, amount: WordCount32) -> bool {
+        self.inner.reclaim(segment_id, end, amount)
+    }
+
+    #[cfg(any(debug_assertions, feature = "paranoid-builder"))]
+    fn segment_capacity(&self, id: u32) -> u32 {
+        self.inner.segments[id as usize].capacity
+    }
+
     fn as_reader(&self) -> &dyn ReaderArena {
         self
     }
@@ -482,4 +885,12 @@ impl ReaderArena for NullArena {
     fn nesting_limit(&self) -> i32 {
         0x7fffffff
     }
+
+    #[cfg(feature = "alloc")]
+    fn can_use_cap(&self, _index: u32) -> Result<()> {
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn imbue_cap_table(&self, _len: usize) {}
 }
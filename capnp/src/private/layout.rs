@@ -21,12 +21,13 @@
 
 #[cfg(feature = "alloc")]
 use alloc::{boxed::Box, vec::Vec};
-use core::cell::Cell;
 use core::mem;
 use core::ptr;
+use core::slice;
 
 use crate::data;
-use crate::private::arena::{BuilderArena, NullArena, ReaderArena, SegmentId};
+use crate::fingerprint::Hasher;
+use crate::private::arena::{BuilderArena, LimitedReaderArena, NullArena, ReaderArena, SegmentId};
 #[cfg(feature = "alloc")]
 use crate::private::capability::ClientHook;
 use crate::private::mask::Mask;
@@ -36,6 +37,57 @@ use crate::private::zero;
 use crate::text;
 use crate::{Error, ErrorKind, MessageSize, Result};
 
+// Panics in this module and in the list and blob modules built on it fall into three classes.
+// Nothing that a message being read can contain should cause a panic; data that comes from a
+// reader is reported as an error instead.
+//
+// Caller contract violations. These stay panics, are documented on the public method, and have
+// a `try_` variant where the caller can't cheaply check the condition up front:
+//
+//   | site                                          | condition                     | try_ variant             |
+//   |-----------------------------------------------|-------------------------------|--------------------------|
+//   | `*_list::{Reader, Builder}::get()`            | index >= len                  | `try_get()`              |
+//   | `*_list::Builder::set()`                      | index >= len                  | `try_set()`              |
+//   | `struct_list::Builder::set_with_caveats()`    | index >= len                  | `try_set_with_caveats()` |
+//   | `struct_list::Builder::swap()`                | index >= len                  | `try_swap()`             |
+//   | `list_list::Builder::init()`                  | index >= len                  | `try_init()`             |
+//   | `text::Builder::push_ascii()`                 | not ascii, or no room         | `try_push_ascii()`       |
+//   | `text::Builder::push_str()`                   | no room                       | `try_push_str()`         |
+//   | `primitive_list::Reader::as_slice()`          | unsupported target or feature | (compile-time check)     |
+//   | `BlobReservation::finish()`                   | len > capacity                | (caller knows both)      |
+//   | `StructBuilder::write_data_prefix()`          | prefix > data section         | (caller knows both)      |
+//   | `WirePointer::set_list_size_and_count()`,     | count >= 2**29                | (caller's count)         |
+//   | `WirePointer::set_list_inline_composite()`    |                               |                          |
+//   | `CapTableBuilder::inject_cap()`, `drop_cap()` | message not imbued            | (caller's setup)         |
+//   | `copy_message()` on OTHER or FAR pointers     | "unchecked" input was invalid | (unsafe contract)        |
+//
+// Data-dependent. The length of a blob comes from the program's data rather than being fixed
+// at the call site, so the paths that already return `Result` fail with `BlobTooLong` instead
+// of panicking: `SetterInput` for text and data, `PointerBuilder::try_set_{text,data}()` and
+// `try_init_{text,data}()`, the dynamic setters, and `{text,data}_list::Builder::try_set()`.
+// The infallible setters (`set_text()`, `set_data()`, `init_text()`, `init_data()`,
+// `reserve_blob()`) panic rather than truncate the length to 32 bits.
+//
+// Out of room. The infallible operations that allocate (`init_struct()`, `init_list()`,
+// `init_struct_list()`, `init_{text,data}()` and `set_{text,data}()`) panic with `Overloaded`
+// when the allocator has no room, as it can with a `SingleSegmentAllocator`. Each has a `try_`
+// variant on `PointerBuilder`, which `message::Builder::try_init_root()` and the generated
+// `try_init_*` and `try_set_*` setters go through.
+//
+// Internal invariants. These can only fail if this module has a bug, because the value was
+// written by a builder here or was already checked by a reader here:
+//
+//   - `ElementSize::from()` and `WirePointerKind::from()`: the argument is masked to 3 and 2 bits.
+//   - `zero_object_helper()` on OTHER, FAR or non-struct inline composite: builder segments only
+//     hold what this module wrote, or what `Builder::thaw()` or `Builder::from_flat_slice_mut()`
+//     validated.
+//   - `transfer_pointer()` onto a non-null pointer, and the landing pad bounds in
+//     `transfer_pointer_split()`.
+//   - `init_list_pointer()` and `get_writable_list_pointer()` with `InlineComposite`, which
+//     callers route to the struct list functions instead.
+//   - `set_list_pointer()` with an unexpected step, as steps are derived from element sizes.
+//   - `PrimitiveElement::element_size()` for types other than the primitive ones.
+
 pub use self::ElementSize::{
     Bit, Byte, EightBytes, FourBytes, InlineComposite, Pointer, TwoBytes, Void,
 };
@@ -117,6 +169,93 @@ pub enum PointerType {
     Capability(u32),
 }
 
+/// The first way in which a message was found not to be
+/// [canonical](https://capnproto.org/encoding.html#canonicalization).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NonCanonical {
+    /// The message has more than one segment.
+    MultipleSegments,
+    /// A pointer is a far pointer, or of an unknown kind.
+    NonPositionalPointer,
+    /// A pointer is a capability pointer.
+    Capability,
+    /// An object does not immediately follow the previous one in depth-first pointer order.
+    OutOfOrder,
+    /// A zero-sized struct does not point to the word just past its pointer.
+    ZeroSizedStructOffset,
+    /// A struct's data section is not a whole number of words.
+    PartialWordStruct,
+    /// A struct's data or pointer section ends with zeroes that should have been truncated.
+    UntruncatedStruct,
+    /// The word count in an inline composite list's pointer does not match its tag.
+    ListWordCount,
+    /// A primitive list has non-zero bits in the padding after its last element.
+    NonZeroPadding,
+    /// The segment has words after the end of the content reachable from the root.
+    UnreachableWords,
+}
+
+impl NonCanonical {
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::MultipleSegments => "message has more than one segment",
+            Self::NonPositionalPointer => "found a far pointer or unknown pointer kind",
+            Self::Capability => "found a capability pointer",
+            Self::OutOfOrder => "objects are not in depth-first pointer order",
+            Self::ZeroSizedStructOffset => "zero-sized struct has a non-canonical offset",
+            Self::PartialWordStruct => "struct data section is not a whole number of words",
+            Self::UntruncatedStruct => "struct has trailing zero fields that were not truncated",
+            Self::ListWordCount => "inline composite list word count does not match its tag",
+            Self::NonZeroPadding => "list has non-zero padding bits",
+            Self::UnreachableWords => "segment has unreachable trailing words",
+        }
+    }
+}
+
+/// One step along the path from the root to a pointer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathStep {
+    /// The pointer at this index of a struct's pointer section, or of a list of pointers.
+    Pointer(u32),
+    /// The element at this index of a list of structs.
+    Element(u32),
+}
+
+/// Receives the words occupied by each object reachable from a pointer, as found by
+/// `PointerReader::walk_objects()`.
+pub(crate) trait ObjectVisitor {
+    /// Called once for each segment of the message, before the walk starts.
+    fn segment(&mut self, segment_id: u32, word_count: u32);
+
+    /// Called for each non-null pointer, including the starting one, before it is followed.
+    /// Returns whether to follow it.
+    fn pointer(&mut self, _reff: &WirePointer) -> bool {
+        true
+    }
+
+    /// Called for each run of words occupied by an object, far pointer landing pad, or the
+    /// starting pointer itself. Returns whether to descend into the object's pointers.
+    fn occupied(&mut self, segment_id: u32, word_offset: u32, word_count: u32) -> bool;
+
+    /// Called for each struct and list that `occupied()` returned true for, with the pointer to
+    /// it, after any far pointers have been followed, and the words it occupies, starting with
+    /// the tag of a list of structs.
+    fn object(&mut self, _reff: &WirePointer, _word_offset: u32, _words: &[u8]) {}
+
+    /// Called before following the pointer or visiting the list element `step`.
+    fn enter(&mut self, step: PathStep);
+
+    /// Called after the pointer or list element from the most recent unmatched `enter()` has
+    /// been walked.
+    fn leave(&mut self);
+
+    /// Called for each list, after `occupied()`. For a list of bytes, `bytes` holds its contents.
+    fn list(&mut self, _element_size: ElementSize, _element_count: u32, _bytes: Option<&[u8]>) {}
+
+    /// Called for each capability pointer, with its cap table index.
+    fn capability(&mut self, _index: u32) {}
+}
+
 impl WirePointerKind {
     fn from(val: u8) -> Self {
         match val {
@@ -124,7 +263,7 @@ impl WirePointerKind {
             1 => Self::List,
             2 => Self::Far,
             3 => Self::Other,
-            _ => panic!("illegal element size: {val}"),
+            _ => panic!("illegal pointer kind: {val}"),
         }
     }
 }
@@ -344,18 +483,42 @@ impl WirePointer {
     }
 }
 
+/// How `wire_helpers::copy_pointer()` and the functions it calls copy a value.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CopyMode {
+    /// Capabilities are moved from the source's cap table to the destination's.
+    Plain,
+    /// The copy is canonical, and fails on capabilities.
+    Canonical,
+    /// Capability pointers are copied as they are, for a copy into a message that keeps using
+    /// the source's cap table.
+    KeepCapIndices,
+}
+
+impl CopyMode {
+    fn new(canonicalize: bool) -> Self {
+        if canonicalize {
+            Self::Canonical
+        } else {
+            Self::Plain
+        }
+    }
+}
+
 mod wire_helpers {
     #[cfg(feature = "alloc")]
     use alloc::boxed::Box;
     use core::{ptr, slice};
 
     use crate::data;
+    use crate::fingerprint::Hasher;
     use crate::private::arena::*;
     #[cfg(feature = "alloc")]
     use crate::private::capability::ClientHook;
     use crate::private::layout::ElementSize::*;
+    use crate::private::layout::ObjectVisitor;
     use crate::private::layout::{data_bits_per_element, pointers_per_element};
-    use crate::private::layout::{CapTableBuilder, CapTableReader};
+    use crate::private::layout::{CapTableBuilder, CapTableReader, CopyMode};
     use crate::private::layout::{
         ElementSize, ListBuilder, ListReader, StructBuilder, StructReader, StructSize, WirePointer,
         WirePointerKind,
@@ -376,6 +539,49 @@ mod wire_helpers {
         (bytes + 7) / BYTES_PER_WORD as u32
     }
 
+    // Text is encoded as a list of bytes ending in a NUL terminator. The list pointer's element
+    // count includes the terminator, but the length of the text does not, so empty text is a
+    // one-element list holding just the NUL. A list with zero elements has no room for the
+    // terminator, and is rejected as malformed rather than read as empty text.
+
+    /// The element count of the byte list encoding text of `len` bytes.
+    #[inline]
+    pub fn text_element_count(len: ByteCount32) -> ElementCount32 {
+        len + 1
+    }
+
+    /// The largest element count that fits in a list pointer.
+    pub const MAX_LIST_ELEMENTS: ElementCount32 = (1 << 29) - 1;
+
+    /// The element count of the byte list holding `len` bytes, plus a NUL terminator if
+    /// `terminated`. Fails with `BlobTooLong` if that is more than a list can hold.
+    #[inline]
+    pub fn blob_element_count(len: usize, terminated: bool) -> Result<ElementCount32> {
+        match len.checked_add(usize::from(terminated)) {
+            Some(count) if count <= MAX_LIST_ELEMENTS as usize => Ok(count as ElementCount32),
+            _ => Err(Error::from_kind(ErrorKind::BlobTooLong(len))),
+        }
+    }
+
+    /// Like `blob_element_count()`, for sizes that the caller has promised will fit.
+    #[inline]
+    pub fn expect_blob_element_count(len: usize, terminated: bool) -> ElementCount32 {
+        match blob_element_count(len, terminated) {
+            Ok(count) => count,
+            Err(e) => panic!("{}", e.kind),
+        }
+    }
+
+    /// The length of the text encoded by a byte list of `count` elements starting at `ptr`, or
+    /// None if the list does not end in a NUL terminator.
+    #[inline]
+    pub unsafe fn text_len(ptr: *const u8, count: ElementCount32) -> Option<ByteCount32> {
+        match count.checked_sub(1) {
+            Some(len) if *ptr.add(len as usize) == 0 => Some(len),
+            _ => None,
+        }
+    }
+
     //# The maximum object size is 4GB - 1 byte. If measured in bits,
     //# this would overflow a 32-bit counter, so we need to accept
     //# BitCount64. However, 32 bits is enough for the returned
@@ -400,12 +606,36 @@ mod wire_helpers {
         size_in_words: usize,
         _kind: WirePointerKind,
     ) -> Result<()> {
-        arena.contains_interval(segment_id, start, size_in_words)
+        let result = arena.contains_interval(segment_id, start, size_in_words);
+        decode_trace! {
+            if let Err(e) = &result {
+                let check = match e.kind {
+                    ErrorKind::ReadLimitExceeded => crate::DecodeCheck::TraversalLimit,
+                    _ => crate::DecodeCheck::OutOfBounds,
+                };
+                crate::trace::emit(|| crate::DecodeEvent {
+                    segment_id: Some(segment_id),
+                    word_offset: crate::trace::word_offset(arena, segment_id, start),
+                    check,
+                });
+            }
+        }
+        result
     }
 
     #[inline]
     pub fn amplified_read(arena: &dyn ReaderArena, virtual_amount: u64) -> Result<()> {
-        arena.amplified_read(virtual_amount)
+        let result = arena.amplified_read(virtual_amount);
+        decode_trace! {
+            if result.is_err() {
+                crate::trace::emit(|| crate::DecodeEvent {
+                    segment_id: None,
+                    word_offset: None,
+                    check: crate::DecodeCheck::TraversalLimit,
+                });
+            }
+        }
+        result
     }
 
     #[inline]
@@ -415,17 +645,17 @@ mod wire_helpers {
         segment_id: u32,
         amount: WordCount32,
         kind: WirePointerKind,
-    ) -> (*mut u8, *mut WirePointer, u32) {
-        let is_null = (*reff).is_null();
-        if !is_null {
-            zero_object(arena, segment_id, reff)
-        }
-
+    ) -> Result<(*mut u8, *mut WirePointer, u32)> {
         if amount == 0 && kind == WirePointerKind::Struct {
+            if !(*reff).is_null() {
+                zero_object(arena, segment_id, reff)
+            }
             (*reff).set_kind_and_target_for_empty_struct();
-            return (reff as *mut _, reff, segment_id);
+            return Ok((reff as *mut _, reff, segment_id));
         }
 
+        // The space is found before the old object is zeroed, so that if there is none, the
+        // pointer is left as it was.
         match arena.allocate(segment_id, amount) {
             None => {
                 //# Need to allocate in a different segment. We'll need to
@@ -433,14 +663,17 @@ mod wire_helpers {
                 //# the landing pad for a far pointer.
 
                 let amount_plus_ref = amount + POINTER_SIZE_IN_WORDS as u32;
-                let (segment_id, word_idx) = arena.allocate_anywhere(amount_plus_ref);
-                let (seg_start, _seg_len) = arena.get_segment_mut(segment_id);
+                let (far_segment_id, word_idx) = arena.allocate_anywhere(amount_plus_ref)?;
+                if !(*reff).is_null() {
+                    zero_object(arena, segment_id, reff)
+                }
+                let (seg_start, _seg_len) = arena.get_segment_mut(far_segment_id);
                 let ptr = seg_start.offset(word_idx as isize * BYTES_PER_WORD as isize);
 
                 //# Set up the original pointer to be a far pointer to
                 //# the new segment.
                 (*reff).set_far(false, word_idx);
-                (*reff).set_far_segment_id(segment_id);
+                (*reff).set_far_segment_id(far_segment_id);
 
                 //# Initialize the landing pad to indicate that the
                 //# data immediately follows the pad.
@@ -448,13 +681,16 @@ mod wire_helpers {
 
                 let ptr1 = ptr.add(BYTES_PER_WORD);
                 (*reff).set_kind_and_target(kind, ptr1);
-                (ptr1, reff, segment_id)
+                Ok((ptr1, reff, far_segment_id))
             }
             Some(idx) => {
+                if !(*reff).is_null() {
+                    zero_object(arena, segment_id, reff)
+                }
                 let (seg_start, _seg_len) = arena.get_segment_mut(segment_id);
                 let ptr = (seg_start).offset(idx as isize * BYTES_PER_WORD as isize);
                 (*reff).set_kind_and_target(kind, ptr);
-                (ptr, reff, segment_id)
+                Ok((ptr, reff, segment_id))
             }
         }
     }
@@ -510,8 +746,15 @@ mod wire_helpers {
     ) -> Result<(*const u8, *const WirePointer, u32)> {
         if (*reff).kind() == WirePointerKind::Far {
             let far_segment_id = (*reff).far_segment_id();
+            decode_trace! {
+                crate::trace::emit(|| crate::DecodeEvent {
+                    segment_id: Some(far_segment_id),
+                    word_offset: Some((*reff).far_position_in_segment()),
+                    check: crate::DecodeCheck::FarPointerFollowed,
+                });
+            }
 
-            let (seg_start, _seg_len) = arena.get_segment(far_segment_id)?;
+            let (seg_start, _seg_len) = arena.get_far_segment(far_segment_id)?;
             let ptr = seg_start
                 .offset((*reff).far_position_in_segment() as isize * BYTES_PER_WORD as isize);
 
@@ -532,7 +775,14 @@ mod wire_helpers {
 
                 let tag = pad.offset(1);
                 let double_far_segment_id = (*pad).far_segment_id();
-                let (segment_start, _segment_len) = arena.get_segment(double_far_segment_id)?;
+                decode_trace! {
+                    crate::trace::emit(|| crate::DecodeEvent {
+                        segment_id: Some(double_far_segment_id),
+                        word_offset: Some((*pad).far_position_in_segment()),
+                        check: crate::DecodeCheck::FarPointerFollowed,
+                    });
+                }
+                let (segment_start, _segment_len) = arena.get_far_segment(double_far_segment_id)?;
                 let ptr = segment_start
                     .offset((*pad).far_position_in_segment() as isize * BYTES_PER_WORD as isize);
                 Ok((ptr, tag, double_far_segment_id))
@@ -686,10 +936,22 @@ mod wire_helpers {
     }
 
     pub unsafe fn total_size(
+        arena: &dyn ReaderArena,
+        segment_id: u32,
+        reff: *const WirePointer,
+        nesting_limit: i32,
+    ) -> Result<MessageSize> {
+        total_size_visiting_caps(arena, segment_id, reff, nesting_limit, &mut |_| {})
+    }
+
+    // Like total_size(), but also calls `on_cap` with the index of each capability pointer
+    // found, in the order the pointers are encountered.
+    pub unsafe fn total_size_visiting_caps(
         arena: &dyn ReaderArena,
         segment_id: u32,
         reff: *const WirePointer,
         mut nesting_limit: i32,
+        on_cap: &mut dyn FnMut(u32),
     ) -> Result<MessageSize> {
         let mut result = MessageSize {
             word_count: 0,
@@ -724,8 +986,13 @@ mod wire_helpers {
                     as *const _;
                 let count: isize = (*reff).struct_ptr_count() as isize;
                 for i in 0..count {
-                    result +=
-                        total_size(arena, segment_id, pointer_section.offset(i), nesting_limit)?;
+                    result += total_size_visiting_caps(
+                        arena,
+                        segment_id,
+                        pointer_section.offset(i),
+                        nesting_limit,
+                        on_cap,
+                    )?;
                 }
             }
             WirePointerKind::List => {
@@ -758,11 +1025,12 @@ mod wire_helpers {
                         result.word_count += u64::from(count) * WORDS_PER_POINTER as u64;
 
                         for i in 0..count as isize {
-                            result += total_size(
+                            result += total_size_visiting_caps(
                                 arena,
                                 segment_id,
                                 (ptr as *const WirePointer).offset(i),
                                 nesting_limit,
+                                on_cap,
                             )?;
                         }
                     }
@@ -806,11 +1074,12 @@ mod wire_helpers {
                                 pos = pos.offset(data_size as isize * BYTES_PER_WORD as isize);
 
                                 for _ in 0..pointer_count {
-                                    result += total_size(
+                                    result += total_size_visiting_caps(
                                         arena,
                                         segment_id,
                                         pos as *const WirePointer,
                                         nesting_limit,
+                                        on_cap,
                                     )?;
                                     pos = pos.add(BYTES_PER_WORD);
                                 }
@@ -825,6 +1094,7 @@ mod wire_helpers {
             WirePointerKind::Other => {
                 if (*reff).is_capability() {
                     result.cap_count += 1;
+                    on_cap((*reff).cap_index());
                 } else {
                     return Err(Error::from_kind(ErrorKind::UnknownPointerType));
                 }
@@ -834,6 +1104,564 @@ mod wire_helpers {
         Ok(result)
     }
 
+    // Hashes the value that `reff` points to into `hasher`, as `crate::fingerprint()` describes.
+    // The checks made are those of total_size().
+    pub unsafe fn fingerprint(
+        arena: &dyn ReaderArena,
+        segment_id: u32,
+        reff: *const WirePointer,
+        mut nesting_limit: i32,
+        hasher: &mut Hasher,
+    ) -> Result<()> {
+        if (*reff).is_null() {
+            hasher.null();
+            return Ok(());
+        };
+
+        if nesting_limit <= 0 {
+            return Err(Error::from_kind(ErrorKind::MessageIsTooDeeplyNested));
+        }
+
+        nesting_limit -= 1;
+
+        let (ptr, reff, segment_id) = follow_fars(arena, reff, segment_id)?;
+
+        match (*reff).kind() {
+            WirePointerKind::Struct => {
+                bounds_check(
+                    arena,
+                    segment_id,
+                    ptr,
+                    (*reff).struct_word_size() as usize,
+                    WirePointerKind::Struct,
+                )?;
+                hasher.struct_pointer();
+                fingerprint_struct(
+                    arena,
+                    segment_id,
+                    ptr,
+                    (*reff).struct_data_size(),
+                    (*reff).struct_ptr_count(),
+                    nesting_limit,
+                    hasher,
+                )?;
+            }
+            WirePointerKind::List => {
+                let element_size = (*reff).list_element_size();
+                match element_size {
+                    Void | Bit | Byte | TwoBytes | FourBytes | EightBytes => {
+                        let count = (*reff).list_element_count();
+                        let total_bits =
+                            u64::from(count) * u64::from(data_bits_per_element(element_size));
+                        bounds_check(
+                            arena,
+                            segment_id,
+                            ptr,
+                            round_bits_up_to_words(total_bits) as usize,
+                            WirePointerKind::List,
+                        )?;
+                        hasher.list(element_size, count);
+                        let bytes = if total_bits == 0 {
+                            &[]
+                        } else {
+                            slice::from_raw_parts(ptr, round_bits_up_to_bytes(total_bits) as usize)
+                        };
+                        hasher.list_data(bytes, element_size, count);
+                    }
+                    Pointer => {
+                        let count = (*reff).list_element_count();
+                        bounds_check(
+                            arena,
+                            segment_id,
+                            ptr,
+                            count as usize * WORDS_PER_POINTER,
+                            WirePointerKind::List,
+                        )?;
+                        hasher.list(Pointer, count);
+                        for i in 0..count as isize {
+                            fingerprint(
+                                arena,
+                                segment_id,
+                                (ptr as *const WirePointer).offset(i),
+                                nesting_limit,
+                                hasher,
+                            )?;
+                        }
+                    }
+                    InlineComposite => {
+                        let word_count = (*reff).list_inline_composite_word_count();
+                        bounds_check(
+                            arena,
+                            segment_id,
+                            ptr,
+                            word_count as usize + POINTER_SIZE_IN_WORDS,
+                            WirePointerKind::List,
+                        )?;
+
+                        let element_tag: *const WirePointer = ptr as *const _;
+                        let count = (*element_tag).inline_composite_list_element_count();
+
+                        if (*element_tag).kind() != WirePointerKind::Struct {
+                            return Err(Error::from_kind(
+                                ErrorKind::CantHandleNonStructInlineComposite,
+                            ));
+                        }
+
+                        let element_words = (*element_tag).struct_word_size();
+                        if u64::from(element_words) * u64::from(count) > u64::from(word_count) {
+                            return Err(Error::from_kind(
+                                ErrorKind::InlineCompositeListsElementsOverrunItsWordCount,
+                            ));
+                        }
+
+                        hasher.list(InlineComposite, count);
+                        let mut pos = ptr.add(BYTES_PER_WORD);
+                        for _ in 0..count {
+                            fingerprint_struct(
+                                arena,
+                                segment_id,
+                                pos,
+                                (*element_tag).struct_data_size(),
+                                (*element_tag).struct_ptr_count(),
+                                nesting_limit,
+                                hasher,
+                            )?;
+                            pos = pos.offset(element_words as isize * BYTES_PER_WORD as isize);
+                        }
+                    }
+                }
+            }
+            WirePointerKind::Far => {
+                return Err(Error::from_kind(ErrorKind::MalformedDoubleFarPointer));
+            }
+            WirePointerKind::Other => {
+                if (*reff).is_capability() {
+                    hasher.capability((*reff).cap_index());
+                } else {
+                    return Err(Error::from_kind(ErrorKind::UnknownPointerType));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Hashes the sections of the struct at `ptr`, which must have been bounds-checked.
+    unsafe fn fingerprint_struct(
+        arena: &dyn ReaderArena,
+        segment_id: u32,
+        ptr: *const u8,
+        data_size: WordCount16,
+        pointer_count: WirePointerCount16,
+        nesting_limit: i32,
+        hasher: &mut Hasher,
+    ) -> Result<()> {
+        let data = slice::from_raw_parts(ptr, data_size as usize * BYTES_PER_WORD);
+        let pointer_section: *const WirePointer = ptr.add(data.len()) as *const _;
+        let mut pointer_count = pointer_count;
+        while pointer_count > 0 && (*pointer_section.add(pointer_count as usize - 1)).is_null() {
+            pointer_count -= 1;
+        }
+        hasher.struct_sections(data, pointer_count);
+        for i in 0..pointer_count as usize {
+            fingerprint(
+                arena,
+                segment_id,
+                pointer_section.add(i),
+                nesting_limit,
+                hasher,
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn word_offset_in_segment(
+        arena: &dyn ReaderArena,
+        segment_id: u32,
+        ptr: *const u8,
+    ) -> Result<u32> {
+        let (segment_start, _) = arena.get_segment(segment_id)?;
+        Ok(((ptr as usize - segment_start as usize) / BYTES_PER_WORD) as u32)
+    }
+
+    // Panics, saying what is wrong, unless the pointer at `reff` in segment `segment_id`, any
+    // far pointer landing pads it leads to, and the object it points at all lie within the words
+    // in use of their segments. The pointers within that object are not followed, so this takes
+    // constant time.
+    #[cfg(any(debug_assertions, feature = "paranoid-builder"))]
+    pub unsafe fn check_written_pointer(
+        arena: &dyn BuilderArena,
+        segment_id: u32,
+        reff: *const WirePointer,
+    ) {
+        let segment_start = paranoid_segment_start(arena, segment_id);
+        // A pointer before the start of the segment wraps around to a huge position.
+        let position =
+            ((reff as usize).wrapping_sub(segment_start as usize) / BYTES_PER_WORD) as u64 as i64;
+        let at = (segment_id, position);
+        paranoid_check_words(arena, at, "the pointer itself", segment_id, position, 1);
+        if (*reff).is_null() {
+            return;
+        }
+        if (*reff).kind() != WirePointerKind::Far {
+            let target = position + 1 + i64::from(((*reff).offset_and_kind.get() as i32) >> 2);
+            paranoid_check_object(arena, at, segment_id, target, reff);
+            return;
+        }
+
+        let pad_segment_id = (*reff).far_segment_id();
+        let pad_position = i64::from((*reff).far_position_in_segment());
+        let pad_words = if (*reff).is_double_far() { 2 } else { 1 };
+        paranoid_check_words(
+            arena,
+            at,
+            "the landing pad",
+            pad_segment_id,
+            pad_position,
+            pad_words,
+        );
+        let pad = paranoid_segment_start(arena, pad_segment_id)
+            .add(pad_position as usize * BYTES_PER_WORD) as *const WirePointer;
+        if !(*reff).is_double_far() {
+            if (*pad).kind() == WirePointerKind::Far {
+                panic!(
+                    "paranoid builder check: the landing pad of the far pointer at word \
+                     {position} of segment {segment_id} is itself a far pointer"
+                );
+            }
+            let target = pad_position + 1 + i64::from(((*pad).offset_and_kind.get() as i32) >> 2);
+            paranoid_check_object(arena, at, pad_segment_id, target, pad);
+        } else {
+            if (*pad).kind() != WirePointerKind::Far || (*pad).is_double_far() {
+                panic!(
+                    "paranoid builder check: the landing pad of the double-far pointer at word \
+                     {position} of segment {segment_id} does not start with a far pointer"
+                );
+            }
+            paranoid_check_object(
+                arena,
+                at,
+                (*pad).far_segment_id(),
+                i64::from((*pad).far_position_in_segment()),
+                pad.add(1),
+            );
+        }
+    }
+
+    #[cfg(any(debug_assertions, feature = "paranoid-builder"))]
+    fn paranoid_segment_start(arena: &dyn BuilderArena, segment_id: u32) -> *const u8 {
+        match arena.get_segment(segment_id) {
+            Ok((segment_start, _)) => segment_start,
+            Err(_) => panic!("paranoid builder check: segment {segment_id} does not exist"),
+        }
+    }
+
+    // Checks that `tag` describes an object that fits in the words in use of segment
+    // `segment_id` when starting at word `start`.
+    #[cfg(any(debug_assertions, feature = "paranoid-builder"))]
+    unsafe fn paranoid_check_object(
+        arena: &dyn BuilderArena,
+        at: (u32, i64),
+        segment_id: u32,
+        start: i64,
+        tag: *const WirePointer,
+    ) {
+        let words = match (*tag).kind() {
+            WirePointerKind::Struct => u64::from((*tag).struct_word_size()),
+            WirePointerKind::List => match (*tag).list_element_size() {
+                InlineComposite => u64::from((*tag).list_inline_composite_word_count()) + 1,
+                Pointer => u64::from((*tag).list_element_count()),
+                element_size => u64::from(round_bits_up_to_words(
+                    u64::from((*tag).list_element_count())
+                        * u64::from(data_bits_per_element(element_size)),
+                )),
+            },
+            WirePointerKind::Other if (*tag).is_capability() => return,
+            kind => panic!(
+                "paranoid builder check: the pointer at word {} of segment {} leads to a pointer \
+                 of kind {}, which does not describe an object",
+                at.1, at.0, kind as u8
+            ),
+        };
+        paranoid_check_words(arena, at, "the target", segment_id, start, words);
+
+        if (*tag).kind() == WirePointerKind::List && (*tag).list_element_size() == InlineComposite {
+            let element_tag = paranoid_segment_start(arena, segment_id)
+                .add(start as usize * BYTES_PER_WORD)
+                as *const WirePointer;
+            let element_words = u64::from((*element_tag).inline_composite_list_element_count())
+                * u64::from((*element_tag).struct_word_size());
+            if (*element_tag).kind() != WirePointerKind::Struct || element_words + 1 > words {
+                panic!(
+                    "paranoid builder check: the inline composite list of the pointer at word {} \
+                     of segment {} has a tag that does not describe {} words of structs",
+                    at.1,
+                    at.0,
+                    words - 1
+                );
+            }
+        }
+    }
+
+    // Checks that words `start..start + words` of segment `segment_id` are in use, and that the
+    // segment does not use more words than it has room for. `what` says which part of the
+    // pointer at `at` the words hold.
+    #[cfg(any(debug_assertions, feature = "paranoid-builder"))]
+    fn paranoid_check_words(
+        arena: &dyn BuilderArena,
+        at: (u32, i64),
+        what: &str,
+        segment_id: u32,
+        start: i64,
+        words: u64,
+    ) {
+        let in_use = match arena.get_segment(segment_id) {
+            Ok((_, in_use)) => in_use,
+            Err(_) => panic!(
+                "paranoid builder check: the pointer at word {} of segment {} leads to segment \
+                 {segment_id}, which does not exist",
+                at.1, at.0
+            ),
+        };
+        let capacity = arena.segment_capacity(segment_id);
+        assert!(
+            in_use <= capacity,
+            "paranoid builder check: segment {segment_id} has {in_use} words in use, but room \
+             for only {capacity}"
+        );
+        if start < 0 || start as u64 + words > u64::from(in_use) {
+            panic!(
+                "paranoid builder check: {what} of the pointer at word {} of segment {} is at \
+                 words {start}..{} of segment {segment_id}, which has {in_use} words in use",
+                at.1,
+                at.0,
+                start as i128 + i128::from(words)
+            );
+        }
+    }
+
+    // Walks the objects reachable from `reff`, telling `visitor` which words each of them
+    // occupies. Unlike total_size(), inline composite lists are reported with their claimed word
+    // count, and far pointer landing pads are reported too.
+    pub unsafe fn walk_objects(
+        arena: &dyn ReaderArena,
+        segment_id: u32,
+        reff: *const WirePointer,
+        mut nesting_limit: i32,
+        visitor: &mut dyn ObjectVisitor,
+    ) -> Result<()> {
+        use crate::private::layout::PathStep;
+
+        if (*reff).is_null() {
+            return Ok(());
+        };
+
+        if nesting_limit <= 0 {
+            return Err(Error::from_kind(ErrorKind::MessageIsTooDeeplyNested));
+        }
+
+        if !visitor.pointer(&*reff) {
+            return Ok(());
+        }
+
+        nesting_limit -= 1;
+
+        let far = reff;
+        let (ptr, reff, segment_id) = follow_fars(arena, reff, segment_id)?;
+        if (*far).kind() == WirePointerKind::Far {
+            let pad_words = if (*far).is_double_far() { 2 } else { 1 };
+            visitor.occupied(
+                (*far).far_segment_id(),
+                (*far).far_position_in_segment(),
+                pad_words,
+            );
+        }
+
+        match (*reff).kind() {
+            WirePointerKind::Struct => {
+                let word_count = (*reff).struct_word_size();
+                bounds_check(
+                    arena,
+                    segment_id,
+                    ptr,
+                    word_count as usize,
+                    WirePointerKind::Struct,
+                )?;
+                let word_offset = word_offset_in_segment(arena, segment_id, ptr)?;
+                if !visitor.occupied(segment_id, word_offset, word_count) {
+                    return Ok(());
+                }
+                visitor.object(
+                    &*reff,
+                    word_offset,
+                    slice::from_raw_parts(ptr, word_count as usize * BYTES_PER_WORD),
+                );
+
+                let pointer_section: *const WirePointer = ptr
+                    .offset((*reff).struct_data_size() as isize * BYTES_PER_WORD as isize)
+                    as *const _;
+                for i in 0..(*reff).struct_ptr_count() {
+                    visitor.enter(PathStep::Pointer(u32::from(i)));
+                    walk_objects(
+                        arena,
+                        segment_id,
+                        pointer_section.offset(i as isize),
+                        nesting_limit,
+                        visitor,
+                    )?;
+                    visitor.leave();
+                }
+            }
+            WirePointerKind::List => match (*reff).list_element_size() {
+                Void => {
+                    bounds_check(arena, segment_id, ptr, 0, WirePointerKind::List)?;
+                    let word_offset = word_offset_in_segment(arena, segment_id, ptr)?;
+                    if visitor.occupied(segment_id, word_offset, 0) {
+                        visitor.object(&*reff, word_offset, &[]);
+                        visitor.list(Void, (*reff).list_element_count(), None);
+                    }
+                }
+                element_size @ (Bit | Byte | TwoBytes | FourBytes | EightBytes) => {
+                    let count = (*reff).list_element_count();
+                    let total_words = round_bits_up_to_words(
+                        u64::from(count) * u64::from(data_bits_per_element(element_size)),
+                    );
+                    bounds_check(
+                        arena,
+                        segment_id,
+                        ptr,
+                        total_words as usize,
+                        WirePointerKind::List,
+                    )?;
+                    let word_offset = word_offset_in_segment(arena, segment_id, ptr)?;
+                    if visitor.occupied(segment_id, word_offset, total_words) {
+                        visitor.object(
+                            &*reff,
+                            word_offset,
+                            slice::from_raw_parts(ptr, total_words as usize * BYTES_PER_WORD),
+                        );
+                        let bytes = (element_size == Byte)
+                            .then(|| slice::from_raw_parts(ptr, count as usize));
+                        visitor.list(element_size, count, bytes);
+                    }
+                }
+                Pointer => {
+                    let count = (*reff).list_element_count();
+                    bounds_check(
+                        arena,
+                        segment_id,
+                        ptr,
+                        count as usize * WORDS_PER_POINTER,
+                        WirePointerKind::List,
+                    )?;
+                    let word_offset = word_offset_in_segment(arena, segment_id, ptr)?;
+                    if !visitor.occupied(segment_id, word_offset, count * WORDS_PER_POINTER as u32)
+                    {
+                        return Ok(());
+                    }
+                    visitor.object(
+                        &*reff,
+                        word_offset,
+                        slice::from_raw_parts(ptr, count as usize * BYTES_PER_WORD),
+                    );
+                    visitor.list(Pointer, count, None);
+
+                    for i in 0..count {
+                        visitor.enter(PathStep::Pointer(i));
+                        walk_objects(
+                            arena,
+                            segment_id,
+                            (ptr as *const WirePointer).offset(i as isize),
+                            nesting_limit,
+                            visitor,
+                        )?;
+                        visitor.leave();
+                    }
+                }
+                InlineComposite => {
+                    let word_count = (*reff).list_inline_composite_word_count();
+                    bounds_check(
+                        arena,
+                        segment_id,
+                        ptr,
+                        word_count as usize + POINTER_SIZE_IN_WORDS,
+                        WirePointerKind::List,
+                    )?;
+
+                    let element_tag: *const WirePointer = ptr as *const _;
+                    let count = (*element_tag).inline_composite_list_element_count();
+
+                    if (*element_tag).kind() != WirePointerKind::Struct {
+                        return Err(Error::from_kind(
+                            ErrorKind::CantHandleNonStructInlineComposite,
+                        ));
+                    }
+
+                    let actual_size =
+                        u64::from((*element_tag).struct_word_size()) * u64::from(count);
+                    if actual_size > u64::from(word_count) {
+                        return Err(Error::from_kind(
+                            ErrorKind::InlineCompositeListsElementsOverrunItsWordCount,
+                        ));
+                    }
+
+                    let word_offset = word_offset_in_segment(arena, segment_id, ptr)?;
+                    if !visitor.occupied(
+                        segment_id,
+                        word_offset,
+                        word_count + POINTER_SIZE_IN_WORDS as u32,
+                    ) {
+                        return Ok(());
+                    }
+                    visitor.object(
+                        &*reff,
+                        word_offset,
+                        slice::from_raw_parts(
+                            ptr,
+                            (word_count as usize + POINTER_SIZE_IN_WORDS) * BYTES_PER_WORD,
+                        ),
+                    );
+                    visitor.list(InlineComposite, count, None);
+
+                    let data_size = (*element_tag).struct_data_size();
+                    let pointer_count = (*element_tag).struct_ptr_count();
+
+                    if pointer_count > 0 {
+                        let mut pos = ptr.add(BYTES_PER_WORD);
+                        for i in 0..count {
+                            pos = pos.offset(data_size as isize * BYTES_PER_WORD as isize);
+                            visitor.enter(PathStep::Element(i));
+                            for j in 0..pointer_count {
+                                visitor.enter(PathStep::Pointer(u32::from(j)));
+                                walk_objects(
+                                    arena,
+                                    segment_id,
+                                    pos as *const WirePointer,
+                                    nesting_limit,
+                                    visitor,
+                                )?;
+                                visitor.leave();
+                                pos = pos.add(BYTES_PER_WORD);
+                            }
+                            visitor.leave();
+                        }
+                    }
+                }
+            },
+            WirePointerKind::Far => {
+                return Err(Error::from_kind(ErrorKind::MalformedDoubleFarPointer));
+            }
+            WirePointerKind::Other => {
+                if !(*reff).is_capability() {
+                    return Err(Error::from_kind(ErrorKind::UnknownPointerType));
+                }
+                visitor.capability((*reff).cap_index());
+            }
+        }
+
+        Ok(())
+    }
+
     // Helper for copy_message().
     unsafe fn copy_struct(
         arena: &mut dyn BuilderArena,
@@ -843,7 +1671,7 @@ mod wire_helpers {
         src: *const u8,
         data_size: isize,
         pointer_count: isize,
-    ) {
+    ) -> Result<()> {
         ptr::copy_nonoverlapping(src, dst, data_size as usize * BYTES_PER_WORD);
 
         let src_refs: *const WirePointer = (src as *const WirePointer).offset(data_size);
@@ -856,8 +1684,39 @@ mod wire_helpers {
                 cap_table,
                 dst_refs.offset(ii),
                 src_refs.offset(ii),
-            );
+            )?;
+        }
+        Ok(())
+    }
+
+    // Copies a canonical single-segment message, given as its words starting with the root
+    // pointer, by copying its body as a block and pointing `dst` at the copy. Pointers within
+    // the body are relative, so only the root pointer needs fixing up.
+    // The caller must have checked that `words` is canonical.
+    pub unsafe fn copy_canonical_words(
+        arena: &mut dyn BuilderArena,
+        segment_id: u32,
+        dst: *mut WirePointer,
+        words: &[crate::Word],
+    ) -> Result<()> {
+        let src = words.as_ptr() as *const WirePointer;
+        if (*src).is_null() {
+            if !(*dst).is_null() {
+                zero_object(arena, segment_id, dst);
+            }
+            ptr::write_bytes(dst, 0, 1);
+            return Ok(());
         }
+
+        let body_words = (words.len() - POINTER_SIZE_IN_WORDS) as WordCount32;
+        let (ptr, dst, _) = allocate(arena, dst, segment_id, body_words, (*src).kind())?;
+        ptr::copy_nonoverlapping(
+            words[POINTER_SIZE_IN_WORDS..].as_ptr() as *const u8,
+            ptr,
+            body_words as usize * BYTES_PER_WORD,
+        );
+        ptr::copy_nonoverlapping(&(*src).upper32bits, &mut (*dst).upper32bits, 1);
+        Ok(())
     }
 
     // Copies from a trusted message.
@@ -868,12 +1727,12 @@ mod wire_helpers {
         cap_table: CapTableBuilder,
         dst: *mut WirePointer,
         src: *const WirePointer,
-    ) -> (*mut u8, *mut WirePointer, u32) {
+    ) -> Result<(*mut u8, *mut WirePointer, u32)> {
         match (*src).kind() {
             WirePointerKind::Struct => {
                 if (*src).is_null() {
                     ptr::write_bytes(dst, 0, 1);
-                    (ptr::null_mut(), dst, segment_id)
+                    Ok((ptr::null_mut(), dst, segment_id))
                 } else {
                     let src_ptr = WirePointer::target(src);
                     let (dst_ptr, dst, segment_id) = allocate(
@@ -882,6 +1741,10 @@ mod wire_helpers {
                         segment_id,
                         (*src).struct_word_size(),
                         WirePointerKind::Struct,
+                    )?;
+                    (*dst).set_struct_size_from_pieces(
+                        (*src).struct_data_size(),
+                        (*src).struct_ptr_count(),
                     );
                     copy_struct(
                         arena,
@@ -891,12 +1754,8 @@ mod wire_helpers {
                         src_ptr,
                         (*src).struct_data_size() as isize,
                         (*src).struct_ptr_count() as isize,
-                    );
-                    (*dst).set_struct_size_from_pieces(
-                        (*src).struct_data_size(),
-                        (*src).struct_ptr_count(),
-                    );
-                    (dst_ptr, dst, segment_id)
+                    )?;
+                    Ok((dst_ptr, dst, segment_id))
                 }
             }
             WirePointerKind::List => match (*src).list_element_size() {
@@ -912,7 +1771,7 @@ mod wire_helpers {
                     );
                     let src_ptr = WirePointer::target(src);
                     let (dst_ptr, dst, segment_id) =
-                        allocate(arena, dst, segment_id, word_count, WirePointerKind::List);
+                        allocate(arena, dst, segment_id, word_count, WirePointerKind::List)?;
                     ptr::copy_nonoverlapping(
                         src_ptr,
                         dst_ptr,
@@ -922,7 +1781,7 @@ mod wire_helpers {
                         (*src).list_element_size(),
                         (*src).list_element_count(),
                     );
-                    (dst_ptr, dst, segment_id)
+                    Ok((dst_ptr, dst, segment_id))
                 }
 
                 ElementSize::Pointer => {
@@ -933,7 +1792,9 @@ mod wire_helpers {
                         segment_id,
                         (*src).list_element_count(),
                         WirePointerKind::List,
-                    );
+                    )?;
+                    (*dst)
+                        .set_list_size_and_count(ElementSize::Pointer, (*src).list_element_count());
                     for ii in 0..((*src).list_element_count() as isize) {
                         copy_message(
                             arena,
@@ -941,11 +1802,9 @@ mod wire_helpers {
                             cap_table,
                             dst_refs.offset(ii * BYTES_PER_WORD as isize) as *mut WirePointer,
                             src_refs.offset(ii),
-                        );
+                        )?;
                     }
-                    (*dst)
-                        .set_list_size_and_count(ElementSize::Pointer, (*src).list_element_count());
-                    (dst_refs, dst, segment_id)
+                    Ok((dst_refs, dst, segment_id))
                 }
                 ElementSize::InlineComposite => {
                     let src_ptr = WirePointer::target(src);
@@ -955,7 +1814,7 @@ mod wire_helpers {
                         segment_id,
                         (*src).list_inline_composite_word_count() + 1,
                         WirePointerKind::List,
-                    );
+                    )?;
 
                     (*dst).set_list_inline_composite((*src).list_inline_composite_word_count());
 
@@ -977,7 +1836,7 @@ mod wire_helpers {
                             src_element,
                             (*src_tag).struct_data_size() as isize,
                             (*src_tag).struct_ptr_count() as isize,
-                        );
+                        )?;
                         src_element = src_element.offset(
                             BYTES_PER_WORD as isize * (*src_tag).struct_word_size() as isize,
                         );
@@ -985,7 +1844,7 @@ mod wire_helpers {
                             BYTES_PER_WORD as isize * (*src_tag).struct_word_size() as isize,
                         );
                     }
-                    (dst_ptr, dst, segment_id)
+                    Ok((dst_ptr, dst, segment_id))
                 }
             },
             WirePointerKind::Other => {
@@ -1003,7 +1862,7 @@ mod wire_helpers {
         dst: *mut WirePointer,
         src_segment_id: u32,
         src: *mut WirePointer,
-    ) {
+    ) -> Result<()> {
         //# Make *dst point to the same object as *src. Both must
         //# reside in the same message, but can be in different
         //# segments. Not always-inline because this is rarely used.
@@ -1027,10 +1886,39 @@ mod wire_helpers {
                 src_segment_id,
                 src,
                 WirePointer::mut_target(src),
-            );
+            )?;
         } else {
             ptr::copy_nonoverlapping(src, dst, 1);
         }
+        Ok(())
+    }
+
+    /// Swaps the pointers at `a` and `b`, which must reside in the same segment.
+    /// The objects they point to are not moved.
+    pub unsafe fn swap_pointers(a: *mut WirePointer, b: *mut WirePointer) {
+        // Offsets of positional pointers are relative to the pointer's own location, so those
+        // need to be recomputed. Far pointers, capabilities, and empty structs are
+        // location-independent and can be swapped as-is.
+        unsafe fn relocatable_target(p: *mut WirePointer) -> Option<*mut u8> {
+            if (*p).is_null()
+                || !(*p).is_positional()
+                || ((*p).kind() == WirePointerKind::Struct && (*p).struct_word_size() == 0)
+            {
+                None
+            } else {
+                Some(WirePointer::mut_target(p))
+            }
+        }
+
+        let a_target = relocatable_target(a);
+        let b_target = relocatable_target(b);
+        ptr::swap(a, b);
+        if let Some(target) = b_target {
+            (*a).set_kind_and_target((*a).kind(), target);
+        }
+        if let Some(target) = a_target {
+            (*b).set_kind_and_target((*b).kind(), target);
+        }
     }
 
     pub unsafe fn transfer_pointer_split(
@@ -1040,7 +1928,7 @@ mod wire_helpers {
         src_segment_id: u32,
         src_tag: *mut WirePointer,
         src_ptr: *mut u8,
-    ) {
+    ) -> Result<()> {
         // Like the other transfer_pointer, but splits src into a tag and a
         // target. Particularly useful for OrphanBuilder.
 
@@ -1061,7 +1949,7 @@ mod wire_helpers {
             match arena.allocate(src_segment_id, 1) {
                 None => {
                     //# Darn, need a double-far.
-                    let (far_segment_id, word_idx) = arena.allocate_anywhere(2);
+                    let (far_segment_id, word_idx) = arena.allocate_anywhere(2)?;
                     let (seg_start, _seg_len) = arena.get_segment_mut(far_segment_id);
                     let landing_pad: *mut WirePointer =
                         (seg_start as *mut WirePointer).offset(word_idx as isize);
@@ -1104,6 +1992,7 @@ mod wire_helpers {
                 }
             }
         }
+        Ok(())
     }
 
     #[inline]
@@ -1113,17 +2002,17 @@ mod wire_helpers {
         segment_id: u32,
         cap_table: CapTableBuilder,
         size: StructSize,
-    ) -> StructBuilder<'_> {
+    ) -> Result<StructBuilder<'_>> {
         let (ptr, reff, segment_id) = allocate(
             arena,
             reff,
             segment_id,
             size.total(),
             WirePointerKind::Struct,
-        );
+        )?;
         (*reff).set_struct_size(size);
 
-        StructBuilder {
+        Ok(StructBuilder {
             arena,
             segment_id,
             cap_table,
@@ -1131,7 +2020,7 @@ mod wire_helpers {
             pointers: ptr.offset((size.data as usize) as isize * BYTES_PER_WORD as isize) as *mut _,
             data_size: u32::from(size.data) * (BITS_PER_WORD as BitCount32),
             pointer_count: size.pointers,
-        }
+        })
     }
 
     #[inline]
@@ -1147,15 +2036,9 @@ mod wire_helpers {
 
         if (*reff).is_null() {
             match default {
-                None => {
-                    return Ok(init_struct_pointer(
-                        arena, reff, segment_id, cap_table, size,
-                    ))
-                }
+                None => return init_struct_pointer(arena, reff, segment_id, cap_table, size),
                 Some(d) if (*(d.as_ptr() as *const WirePointer)).is_null() => {
-                    return Ok(init_struct_pointer(
-                        arena, reff, segment_id, cap_table, size,
-                    ))
+                    return init_struct_pointer(arena, reff, segment_id, cap_table, size)
                 }
                 Some(d) => {
                     let (new_ref_target, new_reff, new_segment_id) = copy_message(
@@ -1164,7 +2047,7 @@ mod wire_helpers {
                         cap_table,
                         reff,
                         d.as_ptr() as *const WirePointer,
-                    );
+                    )?;
                     reff = new_reff;
                     segment_id = new_segment_id;
                     ref_target = new_ref_target;
@@ -1201,7 +2084,7 @@ mod wire_helpers {
             zero_pointer_and_fars(arena, segment_id, reff)?;
 
             let (ptr, reff, segment_id) =
-                allocate(arena, reff, segment_id, total_size, WirePointerKind::Struct);
+                allocate(arena, reff, segment_id, total_size, WirePointerKind::Struct)?;
             (*reff).set_struct_size_from_pieces(new_data_size, new_pointer_count);
 
             // Copy data section.
@@ -1218,7 +2101,7 @@ mod wire_helpers {
                     new_pointer_section.offset(i),
                     old_segment_id,
                     old_pointer_section.offset(i),
-                );
+                )?;
             }
 
             ptr::write_bytes(
@@ -1257,7 +2140,7 @@ mod wire_helpers {
         cap_table: CapTableBuilder,
         element_count: ElementCount32,
         element_size: ElementSize,
-    ) -> ListBuilder<'_> {
+    ) -> Result<ListBuilder<'_>> {
         assert!(
             element_size != InlineComposite,
             "Should have called initStructListPointer() instead"
@@ -1268,11 +2151,11 @@ mod wire_helpers {
         let step = data_size + pointer_count * BITS_PER_POINTER as u32;
         let word_count = round_bits_up_to_words(u64::from(element_count) * u64::from(step));
         let (ptr, reff, segment_id) =
-            allocate(arena, reff, segment_id, word_count, WirePointerKind::List);
+            allocate(arena, reff, segment_id, word_count, WirePointerKind::List)?;
 
         (*reff).set_list_size_and_count(element_size, element_count);
 
-        ListBuilder {
+        Ok(ListBuilder {
             arena,
             segment_id,
             cap_table,
@@ -1282,7 +2165,7 @@ mod wire_helpers {
             element_size,
             struct_data_size: data_size,
             struct_pointer_count: pointer_count as u16,
-        }
+        })
     }
 
     #[inline]
@@ -1293,7 +2176,7 @@ mod wire_helpers {
         cap_table: CapTableBuilder,
         element_count: ElementCount32,
         element_size: StructSize,
-    ) -> ListBuilder<'_> {
+    ) -> Result<ListBuilder<'_>> {
         let words_per_element = element_size.total();
 
         //# Allocate the list, prefixed by a single WirePointer.
@@ -1304,7 +2187,7 @@ mod wire_helpers {
             segment_id,
             POINTER_SIZE_IN_WORDS as u32 + word_count,
             WirePointerKind::List,
-        );
+        )?;
         let ptr = ptr as *mut WirePointer;
 
         //# Initialize the pointer.
@@ -1317,7 +2200,7 @@ mod wire_helpers {
 
         let ptr1 = ptr.add(POINTER_SIZE_IN_WORDS);
 
-        ListBuilder {
+        Ok(ListBuilder {
             arena,
             segment_id,
             cap_table,
@@ -1327,7 +2210,7 @@ mod wire_helpers {
             element_size: ElementSize::InlineComposite,
             struct_data_size: u32::from(element_size.data) * (BITS_PER_WORD as u32),
             struct_pointer_count: element_size.pointers,
-        }
+        })
     }
 
     #[inline]
@@ -1356,7 +2239,7 @@ mod wire_helpers {
                 cap_table,
                 orig_ref,
                 default_value as *const WirePointer,
-            );
+            )?;
             orig_ref_target = new_orig_ref_target;
             orig_ref = new_orig_ref;
             orig_segment_id = new_orig_segment_id;
@@ -1486,7 +2369,7 @@ mod wire_helpers {
                 cap_table,
                 orig_ref,
                 default_value as *const WirePointer,
-            );
+            )?;
             orig_ref_target = new_orig_ref_target;
             orig_ref = new_orig_ref;
             orig_segment_id = new_orig_segment_id;
@@ -1553,7 +2436,7 @@ mod wire_helpers {
                 orig_segment_id,
                 total_size + POINTER_SIZE_IN_WORDS as u32,
                 WirePointerKind::List,
-            );
+            )?;
             (*new_ref).set_list_inline_composite(total_size);
 
             let new_tag: *mut WirePointer = new_ptr as *mut _;
@@ -1580,7 +2463,7 @@ mod wire_helpers {
                         new_pointer_section.offset(jj),
                         old_segment_id,
                         old_pointer_section.offset(jj),
-                    );
+                    )?;
                 }
 
                 dst = dst.offset(new_step as isize);
@@ -1614,14 +2497,14 @@ mod wire_helpers {
 
             if old_size == ElementSize::Void {
                 // Nothing to copy, just allocate a new list.
-                Ok(init_struct_list_pointer(
+                init_struct_list_pointer(
                     arena,
                     orig_ref,
                     orig_segment_id,
                     cap_table,
                     element_count,
                     element_size,
-                ))
+                )
             } else {
                 // Upgrade to an inline composite list.
 
@@ -1654,7 +2537,7 @@ mod wire_helpers {
                     orig_segment_id,
                     total_words + POINTER_SIZE_IN_WORDS as u32,
                     WirePointerKind::List,
-                );
+                )?;
                 (*new_ref).set_list_inline_composite(total_words);
 
                 let tag: *mut WirePointer = new_ptr as *mut _;
@@ -1669,7 +2552,13 @@ mod wire_helpers {
                     let mut dst = new_ptr.offset(new_data_size as isize * BYTES_PER_WORD as isize);
                     let mut src: *mut WirePointer = old_ptr as *mut _;
                     for _ in 0..element_count {
-                        transfer_pointer(arena, new_segment_id, dst as *mut _, old_segment_id, src);
+                        transfer_pointer(
+                            arena,
+                            new_segment_id,
+                            dst as *mut _,
+                            old_segment_id,
+                            src,
+                        )?;
                         dst = dst.offset(new_step as isize * BYTES_PER_WORD as isize);
                         src = src.offset(1);
                     }
@@ -1712,26 +2601,26 @@ mod wire_helpers {
         reff: *mut WirePointer,
         segment_id: u32,
         size: ByteCount32,
-    ) -> SegmentAnd<text::Builder<'_>> {
-        //# The byte list must include a NUL terminator.
-        let byte_size = size + 1;
+    ) -> Result<SegmentAnd<text::Builder<'_>>> {
+        let byte_size = expect_blob_element_count(size as usize, true);
 
         //# Allocate the space.
-        let (ptr, reff, segment_id) = allocate(
+        let (ptr, list_reff, list_segment_id) = allocate(
             arena,
             reff,
             segment_id,
             round_bytes_up_to_words(byte_size),
             WirePointerKind::List,
-        );
+        )?;
 
         //# Initialize the pointer.
-        (*reff).set_list_size_and_count(Byte, byte_size);
+        (*list_reff).set_list_size_and_count(Byte, byte_size);
+        super::check_written_pointer(arena, segment_id, reff);
 
-        SegmentAnd {
-            segment_id,
+        Ok(SegmentAnd {
+            segment_id: list_segment_id,
             value: text::Builder::new(slice::from_raw_parts_mut(ptr, size as usize)),
-        }
+        })
     }
 
     #[inline]
@@ -1740,16 +2629,16 @@ mod wire_helpers {
         reff: *mut WirePointer,
         segment_id: u32,
         value: crate::text::Reader<'_>,
-    ) -> SegmentAnd<text::Builder<'a>> {
+    ) -> Result<SegmentAnd<text::Builder<'a>>> {
         let value_bytes = value.as_bytes();
-        // TODO make sure the string is not longer than 2 ** 29.
-        let mut allocation = init_text_pointer(arena, reff, segment_id, value_bytes.len() as u32);
+        let len = expect_blob_element_count(value_bytes.len(), true) - 1;
+        let mut allocation = init_text_pointer(arena, reff, segment_id, len)?;
         allocation
             .value
             .reborrow()
             .as_bytes_mut()
             .copy_from_slice(value_bytes);
-        allocation
+        Ok(allocation)
     }
 
     #[inline]
@@ -1769,7 +2658,8 @@ mod wire_helpers {
                         Default::default(),
                         reff,
                         d.as_ptr() as *const _,
-                    );
+                    )?;
+                    super::check_written_pointer(arena, segment_id, reff);
                     reff = new_reff;
                     segment_id = new_segment_id;
                     new_ref_target
@@ -1790,15 +2680,13 @@ mod wire_helpers {
             ));
         }
 
-        let count = (*reff).list_element_count();
-        if count == 0 || *ptr.offset((count - 1) as isize) != 0 {
+        let Some(len) = text_len(ptr, (*reff).list_element_count()) else {
             return Err(Error::from_kind(ErrorKind::TextBlobMissingNULTerminator));
-        }
+        };
 
-        // Subtract 1 from the size for the NUL terminator.
         Ok(text::Builder::with_pos(
-            slice::from_raw_parts_mut(ptr, (count - 1) as usize),
-            (count - 1) as usize,
+            slice::from_raw_parts_mut(ptr, len as usize),
+            len as usize,
         ))
     }
 
@@ -1808,23 +2696,26 @@ mod wire_helpers {
         reff: *mut WirePointer,
         segment_id: u32,
         size: ByteCount32,
-    ) -> SegmentAnd<data::Builder<'_>> {
+    ) -> Result<SegmentAnd<data::Builder<'_>>> {
+        expect_blob_element_count(size as usize, false);
+
         //# Allocate the space.
-        let (ptr, reff, segment_id) = allocate(
+        let (ptr, list_reff, list_segment_id) = allocate(
             arena,
             reff,
             segment_id,
             round_bytes_up_to_words(size),
             WirePointerKind::List,
-        );
+        )?;
 
         //# Initialize the pointer.
-        (*reff).set_list_size_and_count(Byte, size);
+        (*list_reff).set_list_size_and_count(Byte, size);
+        super::check_written_pointer(arena, segment_id, reff);
 
-        SegmentAnd {
-            segment_id,
+        Ok(SegmentAnd {
+            segment_id: list_segment_id,
             value: data::builder_from_raw_parts(ptr, size),
-        }
+        })
     }
 
     #[inline]
@@ -1833,10 +2724,11 @@ mod wire_helpers {
         reff: *mut WirePointer,
         segment_id: u32,
         value: &[u8],
-    ) -> SegmentAnd<data::Builder<'a>> {
-        let allocation = init_data_pointer(arena, reff, segment_id, value.len() as u32);
+    ) -> Result<SegmentAnd<data::Builder<'a>>> {
+        let len = expect_blob_element_count(value.len(), false);
+        let allocation = init_data_pointer(arena, reff, segment_id, len)?;
         ptr::copy_nonoverlapping(value.as_ptr(), allocation.value.as_mut_ptr(), value.len());
-        allocation
+        Ok(allocation)
     }
 
     #[inline]
@@ -1856,7 +2748,8 @@ mod wire_helpers {
                         Default::default(),
                         reff,
                         d.as_ptr() as *const _,
-                    );
+                    )?;
+                    super::check_written_pointer(arena, segment_id, reff);
                     reff = new_reff;
                     segment_id = new_segment_id;
                     new_ref_target
@@ -1889,12 +2782,12 @@ mod wire_helpers {
         cap_table: CapTableBuilder,
         reff: *mut WirePointer,
         value: StructReader,
-        canonicalize: bool,
+        mode: CopyMode,
     ) -> Result<SegmentAnd<*mut u8>> {
         let mut data_size: ByteCount32 = round_bits_up_to_bytes(u64::from(value.data_size));
         let mut ptr_count = value.pointer_count;
 
-        if canonicalize {
+        if mode == CopyMode::Canonical {
             // StructReaders should not have bitwidths other than 1, but let's be safe
             if !(value.data_size == 1 || value.data_size % BITS_PER_BYTE as u32 == 0) {
                 return Err(Error::from_kind(
@@ -1932,7 +2825,7 @@ mod wire_helpers {
         let total_size: WordCount32 = data_words + u32::from(ptr_count) * WORDS_PER_POINTER as u32;
 
         let (ptr, reff, segment_id) =
-            allocate(arena, reff, segment_id, total_size, WirePointerKind::Struct);
+            allocate(arena, reff, segment_id, total_size, WirePointerKind::Struct)?;
         (*reff).set_struct_size_from_pieces(data_words as u16, ptr_count);
 
         if value.data_size == 1 {
@@ -1957,7 +2850,7 @@ mod wire_helpers {
                 value.cap_table,
                 value.pointers.offset(i),
                 value.nesting_limit,
-                canonicalize,
+                mode,
             )?;
         }
 
@@ -1987,7 +2880,7 @@ mod wire_helpers {
         cap_table: CapTableBuilder,
         reff: *mut WirePointer,
         value: ListReader,
-        canonicalize: bool,
+        mode: CopyMode,
     ) -> Result<SegmentAnd<*mut u8>> {
         let total_size =
             round_bits_up_to_words(u64::from(value.element_count) * u64::from(value.step));
@@ -1995,7 +2888,7 @@ mod wire_helpers {
         if value.element_size != ElementSize::InlineComposite {
             //# List of non-structs.
             let (ptr, reff, segment_id) =
-                allocate(arena, reff, segment_id, total_size, WirePointerKind::List);
+                allocate(arena, reff, segment_id, total_size, WirePointerKind::List)?;
 
             if value.struct_pointer_count == 1 {
                 //# List of pointers.
@@ -2011,7 +2904,7 @@ mod wire_helpers {
                         value.cap_table,
                         (value.ptr as *const WirePointer).offset(i),
                         value.nesting_limit,
-                        canonicalize,
+                        mode,
                     )?;
                 }
             } else {
@@ -2061,7 +2954,7 @@ mod wire_helpers {
             let mut ptr_count = 0;
             let mut total_size = total_size;
 
-            if canonicalize {
+            if mode == CopyMode::Canonical {
                 for ec in 0..value.element_count {
                     let se = value.get_struct_element(ec);
                     let mut local_data_size = decl_data_size;
@@ -2102,7 +2995,7 @@ mod wire_helpers {
                 segment_id,
                 total_size + POINTER_SIZE_IN_WORDS as u32,
                 WirePointerKind::List,
-            );
+            )?;
             (*reff).set_list_inline_composite(total_size);
 
             let tag: *mut WirePointer = ptr as *mut _;
@@ -2130,7 +3023,7 @@ mod wire_helpers {
                         value.cap_table,
                         src as *const WirePointer,
                         value.nesting_limit,
-                        canonicalize,
+                        mode,
                     )?;
                     dst = dst.add(BYTES_PER_WORD);
                     src = src.add(BYTES_PER_WORD);
@@ -2156,7 +3049,7 @@ mod wire_helpers {
         src_cap_table: CapTableReader,
         src: *const WirePointer,
         nesting_limit: i32,
-        canonicalize: bool,
+        mode: CopyMode,
     ) -> Result<SegmentAnd<*mut u8>> {
         if (*src).is_null() {
             ptr::write_bytes(dst, 0, 1);
@@ -2200,8 +3093,9 @@ mod wire_helpers {
                         data_size: u32::from((*src).struct_data_size()) * BITS_PER_WORD as u32,
                         pointer_count: (*src).struct_ptr_count(),
                         nesting_limit: nesting_limit - 1,
+                        list_nesting_limit: 0x7fffffff,
                     },
-                    canonicalize,
+                    mode,
                 )
             }
             WirePointerKind::List => {
@@ -2265,8 +3159,9 @@ mod wire_helpers {
                                 * BITS_PER_WORD as u32,
                             struct_pointer_count: (*tag).struct_ptr_count(),
                             nesting_limit: nesting_limit - 1,
+                            list_nesting_limit: 0x7fffffff,
                         },
-                        canonicalize,
+                        mode,
                     )
                 } else {
                     let data_size = data_bits_per_element(element_size);
@@ -2306,8 +3201,9 @@ mod wire_helpers {
                             struct_data_size: data_size,
                             struct_pointer_count: pointer_count as u16,
                             nesting_limit: nesting_limit - 1,
+                            list_nesting_limit: 0x7fffffff,
                         },
-                        canonicalize,
+                        mode,
                     )
                 }
             }
@@ -2316,11 +3212,23 @@ mod wire_helpers {
                 if !(*src).is_capability() {
                     return Err(Error::from_kind(ErrorKind::UnknownPointerType));
                 }
-                if canonicalize {
+                if mode == CopyMode::Canonical {
                     return Err(Error::from_kind(
                         ErrorKind::CannotCreateACanonicalMessageWithACapability,
                     ));
                 }
+                if mode == CopyMode::KeepCapIndices {
+                    (*dst).set_cap((*src).cap_index());
+                    return Ok(SegmentAnd {
+                        segment_id: dst_segment_id,
+                        value: ptr::null_mut(),
+                    });
+                }
+                if dst_cap_table.is_null() {
+                    return Err(Error::from_kind(
+                        ErrorKind::CannotCopyACapabilityWithoutACapTable,
+                    ));
+                }
                 #[cfg(feature = "alloc")]
                 match src_cap_table.extract_cap((*src).cap_index() as usize) {
                     Some(cap) => {
@@ -2348,6 +3256,7 @@ mod wire_helpers {
         mut reff: *const WirePointer,
         default: Option<&'a [crate::Word]>,
         nesting_limit: i32,
+        list_nesting_limit: i32,
     ) -> Result<StructReader<'a>> {
         if (*reff).is_null() {
             match default {
@@ -2396,13 +3305,14 @@ mod wire_helpers {
             data_size: u32::from(data_size_words) * BITS_PER_WORD as BitCount32,
             pointer_count: (*reff).struct_ptr_count(),
             nesting_limit: nesting_limit - 1,
+            list_nesting_limit,
         })
     }
 
     #[inline]
     #[cfg(feature = "alloc")]
     pub unsafe fn read_capability_pointer(
-        _arena: &dyn ReaderArena,
+        arena: &dyn ReaderArena,
         _segment_id: u32,
         cap_table: CapTableReader,
         reff: *const WirePointer,
@@ -2417,8 +3327,14 @@ mod wire_helpers {
                 ErrorKind::MessageContainsNonCapabilityPointerWhereCapabilityPointerWasExpected,
             ))
         } else {
-            let n = (*reff).cap_index() as usize;
-            match cap_table.extract_cap(n) {
+            let n = (*reff).cap_index();
+            arena.can_use_cap(n)?;
+            if cap_table.is_null() {
+                return Err(Error::from_kind(
+                    ErrorKind::CannotReadACapabilityWithoutACapTable,
+                ));
+            }
+            match cap_table.extract_cap(n as usize) {
                 Some(client_hook) => Ok(client_hook),
                 None => Err(Error::from_kind(
                     ErrorKind::MessageContainsInvalidCapabilityPointer,
@@ -2436,6 +3352,7 @@ mod wire_helpers {
         default_value: *const u8,
         expected_element_size: Option<ElementSize>,
         nesting_limit: i32,
+        list_nesting_limit: i32,
     ) -> Result<ListReader<'_>> {
         if (*reff).is_null() {
             if default_value.is_null() || (*(default_value as *const WirePointer)).is_null() {
@@ -2537,6 +3454,7 @@ mod wire_helpers {
                     struct_data_size: u32::from(data_size) * (BITS_PER_WORD as u32),
                     struct_pointer_count: ptr_count,
                     nesting_limit: nesting_limit - 1,
+                    list_nesting_limit,
                 })
             }
             _ => {
@@ -2600,6 +3518,7 @@ mod wire_helpers {
                     struct_data_size: data_size,
                     struct_pointer_count: pointer_count as u16,
                     nesting_limit: nesting_limit - 1,
+                    list_nesting_limit,
                 })
             }
         }
@@ -2646,24 +3565,13 @@ mod wire_helpers {
             WirePointerKind::List,
         )?;
 
-        if size == 0 {
-            return Err(Error::from_kind(
-                ErrorKind::MessageContainsTextThatIsNotNULTerminated,
-            ));
-        }
-
-        let str_ptr = ptr;
-
-        if (*str_ptr.offset((size - 1) as isize)) != 0u8 {
+        let Some(len) = text_len(ptr, size) else {
             return Err(Error::from_kind(
                 ErrorKind::MessageContainsTextThatIsNotNULTerminated,
             ));
-        }
+        };
 
-        Ok(text::Reader(slice::from_raw_parts(
-            str_ptr,
-            size as usize - 1,
-        )))
+        Ok(text::Reader(slice::from_raw_parts(ptr, len as usize)))
     }
 
     #[inline]
@@ -2757,6 +3665,13 @@ impl CapTableReader {
         self.len() == 0
     }
 
+    /// Whether there is no table, as when the reader has not been imbued with one.
+    pub fn is_null(&self) -> bool {
+        match *self {
+            Self::Plain(hooks) => hooks.is_null(),
+        }
+    }
+
     pub fn extract_cap(&self, index: usize) -> Option<Box<dyn ClientHook>> {
         match *self {
             Self::Plain(hooks) => {
@@ -2795,6 +3710,13 @@ impl CapTableBuilder {
         }
     }
 
+    /// Whether there is no table, as when the builder has not been imbued with one.
+    pub fn is_null(&self) -> bool {
+        match *self {
+            Self::Plain(hooks) => hooks.is_null(),
+        }
+    }
+
     #[cfg(feature = "alloc")]
     pub fn extract_cap(&self, index: usize) -> Option<Box<dyn ClientHook>> {
         match *self {
@@ -2855,6 +3777,7 @@ pub struct PointerReader<'a> {
     pointer: *const WirePointer,
     segment_id: u32,
     nesting_limit: i32,
+    list_nesting_limit: i32,
 }
 
 impl<'a> PointerReader<'a> {
@@ -2865,6 +3788,7 @@ impl<'a> PointerReader<'a> {
             cap_table: Default::default(),
             pointer: ptr::null(),
             nesting_limit: 0x7fffffff,
+            list_nesting_limit: 0x7fffffff,
         }
     }
 
@@ -2888,6 +3812,7 @@ impl<'a> PointerReader<'a> {
             cap_table: Default::default(),
             pointer: location as *const _,
             nesting_limit,
+            list_nesting_limit: arena.list_nesting_limit(),
         })
     }
 
@@ -2902,6 +3827,36 @@ impl<'a> PointerReader<'a> {
         }
     }
 
+    pub(crate) fn arena(&self) -> &'a dyn ReaderArena {
+        self.arena
+    }
+
+    /// Returns a copy of this reader that reads through `arena`, which must wrap this reader's
+    /// arena.
+    pub(crate) fn with_limits<'b>(&self, arena: &'b LimitedReaderArena<'a>) -> PointerReader<'b> {
+        PointerReader { arena, ..*self }
+    }
+
+    /// Returns a copy of this reader that reads through `arena`, as made by
+    /// `LimitedReaderArena::with_options()`, and whose nesting limits are at most those of
+    /// `options`.
+    pub(crate) fn with_options<'b>(
+        &self,
+        arena: &'b LimitedReaderArena<'a>,
+        options: &crate::message::ReaderOptions,
+    ) -> PointerReader<'b> {
+        PointerReader {
+            arena,
+            nesting_limit: self.nesting_limit.min(options.nesting_limit),
+            list_nesting_limit: options
+                .list_nesting_limit
+                .map_or(self.list_nesting_limit, |limit| {
+                    self.list_nesting_limit.min(limit)
+                }),
+            ..*self
+        }
+    }
+
     pub unsafe fn get_root_unchecked<'b>(location: *const u8) -> PointerReader<'b> {
         PointerReader {
             arena: &NULL_ARENA,
@@ -2909,11 +3864,14 @@ impl<'a> PointerReader<'a> {
             cap_table: Default::default(),
             pointer: location as *const _,
             nesting_limit: 0x7fffffff,
+            list_nesting_limit: 0x7fffffff,
         }
     }
 
     pub fn imbue(&mut self, cap_table: CapTableReader) {
-        self.cap_table = cap_table;
+        #[cfg(feature = "alloc")]
+        self.arena.imbue_cap_table(cap_table.len());
+        self.cap_table = cap_table
     }
 
     #[inline]
@@ -2921,6 +3879,102 @@ impl<'a> PointerReader<'a> {
         self.pointer.is_null() || unsafe { (*self.pointer).is_null() }
     }
 
+    /// The kind of the pointer, as read from the pointer itself, without following it.
+    pub(crate) fn kind_name(&self) -> &'static str {
+        if self.is_null() {
+            return "null";
+        }
+        let pointer = unsafe { &*self.pointer };
+        match pointer.kind() {
+            WirePointerKind::Struct => "struct",
+            WirePointerKind::List => "list",
+            WirePointerKind::Far => "far",
+            WirePointerKind::Other if pointer.is_capability() => "capability",
+            WirePointerKind::Other => "unknown",
+        }
+    }
+
+    /// Calls `on_cap` with the cap table index of each capability pointer reachable from this
+    /// pointer, in the order they are encountered.
+    pub fn visit_capabilities(&self, on_cap: &mut dyn FnMut(u32)) -> Result<()> {
+        if !self.pointer.is_null() {
+            unsafe {
+                wire_helpers::total_size_visiting_caps(
+                    self.arena,
+                    self.segment_id,
+                    self.pointer,
+                    self.nesting_limit,
+                    on_cap,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Hashes the value this pointer points to into `hasher`.
+    pub(crate) fn fingerprint(&self, hasher: &mut Hasher) -> Result<()> {
+        if self.pointer.is_null() {
+            hasher.null();
+            Ok(())
+        } else {
+            unsafe {
+                wire_helpers::fingerprint(
+                    self.arena,
+                    self.segment_id,
+                    self.pointer,
+                    self.nesting_limit,
+                    hasher,
+                )
+            }
+        }
+    }
+
+    /// Returns whether this pointer and `other` point to equal values, as
+    /// `any_pointer::Reader::eq_deep()` describes.
+    pub(crate) fn eq_deep(&self, other: &PointerReader<'_>) -> Result<bool> {
+        match (self.get_pointer_type()?, other.get_pointer_type()?) {
+            (PointerType::Null, PointerType::Null) => Ok(true),
+            (PointerType::Struct, PointerType::Struct) => {
+                self.get_struct(None)?.eq_deep(&other.get_struct(None)?)
+            }
+            (PointerType::List, PointerType::List) => self
+                .get_list_any_size(None)?
+                .eq_deep(&other.get_list_any_size(None)?),
+            (PointerType::Capability(_), _) | (_, PointerType::Capability(_)) => {
+                Err(Error::from_kind(ErrorKind::CannotCompareCapabilities))
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Tells `visitor` about every segment of the message, then about the words occupied by this
+    /// pointer and by each object reachable from it.
+    pub(crate) fn walk_objects(&self, visitor: &mut dyn ObjectVisitor) -> Result<()> {
+        if self.pointer.is_null() {
+            return Ok(());
+        }
+        let mut id = 0;
+        while let Ok((_, word_count)) = self.arena.get_segment(id) {
+            visitor.segment(id, word_count);
+            id += 1;
+        }
+        unsafe {
+            let word_offset = wire_helpers::word_offset_in_segment(
+                self.arena,
+                self.segment_id,
+                self.pointer as *const u8,
+            )?;
+            visitor.occupied(self.segment_id, word_offset, 1);
+            wire_helpers::walk_objects(
+                self.arena,
+                self.segment_id,
+                self.pointer,
+                self.nesting_limit,
+                visitor,
+            )
+        }
+    }
+
     pub fn total_size(&self) -> Result<MessageSize> {
         if self.pointer.is_null() {
             Ok(MessageSize {
@@ -2953,10 +4007,14 @@ impl<'a> PointerReader<'a> {
                 reff,
                 default,
                 self.nesting_limit,
+                self.list_nesting_limit,
             )
         }
     }
 
+    /// Checks that every element of the list lies within its segment before returning, so that a
+    /// list that declares more elements than there is room for fails here, rather than when an
+    /// element past the end is read.
     pub fn get_list(
         self,
         expected_element_size: ElementSize,
@@ -2980,6 +4038,7 @@ impl<'a> PointerReader<'a> {
                 default_value,
                 Some(expected_element_size),
                 self.nesting_limit,
+                self.list_nesting_limit,
             )
         }
     }
@@ -3003,6 +4062,7 @@ impl<'a> PointerReader<'a> {
                 default_value,
                 None,
                 self.nesting_limit,
+                self.list_nesting_limit,
             )
         }
     }
@@ -3065,32 +4125,159 @@ impl<'a> PointerReader<'a> {
         }
     }
 
-    pub fn is_canonical(&self, read_head: &Cell<*const u8>) -> Result<bool> {
-        if self.pointer.is_null() || unsafe { !(*self.pointer).is_positional() } {
-            return Ok(false);
+    /// Returns the first way in which this pointer, taken as the root pointer of a message that
+    /// consists of the segment holding it alone, is not canonical, if any. The message is walked
+    /// in the same way as by `check_no_overlap()`, with the further requirement that each object
+    /// start where the previous one ends, in depth-first pointer order.
+    pub fn canonical_violation(&self) -> Result<Option<NonCanonical>> {
+        if self.pointer.is_null() {
+            return Ok(Some(NonCanonical::NonPositionalPointer));
+        }
+        let mut checker = CanonicalChecker {
+            segment_id: self.segment_id,
+            segment_words: 0,
+            next_word: 0,
+            violation: None,
+        };
+        self.walk_objects(&mut checker)?;
+        if checker.violation.is_none() && checker.next_word != checker.segment_words {
+            checker.report(NonCanonical::UnreachableWords);
         }
+        Ok(checker.violation)
+    }
+}
 
-        match self.get_pointer_type()? {
-            PointerType::Null => Ok(true),
-            PointerType::Struct => {
-                let mut data_trunc = false;
-                let mut ptr_trunc = false;
-                let st = self.get_struct(None)?;
-                if st.get_data_section_size() == 0 && st.get_pointer_section_size() == 0 {
-                    Ok(self.pointer as *const _ == st.get_location())
-                } else {
-                    let result =
-                        st.is_canonical(read_head, read_head, &mut data_trunc, &mut ptr_trunc)?;
-                    Ok(result && data_trunc && ptr_trunc)
+/// Looks for the first violation of canonical form in a walk of a single-segment message.
+struct CanonicalChecker {
+    segment_id: u32,
+    segment_words: u32,
+
+    /// The word at which the next object must start.
+    next_word: u32,
+    violation: Option<NonCanonical>,
+}
+
+impl CanonicalChecker {
+    fn report(&mut self, violation: NonCanonical) {
+        self.violation.get_or_insert(violation);
+    }
+
+    /// Checks the truncation of the struct at the start of `words`, returning whether its data
+    /// section, and whether its pointer section, ends with a non-zero word, or is empty.
+    fn truncation(words: &[u8], data_words: usize, pointer_count: usize) -> (bool, bool) {
+        let nonzero = |word: usize| words[word * BYTES_PER_WORD..][..BYTES_PER_WORD] != [0; 8];
+        (
+            data_words == 0 || nonzero(data_words - 1),
+            pointer_count == 0 || nonzero(data_words + pointer_count - 1),
+        )
+    }
+}
+
+impl ObjectVisitor for CanonicalChecker {
+    fn segment(&mut self, segment_id: u32, word_count: u32) {
+        if segment_id == self.segment_id {
+            self.segment_words = word_count;
+        }
+    }
+
+    fn pointer(&mut self, reff: &WirePointer) -> bool {
+        if self.violation.is_some() {
+            false
+        } else if reff.is_capability() {
+            self.report(NonCanonical::Capability);
+            false
+        } else if !reff.is_positional() {
+            self.report(NonCanonical::NonPositionalPointer);
+            false
+        } else {
+            true
+        }
+    }
+
+    fn occupied(&mut self, _segment_id: u32, word_offset: u32, word_count: u32) -> bool {
+        if self.violation.is_some() {
+            return false;
+        }
+        // Where the empty objects must be is checked by object().
+        if word_count > 0 {
+            if word_offset != self.next_word {
+                self.report(NonCanonical::OutOfOrder);
+                return false;
+            }
+            self.next_word += word_count;
+        }
+        true
+    }
+
+    fn object(&mut self, reff: &WirePointer, word_offset: u32, words: &[u8]) {
+        if reff.kind() == WirePointerKind::Struct {
+            let data_words = usize::from(reff.struct_data_size());
+            let pointer_count = usize::from(reff.struct_ptr_count());
+            if data_words + pointer_count == 0 {
+                // An empty struct points at its own pointer.
+                if reff.offset_and_kind.get() as i32 >> 2 != -1 {
+                    self.report(NonCanonical::ZeroSizedStructOffset);
+                }
+            } else if Self::truncation(words, data_words, pointer_count) != (true, true) {
+                self.report(NonCanonical::UntruncatedStruct);
+            }
+            return;
+        }
+        match reff.list_element_size() {
+            ElementSize::Void => {
+                if word_offset != self.next_word {
+                    self.report(NonCanonical::OutOfOrder);
+                }
+            }
+            ElementSize::Pointer => {}
+            ElementSize::InlineComposite => {
+                let tag = unsafe { &*(words.as_ptr() as *const WirePointer) };
+                let data_words = usize::from(tag.struct_data_size());
+                let pointer_count = usize::from(tag.struct_ptr_count());
+                let struct_words = data_words + pointer_count;
+                let element_count = tag.inline_composite_list_element_count() as usize;
+                if struct_words * element_count != reff.list_inline_composite_word_count() as usize
+                {
+                    self.report(NonCanonical::ListWordCount);
+                } else if struct_words > 0 {
+                    // Each section must end with a non-zero word in at least one element.
+                    let (data_truncated, pointers_truncated) = words[BYTES_PER_WORD..]
+                        .chunks(struct_words * BYTES_PER_WORD)
+                        .map(|element| Self::truncation(element, data_words, pointer_count))
+                        .fold(
+                            (false, false),
+                            |(data, pointers), (element_data, element_pointers)| {
+                                (data || element_data, pointers || element_pointers)
+                            },
+                        );
+                    if !(data_truncated && pointers_truncated) {
+                        self.report(NonCanonical::UntruncatedStruct);
+                    }
+                }
+            }
+            element_size => {
+                // The bits after the last element, up to the end of the word, must be zero.
+                let bits = u64::from(reff.list_element_count())
+                    * u64::from(data_bits_per_element(element_size));
+                let mut padding = &words[(bits / BITS_PER_BYTE as u64) as usize..];
+                let leftover_bits = bits % BITS_PER_BYTE as u64;
+                if leftover_bits > 0 {
+                    let mask: u8 = !((1 << leftover_bits as u8) - 1);
+                    if padding[0] & mask != 0 {
+                        self.report(NonCanonical::NonZeroPadding);
+                    }
+                    padding = &padding[1..];
+                }
+                if padding.iter().any(|&byte| byte != 0) {
+                    self.report(NonCanonical::NonZeroPadding);
                 }
             }
-            PointerType::List => unsafe {
-                self.get_list_any_size(None)?
-                    .is_canonical(read_head, self.pointer)
-            },
-            PointerType::Capability(_) => Ok(false),
         }
     }
+
+    fn enter(&mut self, _step: PathStep) {}
+
+    fn leave(&mut self) {}
 }
 
 pub struct PointerBuilder<'a> {
@@ -3100,6 +4287,29 @@ pub struct PointerBuilder<'a> {
     pointer: *mut WirePointer,
 }
 
+/// Checks the pointer at `reff`, which has just been written, if paranoid builder checks are
+/// enabled, as they are by `debug_assertions` and by the `paranoid-builder` feature. Otherwise
+/// this does nothing.
+#[inline(always)]
+fn check_written_pointer(arena: &dyn BuilderArena, segment_id: u32, reff: *const WirePointer) {
+    #[cfg(any(debug_assertions, feature = "paranoid-builder"))]
+    unsafe {
+        wire_helpers::check_written_pointer(arena, segment_id, reff)
+    }
+    #[cfg(not(any(debug_assertions, feature = "paranoid-builder")))]
+    let _ = (arena, segment_id, reff);
+}
+
+/// Unwraps the result of an allocation made by an operation that cannot return an error, such as
+/// `PointerBuilder::init_struct()`, panicking with the error if the allocator had no room.
+#[inline]
+fn expect_room<T>(result: Result<T>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(error) => panic!("{error}"),
+    }
+}
+
 impl<'a> PointerBuilder<'a> {
     #[inline]
     pub fn get_root(arena: &'a mut dyn BuilderArena, segment_id: u32, location: *mut u8) -> Self {
@@ -3133,6 +4343,7 @@ impl<'a> PointerBuilder<'a> {
         size: StructSize,
         default: Option<&'a [crate::Word]>,
     ) -> Result<StructBuilder<'a>> {
+        let (segment_id, pointer) = (self.segment_id, self.pointer);
         unsafe {
             wire_helpers::get_writable_struct_pointer(
                 self.arena,
@@ -3143,6 +4354,7 @@ impl<'a> PointerBuilder<'a> {
                 default,
             )
         }
+        .inspect(|builder| check_written_pointer(builder.arena, segment_id, pointer))
     }
 
     pub fn get_list(
@@ -3154,6 +4366,7 @@ impl<'a> PointerBuilder<'a> {
             None => core::ptr::null(),
             Some(d) => d.as_ptr() as *const u8,
         };
+        let (segment_id, pointer) = (self.segment_id, self.pointer);
         unsafe {
             wire_helpers::get_writable_list_pointer(
                 self.arena,
@@ -3164,6 +4377,7 @@ impl<'a> PointerBuilder<'a> {
                 default_value,
             )
         }
+        .inspect(|builder| check_written_pointer(builder.arena, segment_id, pointer))
     }
 
     pub fn get_struct_list(
@@ -3175,6 +4389,7 @@ impl<'a> PointerBuilder<'a> {
             None => core::ptr::null(),
             Some(d) => d.as_ptr() as *const u8,
         };
+        let (segment_id, pointer) = (self.segment_id, self.pointer);
         unsafe {
             wire_helpers::get_writable_struct_list_pointer(
                 self.arena,
@@ -3185,6 +4400,7 @@ impl<'a> PointerBuilder<'a> {
                 default_value,
             )
         }
+        .inspect(|builder| check_written_pointer(builder.arena, segment_id, pointer))
     }
 
     pub fn get_text(self, default: Option<&'a [crate::Word]>) -> Result<text::Builder<'a>> {
@@ -3222,24 +4438,44 @@ impl<'a> PointerBuilder<'a> {
         }
     }
 
+    /// Panics if the allocator has no room for the struct. See `try_init_struct()`.
     pub fn init_struct(self, size: StructSize) -> StructBuilder<'a> {
-        unsafe {
+        expect_room(self.try_init_struct(size))
+    }
+
+    /// Like `init_struct()`, but fails with `Overloaded` instead of panicking.
+    pub fn try_init_struct(self, size: StructSize) -> Result<StructBuilder<'a>> {
+        let (segment_id, pointer) = (self.segment_id, self.pointer);
+        let builder = unsafe {
             wire_helpers::init_struct_pointer(
                 self.arena,
                 self.pointer,
                 self.segment_id,
                 self.cap_table,
                 size,
-            )
-        }
+            )?
+        };
+        check_written_pointer(builder.arena, segment_id, pointer);
+        Ok(builder)
     }
 
+    /// Panics if the allocator has no room for the list. See `try_init_list()`.
     pub fn init_list(
         self,
         element_size: ElementSize,
         element_count: ElementCount32,
     ) -> ListBuilder<'a> {
-        unsafe {
+        expect_room(self.try_init_list(element_size, element_count))
+    }
+
+    /// Like `init_list()`, but fails with `Overloaded` instead of panicking.
+    pub fn try_init_list(
+        self,
+        element_size: ElementSize,
+        element_count: ElementCount32,
+    ) -> Result<ListBuilder<'a>> {
+        let (segment_id, pointer) = (self.segment_id, self.pointer);
+        let builder = unsafe {
             wire_helpers::init_list_pointer(
                 self.arena,
                 self.pointer,
@@ -3247,16 +4483,29 @@ impl<'a> PointerBuilder<'a> {
                 self.cap_table,
                 element_count,
                 element_size,
-            )
-        }
+            )?
+        };
+        check_written_pointer(builder.arena, segment_id, pointer);
+        Ok(builder)
     }
 
+    /// Panics if the allocator has no room for the list. See `try_init_struct_list()`.
     pub fn init_struct_list(
         self,
         element_count: ElementCount32,
         element_size: StructSize,
     ) -> ListBuilder<'a> {
-        unsafe {
+        expect_room(self.try_init_struct_list(element_count, element_size))
+    }
+
+    /// Like `init_struct_list()`, but fails with `Overloaded` instead of panicking.
+    pub fn try_init_struct_list(
+        self,
+        element_count: ElementCount32,
+        element_size: StructSize,
+    ) -> Result<ListBuilder<'a>> {
+        let (segment_id, pointer) = (self.segment_id, self.pointer);
+        let builder = unsafe {
             wire_helpers::init_struct_list_pointer(
                 self.arena,
                 self.pointer,
@@ -3264,19 +4513,111 @@ impl<'a> PointerBuilder<'a> {
                 self.cap_table,
                 element_count,
                 element_size,
-            )
-        }
+            )?
+        };
+        check_written_pointer(builder.arena, segment_id, pointer);
+        Ok(builder)
     }
 
+    /// Panics if `size` is too large for the text, with its NUL terminator, to fit in a list, or
+    /// if the allocator has no room for it. See `try_init_text()`.
     pub fn init_text(self, size: ByteCount32) -> text::Builder<'a> {
-        unsafe {
-            wire_helpers::init_text_pointer(self.arena, self.pointer, self.segment_id, size).value
+        expect_room(self.try_init_text(size))
+    }
+
+    /// Like `init_text()`, but fails with `BlobTooLong` or `Overloaded` instead of panicking.
+    pub fn try_init_text(self, size: ByteCount32) -> Result<text::Builder<'a>> {
+        wire_helpers::blob_element_count(size as usize, true)?;
+        Ok(unsafe {
+            wire_helpers::init_text_pointer(self.arena, self.pointer, self.segment_id, size)?
         }
+        .value)
     }
 
+    /// Panics if `size` is too large for the data to fit in a list, or if the allocator has no
+    /// room for it. See `try_init_data()`.
     pub fn init_data(self, size: ByteCount32) -> data::Builder<'a> {
+        expect_room(self.try_init_data(size))
+    }
+
+    /// Like `init_data()`, but fails with `BlobTooLong` or `Overloaded` instead of panicking.
+    pub fn try_init_data(self, size: ByteCount32) -> Result<data::Builder<'a>> {
+        wire_helpers::blob_element_count(size as usize, false)?;
+        Ok(unsafe {
+            wire_helpers::init_data_pointer(self.arena, self.pointer, self.segment_id, size)?
+        }
+        .value)
+    }
+
+    /// Allocates a byte list with room for `capacity` bytes, plus a NUL terminator if
+    /// `terminated` is set, whose length can later be cut down by `BlobReservation::finish()`.
+    ///
+    /// Fails with `BlobTooLong` if the list would have more than 2**29 - 1 elements.
+    pub fn reserve_blob(
+        self,
+        capacity: ByteCount32,
+        terminated: bool,
+    ) -> Result<BlobReservation<'a>> {
+        let byte_size = wire_helpers::blob_element_count(capacity as usize, terminated)?;
         unsafe {
-            wire_helpers::init_data_pointer(self.arena, self.pointer, self.segment_id, size).value
+            let (ptr, reff, segment_id) = wire_helpers::allocate(
+                self.arena,
+                self.pointer,
+                self.segment_id,
+                wire_helpers::round_bytes_up_to_words(byte_size),
+                WirePointerKind::List,
+            )?;
+            (*reff).set_list_size_and_count(ElementSize::Byte, byte_size);
+            check_written_pointer(self.arena, self.segment_id, self.pointer);
+            Ok(BlobReservation {
+                arena: self.arena,
+                segment_id,
+                reff,
+                ptr,
+                capacity,
+                terminated,
+            })
+        }
+    }
+
+    /// Wraps the existing byte list in a `BlobReservation` whose capacity is the list's current
+    /// length, not counting the NUL terminator if `terminated` is set.
+    pub fn get_blob_reservation(self, terminated: bool) -> Result<BlobReservation<'a>> {
+        unsafe {
+            if (*self.pointer).is_null() {
+                return Err(Error::from_kind(ErrorKind::ExistingPointerIsNotAList));
+            }
+            let (ptr, reff, segment_id) = wire_helpers::follow_builder_fars(
+                self.arena,
+                self.pointer,
+                WirePointer::mut_target(self.pointer),
+                self.segment_id,
+            )?;
+            if (*reff).kind() != WirePointerKind::List {
+                return Err(Error::from_kind(ErrorKind::ExistingPointerIsNotAList));
+            }
+            if (*reff).list_element_size() != ElementSize::Byte {
+                return Err(Error::from_kind(
+                    ErrorKind::ExistingListPointerIsNotByteSized,
+                ));
+            }
+            let count = (*reff).list_element_count();
+            let capacity = if terminated {
+                let Some(len) = wire_helpers::text_len(ptr, count) else {
+                    return Err(Error::from_kind(ErrorKind::TextBlobMissingNULTerminator));
+                };
+                len
+            } else {
+                count
+            };
+            Ok(BlobReservation {
+                arena: self.arena,
+                segment_id,
+                reff,
+                ptr,
+                capacity,
+                terminated,
+            })
         }
     }
 
@@ -3288,8 +4629,9 @@ impl<'a> PointerBuilder<'a> {
                 self.cap_table,
                 self.pointer,
                 *value,
-                canonicalize,
+                CopyMode::new(canonicalize),
             )?;
+            check_written_pointer(self.arena, self.segment_id, self.pointer);
             Ok(())
         }
     }
@@ -3302,22 +4644,57 @@ impl<'a> PointerBuilder<'a> {
                 self.cap_table,
                 self.pointer,
                 *value,
-                canonicalize,
+                CopyMode::new(canonicalize),
             )?;
+            check_written_pointer(self.arena, self.segment_id, self.pointer);
             Ok(())
         }
     }
 
+    /// Points this pointer at a copy of the canonical single-segment message in `words`.
+    ///
+    /// # Safety
+    /// `words` must be a canonical message, starting with its root pointer.
+    pub unsafe fn set_canonical_words(&mut self, words: &[crate::Word]) -> Result<()> {
+        unsafe {
+            wire_helpers::copy_canonical_words(self.arena, self.segment_id, self.pointer, words)?
+        }
+        check_written_pointer(self.arena, self.segment_id, self.pointer);
+        Ok(())
+    }
+
+    /// Panics if `value` is too long to fit in a list, or if the allocator has no room for it.
+    /// See `try_set_text()`.
     pub fn set_text(&mut self, value: crate::text::Reader<'_>) {
+        expect_room(unsafe {
+            wire_helpers::set_text_pointer(self.arena, self.pointer, self.segment_id, value)
+        });
+    }
+
+    /// Like `set_text()`, but fails with `BlobTooLong` or `Overloaded` instead of panicking.
+    pub fn try_set_text(&mut self, value: crate::text::Reader<'_>) -> Result<()> {
+        wire_helpers::blob_element_count(value.as_bytes().len(), true)?;
         unsafe {
-            wire_helpers::set_text_pointer(self.arena, self.pointer, self.segment_id, value);
+            wire_helpers::set_text_pointer(self.arena, self.pointer, self.segment_id, value)?;
         }
+        Ok(())
     }
 
+    /// Panics if `value` is too long to fit in a list, or if the allocator has no room for it.
+    /// See `try_set_data()`.
     pub fn set_data(&mut self, value: &[u8]) {
+        expect_room(unsafe {
+            wire_helpers::set_data_pointer(self.arena, self.pointer, self.segment_id, value)
+        });
+    }
+
+    /// Like `set_data()`, but fails with `BlobTooLong` or `Overloaded` instead of panicking.
+    pub fn try_set_data(&mut self, value: &[u8]) -> Result<()> {
+        wire_helpers::blob_element_count(value.len(), false)?;
         unsafe {
-            wire_helpers::set_data_pointer(self.arena, self.pointer, self.segment_id, value);
+            wire_helpers::set_data_pointer(self.arena, self.pointer, self.segment_id, value)?;
         }
+        Ok(())
     }
 
     #[cfg(feature = "alloc")]
@@ -3329,13 +4706,36 @@ impl<'a> PointerBuilder<'a> {
             self.pointer,
             cap,
         );
+        check_written_pointer(self.arena, self.segment_id, self.pointer);
     }
 
     pub unsafe fn set_capability_directly(&mut self, cap: u32) {
         (*self.pointer).set_cap(cap);
+        check_written_pointer(self.arena, self.segment_id, self.pointer);
+    }
+
+    /// Overwrites the pointer with `word`, as it is.
+    ///
+    /// # Safety
+    /// Builders follow the pointers of their message without checking them, so `word` must be a
+    /// valid pointer to an object in this message, unless nothing but a paranoid builder check
+    /// will look at it.
+    pub unsafe fn set_raw_word(&mut self, word: crate::Word) {
+        ptr::write(self.pointer as *mut crate::Word, word);
+        check_written_pointer(self.arena, self.segment_id, self.pointer);
     }
 
     pub fn copy_from(&mut self, other: PointerReader, canonicalize: bool) -> Result<()> {
+        self.copy_from_with_mode(other, CopyMode::new(canonicalize))
+    }
+
+    /// Like `copy_from()`, but copies capability pointers as they are, so that the copy can only
+    /// be used with the cap table of `other`.
+    pub(crate) fn copy_from_keeping_cap_indices(&mut self, other: PointerReader) -> Result<()> {
+        self.copy_from_with_mode(other, CopyMode::KeepCapIndices)
+    }
+
+    fn copy_from_with_mode(&mut self, other: PointerReader, mode: CopyMode) -> Result<()> {
         if other.pointer.is_null() {
             if !self.pointer.is_null() {
                 unsafe {
@@ -3355,9 +4755,10 @@ impl<'a> PointerBuilder<'a> {
                     other.cap_table,
                     other.pointer,
                     other.nesting_limit,
-                    canonicalize,
+                    mode,
                 )?;
             }
+            check_written_pointer(self.arena, self.segment_id, self.pointer);
         }
         Ok(())
     }
@@ -3376,6 +4777,7 @@ impl<'a> PointerBuilder<'a> {
             cap_table: self.cap_table.into_reader(),
             pointer: self.pointer,
             nesting_limit: 0x7fffffff,
+            list_nesting_limit: 0x7fffffff,
         }
     }
 
@@ -3386,6 +4788,73 @@ impl<'a> PointerBuilder<'a> {
             cap_table: self.cap_table.into_reader(),
             pointer: self.pointer,
             nesting_limit: 0x7fffffff,
+            list_nesting_limit: 0x7fffffff,
+        }
+    }
+}
+
+/// A byte list allocated by `PointerBuilder::reserve_blob()`, whose final length is not known yet.
+pub struct BlobReservation<'a> {
+    arena: &'a mut dyn BuilderArena,
+    segment_id: u32,
+
+    /// The list pointer, which is the landing pad if the list ended up in another segment.
+    reff: *mut WirePointer,
+    ptr: *mut u8,
+
+    /// Does not include the NUL terminator.
+    capacity: ByteCount32,
+    terminated: bool,
+}
+
+impl<'a> BlobReservation<'a> {
+    pub fn capacity(&self) -> ByteCount32 {
+        self.capacity
+    }
+
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.ptr, self.capacity as usize) }
+    }
+
+    fn element_count(&self, len: ByteCount32) -> ElementCount32 {
+        if self.terminated {
+            wire_helpers::text_element_count(len)
+        } else {
+            len
+        }
+    }
+
+    /// Cuts the list down to its first `len` bytes (plus the NUL terminator, if any), zeroing the
+    /// rest. If nothing has been allocated in the segment since the list, the words that are no
+    /// longer needed are given back to the segment.
+    ///
+    /// Panics if `len` is greater than the capacity.
+    pub fn finish(self, len: ByteCount32) -> &'a mut [u8] {
+        assert!(
+            len <= self.capacity,
+            "length {len} exceeds reserved capacity {}",
+            self.capacity
+        );
+        let old_count = self.element_count(self.capacity);
+        let new_count = self.element_count(len);
+        let old_words = wire_helpers::round_bytes_up_to_words(old_count);
+        let new_words = wire_helpers::round_bytes_up_to_words(new_count);
+        unsafe {
+            ptr::write_bytes(
+                self.ptr.add(len as usize),
+                0,
+                (self.capacity - len) as usize,
+            );
+            (*self.reff).set_list_size_and_count(ElementSize::Byte, new_count);
+            if new_words < old_words {
+                let (seg_start, _) = self.arena.get_segment_mut(self.segment_id);
+                let end =
+                    (self.ptr.offset_from(seg_start) as usize / BYTES_PER_WORD) as u32 + old_words;
+                self.arena
+                    .reclaim(self.segment_id, end, old_words - new_words);
+            }
+            check_written_pointer(self.arena, self.segment_id, self.reff);
+            slice::from_raw_parts_mut(self.ptr, len as usize)
         }
     }
 }
@@ -3400,6 +4869,7 @@ pub struct StructReader<'a> {
     data_size: BitCount32,
     pointer_count: WirePointerCount16,
     nesting_limit: i32,
+    list_nesting_limit: i32,
 }
 
 impl<'a> StructReader<'a> {
@@ -3413,10 +4883,23 @@ impl<'a> StructReader<'a> {
             data_size: 0,
             pointer_count: 0,
             nesting_limit: 0x7fffffff,
+            list_nesting_limit: 0x7fffffff,
         }
     }
 
+    pub(crate) fn arena(&self) -> &'a dyn ReaderArena {
+        self.arena
+    }
+
+    /// Returns a copy of this reader that reads through `arena`, which must wrap this reader's
+    /// arena.
+    pub(crate) fn with_limits<'b>(&self, arena: &'b LimitedReaderArena<'a>) -> StructReader<'b> {
+        StructReader { arena, ..*self }
+    }
+
     pub fn imbue(&mut self, cap_table: CapTableReader) {
+        #[cfg(feature = "alloc")]
+        self.arena.imbue_cap_table(cap_table.len());
         self.cap_table = cap_table
     }
 
@@ -3440,6 +4923,7 @@ impl<'a> StructReader<'a> {
             struct_data_size: 0,
             struct_pointer_count: 0,
             nesting_limit: self.nesting_limit,
+            list_nesting_limit: self.list_nesting_limit,
         }
     }
 
@@ -3455,6 +4939,82 @@ impl<'a> StructReader<'a> {
         }
     }
 
+    /// Returns the data section exactly as it is on the wire, e.g. to compute a digest over it.
+    /// Each field is stored XORed with its default value, so a field that has its default value
+    /// is all zeros here, whichever implementation wrote it.
+    pub fn raw_data(&self) -> &'a [u8] {
+        self.get_data_section_as_blob()
+    }
+
+    /// Returns the pointer section exactly as it is on the wire. Pointers encode the offsets of
+    /// their targets, which depend on how the message was built, so these words only agree
+    /// between implementations for canonical messages.
+    ///
+    /// Returns `None` if the section is not 8-byte aligned, which can only happen with the
+    /// `unaligned` feature.
+    pub fn raw_pointer_section_words(&self) -> Option<&'a [crate::Word]> {
+        if self.pointer_count == 0 {
+            Some(&[])
+        } else if self.pointers as usize % BYTES_PER_WORD != 0 {
+            None
+        } else {
+            Some(unsafe {
+                ::core::slice::from_raw_parts(
+                    self.pointers as *const crate::Word,
+                    usize::from(self.pointer_count),
+                )
+            })
+        }
+    }
+
+    /// Copies the first `out.len()` bytes of the data section into `out` in one go, so that a
+    /// number of fields can be decoded from the copy without re-checking bounds for each one.
+    /// Bytes past the end of the data section are zeroed, as if read from an older version of the
+    /// struct.
+    #[inline]
+    pub fn read_data_prefix(&self, out: &mut [u8]) {
+        let data = self.get_data_section_as_blob();
+        if let Some(prefix) = data.get(..out.len()) {
+            // The common case, where the copy has the (usually constant) length of `out`.
+            out.copy_from_slice(prefix);
+        } else {
+            out[..data.len()].copy_from_slice(data);
+            out[data.len()..].fill(0);
+        }
+    }
+
+    /// Copies the data section, which holds all of the struct's primitive fields. If the data
+    /// section is a single bit, as for an element of a bit list read as a struct list, the copy
+    /// is one byte with the other bits cleared.
+    #[cfg(feature = "alloc")]
+    pub fn copy_data_section(&self) -> Vec<u8> {
+        if self.data_size == 0 {
+            return Vec::new();
+        }
+        let len = wire_helpers::round_bits_up_to_bytes(u64::from(self.data_size)) as usize;
+        let mut bytes = unsafe { slice::from_raw_parts(self.data, len) }.to_vec();
+        let extra_bits = self.data_size as usize % BITS_PER_BYTE;
+        if extra_bits != 0 {
+            bytes[len - 1] &= (1 << extra_bits) - 1;
+        }
+        bytes
+    }
+
+    /// Copies the data section into an `OwnedStructData`, which can outlive the message.
+    #[cfg(feature = "alloc")]
+    pub fn to_owned_data(&self) -> OwnedStructData {
+        OwnedStructData::with_data_size(&self.copy_data_section(), self.data_size)
+    }
+
+    /// Reads the field at `offset`, counted in units of `T`, or zero if it is past the end of the
+    /// data section.
+    ///
+    /// This cannot fail, so primitive getters return values rather than `Result`s; only following
+    /// a pointer can fail. The data section was bounds-checked when this reader was made:
+    /// `read_struct_pointer()` checks the whole struct against its segment, a list's elements are
+    /// checked together when the list is read, and a builder's data section is its own
+    /// allocation. A default reader has an empty data section. So every byte below `data_size` is
+    /// readable, and a field past it is the zero that an older writer left out.
     #[inline]
     pub fn get_data_field<T: Primitive + zero::Zero>(&self, offset: ElementCount) -> T {
         // We need to check the offset because the struct may have
@@ -3468,6 +5028,7 @@ impl<'a> StructReader<'a> {
         }
     }
 
+    /// Like `get_data_field()`, for the bit at `offset`.
     #[inline]
     pub fn get_bool_field(&self, offset: ElementCount) -> bool {
         let boffset: BitCount32 = offset as BitCount32;
@@ -3504,6 +5065,7 @@ impl<'a> StructReader<'a> {
                 cap_table: self.cap_table,
                 pointer: unsafe { self.pointers.add(ptr_index) },
                 nesting_limit: self.nesting_limit,
+                list_nesting_limit: self.list_nesting_limit,
             }
         } else {
             PointerReader::new_default()
@@ -3546,59 +5108,89 @@ impl<'a> StructReader<'a> {
         }
     }
 
-    fn get_location(&self) -> *const u8 {
-        self.data
-    }
-
-    pub fn is_canonical(
-        &self,
-        read_head: &Cell<*const u8>,
-        ptr_head: &Cell<*const u8>,
-        data_trunc: &mut bool,
-        ptr_trunc: &mut bool,
-    ) -> Result<bool> {
-        if self.get_location() != read_head.get() {
+    /// Returns whether this struct and `other` have equal sections. Where one section is longer
+    /// than the other, its extra data must be zero and its extra pointers null, as that is how
+    /// the missing fields of the shorter one read.
+    pub(crate) fn eq_deep(&self, other: &StructReader<'_>) -> Result<bool> {
+        let data = self.get_data_section_as_blob();
+        let other_data = other.get_data_section_as_blob();
+        let common = data.len().min(other_data.len());
+        if data[..common] != other_data[..common]
+            || data[common..]
+                .iter()
+                .chain(&other_data[common..])
+                .any(|&byte| byte != 0)
+        {
             return Ok(false);
         }
 
-        if self.get_data_section_size() % BITS_PER_WORD as u32 != 0 {
-            // legacy non-word-size struct
-            return Ok(false);
+        let common = self.pointer_count.min(other.pointer_count) as usize;
+        for i in 0..common {
+            if !self
+                .get_pointer_field(i)
+                .eq_deep(&other.get_pointer_field(i))?
+            {
+                return Ok(false);
+            }
         }
+        Ok(
+            (common..self.pointer_count as usize).all(|i| self.is_pointer_field_null(i))
+                && (common..other.pointer_count as usize).all(|i| other.is_pointer_field_null(i)),
+        )
+    }
+}
 
-        let data_size = self.get_data_section_size() / BITS_PER_WORD as u32;
+/// An owned copy of a struct's data section, for keeping a snapshot of its primitive fields
+/// without keeping the message alive, for example to send it to another thread.
+///
+/// Made by `raw::to_owned_struct_data()`, and exported as `raw::OwnedStructData`.
+///
+/// `reader()` reads the copy as a struct with no pointer section, so reading a pointer field
+/// from it gives the field's default value, as if the struct had been written by an older
+/// version of the schema that lacked the field.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OwnedStructData {
+    /// The data section, padded out to whole words to keep it aligned.
+    words: Vec<crate::Word>,
+    data_size: BitCount32,
+}
 
-        // mark whether the struct is properly truncated
-        if data_size != 0 {
-            *data_trunc = self.get_data_field::<u64>((data_size - 1) as usize) != 0;
-        } else {
-            *data_trunc = true;
-        }
+#[cfg(feature = "alloc")]
+impl OwnedStructData {
+    /// Wraps a data section, such as one returned by `raw::get_struct_data_section()`.
+    pub fn new(bytes: &[u8]) -> Self {
+        Self::with_data_size(bytes, (bytes.len() * BITS_PER_BYTE) as BitCount32)
+    }
 
-        if self.pointer_count != 0 {
-            *ptr_trunc = !self
-                .get_pointer_field(self.pointer_count as usize - 1)
-                .is_null();
-        } else {
-            *ptr_trunc = true;
-        }
+    fn with_data_size(bytes: &[u8], data_size: BitCount32) -> Self {
+        let mut words = crate::Word::allocate_zeroed_vec(bytes.len().div_ceil(BYTES_PER_WORD));
+        crate::Word::words_to_bytes_mut(&mut words)[..bytes.len()].copy_from_slice(bytes);
+        Self { words, data_size }
+    }
 
-        read_head.set(unsafe {
-            (read_head.get()).offset(
-                (data_size as isize + self.pointer_count as isize) * (BYTES_PER_WORD as isize),
-            )
-        });
+    /// The copied data section.
+    pub fn as_bytes(&self) -> &[u8] {
+        let len = wire_helpers::round_bits_up_to_bytes(u64::from(self.data_size)) as usize;
+        &crate::Word::words_to_bytes(&self.words)[..len]
+    }
 
-        for ptr_idx in 0..self.pointer_count {
-            if !self
-                .get_pointer_field(ptr_idx as usize)
-                .is_canonical(ptr_head)?
-            {
-                return Ok(false);
-            }
+    /// Returns a reader over the copy.
+    pub fn reader(&self) -> StructReader<'_> {
+        StructReader {
+            data: if self.words.is_empty() {
+                ptr::null()
+            } else {
+                self.words.as_ptr() as *const u8
+            },
+            data_size: self.data_size,
+            ..StructReader::new_default()
         }
+    }
 
-        Ok(true)
+    /// Returns a reader of struct type `T` over the copy.
+    pub fn get<T: crate::traits::OwnedStruct>(&self) -> T::Reader<'_> {
+        self.reader().into()
     }
 }
 
@@ -3631,6 +5223,7 @@ impl<'a> StructBuilder<'a> {
             segment_id: self.segment_id,
             data_size: self.data_size,
             nesting_limit: 0x7fffffff,
+            list_nesting_limit: 0x7fffffff,
         }
     }
 
@@ -3644,6 +5237,7 @@ impl<'a> StructBuilder<'a> {
             segment_id: self.segment_id,
             data_size: self.data_size,
             nesting_limit: 0x7fffffff,
+            list_nesting_limit: 0x7fffffff,
         }
     }
 
@@ -3651,6 +5245,35 @@ impl<'a> StructBuilder<'a> {
         self.cap_table = cap_table
     }
 
+    pub fn get_data_section_size(&self) -> BitCount32 {
+        self.data_size
+    }
+
+    pub fn get_pointer_section_size(&self) -> WirePointerCount16 {
+        self.pointer_count
+    }
+
+    /// Copies `bytes` into the start of the data section in one go. This is the counterpart of
+    /// `StructReader::read_data_prefix()`.
+    ///
+    /// Panics if `bytes` is longer than the data section.
+    #[inline]
+    pub fn write_data_prefix(&self, bytes: &[u8]) {
+        let data_len = self.data_size as usize / BITS_PER_BYTE;
+        assert!(
+            bytes.len() <= data_len,
+            "prefix of {} bytes is longer than the data section of {data_len} bytes",
+            bytes.len()
+        );
+        unsafe { ptr::copy_nonoverlapping(bytes.as_ptr(), self.data, bytes.len()) }
+    }
+
+    /// Writes the field at `offset`, counted in units of `T`. Unlike the reader's accessors, the
+    /// builder's do not check `offset` against `data_size`: a `StructBuilder` for a type is only
+    /// handed out once its data section is at least as large as the type's, since
+    /// `get_writable_struct_pointer()` and `get_writable_struct_list_pointer()` copy smaller structs
+    /// into a large enough allocation first, so the offsets that generated code passes are in
+    /// bounds.
     #[inline]
     pub fn set_data_field<T: Primitive>(&self, offset: ElementCount, value: T) {
         let ptr: *mut <T as Primitive>::Raw = self.data as *mut _;
@@ -3807,7 +5430,7 @@ impl<'a> StructBuilder<'a> {
                     other.cap_table,
                     other.pointers.offset(i),
                     other.nesting_limit,
-                    false,
+                    CopyMode::Plain,
                 )?;
             }
         }
@@ -3864,6 +5487,7 @@ pub struct ListReader<'a> {
     step: BitCount32,
     struct_data_size: BitCount32,
     nesting_limit: i32,
+    list_nesting_limit: i32,
     struct_pointer_count: WirePointerCount16,
     element_size: ElementSize,
 }
@@ -3881,13 +5505,55 @@ impl<'a> ListReader<'a> {
             struct_data_size: 0,
             struct_pointer_count: 0,
             nesting_limit: 0x7fffffff,
+            list_nesting_limit: 0x7fffffff,
         }
     }
 
     pub fn imbue(&mut self, cap_table: CapTableReader) {
+        #[cfg(feature = "alloc")]
+        self.arena.imbue_cap_table(cap_table.len());
         self.cap_table = cap_table
     }
 
+    pub(crate) fn arena(&self) -> &'a dyn ReaderArena {
+        self.arena
+    }
+
+    /// Returns a copy of this reader that reads through `arena`, which must wrap this reader's
+    /// arena.
+    pub(crate) fn with_limits<'b>(&self, arena: &'b LimitedReaderArena<'a>) -> ListReader<'b> {
+        ListReader { arena, ..*self }
+    }
+
+    /// Returns whether this reader has a capability table, which, unlike its arena, is never
+    /// `Sync`.
+    #[cfg(feature = "sync_reader")]
+    pub(crate) fn has_cap_table(&self) -> bool {
+        let CapTableReader::Plain(cap_table) = self.cap_table;
+        !cap_table.is_null()
+    }
+
+    /// Splits the list into the elements before `mid` and those from `mid` on. Panics if `mid` is
+    /// greater than `len()`, or if `mid` elements do not end on a byte boundary.
+    pub fn split_at(&self, mid: ElementCount32) -> (ListReader<'a>, ListReader<'a>) {
+        assert!(mid <= self.element_count);
+        let mid_bits = u64::from(mid) * u64::from(self.step);
+        assert_eq!(mid_bits % BITS_PER_BYTE as u64, 0);
+        let head = ListReader {
+            element_count: mid,
+            ..*self
+        };
+        let tail = ListReader {
+            // `wrapping_add()`, as the pointer of an empty list may be null.
+            ptr: self
+                .ptr
+                .wrapping_add((mid_bits / BITS_PER_BYTE as u64) as usize),
+            element_count: self.element_count - mid,
+            ..*self
+        };
+        (head, tail)
+    }
+
     #[inline]
     pub fn len(&self) -> ElementCount32 {
         self.element_count
@@ -3925,6 +5591,51 @@ impl<'a> ListReader<'a> {
         }
     }
 
+    /// Returns whether this list and `other` have the same element size and length, and equal
+    /// elements. The bits past the end of a list of bits do not matter.
+    pub(crate) fn eq_deep(&self, other: &ListReader<'_>) -> Result<bool> {
+        if self.element_size != other.element_size || self.element_count != other.element_count {
+            return Ok(false);
+        }
+        let tail_bits = self.element_count % 8;
+        match self.element_size {
+            ElementSize::Void => Ok(true),
+            ElementSize::Bit if tail_bits != 0 => {
+                let (last, bytes) = self.into_raw_bytes().split_last().unwrap();
+                let (other_last, other_bytes) = other.into_raw_bytes().split_last().unwrap();
+                let mask = (1 << tail_bits) - 1;
+                Ok(bytes == other_bytes && last & mask == other_last & mask)
+            }
+            ElementSize::Bit
+            | ElementSize::Byte
+            | ElementSize::TwoBytes
+            | ElementSize::FourBytes
+            | ElementSize::EightBytes => Ok(self.into_raw_bytes() == other.into_raw_bytes()),
+            ElementSize::Pointer => {
+                for i in 0..self.element_count {
+                    if !self
+                        .get_pointer_element(i)
+                        .eq_deep(&other.get_pointer_element(i))?
+                    {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            ElementSize::InlineComposite => {
+                for i in 0..self.element_count {
+                    if !self
+                        .get_struct_element(i)
+                        .eq_deep(&other.get_struct_element(i))?
+                    {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+        }
+    }
+
     #[inline]
     pub fn get_struct_element(&self, index: ElementCount32) -> StructReader<'a> {
         let index_byte: ByteCount32 =
@@ -3944,7 +5655,20 @@ impl<'a> ListReader<'a> {
             data_size: self.struct_data_size,
             pointer_count: self.struct_pointer_count,
             nesting_limit: self.nesting_limit - 1,
+            list_nesting_limit: self.list_nesting_limit,
+        }
+    }
+
+    /// Like `get_pointer_element()`, for an element of a list of lists. Reading the element
+    /// counts against `ReaderOptions::list_nesting_limit`, in addition to the nesting limit.
+    pub fn get_list_list_element(self, index: ElementCount32) -> Result<PointerReader<'a>> {
+        if self.list_nesting_limit <= 0 {
+            return Err(Error::from_kind(ErrorKind::NestingLimitExceeded));
         }
+        Ok(PointerReader {
+            list_nesting_limit: self.list_nesting_limit - 1,
+            ..self.get_pointer_element(index)
+        })
     }
 
     #[inline]
@@ -3958,115 +5682,7 @@ impl<'a> ListReader<'a> {
             cap_table: self.cap_table,
             pointer: unsafe { self.ptr.offset(offset) } as *const _,
             nesting_limit: self.nesting_limit,
-        }
-    }
-
-    pub unsafe fn is_canonical(
-        &self,
-        read_head: &Cell<*const u8>,
-        reff: *const WirePointer,
-    ) -> Result<bool> {
-        match self.element_size {
-            ElementSize::InlineComposite => {
-                read_head.set(unsafe { read_head.get().add(BYTES_PER_WORD) }); // tag word
-                if self.ptr as *const _ != read_head.get() {
-                    return Ok(false);
-                }
-                if self.struct_data_size % BITS_PER_WORD as u32 != 0 {
-                    return Ok(false);
-                }
-                let struct_size = (self.struct_data_size / BITS_PER_WORD as u32)
-                    + u32::from(self.struct_pointer_count);
-                let word_count = unsafe { (*reff).list_inline_composite_word_count() };
-                if struct_size * self.element_count != word_count {
-                    return Ok(false);
-                }
-                if struct_size == 0 {
-                    return Ok(true);
-                }
-                let list_end = unsafe {
-                    read_head
-                        .get()
-                        .add((self.element_count * struct_size) as usize * BYTES_PER_WORD)
-                };
-                let pointer_head = Cell::new(list_end);
-                let mut list_data_trunc = false;
-                let mut list_ptr_trunc = false;
-                for idx in 0..self.element_count {
-                    let mut data_trunc = false;
-                    let mut ptr_trunc = false;
-                    if !self.get_struct_element(idx).is_canonical(
-                        read_head,
-                        &pointer_head,
-                        &mut data_trunc,
-                        &mut ptr_trunc,
-                    )? {
-                        return Ok(false);
-                    }
-                    list_data_trunc |= data_trunc;
-                    list_ptr_trunc |= ptr_trunc;
-                }
-                assert_eq!(read_head.get(), list_end);
-                read_head.set(pointer_head.get());
-                Ok(list_data_trunc && list_ptr_trunc)
-            }
-            ElementSize::Pointer => {
-                if self.ptr as *const _ != read_head.get() {
-                    return Ok(false);
-                }
-                read_head.set(unsafe {
-                    read_head
-                        .get()
-                        .offset(self.element_count as isize * BYTES_PER_WORD as isize)
-                });
-                for idx in 0..self.element_count {
-                    if !self.get_pointer_element(idx).is_canonical(read_head)? {
-                        return Ok(false);
-                    }
-                }
-                Ok(true)
-            }
-            element_size => {
-                if self.ptr != read_head.get() as *const _ {
-                    return Ok(false);
-                }
-                let bit_size =
-                    u64::from(self.element_count) * u64::from(data_bits_per_element(element_size));
-                let mut word_size = bit_size / BITS_PER_WORD as u64;
-                if bit_size % BITS_PER_WORD as u64 != 0 {
-                    word_size += 1
-                }
-
-                let byte_size = bit_size / BITS_PER_BYTE as u64;
-                let mut byte_read_head: *const u8 = read_head.get();
-                byte_read_head = unsafe { byte_read_head.offset(byte_size as isize) };
-                let read_head_end = unsafe {
-                    read_head
-                        .get()
-                        .offset(word_size as isize * BYTES_PER_WORD as isize)
-                };
-
-                let leftover_bits = bit_size % BITS_PER_BYTE as u64;
-                if leftover_bits > 0 {
-                    let mask: u8 = !((1 << leftover_bits as u8) - 1);
-                    let partial_byte = unsafe { *byte_read_head };
-
-                    if partial_byte & mask != 0 {
-                        return Ok(false);
-                    }
-                    byte_read_head = unsafe { byte_read_head.offset(1_isize) };
-                }
-
-                while byte_read_head != read_head_end {
-                    if unsafe { *byte_read_head } != 0 {
-                        return Ok(false);
-                    }
-                    byte_read_head = unsafe { byte_read_head.offset(1_isize) };
-                }
-
-                read_head.set(read_head_end);
-                Ok(true)
-            }
+            list_nesting_limit: self.list_nesting_limit,
         }
     }
 }
@@ -4111,6 +5727,7 @@ impl<'a> ListBuilder<'a> {
             struct_data_size: self.struct_data_size,
             struct_pointer_count: self.struct_pointer_count,
             nesting_limit: 0x7fffffff,
+            list_nesting_limit: 0x7fffffff,
         }
     }
 
@@ -4135,6 +5752,11 @@ impl<'a> ListBuilder<'a> {
         self.len() == 0
     }
 
+    #[inline]
+    pub(crate) fn get_step_size_in_bits(&self) -> u32 {
+        self.step
+    }
+
     #[inline]
     pub fn get_struct_element(self, index: ElementCount32) -> StructBuilder<'a> {
         let index_byte = ((u64::from(index) * u64::from(self.step)) / BITS_PER_BYTE as u64) as u32;
@@ -4156,6 +5778,41 @@ impl<'a> ListBuilder<'a> {
         self.element_size
     }
 
+    /// Swaps the struct elements at positions `a` and `b`, including their pointer fields.
+    /// Bounds checking is *not* performed.
+    pub fn swap_struct_elements(&mut self, a: ElementCount32, b: ElementCount32) {
+        if a == b {
+            return;
+        }
+        let a_bit = u64::from(a) * u64::from(self.step);
+        let b_bit = u64::from(b) * u64::from(self.step);
+        unsafe {
+            if self.element_size == Bit {
+                // A list of bools, viewed as a list of structs with a one-bit data section.
+                let a_byte = self.ptr.add((a_bit / BITS_PER_BYTE as u64) as usize);
+                let b_byte = self.ptr.add((b_bit / BITS_PER_BYTE as u64) as usize);
+                let a_shift = a_bit % BITS_PER_BYTE as u64;
+                let b_shift = b_bit % BITS_PER_BYTE as u64;
+                let a_value = (*a_byte >> a_shift) & 1;
+                let b_value = (*b_byte >> b_shift) & 1;
+                *a_byte = (*a_byte & !(1 << a_shift)) | (b_value << a_shift);
+                *b_byte = (*b_byte & !(1 << b_shift)) | (a_value << b_shift);
+                return;
+            }
+
+            let a_data = self.ptr.add((a_bit / BITS_PER_BYTE as u64) as usize);
+            let b_data = self.ptr.add((b_bit / BITS_PER_BYTE as u64) as usize);
+            let data_bytes = self.struct_data_size as usize / BITS_PER_BYTE;
+            ptr::swap_nonoverlapping(a_data, b_data, data_bytes);
+
+            let a_pointers = a_data.add(data_bytes) as *mut WirePointer;
+            let b_pointers = b_data.add(data_bytes) as *mut WirePointer;
+            for i in 0..self.struct_pointer_count as usize {
+                wire_helpers::swap_pointers(a_pointers.add(i), b_pointers.add(i));
+            }
+        }
+    }
+
     #[inline]
     pub fn get_pointer_element(self, index: ElementCount32) -> PointerBuilder<'a> {
         let offset = (u64::from(index) * u64::from(self.step) / BITS_PER_BYTE as u64) as u32;
@@ -4179,6 +5836,66 @@ impl<'a> ListBuilder<'a> {
             unsafe { ::core::slice::from_raw_parts_mut(self.ptr, num_bytes) }
         }
     }
+
+    /// Returns a cursor over the list's struct elements, starting at the first.
+    pub fn into_struct_cursor(self) -> StructElementCursor<'a> {
+        StructElementCursor {
+            remaining: self.element_count,
+            offset: 0,
+            list: self,
+        }
+    }
+}
+
+/// Walks the struct elements of a list in order. The cursor keeps a running offset to the next
+/// element, so each step is an addition and a single check against the end of the list.
+pub struct StructElementCursor<'a> {
+    list: ListBuilder<'a>,
+
+    /// The offset of the next element from the start of the list, in bits.
+    offset: u64,
+    remaining: ElementCount32,
+}
+
+impl StructElementCursor<'_> {
+    /// The number of elements that `next()` has not returned yet.
+    #[inline]
+    pub fn remaining(&self) -> ElementCount32 {
+        self.remaining
+    }
+
+    /// The index of the element that `next()` will return.
+    #[inline]
+    pub fn position(&self) -> ElementCount32 {
+        self.list.element_count - self.remaining
+    }
+
+    /// Returns the next element, or `None` if every element has been returned.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<StructBuilder<'_>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let data = unsafe {
+            self.list
+                .ptr
+                .add((self.offset / BITS_PER_BYTE as u64) as usize)
+        };
+        self.offset += u64::from(self.list.step);
+        self.remaining -= 1;
+        Some(StructBuilder {
+            arena: &mut *self.list.arena,
+            segment_id: self.list.segment_id,
+            cap_table: self.list.cap_table,
+            data,
+            pointers: unsafe {
+                data.add(self.list.struct_data_size as usize / BITS_PER_BYTE) as *mut _
+            },
+            data_size: self.list.struct_data_size,
+            pointer_count: self.list.struct_pointer_count,
+        })
+    }
 }
 
 /**
@@ -4194,6 +5911,16 @@ pub trait PrimitiveElement {
     /// Sets to element at position `index` to be `value`. Bounds checking is *not* performed.
     fn set(list_builder: &ListBuilder, index: ElementCount32, value: Self);
 
+    /// Sets the element that starts `bit_offset` bits into the list to be `value`, for callers
+    /// that keep a running offset instead of an index. Bounds checking is *not* performed.
+    fn set_at_bit_offset(list_builder: &ListBuilder, bit_offset: u64, value: Self)
+    where
+        Self: Sized,
+    {
+        let step = u64::from(list_builder.step).max(1);
+        Self::set(list_builder, (bit_offset / step) as ElementCount32, value)
+    }
+
     /// Returns the size of an individual element.
     fn element_size() -> ElementSize;
 }
@@ -4221,8 +5948,16 @@ impl<T: Primitive> PrimitiveElement for T {
 
     #[inline]
     fn set(list_builder: &ListBuilder, index: ElementCount32, value: Self) {
-        let offset =
-            (u64::from(index) * u64::from(list_builder.step) / BITS_PER_BYTE as u64) as u32;
+        Self::set_at_bit_offset(
+            list_builder,
+            u64::from(index) * u64::from(list_builder.step),
+            value,
+        )
+    }
+
+    #[inline]
+    fn set_at_bit_offset(list_builder: &ListBuilder, bit_offset: u64, value: Self) {
+        let offset = (bit_offset / BITS_PER_BYTE as u64) as u32;
         unsafe {
             let ptr: *mut <Self as Primitive>::Raw =
                 list_builder.ptr.offset(offset as isize) as *mut _;
@@ -4259,7 +5994,10 @@ impl PrimitiveElement for bool {
     }
     #[inline]
     fn set(list: &ListBuilder, index: ElementCount32, value: Self) {
-        let bindex = u64::from(index) * u64::from(list.step);
+        Self::set_at_bit_offset(list, u64::from(index) * u64::from(list.step), value)
+    }
+    #[inline]
+    fn set_at_bit_offset(list: &ListBuilder, bindex: u64, value: Self) {
         let b = unsafe { list.ptr.offset((bindex / BITS_PER_BYTE as u64) as isize) };
 
         let bitnum = bindex % BITS_PER_BYTE as u64;
@@ -4280,6 +6018,9 @@ impl PrimitiveElement for () {
     #[inline]
     fn set(_list: &ListBuilder, _index: ElementCount32, _value: ()) {}
 
+    #[inline]
+    fn set_at_bit_offset(_list: &ListBuilder, _bit_offset: u64, _value: ()) {}
+
     fn element_size() -> ElementSize {
         Void
     }
@@ -190,3 +190,201 @@ fn empty_struct_list_size() {
         assert_eq!(2, pointer_reader.total_size().unwrap().word_count);
     }
 }
+
+/// A struct with two data words holding the bytes 0x01 through 0x10.
+const TWO_DATA_WORDS: &[crate::Word] = &[
+    crate::word(0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00),
+    crate::word(0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08),
+    crate::word(0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10),
+];
+
+fn two_data_words_bytes() -> [u8; 16] {
+    core::array::from_fn(|idx| idx as u8 + 1)
+}
+
+macro_rules! check_fields(
+    ($reader:expr, $($typ:ty),*) => (
+        $(
+            let size = core::mem::size_of::<$typ>();
+            let bytes = two_data_words_bytes();
+            for idx in 0..16 / size {
+                let expected = <$typ>::from_le_bytes(
+                    bytes[idx * size..(idx + 1) * size].try_into().unwrap(),
+                );
+                let actual = $reader.get_data_field::<$typ>(idx);
+                assert_eq!(actual.to_le_bytes(), expected.to_le_bytes());
+            }
+            // past end of struct --> default value
+            assert_eq!($reader.get_data_field::<$typ>(16 / size).to_le_bytes(), [0; core::mem::size_of::<$typ>()]);
+        )*
+    );
+);
+
+#[test]
+fn primitive_fields_of_every_width() {
+    test_at_alignments(TWO_DATA_WORDS, &verify);
+    fn verify(pointer_reader: PointerReader) {
+        let reader = pointer_reader.get_struct(None).unwrap();
+        check_fields!(reader, u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+        let bytes = two_data_words_bytes();
+        for bit in 0..128 {
+            assert_eq!(
+                reader.get_bool_field(bit),
+                bytes[bit / 8] & (1 << (bit % 8)) != 0
+            );
+        }
+    }
+}
+
+/// A struct with two data words and no pointers, built without a schema.
+#[cfg(feature = "alloc")]
+struct RawStructBuilder<'a>(crate::private::layout::StructBuilder<'a>);
+
+#[cfg(feature = "alloc")]
+const RAW_STRUCT_SIZE: crate::private::layout::StructSize = crate::private::layout::StructSize {
+    data: 2,
+    pointers: 0,
+};
+
+#[cfg(feature = "alloc")]
+impl<'a> crate::traits::FromPointerBuilder<'a> for RawStructBuilder<'a> {
+    fn init_pointer(builder: crate::private::layout::PointerBuilder<'a>, _size: u32) -> Self {
+        RawStructBuilder(builder.init_struct(RAW_STRUCT_SIZE))
+    }
+
+    fn get_from_pointer(
+        builder: crate::private::layout::PointerBuilder<'a>,
+        default: Option<&'a [crate::Word]>,
+    ) -> crate::Result<Self> {
+        Ok(RawStructBuilder(
+            builder.get_struct(RAW_STRUCT_SIZE, default)?,
+        ))
+    }
+}
+
+/// Like `test_at_alignments()`, but for a struct with two data words that is built in scratch
+/// space starting at every offset mod 8, if the "unaligned" feature is enabled. Afterwards, the
+/// data section must hold the bytes 0x01 through 0x10.
+#[cfg(feature = "alloc")]
+fn test_builder_at_alignments(verify: &dyn Fn(crate::private::layout::StructBuilder)) {
+    let offsets = if cfg!(feature = "unaligned") {
+        0..8
+    } else {
+        0..1
+    };
+    for offset in offsets {
+        // Start from a word-aligned buffer, so that `offset` determines the alignment.
+        let mut words = crate::Vec::new();
+        words.resize(4, crate::word(0, 0, 0, 0, 0, 0, 0, 0));
+        let bytes = crate::Word::words_to_bytes_mut(&mut words);
+        let mut message = crate::message::Builder::new(
+            crate::message::ScratchSpaceHeapAllocator::new(&mut bytes[offset..offset + 24]),
+        );
+        let RawStructBuilder(builder) =
+            message.init_root::<crate::any_pointer::Builder>().init_as();
+        verify(builder);
+        let segments = message.get_segments_for_output();
+        assert_eq!(&segments[0][8..], &two_data_words_bytes()[..]);
+
+        // The struct reads back the same when it is got again, rather than initialized.
+        let RawStructBuilder(builder) = message
+            .get_root::<crate::any_pointer::Builder>()
+            .unwrap()
+            .get_as()
+            .unwrap();
+        let reader = builder.into_reader();
+        check_fields!(reader, u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+    }
+}
+
+macro_rules! set_fields(
+    ($builder:expr, $($typ:ty),*) => (
+        $(
+            let size = core::mem::size_of::<$typ>();
+            let bytes = two_data_words_bytes();
+            for idx in 0..16 / size {
+                let value = <$typ>::from_le_bytes(
+                    bytes[idx * size..(idx + 1) * size].try_into().unwrap(),
+                );
+                $builder.set_data_field::<$typ>(idx, value);
+                assert_eq!(
+                    $builder.get_data_field::<$typ>(idx).to_le_bytes(),
+                    value.to_le_bytes()
+                );
+            }
+        )*
+    );
+);
+
+#[cfg(feature = "alloc")]
+#[test]
+fn set_primitive_fields_of_every_width() {
+    test_builder_at_alignments(&verify);
+    fn verify(builder: crate::private::layout::StructBuilder) {
+        set_fields!(builder, u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+        let bytes = two_data_words_bytes();
+        for bit in 0..128 {
+            builder.set_bool_field(bit, bytes[bit / 8] & (1 << (bit % 8)) != 0);
+        }
+        let reader = builder.into_reader();
+        check_fields!(reader, u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+    }
+}
+
+#[test]
+fn read_data_prefix() {
+    test_at_alignments(TWO_DATA_WORDS, &verify);
+    fn verify(pointer_reader: PointerReader) {
+        let reader = pointer_reader.get_struct(None).unwrap();
+        let bytes = two_data_words_bytes();
+
+        let mut out = [0xff; 12];
+        reader.read_data_prefix(&mut out);
+        assert_eq!(out, bytes[..12]);
+
+        reader.read_data_prefix(&mut []);
+
+        // past end of struct --> zeroed
+        let mut out = [0xff; 24];
+        reader.read_data_prefix(&mut out);
+        assert_eq!(out[..16], bytes);
+        assert_eq!(out[16..], [0; 8]);
+    }
+}
+
+#[test]
+fn read_data_prefix_of_empty_struct() {
+    let data: &[crate::Word] = &[crate::word(0xfc, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00)];
+
+    test_at_alignments(data, &verify);
+    fn verify(pointer_reader: PointerReader) {
+        let reader = pointer_reader.get_struct(None).unwrap();
+        let mut out = [0xff; 8];
+        reader.read_data_prefix(&mut out);
+        assert_eq!(out, [0; 8]);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn write_data_prefix() {
+    test_builder_at_alignments(&verify);
+    fn verify(builder: crate::private::layout::StructBuilder) {
+        let bytes = two_data_words_bytes();
+        builder.write_data_prefix(&[0xff; 16]);
+        builder.write_data_prefix(&bytes[..16]);
+        builder.write_data_prefix(&[]);
+        assert_eq!(builder.get_data_field::<u64>(1), 0x100f0e0d0c0b0a09);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+#[should_panic]
+fn write_data_prefix_too_long() {
+    test_builder_at_alignments(&verify);
+    fn verify(builder: crate::private::layout::StructBuilder) {
+        builder.write_data_prefix(&[0; 17]);
+    }
+}
@@ -28,7 +28,8 @@ pub mod capability;
 pub mod layout;
 mod mask;
 mod primitive;
-mod read_limiter;
+pub(crate) mod read_limiter;
+pub(crate) mod scrub;
 pub mod units;
 mod zero;
 
@@ -1,3 +1,12 @@
+/// A fixed-width value as it appears on the wire.
+///
+/// Struct data sections and primitive lists are read and written through `Raw`. With the
+/// "unaligned" feature, `Raw` is a byte array, which may live at any address; otherwise it is the
+/// type itself, and the arena guarantees that segments are 8-byte aligned.
+///
+/// The choice is made by the feature rather than by an alignment marker picked when a reader is
+/// constructed. The marker would be a type parameter of every reader and builder, generated ones
+/// included, or else a branch on every field access, which the aligned path should not pay for.
 pub trait Primitive {
     type Raw;
 
@@ -19,80 +19,332 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
 // THE SOFTWARE.
 
+#[cfg(all(feature = "sync_reader", feature = "alloc"))]
+pub use sync::CapLimiter;
 #[cfg(feature = "sync_reader")]
 pub use sync::ReadLimiter;
 
+use crate::{Error, ErrorKind};
+
+fn ops_to_usize(ops: u64) -> usize {
+    usize::try_from(ops).unwrap_or(usize::MAX)
+}
+
+/// Like other limit errors, this is described by its kind alone, so that it does not allocate.
+fn ops_exceeded() -> Error {
+    Error::from_kind(ErrorKind::Overloaded)
+}
+
+pub(crate) fn caps_exceeded() -> Error {
+    Error::from_kind(ErrorKind::Overloaded)
+}
+
+/// A limit on words read and pointer dereferences, like that of a `ReadLimiter` with both limits
+/// set, that can be shared by several readers, whichever of `sync_reader` is enabled, and refilled.
+#[cfg(feature = "alloc")]
+pub struct SharedLimiter {
+    words: core::sync::atomic::AtomicUsize,
+    ops: core::sync::atomic::AtomicUsize,
+}
+
+#[cfg(feature = "alloc")]
+impl SharedLimiter {
+    pub fn new(words: u64, ops: u64) -> Self {
+        Self {
+            words: usize::try_from(words).unwrap_or(usize::MAX).into(),
+            ops: ops_to_usize(ops).into(),
+        }
+    }
+
+    #[inline]
+    pub fn can_read(&self, amount: usize) -> crate::Result<()> {
+        use core::sync::atomic::Ordering;
+        self.words
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |words| {
+                words.checked_sub(amount)
+            })
+            .map(drop)
+            .map_err(|_| Error::from_kind(ErrorKind::ReadLimitExceeded))
+    }
+
+    #[inline]
+    pub fn can_dereference(&self) -> crate::Result<()> {
+        use core::sync::atomic::Ordering;
+        self.ops
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |ops| {
+                ops.checked_sub(1)
+            })
+            .map(drop)
+            .map_err(|_| ops_exceeded())
+    }
+
+    /// The number of words that may still be read, and of pointer dereferences still allowed.
+    pub fn remaining(&self) -> (u64, u64) {
+        use core::sync::atomic::Ordering;
+        (
+            self.words.load(Ordering::Relaxed) as u64,
+            self.ops.load(Ordering::Relaxed) as u64,
+        )
+    }
+
+    /// Allows `words` more words to be read, and `ops` more pointer dereferences.
+    pub fn refill(&self, words: u64, ops: u64) {
+        use core::sync::atomic::Ordering;
+        let words = usize::try_from(words).unwrap_or(usize::MAX);
+        let ops = ops_to_usize(ops);
+        let _ = self
+            .words
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| {
+                Some(left.saturating_add(words))
+            });
+        let _ = self
+            .ops
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| {
+                Some(left.saturating_add(ops))
+            });
+    }
+}
+
+/// Adds `index` to `seen`, the distinct cap table indices dereferenced so far, unless that would
+/// make more than `limit` of them.
+#[cfg(all(feature = "alloc", any(feature = "std", not(feature = "sync_reader"))))]
+fn admit_cap(
+    seen: &mut alloc::collections::BTreeSet<u32>,
+    limit: u32,
+    index: u32,
+) -> crate::Result<()> {
+    if seen.contains(&index) {
+        Ok(())
+    } else if seen.len() >= limit as usize {
+        Err(caps_exceeded())
+    } else {
+        seen.insert(index);
+        Ok(())
+    }
+}
+
 #[cfg(feature = "sync_reader")]
 mod sync {
     use crate::{Error, ErrorKind, Result};
+    #[cfg(all(feature = "alloc", feature = "std"))]
+    use alloc::collections::BTreeSet;
+    #[cfg(feature = "alloc")]
+    use core::sync::atomic::AtomicBool;
     use core::sync::atomic::{AtomicUsize, Ordering};
 
     pub struct ReadLimiter {
         limit: AtomicUsize,
         error_on_limit_exceeded: bool,
+        ops: AtomicUsize,
+        error_on_ops_exceeded: bool,
     }
 
     impl ReadLimiter {
-        pub fn new(limit: Option<usize>) -> Self {
-            match limit {
-                Some(value) => Self {
-                    limit: AtomicUsize::new(value),
-                    error_on_limit_exceeded: true,
-                },
-                None => Self {
-                    limit: AtomicUsize::new(usize::MAX),
-                    error_on_limit_exceeded: false,
-                },
+        pub fn new(limit: Option<usize>, op_limit: Option<u64>) -> Self {
+            Self {
+                limit: AtomicUsize::new(limit.unwrap_or(usize::MAX)),
+                error_on_limit_exceeded: limit.is_some(),
+                ops: AtomicUsize::new(op_limit.map_or(usize::MAX, super::ops_to_usize)),
+                error_on_ops_exceeded: op_limit.is_some(),
             }
         }
 
         #[inline]
         pub fn can_read(&self, amount: usize) -> Result<()> {
-            // We use separate AtomicUsize::load() and AtomicUsize::store() steps, which may
-            // result in undercounting reads if multiple threads are reading at the same.
-            // That's okay -- a denial of service attack will eventually hit the limit anyway.
-            //
-            // We could instead do a single fetch_sub() step, but that seems to be slower.
+            // Each charge is a single atomic update, so that readers of one message on several
+            // threads, such as the chunks of `struct_list::Reader::par_chunks()`, are charged
+            // exactly what a single reader would be.
+            if !self.error_on_limit_exceeded {
+                self.limit.fetch_sub(amount, Ordering::Relaxed);
+                return Ok(());
+            }
+            self.limit
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                    current.checked_sub(amount)
+                })
+                .map(drop)
+                .map_err(|_| Error::from_kind(ErrorKind::ReadLimitExceeded))
+        }
 
-            let current = self.limit.load(Ordering::Relaxed);
-            if amount > current && self.error_on_limit_exceeded {
-                return Err(Error::from_kind(ErrorKind::ReadLimitExceeded));
-            } else {
-                // The common case is current >= amount. Note that we only branch once in that case.
-                // If we combined the fields into an Option<AtomicUsize>, we would
-                // need to branch twice in the common case.
-                self.limit
-                    .store(current.wrapping_sub(amount), Ordering::Relaxed);
+        #[inline]
+        pub fn can_dereference(&self) -> Result<()> {
+            if !self.error_on_ops_exceeded {
+                self.ops.fetch_sub(1, Ordering::Relaxed);
+                return Ok(());
             }
-            Ok(())
+            self.ops
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                    current.checked_sub(1)
+                })
+                .map(drop)
+                .map_err(|_| super::ops_exceeded())
+        }
+
+        /// The number of words that may still be read, or `None` if there is no limit.
+        pub fn remaining_words(&self) -> Option<usize> {
+            self.error_on_limit_exceeded
+                .then(|| self.limit.load(Ordering::Relaxed))
+        }
+
+        /// The number of pointer dereferences still allowed, or `None` if there is no limit.
+        pub fn remaining_ops(&self) -> Option<u64> {
+            self.error_on_ops_exceeded
+                .then(|| self.ops.load(Ordering::Relaxed) as u64)
+        }
+
+        /// Allows `words` more words to be read, and `ops` more pointer dereferences.
+        pub fn refill(&self, words: u64, ops: u64) {
+            let words = usize::try_from(words).unwrap_or(usize::MAX);
+            let ops = super::ops_to_usize(ops);
+            let _ = self
+                .limit
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| {
+                    Some(left.saturating_add(words))
+                });
+            let _ = self
+                .ops
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| {
+                    Some(left.saturating_add(ops))
+                });
+        }
+
+        /// Starts counting again from the limits that the limiter was created with, which are
+        /// passed in again.
+        pub fn reset(&self, limit: Option<usize>, op_limit: Option<u64>) {
+            self.limit
+                .store(limit.unwrap_or(usize::MAX), Ordering::Relaxed);
+            self.ops.store(
+                op_limit.map_or(usize::MAX, super::ops_to_usize),
+                Ordering::Relaxed,
+            );
+        }
+    }
+
+    /// Records which entries of the cap table a message has referred to, in a set that grows as
+    /// they are seen, and so never holds more than the limit.
+    #[cfg(all(feature = "alloc", feature = "std"))]
+    pub struct CapLimiter {
+        limit: Option<u32>,
+        seen: std::sync::Mutex<BTreeSet<u32>>,
+        table_too_large: AtomicBool,
+    }
+
+    #[cfg(all(feature = "alloc", feature = "std"))]
+    impl CapLimiter {
+        pub fn new(limit: Option<u32>) -> Self {
+            Self {
+                limit,
+                seen: std::sync::Mutex::new(BTreeSet::new()),
+                table_too_large: AtomicBool::new(false),
+            }
+        }
+
+        fn seen(&self) -> std::sync::MutexGuard<'_, BTreeSet<u32>> {
+            // The set is valid even if a thread panicked while holding the lock, as
+            // `admit_cap()` only inserts once it has checked the limit.
+            self.seen
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+        }
+
+        pub fn can_use_cap(&self, index: u32) -> Result<()> {
+            let Some(limit) = self.limit else {
+                return Ok(());
+            };
+            if self.table_too_large.load(Ordering::Relaxed) {
+                return Err(super::caps_exceeded());
+            }
+            super::admit_cap(&mut self.seen(), limit, index)
+        }
+
+        /// Called when a reader of the message is imbued with a cap table of `len` entries.
+        pub fn imbue_cap_table(&self, len: usize) {
+            if self.limit.is_some_and(|limit| len > limit as usize) {
+                self.table_too_large.store(true, Ordering::Relaxed);
+            }
+        }
+
+        pub fn count(&self) -> u32 {
+            self.seen().len() as u32
+        }
+    }
+
+    /// Without `std` there is no lock to guard a set of indices with, so every capability
+    /// dereference is counted, whether or not its index has been seen before. A message is then
+    /// rejected no later than it would be by counting distinct indices.
+    #[cfg(all(feature = "alloc", not(feature = "std")))]
+    pub struct CapLimiter {
+        limit: Option<u32>,
+        count: AtomicUsize,
+        table_too_large: AtomicBool,
+    }
+
+    #[cfg(all(feature = "alloc", not(feature = "std")))]
+    impl CapLimiter {
+        pub fn new(limit: Option<u32>) -> Self {
+            Self {
+                limit,
+                count: AtomicUsize::new(0),
+                table_too_large: AtomicBool::new(false),
+            }
+        }
+
+        pub fn can_use_cap(&self, _index: u32) -> Result<()> {
+            let Some(limit) = self.limit else {
+                return Ok(());
+            };
+            if self.table_too_large.load(Ordering::Relaxed) {
+                return Err(super::caps_exceeded());
+            }
+            self.count
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                    (count < limit as usize).then_some(count + 1)
+                })
+                .map(drop)
+                .map_err(|_| super::caps_exceeded())
+        }
+
+        /// Called when a reader of the message is imbued with a cap table of `len` entries.
+        pub fn imbue_cap_table(&self, len: usize) {
+            if self.limit.is_some_and(|limit| len > limit as usize) {
+                self.table_too_large.store(true, Ordering::Relaxed);
+            }
+        }
+
+        pub fn count(&self) -> u32 {
+            self.count.load(Ordering::Relaxed) as u32
         }
     }
 }
 
+#[cfg(all(not(feature = "sync_reader"), feature = "alloc"))]
+pub use unsync::CapLimiter;
 #[cfg(not(feature = "sync_reader"))]
 pub use unsync::ReadLimiter;
 
 #[cfg(not(feature = "sync_reader"))]
 mod unsync {
     use crate::{Error, ErrorKind, Result};
+    #[cfg(feature = "alloc")]
+    use alloc::collections::BTreeSet;
     use core::cell::Cell;
+    #[cfg(feature = "alloc")]
+    use core::cell::RefCell;
 
     pub struct ReadLimiter {
         limit: Cell<usize>,
         error_on_limit_exceeded: bool,
+        ops: Cell<usize>,
+        error_on_ops_exceeded: bool,
     }
 
     impl ReadLimiter {
-        pub fn new(limit: Option<usize>) -> Self {
-            match limit {
-                Some(value) => Self {
-                    limit: Cell::new(value),
-                    error_on_limit_exceeded: true,
-                },
-                None => Self {
-                    limit: Cell::new(usize::MAX),
-                    error_on_limit_exceeded: false,
-                },
+        pub fn new(limit: Option<usize>, op_limit: Option<u64>) -> Self {
+            Self {
+                limit: Cell::new(limit.unwrap_or(usize::MAX)),
+                error_on_limit_exceeded: limit.is_some(),
+                ops: Cell::new(op_limit.map_or(usize::MAX, super::ops_to_usize)),
+                error_on_ops_exceeded: op_limit.is_some(),
             }
         }
 
@@ -109,5 +361,83 @@ mod unsync {
                 Ok(())
             }
         }
+
+        #[inline]
+        pub fn can_dereference(&self) -> Result<()> {
+            let current = self.ops.get();
+            if current == 0 && self.error_on_ops_exceeded {
+                Err(super::ops_exceeded())
+            } else {
+                self.ops.set(current.wrapping_sub(1));
+                Ok(())
+            }
+        }
+
+        /// The number of words that may still be read, or `None` if there is no limit.
+        pub fn remaining_words(&self) -> Option<usize> {
+            self.error_on_limit_exceeded.then(|| self.limit.get())
+        }
+
+        /// The number of pointer dereferences still allowed, or `None` if there is no limit.
+        pub fn remaining_ops(&self) -> Option<u64> {
+            self.error_on_ops_exceeded.then(|| self.ops.get() as u64)
+        }
+
+        /// Allows `words` more words to be read, and `ops` more pointer dereferences.
+        pub fn refill(&self, words: u64, ops: u64) {
+            let words = usize::try_from(words).unwrap_or(usize::MAX);
+            self.limit.set(self.limit.get().saturating_add(words));
+            self.ops
+                .set(self.ops.get().saturating_add(super::ops_to_usize(ops)));
+        }
+
+        /// Starts counting again from the limits that the limiter was created with, which are
+        /// passed in again.
+        pub fn reset(&self, limit: Option<usize>, op_limit: Option<u64>) {
+            self.limit.set(limit.unwrap_or(usize::MAX));
+            self.ops
+                .set(op_limit.map_or(usize::MAX, super::ops_to_usize));
+        }
+    }
+
+    /// Records which entries of the cap table a message has referred to, in a set that grows as
+    /// they are seen, and so never holds more than the limit.
+    #[cfg(feature = "alloc")]
+    pub struct CapLimiter {
+        limit: Option<u32>,
+        seen: RefCell<BTreeSet<u32>>,
+        table_too_large: Cell<bool>,
+    }
+
+    #[cfg(feature = "alloc")]
+    impl CapLimiter {
+        pub fn new(limit: Option<u32>) -> Self {
+            Self {
+                limit,
+                seen: RefCell::new(BTreeSet::new()),
+                table_too_large: Cell::new(false),
+            }
+        }
+
+        pub fn can_use_cap(&self, index: u32) -> Result<()> {
+            let Some(limit) = self.limit else {
+                return Ok(());
+            };
+            if self.table_too_large.get() {
+                return Err(super::caps_exceeded());
+            }
+            super::admit_cap(&mut self.seen.borrow_mut(), limit, index)
+        }
+
+        /// Called when a reader of the message is imbued with a cap table of `len` entries.
+        pub fn imbue_cap_table(&self, len: usize) {
+            if self.limit.is_some_and(|limit| len > limit as usize) {
+                self.table_too_large.set(true);
+            }
+        }
+
+        pub fn count(&self) -> u32 {
+            self.seen.borrow().len() as u32
+        }
     }
 }
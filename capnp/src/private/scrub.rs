@@ -0,0 +1,14 @@
+//! Zeroing of memory that may have held sensitive data.
+
+/// Writes zeroes to the `len` bytes starting at `ptr`, in a way that the compiler will not
+/// optimize away, even if the memory is never read again before being freed.
+///
+/// # Safety
+/// `ptr` must be valid for writes of `len` bytes.
+pub(crate) unsafe fn scrub_bytes(ptr: *mut u8, len: usize) {
+    for idx in 0..len {
+        unsafe { core::ptr::write_volatile(ptr.add(idx), 0) };
+    }
+    // Keep later accesses from being reordered before the writes above.
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+}
@@ -20,7 +20,10 @@
 
 //! Functions providing low level access to encoded data.
 
-use crate::traits::{IntoInternalListReader, IntoInternalStructReader};
+use crate::traits::{IntoInternalListReader, IntoInternalStructBuilder, IntoInternalStructReader};
+
+#[cfg(feature = "alloc")]
+pub use crate::private::layout::OwnedStructData;
 
 /// Gets a slice view of the data section of a struct.
 pub fn get_struct_data_section<'a, T>(value: T) -> &'a [u8]
@@ -32,6 +35,15 @@ where
         .get_data_section_as_blob()
 }
 
+/// Copies the data section of a struct into an `OwnedStructData`, which can outlive the message.
+#[cfg(feature = "alloc")]
+pub fn to_owned_struct_data<'a, T>(value: T) -> OwnedStructData
+where
+    T: IntoInternalStructReader<'a>,
+{
+    value.into_internal_struct_reader().to_owned_data()
+}
+
 /// Gets the pointer section as a list.
 pub fn get_struct_pointer_section<'a, T>(value: T) -> crate::any_pointer_list::Reader<'a>
 where
@@ -44,6 +56,17 @@ where
     )
 }
 
+/// Gets a slice view of the pointer section of a struct, or `None` if it is not 8-byte aligned.
+/// See `StructReader::raw_pointer_section_words()`.
+pub fn get_struct_pointer_section_words<'a, T>(value: T) -> Option<&'a [crate::Word]>
+where
+    T: IntoInternalStructReader<'a>,
+{
+    value
+        .into_internal_struct_reader()
+        .raw_pointer_section_words()
+}
+
 /// Gets the size of the elements in a list.
 pub fn get_list_element_size<'a, T>(value: T) -> crate::private::layout::ElementSize
 where
@@ -67,3 +90,28 @@ where
 {
     value.into_internal_list_reader().into_raw_bytes()
 }
+
+/// Overwrites pointer `index` of a struct with the raw `word`. When paranoid builder checks are
+/// enabled, by `debug_assertions` or by the `paranoid-builder` feature, this panics on a word that
+/// points outside of the message.
+///
+/// # Panics
+///
+/// If the struct has no pointer `index`.
+///
+/// # Safety
+///
+/// Builders follow the pointers of their message without checking them, so `word` must be a
+/// valid pointer to an object in the message, unless the message is only going to be serialized
+/// or dropped.
+pub unsafe fn set_struct_pointer_word<'a, T>(value: T, index: u16, word: crate::Word)
+where
+    T: IntoInternalStructBuilder<'a>,
+{
+    let builder = value.into_internal_struct_builder();
+    assert!(
+        index < builder.get_pointer_section_size(),
+        "struct has no pointer {index}"
+    );
+    builder.get_pointer_field(index as usize).set_raw_word(word)
+}
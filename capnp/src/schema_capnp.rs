@@ -209,6 +209,12 @@ pub mod node {
         }
     }
 
+    impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(crate::dynamic_struct::Builder::new(
@@ -236,6 +242,16 @@ pub mod node {
                 .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: crate::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> crate::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: crate::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [crate::Word]>,
@@ -294,10 +310,34 @@ pub mod node {
             self.builder.reborrow().get_pointer_field(0).set_text(value);
         }
         #[inline]
+        pub fn try_set_display_name(
+            &mut self,
+            value: crate::text::Reader<'_>,
+        ) -> crate::Result<()> {
+            self.builder
+                .reborrow()
+                .get_pointer_field(0)
+                .try_set_text(value)
+        }
+        #[inline]
         pub fn init_display_name(self, size: u32) -> crate::text::Builder<'a> {
             self.builder.get_pointer_field(0).init_text(size)
         }
         #[inline]
+        pub fn try_init_display_name(self, size: u32) -> crate::Result<crate::text::Builder<'a>> {
+            self.builder.get_pointer_field(0).try_init_text(size)
+        }
+        #[inline]
+        pub fn reserve_display_name(
+            self,
+            max_len: u32,
+        ) -> crate::Result<crate::text::Reservation<'a>> {
+            crate::text::reserve(
+                crate::any_pointer::Builder::new(self.builder.get_pointer_field(0)),
+                max_len,
+            )
+        }
+        #[inline]
         pub fn has_display_name(&self) -> bool {
             !self.builder.is_pointer_field_null(0)
         }
@@ -348,6 +388,18 @@ pub mod node {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(1), size)
         }
         #[inline]
+        pub fn try_init_nested_nodes(
+            self,
+            size: u32,
+        ) -> crate::Result<
+            crate::struct_list::Builder<'a, crate::schema_capnp::node::nested_node::Owned>,
+        > {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(1),
+                size,
+            )
+        }
+        #[inline]
         pub fn has_nested_nodes(&self) -> bool {
             !self.builder.is_pointer_field_null(1)
         }
@@ -380,6 +432,17 @@ pub mod node {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(2), size)
         }
         #[inline]
+        pub fn try_init_annotations(
+            self,
+            size: u32,
+        ) -> crate::Result<crate::struct_list::Builder<'a, crate::schema_capnp::annotation::Owned>>
+        {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(2),
+                size,
+            )
+        }
+        #[inline]
         pub fn has_annotations(&self) -> bool {
             !self.builder.is_pointer_field_null(2)
         }
@@ -467,6 +530,18 @@ pub mod node {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(5), size)
         }
         #[inline]
+        pub fn try_init_parameters(
+            self,
+            size: u32,
+        ) -> crate::Result<
+            crate::struct_list::Builder<'a, crate::schema_capnp::node::parameter::Owned>,
+        > {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(5),
+                size,
+            )
+        }
+        #[inline]
         pub fn has_parameters(&self) -> bool {
             !self.builder.is_pointer_field_null(5)
         }
@@ -922,6 +997,12 @@ pub mod node {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -952,6 +1033,16 @@ pub mod node {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -1002,10 +1093,28 @@ pub mod node {
                 self.builder.reborrow().get_pointer_field(0).set_text(value);
             }
             #[inline]
+            pub fn try_set_name(&mut self, value: crate::text::Reader<'_>) -> crate::Result<()> {
+                self.builder
+                    .reborrow()
+                    .get_pointer_field(0)
+                    .try_set_text(value)
+            }
+            #[inline]
             pub fn init_name(self, size: u32) -> crate::text::Builder<'a> {
                 self.builder.get_pointer_field(0).init_text(size)
             }
             #[inline]
+            pub fn try_init_name(self, size: u32) -> crate::Result<crate::text::Builder<'a>> {
+                self.builder.get_pointer_field(0).try_init_text(size)
+            }
+            #[inline]
+            pub fn reserve_name(self, max_len: u32) -> crate::Result<crate::text::Reservation<'a>> {
+                crate::text::reserve(
+                    crate::any_pointer::Builder::new(self.builder.get_pointer_field(0)),
+                    max_len,
+                )
+            }
+            #[inline]
             pub fn has_name(&self) -> bool {
                 !self.builder.is_pointer_field_null(0)
             }
@@ -1219,6 +1328,12 @@ pub mod node {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -1249,6 +1364,16 @@ pub mod node {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -1299,10 +1424,28 @@ pub mod node {
                 self.builder.reborrow().get_pointer_field(0).set_text(value);
             }
             #[inline]
+            pub fn try_set_name(&mut self, value: crate::text::Reader<'_>) -> crate::Result<()> {
+                self.builder
+                    .reborrow()
+                    .get_pointer_field(0)
+                    .try_set_text(value)
+            }
+            #[inline]
             pub fn init_name(self, size: u32) -> crate::text::Builder<'a> {
                 self.builder.get_pointer_field(0).init_text(size)
             }
             #[inline]
+            pub fn try_init_name(self, size: u32) -> crate::Result<crate::text::Builder<'a>> {
+                self.builder.get_pointer_field(0).try_init_text(size)
+            }
+            #[inline]
+            pub fn reserve_name(self, max_len: u32) -> crate::Result<crate::text::Reservation<'a>> {
+                crate::text::reserve(
+                    crate::any_pointer::Builder::new(self.builder.get_pointer_field(0)),
+                    max_len,
+                )
+            }
+            #[inline]
             pub fn has_name(&self) -> bool {
                 !self.builder.is_pointer_field_null(0)
             }
@@ -1558,6 +1701,12 @@ pub mod node {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -1588,6 +1737,16 @@ pub mod node {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -1646,10 +1805,37 @@ pub mod node {
                 self.builder.reborrow().get_pointer_field(0).set_text(value);
             }
             #[inline]
+            pub fn try_set_doc_comment(
+                &mut self,
+                value: crate::text::Reader<'_>,
+            ) -> crate::Result<()> {
+                self.builder
+                    .reborrow()
+                    .get_pointer_field(0)
+                    .try_set_text(value)
+            }
+            #[inline]
             pub fn init_doc_comment(self, size: u32) -> crate::text::Builder<'a> {
                 self.builder.get_pointer_field(0).init_text(size)
             }
             #[inline]
+            pub fn try_init_doc_comment(
+                self,
+                size: u32,
+            ) -> crate::Result<crate::text::Builder<'a>> {
+                self.builder.get_pointer_field(0).try_init_text(size)
+            }
+            #[inline]
+            pub fn reserve_doc_comment(
+                self,
+                max_len: u32,
+            ) -> crate::Result<crate::text::Reservation<'a>> {
+                crate::text::reserve(
+                    crate::any_pointer::Builder::new(self.builder.get_pointer_field(0)),
+                    max_len,
+                )
+            }
+            #[inline]
             pub fn has_doc_comment(&self) -> bool {
                 !self.builder.is_pointer_field_null(0)
             }
@@ -1695,6 +1881,21 @@ pub mod node {
                 )
             }
             #[inline]
+            pub fn try_init_members(
+                self,
+                size: u32,
+            ) -> crate::Result<
+                crate::struct_list::Builder<
+                    'a,
+                    crate::schema_capnp::node::source_info::member::Owned,
+                >,
+            > {
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(1),
+                    size,
+                )
+            }
+            #[inline]
             pub fn has_members(&self) -> bool {
                 !self.builder.is_pointer_field_null(1)
             }
@@ -1949,6 +2150,12 @@ pub mod node {
                 }
             }
 
+            impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+                fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                    self.builder
+                }
+            }
+
             impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
                 fn from(builder: Builder<'a>) -> Self {
                     Self::Struct(crate::dynamic_struct::Builder::new(
@@ -1981,6 +2188,16 @@ pub mod node {
                         .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                         .into()
                 }
+                fn try_init_pointer(
+                    builder: crate::private::layout::PointerBuilder<'a>,
+                    _size: u32,
+                ) -> crate::Result<Self> {
+                    ::core::result::Result::Ok(
+                        builder
+                            .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                            .into(),
+                    )
+                }
                 fn get_from_pointer(
                     builder: crate::private::layout::PointerBuilder<'a>,
                     default: ::core::option::Option<&'a [crate::Word]>,
@@ -2034,10 +2251,37 @@ pub mod node {
                     self.builder.reborrow().get_pointer_field(0).set_text(value);
                 }
                 #[inline]
+                pub fn try_set_doc_comment(
+                    &mut self,
+                    value: crate::text::Reader<'_>,
+                ) -> crate::Result<()> {
+                    self.builder
+                        .reborrow()
+                        .get_pointer_field(0)
+                        .try_set_text(value)
+                }
+                #[inline]
                 pub fn init_doc_comment(self, size: u32) -> crate::text::Builder<'a> {
                     self.builder.get_pointer_field(0).init_text(size)
                 }
                 #[inline]
+                pub fn try_init_doc_comment(
+                    self,
+                    size: u32,
+                ) -> crate::Result<crate::text::Builder<'a>> {
+                    self.builder.get_pointer_field(0).try_init_text(size)
+                }
+                #[inline]
+                pub fn reserve_doc_comment(
+                    self,
+                    max_len: u32,
+                ) -> crate::Result<crate::text::Reservation<'a>> {
+                    crate::text::reserve(
+                        crate::any_pointer::Builder::new(self.builder.get_pointer_field(0)),
+                        max_len,
+                    )
+                }
+                #[inline]
                 pub fn has_doc_comment(&self) -> bool {
                     !self.builder.is_pointer_field_null(0)
                 }
@@ -2280,6 +2524,12 @@ pub mod node {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -2310,6 +2560,16 @@ pub mod node {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -2431,6 +2691,17 @@ pub mod node {
                 )
             }
             #[inline]
+            pub fn try_init_fields(
+                self,
+                size: u32,
+            ) -> crate::Result<crate::struct_list::Builder<'a, crate::schema_capnp::field::Owned>>
+            {
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(3),
+                    size,
+                )
+            }
+            #[inline]
             pub fn has_fields(&self) -> bool {
                 !self.builder.is_pointer_field_null(3)
             }
@@ -2750,6 +3021,12 @@ pub mod node {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -2780,6 +3057,16 @@ pub mod node {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -2851,6 +3138,17 @@ pub mod node {
                 )
             }
             #[inline]
+            pub fn try_init_enumerants(
+                self,
+                size: u32,
+            ) -> crate::Result<crate::struct_list::Builder<'a, crate::schema_capnp::enumerant::Owned>>
+            {
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(3),
+                    size,
+                )
+            }
+            #[inline]
             pub fn has_enumerants(&self) -> bool {
                 !self.builder.is_pointer_field_null(3)
             }
@@ -3080,6 +3378,12 @@ pub mod node {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -3110,6 +3414,16 @@ pub mod node {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -3180,6 +3494,17 @@ pub mod node {
                 )
             }
             #[inline]
+            pub fn try_init_methods(
+                self,
+                size: u32,
+            ) -> crate::Result<crate::struct_list::Builder<'a, crate::schema_capnp::method::Owned>>
+            {
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(3),
+                    size,
+                )
+            }
+            #[inline]
             pub fn has_methods(&self) -> bool {
                 !self.builder.is_pointer_field_null(3)
             }
@@ -3217,6 +3542,18 @@ pub mod node {
                 )
             }
             #[inline]
+            pub fn try_init_superclasses(
+                self,
+                size: u32,
+            ) -> crate::Result<
+                crate::struct_list::Builder<'a, crate::schema_capnp::superclass::Owned>,
+            > {
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(4),
+                    size,
+                )
+            }
+            #[inline]
             pub fn has_superclasses(&self) -> bool {
                 !self.builder.is_pointer_field_null(4)
             }
@@ -3461,6 +3798,12 @@ pub mod node {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -3491,6 +3834,16 @@ pub mod node {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -3555,6 +3908,13 @@ pub mod node {
                 )
             }
             #[inline]
+            pub fn try_init_type(self) -> crate::Result<crate::schema_capnp::type_::Builder<'a>> {
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(3),
+                    0,
+                )
+            }
+            #[inline]
             pub fn has_type(&self) -> bool {
                 !self.builder.is_pointer_field_null(3)
             }
@@ -3584,6 +3944,13 @@ pub mod node {
                 )
             }
             #[inline]
+            pub fn try_init_value(self) -> crate::Result<crate::schema_capnp::value::Builder<'a>> {
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(4),
+                    0,
+                )
+            }
+            #[inline]
             pub fn has_value(&self) -> bool {
                 !self.builder.is_pointer_field_null(4)
             }
@@ -3862,6 +4229,12 @@ pub mod node {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -3892,6 +4265,16 @@ pub mod node {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -3956,6 +4339,13 @@ pub mod node {
                 )
             }
             #[inline]
+            pub fn try_init_type(self) -> crate::Result<crate::schema_capnp::type_::Builder<'a>> {
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(3),
+                    0,
+                )
+            }
+            #[inline]
             pub fn has_type(&self) -> bool {
                 !self.builder.is_pointer_field_null(3)
             }
@@ -4508,6 +4898,12 @@ pub mod field {
         }
     }
 
+    impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(crate::dynamic_struct::Builder::new(
@@ -4535,6 +4931,16 @@ pub mod field {
                 .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: crate::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> crate::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: crate::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [crate::Word]>,
@@ -4585,10 +4991,28 @@ pub mod field {
             self.builder.reborrow().get_pointer_field(0).set_text(value);
         }
         #[inline]
+        pub fn try_set_name(&mut self, value: crate::text::Reader<'_>) -> crate::Result<()> {
+            self.builder
+                .reborrow()
+                .get_pointer_field(0)
+                .try_set_text(value)
+        }
+        #[inline]
         pub fn init_name(self, size: u32) -> crate::text::Builder<'a> {
             self.builder.get_pointer_field(0).init_text(size)
         }
         #[inline]
+        pub fn try_init_name(self, size: u32) -> crate::Result<crate::text::Builder<'a>> {
+            self.builder.get_pointer_field(0).try_init_text(size)
+        }
+        #[inline]
+        pub fn reserve_name(self, max_len: u32) -> crate::Result<crate::text::Reservation<'a>> {
+            crate::text::reserve(
+                crate::any_pointer::Builder::new(self.builder.get_pointer_field(0)),
+                max_len,
+            )
+        }
+        #[inline]
         pub fn has_name(&self) -> bool {
             !self.builder.is_pointer_field_null(0)
         }
@@ -4629,6 +5053,17 @@ pub mod field {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(1), size)
         }
         #[inline]
+        pub fn try_init_annotations(
+            self,
+            size: u32,
+        ) -> crate::Result<crate::struct_list::Builder<'a, crate::schema_capnp::annotation::Owned>>
+        {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(1),
+                size,
+            )
+        }
+        #[inline]
         pub fn has_annotations(&self) -> bool {
             !self.builder.is_pointer_field_null(1)
         }
@@ -5002,6 +5437,12 @@ pub mod field {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -5032,6 +5473,16 @@ pub mod field {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -5104,6 +5555,13 @@ pub mod field {
                 )
             }
             #[inline]
+            pub fn try_init_type(self) -> crate::Result<crate::schema_capnp::type_::Builder<'a>> {
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(2),
+                    0,
+                )
+            }
+            #[inline]
             pub fn has_type(&self) -> bool {
                 !self.builder.is_pointer_field_null(2)
             }
@@ -5135,6 +5593,15 @@ pub mod field {
                 )
             }
             #[inline]
+            pub fn try_init_default_value(
+                self,
+            ) -> crate::Result<crate::schema_capnp::value::Builder<'a>> {
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(3),
+                    0,
+                )
+            }
+            #[inline]
             pub fn has_default_value(&self) -> bool {
                 !self.builder.is_pointer_field_null(3)
             }
@@ -5401,6 +5868,12 @@ pub mod field {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -5431,6 +5904,16 @@ pub mod field {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -5681,6 +6164,12 @@ pub mod field {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -5711,6 +6200,16 @@ pub mod field {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -6016,6 +6515,12 @@ pub mod enumerant {
         }
     }
 
+    impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(crate::dynamic_struct::Builder::new(
@@ -6043,6 +6548,16 @@ pub mod enumerant {
                 .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: crate::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> crate::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: crate::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [crate::Word]>,
@@ -6093,10 +6608,28 @@ pub mod enumerant {
             self.builder.reborrow().get_pointer_field(0).set_text(value);
         }
         #[inline]
+        pub fn try_set_name(&mut self, value: crate::text::Reader<'_>) -> crate::Result<()> {
+            self.builder
+                .reborrow()
+                .get_pointer_field(0)
+                .try_set_text(value)
+        }
+        #[inline]
         pub fn init_name(self, size: u32) -> crate::text::Builder<'a> {
             self.builder.get_pointer_field(0).init_text(size)
         }
         #[inline]
+        pub fn try_init_name(self, size: u32) -> crate::Result<crate::text::Builder<'a>> {
+            self.builder.get_pointer_field(0).try_init_text(size)
+        }
+        #[inline]
+        pub fn reserve_name(self, max_len: u32) -> crate::Result<crate::text::Reservation<'a>> {
+            crate::text::reserve(
+                crate::any_pointer::Builder::new(self.builder.get_pointer_field(0)),
+                max_len,
+            )
+        }
+        #[inline]
         pub fn has_name(&self) -> bool {
             !self.builder.is_pointer_field_null(0)
         }
@@ -6137,6 +6670,17 @@ pub mod enumerant {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(1), size)
         }
         #[inline]
+        pub fn try_init_annotations(
+            self,
+            size: u32,
+        ) -> crate::Result<crate::struct_list::Builder<'a, crate::schema_capnp::annotation::Owned>>
+        {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(1),
+                size,
+            )
+        }
+        #[inline]
         pub fn has_annotations(&self) -> bool {
             !self.builder.is_pointer_field_null(1)
         }
@@ -6387,6 +6931,12 @@ pub mod superclass {
         }
     }
 
+    impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(crate::dynamic_struct::Builder::new(
@@ -6414,6 +6964,16 @@ pub mod superclass {
                 .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: crate::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> crate::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: crate::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [crate::Word]>,
@@ -6483,6 +7043,13 @@ pub mod superclass {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_brand(self) -> crate::Result<crate::schema_capnp::brand::Builder<'a>> {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_brand(&self) -> bool {
             !self.builder.is_pointer_field_null(0)
         }
@@ -6777,6 +7344,12 @@ pub mod method {
         }
     }
 
+    impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(crate::dynamic_struct::Builder::new(
@@ -6804,6 +7377,16 @@ pub mod method {
                 .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: crate::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> crate::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: crate::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [crate::Word]>,
@@ -6854,10 +7437,28 @@ pub mod method {
             self.builder.reborrow().get_pointer_field(0).set_text(value);
         }
         #[inline]
+        pub fn try_set_name(&mut self, value: crate::text::Reader<'_>) -> crate::Result<()> {
+            self.builder
+                .reborrow()
+                .get_pointer_field(0)
+                .try_set_text(value)
+        }
+        #[inline]
         pub fn init_name(self, size: u32) -> crate::text::Builder<'a> {
             self.builder.get_pointer_field(0).init_text(size)
         }
         #[inline]
+        pub fn try_init_name(self, size: u32) -> crate::Result<crate::text::Builder<'a>> {
+            self.builder.get_pointer_field(0).try_init_text(size)
+        }
+        #[inline]
+        pub fn reserve_name(self, max_len: u32) -> crate::Result<crate::text::Reservation<'a>> {
+            crate::text::reserve(
+                crate::any_pointer::Builder::new(self.builder.get_pointer_field(0)),
+                max_len,
+            )
+        }
+        #[inline]
         pub fn has_name(&self) -> bool {
             !self.builder.is_pointer_field_null(0)
         }
@@ -6914,6 +7515,17 @@ pub mod method {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(1), size)
         }
         #[inline]
+        pub fn try_init_annotations(
+            self,
+            size: u32,
+        ) -> crate::Result<crate::struct_list::Builder<'a, crate::schema_capnp::annotation::Owned>>
+        {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(1),
+                size,
+            )
+        }
+        #[inline]
         pub fn has_annotations(&self) -> bool {
             !self.builder.is_pointer_field_null(1)
         }
@@ -6940,6 +7552,15 @@ pub mod method {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(2), 0)
         }
         #[inline]
+        pub fn try_init_param_brand(
+            self,
+        ) -> crate::Result<crate::schema_capnp::brand::Builder<'a>> {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(2),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_param_brand(&self) -> bool {
             !self.builder.is_pointer_field_null(2)
         }
@@ -6966,6 +7587,15 @@ pub mod method {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(3), 0)
         }
         #[inline]
+        pub fn try_init_result_brand(
+            self,
+        ) -> crate::Result<crate::schema_capnp::brand::Builder<'a>> {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(3),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_result_brand(&self) -> bool {
             !self.builder.is_pointer_field_null(3)
         }
@@ -6999,6 +7629,18 @@ pub mod method {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(4), size)
         }
         #[inline]
+        pub fn try_init_implicit_parameters(
+            self,
+            size: u32,
+        ) -> crate::Result<
+            crate::struct_list::Builder<'a, crate::schema_capnp::node::parameter::Owned>,
+        > {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(4),
+                size,
+            )
+        }
+        #[inline]
         pub fn has_implicit_parameters(&self) -> bool {
             !self.builder.is_pointer_field_null(4)
         }
@@ -7362,6 +8004,12 @@ pub mod type_ {
         }
     }
 
+    impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(crate::dynamic_struct::Builder::new(
@@ -7389,6 +8037,16 @@ pub mod type_ {
                 .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: crate::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> crate::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: crate::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [crate::Word]>,
@@ -8041,6 +8699,12 @@ pub mod type_ {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -8071,6 +8735,16 @@ pub mod type_ {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -8137,6 +8811,15 @@ pub mod type_ {
                 )
             }
             #[inline]
+            pub fn try_init_element_type(
+                self,
+            ) -> crate::Result<crate::schema_capnp::type_::Builder<'a>> {
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(0),
+                    0,
+                )
+            }
+            #[inline]
             pub fn has_element_type(&self) -> bool {
                 !self.builder.is_pointer_field_null(0)
             }
@@ -8353,6 +9036,12 @@ pub mod type_ {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -8383,6 +9072,16 @@ pub mod type_ {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -8455,6 +9154,13 @@ pub mod type_ {
                 )
             }
             #[inline]
+            pub fn try_init_brand(self) -> crate::Result<crate::schema_capnp::brand::Builder<'a>> {
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(0),
+                    0,
+                )
+            }
+            #[inline]
             pub fn has_brand(&self) -> bool {
                 !self.builder.is_pointer_field_null(0)
             }
@@ -8686,6 +9392,12 @@ pub mod type_ {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -8716,6 +9428,16 @@ pub mod type_ {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -8788,6 +9510,13 @@ pub mod type_ {
                 )
             }
             #[inline]
+            pub fn try_init_brand(self) -> crate::Result<crate::schema_capnp::brand::Builder<'a>> {
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(0),
+                    0,
+                )
+            }
+            #[inline]
             pub fn has_brand(&self) -> bool {
                 !self.builder.is_pointer_field_null(0)
             }
@@ -9020,6 +9749,12 @@ pub mod type_ {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -9050,6 +9785,16 @@ pub mod type_ {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -9122,6 +9867,13 @@ pub mod type_ {
                 )
             }
             #[inline]
+            pub fn try_init_brand(self) -> crate::Result<crate::schema_capnp::brand::Builder<'a>> {
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(0),
+                    0,
+                )
+            }
+            #[inline]
             pub fn has_brand(&self) -> bool {
                 !self.builder.is_pointer_field_null(0)
             }
@@ -9350,6 +10102,12 @@ pub mod type_ {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -9380,6 +10138,16 @@ pub mod type_ {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -9692,6 +10460,12 @@ pub mod type_ {
                 }
             }
 
+            impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+                fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                    self.builder
+                }
+            }
+
             impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
                 fn from(builder: Builder<'a>) -> Self {
                     Self::Struct(crate::dynamic_struct::Builder::new(
@@ -9724,6 +10498,16 @@ pub mod type_ {
                         .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                         .into()
                 }
+                fn try_init_pointer(
+                    builder: crate::private::layout::PointerBuilder<'a>,
+                    _size: u32,
+                ) -> crate::Result<Self> {
+                    ::core::result::Result::Ok(
+                        builder
+                            .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                            .into(),
+                    )
+                }
                 fn get_from_pointer(
                     builder: crate::private::layout::PointerBuilder<'a>,
                     default: ::core::option::Option<&'a [crate::Word]>,
@@ -10056,6 +10840,12 @@ pub mod type_ {
                 }
             }
 
+            impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+                fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                    self.builder
+                }
+            }
+
             impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
                 fn from(builder: Builder<'a>) -> Self {
                     Self::Struct(crate::dynamic_struct::Builder::new(
@@ -10088,6 +10878,16 @@ pub mod type_ {
                         .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                         .into()
                 }
+                fn try_init_pointer(
+                    builder: crate::private::layout::PointerBuilder<'a>,
+                    _size: u32,
+                ) -> crate::Result<Self> {
+                    ::core::result::Result::Ok(
+                        builder
+                            .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                            .into(),
+                    )
+                }
                 fn get_from_pointer(
                     builder: crate::private::layout::PointerBuilder<'a>,
                     default: ::core::option::Option<&'a [crate::Word]>,
@@ -10365,6 +11165,12 @@ pub mod type_ {
                 }
             }
 
+            impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+                fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                    self.builder
+                }
+            }
+
             impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
                 fn from(builder: Builder<'a>) -> Self {
                     Self::Struct(crate::dynamic_struct::Builder::new(
@@ -10397,6 +11203,16 @@ pub mod type_ {
                         .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                         .into()
                 }
+                fn try_init_pointer(
+                    builder: crate::private::layout::PointerBuilder<'a>,
+                    _size: u32,
+                ) -> crate::Result<Self> {
+                    ::core::result::Result::Ok(
+                        builder
+                            .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                            .into(),
+                    )
+                }
                 fn get_from_pointer(
                     builder: crate::private::layout::PointerBuilder<'a>,
                     default: ::core::option::Option<&'a [crate::Word]>,
@@ -10660,6 +11476,12 @@ pub mod brand {
         }
     }
 
+    impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(crate::dynamic_struct::Builder::new(
@@ -10687,6 +11509,16 @@ pub mod brand {
                 .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: crate::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> crate::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: crate::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [crate::Word]>,
@@ -10754,6 +11586,17 @@ pub mod brand {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), size)
         }
         #[inline]
+        pub fn try_init_scopes(
+            self,
+            size: u32,
+        ) -> crate::Result<crate::struct_list::Builder<'a, crate::schema_capnp::brand::scope::Owned>>
+        {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                size,
+            )
+        }
+        #[inline]
         pub fn has_scopes(&self) -> bool {
             !self.builder.is_pointer_field_null(0)
         }
@@ -10986,6 +11829,12 @@ pub mod brand {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -11016,6 +11865,16 @@ pub mod brand {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -11087,6 +11946,19 @@ pub mod brand {
                 )
             }
             #[inline]
+            pub fn try_init_bind(
+                self,
+                size: u32,
+            ) -> crate::Result<
+                crate::struct_list::Builder<'a, crate::schema_capnp::brand::binding::Owned>,
+            > {
+                self.builder.set_data_field::<u16>(4, 0);
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(0),
+                    size,
+                )
+            }
+            #[inline]
             pub fn has_bind(&self) -> bool {
                 if self.builder.get_data_field::<u16>(4) != 0 {
                     return false;
@@ -11377,6 +12249,12 @@ pub mod brand {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -11407,6 +12285,16 @@ pub mod brand {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -11470,6 +12358,14 @@ pub mod brand {
                 )
             }
             #[inline]
+            pub fn try_init_type(self) -> crate::Result<crate::schema_capnp::type_::Builder<'a>> {
+                self.builder.set_data_field::<u16>(0, 1);
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(0),
+                    0,
+                )
+            }
+            #[inline]
             pub fn has_type(&self) -> bool {
                 if self.builder.get_data_field::<u16>(0) != 1 {
                     return false;
@@ -11788,6 +12684,12 @@ pub mod value {
         }
     }
 
+    impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(crate::dynamic_struct::Builder::new(
@@ -11815,6 +12717,16 @@ pub mod value {
                 .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: crate::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> crate::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: crate::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [crate::Word]>,
@@ -11918,11 +12830,32 @@ pub mod value {
             self.builder.reborrow().get_pointer_field(0).set_text(value);
         }
         #[inline]
+        pub fn try_set_text(&mut self, value: crate::text::Reader<'_>) -> crate::Result<()> {
+            self.builder.set_data_field::<u16>(0, 12);
+            self.builder
+                .reborrow()
+                .get_pointer_field(0)
+                .try_set_text(value)
+        }
+        #[inline]
         pub fn init_text(self, size: u32) -> crate::text::Builder<'a> {
             self.builder.set_data_field::<u16>(0, 12);
             self.builder.get_pointer_field(0).init_text(size)
         }
         #[inline]
+        pub fn try_init_text(self, size: u32) -> crate::Result<crate::text::Builder<'a>> {
+            self.builder.set_data_field::<u16>(0, 12);
+            self.builder.get_pointer_field(0).try_init_text(size)
+        }
+        #[inline]
+        pub fn reserve_text(self, max_len: u32) -> crate::Result<crate::text::Reservation<'a>> {
+            self.builder.set_data_field::<u16>(0, 12);
+            crate::text::reserve(
+                crate::any_pointer::Builder::new(self.builder.get_pointer_field(0)),
+                max_len,
+            )
+        }
+        #[inline]
         pub fn has_text(&self) -> bool {
             if self.builder.get_data_field::<u16>(0) != 12 {
                 return false;
@@ -11935,11 +12868,32 @@ pub mod value {
             self.builder.reborrow().get_pointer_field(0).set_data(value);
         }
         #[inline]
+        pub fn try_set_data(&mut self, value: crate::data::Reader<'_>) -> crate::Result<()> {
+            self.builder.set_data_field::<u16>(0, 13);
+            self.builder
+                .reborrow()
+                .get_pointer_field(0)
+                .try_set_data(value)
+        }
+        #[inline]
         pub fn init_data(self, size: u32) -> crate::data::Builder<'a> {
             self.builder.set_data_field::<u16>(0, 13);
             self.builder.get_pointer_field(0).init_data(size)
         }
         #[inline]
+        pub fn try_init_data(self, size: u32) -> crate::Result<crate::data::Builder<'a>> {
+            self.builder.set_data_field::<u16>(0, 13);
+            self.builder.get_pointer_field(0).try_init_data(size)
+        }
+        #[inline]
+        pub fn reserve_data(self, max_len: u32) -> crate::Result<crate::data::Reservation<'a>> {
+            self.builder.set_data_field::<u16>(0, 13);
+            crate::data::reserve(
+                crate::any_pointer::Builder::new(self.builder.get_pointer_field(0)),
+                max_len,
+            )
+        }
+        #[inline]
         pub fn has_data(&self) -> bool {
             if self.builder.get_data_field::<u16>(0) != 13 {
                 return false;
@@ -12585,6 +13539,12 @@ pub mod annotation {
         }
     }
 
+    impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(crate::dynamic_struct::Builder::new(
@@ -12612,6 +13572,16 @@ pub mod annotation {
                 .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: crate::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> crate::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: crate::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [crate::Word]>,
@@ -12681,6 +13651,13 @@ pub mod annotation {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), 0)
         }
         #[inline]
+        pub fn try_init_value(self) -> crate::Result<crate::schema_capnp::value::Builder<'a>> {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_value(&self) -> bool {
             !self.builder.is_pointer_field_null(0)
         }
@@ -12707,6 +13684,13 @@ pub mod annotation {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(1), 0)
         }
         #[inline]
+        pub fn try_init_brand(self) -> crate::Result<crate::schema_capnp::brand::Builder<'a>> {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(1),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_brand(&self) -> bool {
             !self.builder.is_pointer_field_null(1)
         }
@@ -13085,6 +14069,12 @@ pub mod capnp_version {
         }
     }
 
+    impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(crate::dynamic_struct::Builder::new(
@@ -13112,6 +14102,16 @@ pub mod capnp_version {
                 .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: crate::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> crate::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: crate::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [crate::Word]>,
@@ -13461,6 +14461,12 @@ pub mod code_generator_request {
         }
     }
 
+    impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+        fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+            self.builder
+        }
+    }
+
     impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
         fn from(builder: Builder<'a>) -> Self {
             Self::Struct(crate::dynamic_struct::Builder::new(
@@ -13488,6 +14494,16 @@ pub mod code_generator_request {
                 .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                 .into()
         }
+        fn try_init_pointer(
+            builder: crate::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> crate::Result<Self> {
+            ::core::result::Result::Ok(
+                builder
+                    .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                    .into(),
+            )
+        }
         fn get_from_pointer(
             builder: crate::private::layout::PointerBuilder<'a>,
             default: ::core::option::Option<&'a [crate::Word]>,
@@ -13555,6 +14571,17 @@ pub mod code_generator_request {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(0), size)
         }
         #[inline]
+        pub fn try_init_nodes(
+            self,
+            size: u32,
+        ) -> crate::Result<crate::struct_list::Builder<'a, crate::schema_capnp::node::Owned>>
+        {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(0),
+                size,
+            )
+        }
+        #[inline]
         pub fn has_nodes(&self) -> bool {
             !self.builder.is_pointer_field_null(0)
         }
@@ -13597,6 +14624,21 @@ pub mod code_generator_request {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(1), size)
         }
         #[inline]
+        pub fn try_init_requested_files(
+            self,
+            size: u32,
+        ) -> crate::Result<
+            crate::struct_list::Builder<
+                'a,
+                crate::schema_capnp::code_generator_request::requested_file::Owned,
+            >,
+        > {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(1),
+                size,
+            )
+        }
+        #[inline]
         pub fn has_requested_files(&self) -> bool {
             !self.builder.is_pointer_field_null(1)
         }
@@ -13625,6 +14667,15 @@ pub mod code_generator_request {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(2), 0)
         }
         #[inline]
+        pub fn try_init_capnp_version(
+            self,
+        ) -> crate::Result<crate::schema_capnp::capnp_version::Builder<'a>> {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(2),
+                0,
+            )
+        }
+        #[inline]
         pub fn has_capnp_version(&self) -> bool {
             !self.builder.is_pointer_field_null(2)
         }
@@ -13659,6 +14710,18 @@ pub mod code_generator_request {
             crate::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field(3), size)
         }
         #[inline]
+        pub fn try_init_source_info(
+            self,
+            size: u32,
+        ) -> crate::Result<
+            crate::struct_list::Builder<'a, crate::schema_capnp::node::source_info::Owned>,
+        > {
+            crate::traits::FromPointerBuilder::try_init_pointer(
+                self.builder.get_pointer_field(3),
+                size,
+            )
+        }
+        #[inline]
         pub fn has_source_info(&self) -> bool {
             !self.builder.is_pointer_field_null(3)
         }
@@ -13961,6 +15024,12 @@ pub mod code_generator_request {
             }
         }
 
+        impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+            fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                self.builder
+            }
+        }
+
         impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
             fn from(builder: Builder<'a>) -> Self {
                 Self::Struct(crate::dynamic_struct::Builder::new(
@@ -13991,6 +15060,16 @@ pub mod code_generator_request {
                     .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                     .into()
             }
+            fn try_init_pointer(
+                builder: crate::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> crate::Result<Self> {
+                ::core::result::Result::Ok(
+                    builder
+                        .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                        .into(),
+                )
+            }
             fn get_from_pointer(
                 builder: crate::private::layout::PointerBuilder<'a>,
                 default: ::core::option::Option<&'a [crate::Word]>,
@@ -14049,10 +15128,34 @@ pub mod code_generator_request {
                 self.builder.reborrow().get_pointer_field(0).set_text(value);
             }
             #[inline]
+            pub fn try_set_filename(
+                &mut self,
+                value: crate::text::Reader<'_>,
+            ) -> crate::Result<()> {
+                self.builder
+                    .reborrow()
+                    .get_pointer_field(0)
+                    .try_set_text(value)
+            }
+            #[inline]
             pub fn init_filename(self, size: u32) -> crate::text::Builder<'a> {
                 self.builder.get_pointer_field(0).init_text(size)
             }
             #[inline]
+            pub fn try_init_filename(self, size: u32) -> crate::Result<crate::text::Builder<'a>> {
+                self.builder.get_pointer_field(0).try_init_text(size)
+            }
+            #[inline]
+            pub fn reserve_filename(
+                self,
+                max_len: u32,
+            ) -> crate::Result<crate::text::Reservation<'a>> {
+                crate::text::reserve(
+                    crate::any_pointer::Builder::new(self.builder.get_pointer_field(0)),
+                    max_len,
+                )
+            }
+            #[inline]
             pub fn has_filename(&self) -> bool {
                 !self.builder.is_pointer_field_null(0)
             }
@@ -14098,6 +15201,21 @@ pub mod code_generator_request {
                 )
             }
             #[inline]
+            pub fn try_init_imports(
+                self,
+                size: u32,
+            ) -> crate::Result<
+                crate::struct_list::Builder<
+                    'a,
+                    crate::schema_capnp::code_generator_request::requested_file::import::Owned,
+                >,
+            > {
+                crate::traits::FromPointerBuilder::try_init_pointer(
+                    self.builder.get_pointer_field(1),
+                    size,
+                )
+            }
+            #[inline]
             pub fn has_imports(&self) -> bool {
                 !self.builder.is_pointer_field_null(1)
             }
@@ -14358,6 +15476,12 @@ pub mod code_generator_request {
                 }
             }
 
+            impl<'a> crate::traits::IntoInternalStructBuilder<'a> for Builder<'a> {
+                fn into_internal_struct_builder(self) -> crate::private::layout::StructBuilder<'a> {
+                    self.builder
+                }
+            }
+
             impl<'a> ::core::convert::From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
                 fn from(builder: Builder<'a>) -> Self {
                     Self::Struct(crate::dynamic_struct::Builder::new(
@@ -14390,6 +15514,16 @@ pub mod code_generator_request {
                         .init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)
                         .into()
                 }
+                fn try_init_pointer(
+                    builder: crate::private::layout::PointerBuilder<'a>,
+                    _size: u32,
+                ) -> crate::Result<Self> {
+                    ::core::result::Result::Ok(
+                        builder
+                            .try_init_struct(<Self as crate::traits::HasStructSize>::STRUCT_SIZE)?
+                            .into(),
+                    )
+                }
                 fn get_from_pointer(
                     builder: crate::private::layout::PointerBuilder<'a>,
                     default: ::core::option::Option<&'a [crate::Word]>,
@@ -14451,10 +15585,34 @@ pub mod code_generator_request {
                     self.builder.reborrow().get_pointer_field(0).set_text(value);
                 }
                 #[inline]
+                pub fn try_set_name(
+                    &mut self,
+                    value: crate::text::Reader<'_>,
+                ) -> crate::Result<()> {
+                    self.builder
+                        .reborrow()
+                        .get_pointer_field(0)
+                        .try_set_text(value)
+                }
+                #[inline]
                 pub fn init_name(self, size: u32) -> crate::text::Builder<'a> {
                     self.builder.get_pointer_field(0).init_text(size)
                 }
                 #[inline]
+                pub fn try_init_name(self, size: u32) -> crate::Result<crate::text::Builder<'a>> {
+                    self.builder.get_pointer_field(0).try_init_text(size)
+                }
+                #[inline]
+                pub fn reserve_name(
+                    self,
+                    max_len: u32,
+                ) -> crate::Result<crate::text::Reservation<'a>> {
+                    crate::text::reserve(
+                        crate::any_pointer::Builder::new(self.builder.get_pointer_field(0)),
+                        max_len,
+                    )
+                }
+                #[inline]
                 pub fn has_name(&self) -> bool {
                     !self.builder.is_pointer_field_null(0)
                 }
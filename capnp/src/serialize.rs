@@ -23,7 +23,7 @@
 //! [standard stream framing](https://capnproto.org/encoding.html#serialization-over-a-stream),
 //! where each message is preceded by a segment table indicating the size of its segments.
 
-use crate::io::{Read, Write};
+use crate::io::{BufRead, Read, VectoredWrite, Write};
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 #[cfg(feature = "alloc")]
@@ -31,16 +31,24 @@ use core::convert::TryInto;
 #[cfg(feature = "alloc")]
 use core::ops::Deref;
 
+#[cfg(feature = "alloc")]
+mod bundle;
+#[cfg(feature = "alloc")]
+pub use bundle::{BundleReader, BundleWriter};
 pub(crate) mod no_alloc_buffer_segments;
 pub use no_alloc_buffer_segments::{
     NoAllocBufferSegments, NoAllocSegmentTableInfo, NoAllocSliceSegments,
 };
 
 use crate::message;
+#[cfg(feature = "alloc")]
+use crate::private::scrub::scrub_bytes;
 use crate::private::units::BYTES_PER_WORD;
 use crate::Result;
 use crate::{Error, ErrorKind};
 
+/// The default of `ReaderOptions::segments_limit`: messages with this many segments or more are
+/// rejected.
 pub const SEGMENTS_COUNT_LIMIT: usize = 512;
 
 /// Segments read from a single flat slice of words.
@@ -64,9 +72,14 @@ pub fn read_message_from_flat_slice<'a>(
     let Some(segment_lengths_builder) = read_segment_table(&mut bytes, options)? else {
         return Err(Error::from_kind(ErrorKind::EmptySlice));
     };
-    let segment_table_bytes_len = orig_bytes_len - bytes.len();
+    let mut segment_table_bytes_len = orig_bytes_len - bytes.len();
     assert_eq!(segment_table_bytes_len % BYTES_PER_WORD, 0);
     let num_words = segment_lengths_builder.total_words();
+    segment_table_bytes_len += lenient_table_padding(
+        &segment_lengths_builder,
+        &all_bytes[segment_table_bytes_len..],
+        options,
+    )?;
     let body_bytes = &all_bytes[segment_table_bytes_len..];
     if num_words > (body_bytes.len() / BYTES_PER_WORD) {
         Err(Error::from_kind(ErrorKind::MessageEndsPrematurely(
@@ -75,10 +88,10 @@ pub fn read_message_from_flat_slice<'a>(
         )))
     } else {
         *slice = &body_bytes[(num_words * BYTES_PER_WORD)..];
-        Ok(message::Reader::new(
+        message::Reader::new_checked(
             segment_lengths_builder.into_slice_segments(all_bytes, segment_table_bytes_len),
             options,
-        ))
+        )
     }
 }
 
@@ -96,7 +109,126 @@ pub fn read_message_from_flat_slice_no_alloc<'a>(
 ) -> Result<message::Reader<NoAllocSliceSegments<'a>>> {
     let segments = NoAllocSliceSegments::from_slice(slice, options)?;
 
-    Ok(message::Reader::new(segments, options))
+    message::Reader::new_checked(segments, options)
+}
+
+/// Reads a serialized message (including a segment table) from a slice of words, without copying.
+/// The segments of the returned reader borrow `words`, which, unlike a slice of bytes, is always
+/// aligned. Words beyond the end of the message are ignored.
+///
+/// Returns a `MessageEndsPrematurely` error if the segment table declares more words than
+/// `words` holds.
+pub fn read_message_from_words<'a>(
+    words: &'a [crate::Word],
+    options: message::ReaderOptions,
+) -> Result<message::Reader<NoAllocSliceSegments<'a>>> {
+    read_message_from_word_slice(&mut { words }, options)
+}
+
+/// Like [`read_message_from_words()`], but on success updates `slice` to point to the words beyond
+/// the end of the message, which makes it possible to read messages stored back to back one after
+/// another. On failure, `slice` is left unchanged.
+pub fn read_message_from_word_slice<'a>(
+    slice: &mut &'a [crate::Word],
+    options: message::ReaderOptions,
+) -> Result<message::Reader<NoAllocSliceSegments<'a>>> {
+    let mut bytes = crate::Word::words_to_bytes(slice);
+    let message = read_message_from_flat_slice_no_alloc(&mut bytes, options)?;
+    // A message, including its segment table, is a whole number of words.
+    *slice = &slice[(slice.len() - bytes.len() / BYTES_PER_WORD)..];
+    Ok(message)
+}
+
+/// Reads a message from segments that the caller has framed itself, e.g. after parsing the segment
+/// table with `parse_segment_table_bytes()` and then decrypting or decompressing the segments.
+/// Segment 0 holds the root pointer. Nothing is copied, and as with any reader, the segments are
+/// checked as they are read. Up front, their number and total size are checked against `options`,
+/// as those of a segment table read from a stream are.
+///
+/// Returns an `InvalidNumberOfSegments` error if there are no segments, or
+/// `options.segments_limit` or more.
+pub fn read_message_from_segments<'a>(
+    segments: &'a [&'a [crate::Word]],
+    options: message::ReaderOptions,
+) -> Result<message::Reader<&'a [&'a [crate::Word]]>> {
+    if segments.is_empty() || segments.len() >= options.segments_limit {
+        return Err(segment_table_error(ErrorKind::InvalidNumberOfSegments(
+            segments.len(),
+        )));
+    }
+    check_total_words(segments.iter().map(|segment| segment.len()).sum(), options)?;
+    message::Reader::new_checked(segments, options)
+}
+
+/// Reads a serialized message (including a segment table) from `bytes`, taking ownership of them.
+/// The segments of the returned reader are slices of `bytes`, so nothing is copied, and the only
+/// work done up front is parsing and checking the segment table. Bytes beyond the end of the
+/// message are ignored.
+///
+/// ALIGNMENT: Unless the "unaligned" feature is enabled, the segments must be 8-byte aligned. The
+/// allocations of the global allocator nearly always are, but if that of `bytes` is not, the bytes
+/// are copied into aligned storage first; see [`AlignedBytes`].
+#[cfg(feature = "alloc")]
+pub fn read_message_from_owned_bytes(
+    bytes: Vec<u8>,
+    options: message::ReaderOptions,
+) -> Result<message::Reader<BufferSegments<AlignedBytes>>> {
+    let segments = BufferSegments::new(AlignedBytes::new(bytes), options)?;
+    message::Reader::new_checked(segments, options)
+}
+
+/// Bytes whose start is aligned as segments must be, made from a `Vec<u8>`. The vector is kept as
+/// it is if its allocation is 8-byte aligned, or if the "unaligned" feature is enabled. Otherwise,
+/// its contents are copied into a vector of words.
+#[cfg(feature = "alloc")]
+pub struct AlignedBytes {
+    storage: AlignedStorage,
+}
+
+#[cfg(feature = "alloc")]
+enum AlignedStorage {
+    Bytes(Vec<u8>),
+    /// A copy of a misaligned vector of `len` bytes.
+    Words {
+        words: Vec<crate::Word>,
+        len: usize,
+    },
+}
+
+#[cfg(feature = "alloc")]
+impl AlignedBytes {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        if cfg!(feature = "unaligned") || bytes.as_ptr().cast::<crate::Word>().is_aligned() {
+            return Self {
+                storage: AlignedStorage::Bytes(bytes),
+            };
+        }
+        let mut words = crate::Word::allocate_zeroed_vec(bytes.len().div_ceil(BYTES_PER_WORD));
+        crate::Word::words_to_bytes_mut(&mut words)[..bytes.len()].copy_from_slice(&bytes);
+        Self {
+            storage: AlignedStorage::Words {
+                words,
+                len: bytes.len(),
+            },
+        }
+    }
+
+    /// Whether the bytes had to be copied to align them.
+    pub fn was_copied(&self) -> bool {
+        matches!(self.storage, AlignedStorage::Words { .. })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Deref for AlignedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match &self.storage {
+            AlignedStorage::Bytes(bytes) => bytes,
+            AlignedStorage::Words { words, len } => &crate::Word::words_to_bytes(words)[..*len],
+        }
+    }
 }
 
 /// Segments read from a buffer, useful for when you have the message in a buffer and don't want the extra
@@ -128,9 +260,16 @@ impl<T: Deref<Target = [u8]>> BufferSegments<T> {
         let Some(segment_table) = read_segment_table(&mut segment_bytes, options)? else {
             return Err(Error::from_kind(ErrorKind::EmptyBuffer));
         };
-        let segment_table_bytes_len = buffer.len() - segment_bytes.len();
-
-        assert!(segment_table.total_words() * 8 <= buffer.len());
+        let segment_table_bytes_len = buffer.len() - segment_bytes.len()
+            + lenient_table_padding(&segment_table, segment_bytes, options)?;
+
+        let body_words = (buffer.len() - segment_table_bytes_len) / BYTES_PER_WORD;
+        if segment_table.total_words() > body_words {
+            return Err(Error::from_kind(ErrorKind::MessageEndsPrematurely(
+                segment_table.total_words(),
+                body_words,
+            )));
+        }
         let segment_indices = segment_table.to_segment_indices();
         Ok(Self {
             buffer,
@@ -174,6 +313,43 @@ pub struct OwnedSegments {
     owned_space: Vec<crate::Word>,
 }
 
+#[cfg(feature = "alloc")]
+impl OwnedSegments {
+    /// Overwrites the whole buffer with zeroes, using writes that the compiler cannot elide.
+    /// Afterwards every segment reads as zeroed memory.
+    pub fn scrub(&mut self) {
+        let bytes = crate::Word::words_to_bytes_mut(&mut self.owned_space[..]);
+        unsafe { scrub_bytes(bytes.as_mut_ptr(), bytes.len()) };
+    }
+
+    /// Borrows the segments in the form that `message::Builder::get_segments_for_output()`
+    /// returns, so that they can be written out again, e.g. with `OutputSegments::write_to()`.
+    ///
+    /// The segments are exactly those that were read, in the same order, so writing them out
+    /// reproduces the original unpacked stream byte for byte.
+    pub fn as_output_segments(&self) -> crate::OutputSegments<'_> {
+        use crate::message::ReaderSegments;
+        if self.len() == 1 {
+            crate::OutputSegments::SingleSegment([self.get_segment(0).unwrap()])
+        } else {
+            crate::OutputSegments::MultiSegment(
+                (0..self.len() as u32)
+                    .map(|id| self.get_segment(id).unwrap())
+                    .collect(),
+            )
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl message::Reader<OwnedSegments> {
+    /// Consumes the reader, zeroing its buffer before freeing it. Use this to discard
+    /// a message that held secrets such as key material.
+    pub fn scrub(self) {
+        self.into_segments().scrub();
+    }
+}
+
 #[cfg(feature = "alloc")]
 impl core::ops::Deref for OwnedSegments {
     type Target = [u8];
@@ -211,6 +387,155 @@ impl crate::message::ReaderSegments for OwnedSegments {
     }
 }
 
+/// A whole serialized message, including its segment table, in word-aligned memory that it
+/// owns. The segment table is parsed once, when the message is constructed, and
+/// `reader()` may then be called any number of times without parsing it again.
+///
+/// This type exists so that bytes can be converted to a message with `TryFrom` and `?`, and a
+/// builder to bytes with `From` and `.into()`:
+///
+/// ```
+/// # fn main() -> capnp::Result<()> {
+/// use capnp::serialize::SerializedMessage;
+///
+/// let mut builder = capnp::message::Builder::new_default();
+/// builder.set_root("hello")?;
+/// let bytes: Vec<u8> = (&builder).into();
+///
+/// let message = SerializedMessage::try_from(&bytes[..])?;
+/// let reader = message.reader();
+/// let root: capnp::text::Reader = reader.get_root()?;
+/// assert_eq!(root, "hello");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+pub struct SerializedMessage {
+    words: Vec<crate::Word>,
+
+    // Number of bytes in the segment table.
+    segment_table_bytes_len: usize,
+
+    // Each pair represents a segment, as in `BufferSegments`.
+    segment_indices: Vec<(usize, usize)>,
+
+    options: message::ReaderOptions,
+}
+
+#[cfg(feature = "alloc")]
+impl SerializedMessage {
+    /// Copies `bytes`, which must hold exactly one serialized message, and checks its segment
+    /// table against `options`. If `options.require_canonical` is set, also checks that the
+    /// message is canonical. The bytes need not be aligned.
+    pub fn new(bytes: &[u8], options: message::ReaderOptions) -> Result<Self> {
+        let mut words = crate::Word::allocate_zeroed_vec(bytes.len().div_ceil(BYTES_PER_WORD));
+        crate::Word::words_to_bytes_mut(&mut words)[..bytes.len()].copy_from_slice(bytes);
+
+        let mut rest = &crate::Word::words_to_bytes(&words)[..bytes.len()];
+        let BufferSegments {
+            segment_table_bytes_len,
+            segment_indices,
+            ..
+        } = read_message_from_flat_slice(&mut rest, options)?.into_segments();
+        if !rest.is_empty() {
+            let mut error = Error::from_kind(ErrorKind::Failed);
+            write!(error, "{} bytes after the end of the message", rest.len());
+            return Err(error);
+        }
+        Ok(Self {
+            words,
+            segment_table_bytes_len,
+            segment_indices,
+            options,
+        })
+    }
+
+    /// Gets the options that the message was checked against, and that `reader()` reads with.
+    pub fn options(&self) -> message::ReaderOptions {
+        self.options
+    }
+
+    /// Gets a reader for the message. Each reader has its own traversal limit budget.
+    pub fn reader(&self) -> message::Reader<&Self> {
+        message::Reader::new(self, self.options)
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Deref for SerializedMessage {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        crate::Word::words_to_bytes(&self.words)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<[u8]> for SerializedMessage {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl message::ReaderSegments for SerializedMessage {
+    fn get_segment(&self, id: u32) -> Option<&[u8]> {
+        let (a, b) = *self.segment_indices.get(id as usize)?;
+        Some(
+            &self[(self.segment_table_bytes_len + a * BYTES_PER_WORD)
+                ..(self.segment_table_bytes_len + b * BYTES_PER_WORD)],
+        )
+    }
+
+    fn len(&self) -> usize {
+        self.segment_indices.len()
+    }
+}
+
+/// Uses the default `ReaderOptions`.
+#[cfg(feature = "alloc")]
+impl TryFrom<&[u8]> for SerializedMessage {
+    type Error = Error;
+    fn try_from(bytes: &[u8]) -> Result<Self> {
+        Self::new(bytes, message::ReaderOptions::new())
+    }
+}
+
+/// Uses the default `ReaderOptions`.
+#[cfg(feature = "alloc")]
+impl TryFrom<Vec<u8>> for SerializedMessage {
+    type Error = Error;
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        Self::new(&bytes, message::ReaderOptions::new())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<SerializedMessage> for Vec<u8> {
+    fn from(message: SerializedMessage) -> Self {
+        message.into_vec()
+    }
+}
+
+/// Same as `write_message_to_words()`.
+#[cfg(feature = "alloc")]
+impl<A: message::Allocator> From<&message::Builder<A>> for Vec<u8> {
+    fn from(message: &message::Builder<A>) -> Self {
+        write_message_to_words(message)
+    }
+}
+
+/// Same as `write_message_to_words()`.
+#[cfg(feature = "alloc")]
+impl<A: message::Allocator> From<&mut message::Builder<A>> for Vec<u8> {
+    fn from(message: &mut message::Builder<A>) -> Self {
+        write_message_to_words(message)
+    }
+}
+
 #[cfg(feature = "alloc")]
 /// Helper object for constructing an `OwnedSegments` or a `SliceSegments`.
 pub struct SegmentLengthsBuilder {
@@ -255,6 +580,10 @@ impl SegmentLengthsBuilder {
     /// Constructs an `OwnedSegments`, allocating a single buffer of 8-byte aligned memory to hold
     /// all segments.
     pub fn into_owned_segments(self) -> OwnedSegments {
+        // Zeroing the buffer before it is read into might look wasteful, but leaving it
+        // uninitialized would be unsound with an arbitrary `Read`, and is not faster anyway:
+        // zeroing faults in the pages of large buffers more cheaply than the read would. See
+        // `benches/read_message.rs`.
         let owned_space = crate::Word::allocate_zeroed_vec(self.total_words);
         OwnedSegments {
             segment_indices: self.segment_indices,
@@ -289,30 +618,103 @@ impl SegmentLengthsBuilder {
     }
 }
 
+/// Returns the number of bytes of over-padding after a segment table to skip, as permitted by
+/// `ReaderOptions::lenient_segment_table` or `ReaderOptions::tolerate_legacy_padding`. `body`
+/// holds the bytes following the segment table.
+///
+/// Padding is only recognized where the buffer ends exactly at the end of the message once it is
+/// skipped, and the words between the table and the first segment are all zero. Without either
+/// option, a message framed like that is rejected rather than read from the wrong offset. The
+/// only well-formed messages that look like padded ones have a null root pointer, and so no
+/// content.
+#[cfg(feature = "alloc")]
+fn lenient_table_padding(
+    segment_table: &SegmentLengthsBuilder,
+    body: &[u8],
+    options: message::ReaderOptions,
+) -> Result<usize> {
+    let Some(total_bytes) = segment_table.total_words().checked_mul(BYTES_PER_WORD) else {
+        return Ok(0);
+    };
+    let padding_bytes = match body.len().checked_sub(total_bytes) {
+        Some(bytes) if bytes >= BYTES_PER_WORD => bytes / BYTES_PER_WORD * BYTES_PER_WORD,
+        _ => return Ok(0),
+    };
+    if body.len() != total_bytes + padding_bytes
+        || body[..padding_bytes].iter().any(|&byte| byte != 0)
+    {
+        return Ok(0);
+    }
+    // A table for an odd number of segments ends on a word boundary, so it never needs padding.
+    let legacy = padding_bytes == BYTES_PER_WORD && segment_table.segment_indices.len() % 2 == 1;
+    if !(options.lenient_segment_table || legacy && options.tolerate_legacy_padding) {
+        return Err(segment_table_error(
+            ErrorKind::SegmentTableFollowedByPadding,
+        ));
+    }
+    decode_trace! {
+        crate::trace::emit(|| crate::DecodeEvent {
+            segment_id: None,
+            word_offset: None,
+            check: crate::DecodeCheck::LegacySegmentTablePadding,
+        });
+    }
+    Ok(padding_bytes)
+}
+
+/// Constructs an error for a rejected segment table, reporting it to the decode trace hook.
+fn segment_table_error(kind: ErrorKind) -> Error {
+    decode_trace! {
+        crate::trace::emit(|| crate::DecodeEvent {
+            segment_id: None,
+            word_offset: None,
+            check: crate::DecodeCheck::SegmentTable,
+        });
+    }
+    Error::from_kind(kind)
+}
+
+/// Rejects a message whose segment table declares `total_words` words across its segments, if
+/// that is more than `options.total_size_limit_in_words` or `options.traversal_limit_in_words`.
+fn check_total_words(total_words: usize, options: message::ReaderOptions) -> Result<()> {
+    if let Some(limit) = options.total_size_limit_in_words {
+        if total_words > limit {
+            let mut error = segment_table_error(ErrorKind::Overloaded);
+            write!(
+                error,
+                "segment table declares {total_words} words, more than the limit of {limit}"
+            );
+            return Err(error);
+        }
+    }
+    if let Some(limit) = options.traversal_limit_in_words {
+        if total_words > limit {
+            return Err(segment_table_error(ErrorKind::MessageTooLarge(total_words)));
+        }
+    }
+    Ok(())
+}
+
 /// Reads a serialized message from a stream with the provided options.
 ///
 /// For optimal performance, `read` should be a buffered reader type.
 #[cfg(feature = "alloc")]
 pub fn read_message<R>(
-    mut read: R,
+    read: R,
     options: message::ReaderOptions,
 ) -> Result<message::Reader<OwnedSegments>>
 where
     R: Read,
 {
-    let Some(owned_segments_builder) = read_segment_table(&mut read, options)? else {
-        return Err(Error::from_kind(ErrorKind::PrematureEndOfFile));
-    };
-    read_segments(
-        &mut read,
-        owned_segments_builder.into_owned_segments(),
-        options,
-    )
+    try_read_message(read, options)?.ok_or_else(|| Error::from_kind(ErrorKind::PrematureEndOfFile))
 }
 
 /// Like `read_message()`, but returns None instead of an error if there are zero bytes left in
 /// `read`. This is useful for reading a stream containing an unknown number of messages -- you
 /// call this function until it returns None.
+///
+/// Only a stream that ends before the first byte of a message gives None. One that ends partway
+/// through the segment table or the segments is still an error.
 #[cfg(feature = "alloc")]
 pub fn try_read_message<R>(
     mut read: R,
@@ -331,6 +733,233 @@ where
     )?))
 }
 
+/// An iterator over a stream of messages written back to back, such as by repeated calls to
+/// `write_message()`. Each item is the next message, read as by `read_message()`.
+///
+/// Iteration ends with `None` when the stream ends exactly where a message does. If it ends
+/// partway through a message, or a message is malformed, that item is an `Err`, after which the
+/// iterator returns `None`, as there is no telling where the next message would start.
+#[cfg(feature = "alloc")]
+pub struct MessageIterator<R> {
+    read: R,
+    options: message::ReaderOptions,
+    /// Storage for large segment tables, kept from one message to the next.
+    table_scratch: Vec<u8>,
+    done: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl<R> MessageIterator<R>
+where
+    R: Read,
+{
+    pub fn new(read: R, options: message::ReaderOptions) -> Self {
+        Self {
+            read,
+            options,
+            table_scratch: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Returns the underlying stream, positioned after the last message read.
+    pub fn into_inner(self) -> R {
+        self.read
+    }
+
+    fn read_next(&mut self) -> Result<Option<message::Reader<OwnedSegments>>> {
+        let Some(segment_lengths) =
+            read_segment_table_with_scratch(&mut self.read, self.options, &mut self.table_scratch)?
+        else {
+            return Ok(None);
+        };
+        read_segments(
+            &mut self.read,
+            segment_lengths.into_owned_segments(),
+            self.options,
+        )
+        .map(Some)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<R> Iterator for MessageIterator<R>
+where
+    R: Read,
+{
+    type Item = Result<message::Reader<OwnedSegments>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let next = self.read_next().transpose();
+        self.done = !matches!(next, Some(Ok(_)));
+        next
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<R> core::iter::FusedIterator for MessageIterator<R> where R: Read {}
+
+/// Like `read_message()`, for readers that hold the bytes in a buffer of their own, such as an
+/// in-memory cursor or a `std::io::BufReader`. The segment table is parsed where it lies in the
+/// buffer whenever all of it is there, and the segments are copied from the buffer straight into
+/// the message's storage, so that no byte is copied twice.
+#[cfg(feature = "alloc")]
+pub fn read_message_buffered<R>(
+    mut read: R,
+    options: message::ReaderOptions,
+) -> Result<message::Reader<OwnedSegments>>
+where
+    R: BufRead,
+{
+    let Some(segment_lengths) = read_segment_table_buffered(&mut read, options)? else {
+        return Err(Error::from_kind(ErrorKind::PrematureEndOfFile));
+    };
+    let mut owned_segments = segment_lengths.into_owned_segments();
+    let segments = &mut owned_segments[..];
+    // When the segments are all empty, there is nothing more to read, and asking for more could
+    // block until the next message arrives.
+    if !segments.is_empty() {
+        let buffered = read.fill_buf()?;
+        let n = buffered.len().min(segments.len());
+        segments[..n].copy_from_slice(&buffered[..n]);
+        read.consume(n);
+        // Whatever did not fit in the buffer is read directly, which a `BufReader` passes on to
+        // the reader it wraps without going through its buffer.
+        read.read_exact(&mut segments[n..])?;
+    }
+    crate::message::Reader::new_checked(owned_segments, options)
+}
+
+/// Reads a message from bytes that arrive a few at a time, such as from a non-blocking socket,
+/// where `read_message()` would need to block until the whole message has arrived. Feed it the
+/// bytes with `push()` as they arrive; it keeps track of how much of the segment table or the
+/// segments it has seen, and returns each message once all of it is there.
+#[cfg(feature = "alloc")]
+pub struct MessageReadState {
+    options: message::ReaderOptions,
+    state: ReadState,
+
+    /// Bytes pushed past the end of the last message returned, which start the next one.
+    pending: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+enum ReadState {
+    /// Reading the segment table, of which `table` holds the bytes seen so far.
+    SegmentTable { table: Vec<u8> },
+
+    /// Reading the segments, of which the first `filled` bytes have been seen.
+    Segments {
+        segments: OwnedSegments,
+        filled: usize,
+    },
+}
+
+#[cfg(feature = "alloc")]
+impl MessageReadState {
+    pub fn new(options: message::ReaderOptions) -> Self {
+        Self {
+            options,
+            state: ReadState::SegmentTable { table: Vec::new() },
+            pending: Vec::new(),
+        }
+    }
+
+    /// Takes the next bytes of the stream, and returns the message they complete, if any.
+    ///
+    /// Bytes past the end of the returned message are kept as the start of the next one, which
+    /// may be complete already: call `push(&[])` until it returns `None` to read every message
+    /// that has arrived.
+    ///
+    /// A segment table that declares an invalid number of segments, or more words than the
+    /// traversal limit, is an error as soon as enough of it has arrived. The stream cannot be
+    /// read past such an error, and the state is reset, dropping any bytes it held.
+    pub fn push(&mut self, bytes: &[u8]) -> Result<Option<message::Reader<OwnedSegments>>> {
+        let result = if self.pending.is_empty() {
+            let mut input = bytes;
+            let result = self.advance(&mut input);
+            self.pending.extend_from_slice(input);
+            result
+        } else {
+            let mut pending = core::mem::take(&mut self.pending);
+            pending.extend_from_slice(bytes);
+            let mut input = &pending[..];
+            let result = self.advance(&mut input);
+            let consumed = pending.len() - input.len();
+            pending.drain(..consumed);
+            self.pending = pending;
+            result
+        };
+        if result.is_err() {
+            *self = Self::new(self.options);
+        }
+        result
+    }
+
+    /// Returns whether some bytes of a message that is not complete yet have been pushed. If the
+    /// stream ends when this is true, the last message was truncated.
+    pub fn is_mid_message(&self) -> bool {
+        match &self.state {
+            ReadState::SegmentTable { table } => !table.is_empty() || !self.pending.is_empty(),
+            ReadState::Segments { .. } => true,
+        }
+    }
+
+    /// Consumes bytes of `input` until it runs out or a message is complete.
+    fn advance(&mut self, input: &mut &[u8]) -> Result<Option<message::Reader<OwnedSegments>>> {
+        loop {
+            match &mut self.state {
+                ReadState::SegmentTable { table } => {
+                    let first_word = Self::take(table, BYTES_PER_WORD, input);
+                    let Some(first_word) = first_word.first_chunk() else {
+                        return Ok(None);
+                    };
+                    let segment_count =
+                        segment_table_segment_count(first_word, self.options.segments_limit)?;
+                    let table_len = segment_table_len(segment_count);
+                    if Self::take(table, table_len, input).len() < table_len {
+                        return Ok(None);
+                    }
+                    let segments =
+                        segment_lengths(table, segment_count, self.options)?.into_owned_segments();
+                    self.state = ReadState::Segments {
+                        segments,
+                        filled: 0,
+                    };
+                }
+                ReadState::Segments { segments, filled } => {
+                    let len = core::cmp::min(segments.len() - *filled, input.len());
+                    segments[*filled..*filled + len].copy_from_slice(&input[..len]);
+                    *filled += len;
+                    *input = &input[len..];
+                    if *filled < segments.len() {
+                        return Ok(None);
+                    }
+                    let ReadState::Segments { segments, .. } = core::mem::replace(
+                        &mut self.state,
+                        ReadState::SegmentTable { table: Vec::new() },
+                    ) else {
+                        unreachable!()
+                    };
+                    return message::Reader::new_checked(segments, self.options).map(Some);
+                }
+            }
+        }
+    }
+
+    /// Moves bytes from the front of `input` to the end of `table` until it holds `len` bytes, or
+    /// `input` runs out, and returns `table`.
+    fn take<'a>(table: &'a mut Vec<u8>, len: usize, input: &mut &[u8]) -> &'a [u8] {
+        let count = core::cmp::min(len.saturating_sub(table.len()), input.len());
+        table.extend_from_slice(&input[..count]);
+        *input = &input[count..];
+        table
+    }
+}
+
 /// Like `try_read_message()`, but does not allocate any memory.
 /// Stores the message in `buffer`. Returns a `BufferNotLargeEnough`
 /// error if the buffer is not large enough.
@@ -364,65 +993,38 @@ where
     }
 
     let segment_count =
-        u32::from_le_bytes(buffer[0..4].try_into().unwrap()).wrapping_add(1) as usize;
-
-    if segment_count >= SEGMENTS_COUNT_LIMIT || segment_count == 0 {
-        return Err(Error::from_kind(ErrorKind::InvalidNumberOfSegments(
-            segment_count,
-        )));
+        segment_table_segment_count(buffer[0..8].try_into().unwrap(), options.segments_limit)?;
+    let table_len = segment_table_len(segment_count);
+    if buffer.len() < table_len {
+        return Err(Error::from_kind(ErrorKind::BufferNotLargeEnough));
     }
+    read.read_exact(&mut buffer[8..table_len])?;
 
-    let mut total_body_words: usize = u32::from_le_bytes(buffer[4..8].try_into().unwrap()) as usize;
-    let mut num_segment_counts_read = 1;
-    while num_segment_counts_read < segment_count {
-        let start = (num_segment_counts_read + 1) * 4;
-        let end = start + 8;
-        if buffer.len() < end {
-            return Err(Error::from_kind(ErrorKind::BufferNotLargeEnough));
-        }
-
-        read.read(&mut buffer[start..end])?;
-
+    let mut total_body_words: usize = 0;
+    for length in segment_table_lengths(&buffer[..table_len], segment_count) {
         total_body_words = total_body_words
-            .checked_add(
-                u32::from_le_bytes(buffer[start..(start + 4)].try_into().unwrap()) as usize,
-            )
+            .checked_add(length as usize)
             .ok_or_else(|| Error::from_kind(ErrorKind::MessageSizeOverflow))?;
-
-        num_segment_counts_read += 1;
-        if num_segment_counts_read < segment_count {
-            total_body_words = total_body_words
-                .checked_add(
-                    u32::from_le_bytes(buffer[(start + 4)..end].try_into().unwrap()) as usize,
-                )
-                .ok_or_else(|| Error::from_kind(ErrorKind::MessageSizeOverflow))?;
-        }
-        num_segment_counts_read += 1;
     }
 
-    if let Some(limit) = options.traversal_limit_in_words {
-        if total_body_words > limit {
-            return Err(Error::from_kind(ErrorKind::MessageTooLarge(
-                total_body_words,
-            )));
-        }
-    }
+    check_total_words(total_body_words, options)?;
 
-    let start = (num_segment_counts_read + 1) * 4;
-    let end = start + (total_body_words * 8);
+    let end = table_len + (total_body_words * 8);
     if buffer.len() < end {
         return Err(Error::from_kind(ErrorKind::BufferNotLargeEnough));
     }
-    read.read_exact(&mut buffer[start..end])?;
+    read.read_exact(&mut buffer[table_len..end])?;
 
     let info = no_alloc_buffer_segments::NoAllocSegmentTableInfo {
         segments_count: segment_count,
-        segment_table_length_bytes: (num_segment_counts_read + 1) * 4,
+        segment_table_length_bytes: table_len,
         total_segments_length_bytes: total_body_words * 8,
     };
 
     let segments = NoAllocSliceSegments::from_segment_table_info(buffer, info);
-    Ok(Some(crate::message::Reader::new(segments, options)))
+    Ok(Some(crate::message::Reader::new_checked(
+        segments, options,
+    )?))
 }
 
 /// Like `read_message()`, but does not allocate.
@@ -450,9 +1052,23 @@ where
 /// The segment table format for streams is defined in the Cap'n Proto
 /// [encoding spec](https://capnproto.org/encoding.html)
 #[cfg(feature = "alloc")]
-fn read_segment_table<R>(
+pub(crate) fn read_segment_table<R>(
+    read: &mut R,
+    options: message::ReaderOptions,
+) -> Result<Option<SegmentLengthsBuilder>>
+where
+    R: Read,
+{
+    read_segment_table_with_scratch(read, options, &mut Vec::new())
+}
+
+/// Like `read_segment_table()`, but keeps tables too large for the stack in `scratch`, so that its
+/// allocation can be reused from one message to the next.
+#[cfg(feature = "alloc")]
+fn read_segment_table_with_scratch<R>(
     read: &mut R,
     options: message::ReaderOptions,
+    scratch: &mut Vec<u8>,
 ) -> Result<Option<SegmentLengthsBuilder>>
 where
     R: Read,
@@ -469,49 +1085,138 @@ where
         }
     }
 
-    let segment_count = u32::from_le_bytes(buf[0..4].try_into().unwrap()).wrapping_add(1) as usize;
+    let segment_count = segment_table_segment_count(&buf, options.segments_limit)?;
+    let table_len = segment_table_len(segment_count);
 
-    if segment_count >= SEGMENTS_COUNT_LIMIT || segment_count == 0 {
-        return Err(Error::from_kind(ErrorKind::InvalidNumberOfSegments(
-            segment_count,
-        )));
+    // Tables of up to three segments fit on the stack.
+    let mut small_table = [0u8; 2 * BYTES_PER_WORD];
+    let table = if table_len <= small_table.len() {
+        &mut small_table[..table_len]
+    } else {
+        scratch.clear();
+        scratch.resize(table_len, 0);
+        &mut scratch[..]
+    };
+    table[..BYTES_PER_WORD].copy_from_slice(&buf);
+    read.read_exact(&mut table[BYTES_PER_WORD..])?;
+
+    segment_lengths(table, segment_count, options).map(Some)
+}
+
+/// Like `read_segment_table()`, but parses the segment table in the buffer of `read` if all of it
+/// is there. Otherwise, the table straddles the end of the buffer, and is read a piece at a time.
+#[cfg(feature = "alloc")]
+fn read_segment_table_buffered<R>(
+    read: &mut R,
+    options: message::ReaderOptions,
+) -> Result<Option<SegmentLengthsBuilder>>
+where
+    R: BufRead,
+{
+    let buffered = read.fill_buf()?;
+    if buffered.is_empty() {
+        // Clean EOF on message boundary
+        return Ok(None);
     }
+    if let Some(first_word) = buffered.first_chunk() {
+        let segment_count = segment_table_segment_count(first_word, options.segments_limit)?;
+        let table_len = segment_table_len(segment_count);
+        if let Some(table) = buffered.get(..table_len) {
+            let segment_lengths = segment_lengths(table, segment_count, options)?;
+            read.consume(table_len);
+            return Ok(Some(segment_lengths));
+        }
+    }
+    read_segment_table(read, options)
+}
 
+/// Returns the segment lengths of `table`, a complete segment table for `segment_count` segments,
+/// checking them against the traversal limit.
+#[cfg(feature = "alloc")]
+pub(crate) fn segment_lengths(
+    table: &[u8],
+    segment_count: usize,
+    options: message::ReaderOptions,
+) -> Result<SegmentLengthsBuilder> {
     let mut segment_lengths_builder = SegmentLengthsBuilder::with_capacity(segment_count);
-    segment_lengths_builder
-        .try_push_segment(u32::from_le_bytes(buf[4..8].try_into().unwrap()) as usize)?;
-    if segment_count > 1 {
-        if segment_count < 4 {
-            read.read_exact(&mut buf)?;
-            for idx in 0..(segment_count - 1) {
-                let segment_len =
-                    u32::from_le_bytes(buf[(idx * 4)..(idx + 1) * 4].try_into().unwrap()) as usize;
-                segment_lengths_builder.try_push_segment(segment_len)?;
-            }
-        } else {
-            let mut segment_sizes = vec![0u8; (segment_count & !1) * 4];
-            read.read_exact(&mut segment_sizes[..])?;
-            for idx in 0..(segment_count - 1) {
-                let segment_len =
-                    u32::from_le_bytes(segment_sizes[(idx * 4)..(idx + 1) * 4].try_into().unwrap())
-                        as usize;
-                segment_lengths_builder.try_push_segment(segment_len)?;
-            }
-        }
+    for length in segment_table_lengths(table, segment_count) {
+        segment_lengths_builder.try_push_segment(length as usize)?;
     }
 
     // Don't accept a message which the receiver couldn't possibly traverse without hitting the
-    // traversal limit. Without this check, a malicious client could transmit a very large segment
-    // size to make the receiver allocate excessive space and possibly crash.
-    if let Some(limit) = options.traversal_limit_in_words {
-        if segment_lengths_builder.total_words() > limit {
-            return Err(Error::from_kind(ErrorKind::MessageTooLarge(
-                segment_lengths_builder.total_words(),
-            )));
-        }
+    // traversal limit, or that is over the total size limit. Without this check, a malicious client
+    // could transmit a very large segment size to make the receiver allocate excessive space and
+    // possibly crash.
+    check_total_words(segment_lengths_builder.total_words(), options)?;
+
+    Ok(segment_lengths_builder)
+}
+
+/// Returns the number of segments that a segment table declares in its first word, rejecting
+/// zero, and counts of `segments_limit` or more.
+pub(crate) fn segment_table_segment_count(
+    first_word: &[u8; 8],
+    segments_limit: usize,
+) -> Result<usize> {
+    let segment_count =
+        u32::from_le_bytes(first_word[0..4].try_into().unwrap()).wrapping_add(1) as usize;
+    if segment_count >= segments_limit || segment_count == 0 {
+        return Err(segment_table_error(ErrorKind::InvalidNumberOfSegments(
+            segment_count,
+        )));
     }
+    Ok(segment_count)
+}
 
-    Ok(Some(segment_lengths_builder))
+/// Returns the size in bytes of the segment table for `segment_count` segments, including the
+/// padding that ends it on a word boundary.
+pub(crate) fn segment_table_len(segment_count: usize) -> usize {
+    (segment_count / 2 + 1) * BYTES_PER_WORD
+}
+
+/// Returns the lengths in words of the segments in `table`, a complete segment table for
+/// `segment_count` segments.
+fn segment_table_lengths(table: &[u8], segment_count: usize) -> impl Iterator<Item = u32> + '_ {
+    table[4..(segment_count + 1) * 4]
+        .chunks_exact(4)
+        .map(|length| u32::from_le_bytes(length.try_into().unwrap()))
+}
+
+/// Parses the segment table at the start of `bytes`, for framers that read the segment table
+/// themselves, and returns the segment lengths in words and the size of the table in bytes.
+/// The first segment starts right after the table; any bytes there are ignored.
+///
+/// This is the parser that `read_message()` and friends use, but as it takes no `ReaderOptions`,
+/// it does not check the traversal limit, and rejects counts of segments of
+/// [`SEGMENTS_COUNT_LIMIT`] or more, as the default options do.
+#[cfg(feature = "alloc")]
+pub fn parse_segment_table_bytes(bytes: &[u8]) -> Result<(Vec<u32>, usize)> {
+    let Some(first_word) = bytes.first_chunk() else {
+        return Err(Error::from_kind(ErrorKind::PrematureEndOfFile));
+    };
+    let segment_count = segment_table_segment_count(first_word, SEGMENTS_COUNT_LIMIT)?;
+    let table_len = segment_table_len(segment_count);
+    let Some(table) = bytes.get(..table_len) else {
+        return Err(Error::from_kind(ErrorKind::PrematureEndOfFile));
+    };
+    Ok((
+        segment_table_lengths(table, segment_count).collect(),
+        table_len,
+    ))
+}
+
+/// Returns the segment table for a message whose segments have the given lengths in words,
+/// exactly as `write_message()` writes it, for framers that write the segment table themselves.
+///
+/// # Panics
+///
+/// Panics if `lengths` is empty, as a message always has at least one segment.
+#[cfg(feature = "alloc")]
+pub fn segment_table_bytes(lengths: &[u32]) -> Vec<u8> {
+    assert!(!lengths.is_empty(), "a message has at least one segment");
+    let mut bytes = Vec::with_capacity(segment_table_len(lengths.len()));
+    write_segment_table_lengths(&mut bytes, lengths.iter().copied()).expect("writing to a Vec");
+    bytes
 }
 
 #[cfg(feature = "alloc")]
@@ -525,10 +1230,11 @@ where
     R: Read,
 {
     read.read_exact(&mut owned_segments[..])?;
-    Ok(crate::message::Reader::new(owned_segments, options))
+    crate::message::Reader::new_checked(owned_segments, options)
 }
 
 /// Constructs a flat vector containing the entire message, including a segment header.
+/// Despite its name, this returns bytes; it is the same as `write_message_to_bytes()`.
 #[cfg(feature = "alloc")]
 pub fn write_message_to_words<A>(message: &message::Builder<A>) -> Vec<u8>
 where
@@ -537,6 +1243,17 @@ where
     flatten_segments(&*message.get_segments_for_output())
 }
 
+/// Serializes the message, including its segment table, into a vector of exactly the size that
+/// it needs, which is allocated once, up front. The result is what `write_message()` would have
+/// written.
+#[cfg(feature = "alloc")]
+pub fn write_message_to_bytes<A>(message: &message::Builder<A>) -> Vec<u8>
+where
+    A: message::Allocator,
+{
+    flatten_segments(&*message.get_segments_for_output())
+}
+
 /// Like `write_message_to_words()`, but takes a `ReaderSegments`, allowing it to be
 /// used on `message::Reader` objects (via `into_segments()`).
 #[cfg(feature = "alloc")]
@@ -552,7 +1269,7 @@ fn flatten_segments<R: message::ReaderSegments + ?Sized>(segments: &R) -> Vec<u8
     let word_count = compute_serialized_size(segments);
     let segment_count = segments.len();
     let table_size = segment_count / 2 + 1;
-    let mut result = Vec::with_capacity(word_count);
+    let mut result = Vec::with_capacity(word_count * BYTES_PER_WORD);
     result.resize(table_size * BYTES_PER_WORD, 0);
     {
         let mut bytes = &mut result[..];
@@ -572,32 +1289,65 @@ fn flatten_segments<R: message::ReaderSegments + ?Sized>(segments: &R) -> Vec<u8
 ///
 /// The only source of errors from this function are `write.write_all()` calls. If you pass in
 /// a writer that never returns an error, then this function will never return an error.
-pub fn write_message<W, A>(mut write: W, message: &message::Builder<A>) -> Result<()>
+pub fn write_message<W, A>(write: W, message: &message::Builder<A>) -> Result<()>
 where
     W: Write,
     A: message::Allocator,
+{
+    message.get_segments_for_output().write_to(write)
+}
+
+/// Like `write_message()`, but hands the segment table and all of the segments to `write` in a
+/// single `write_all_vectored()` call, which for writers that support vectored writes, such as
+/// sockets, takes one system call rather than one per segment.
+#[cfg(feature = "alloc")]
+pub fn write_message_vectored<W, A>(mut write: W, message: &message::Builder<A>) -> Result<()>
+where
+    W: VectoredWrite,
+    A: message::Allocator,
 {
     let segments = message.get_segments_for_output();
-    write_segment_table(&mut write, &segments)?;
-    write_segments(&mut write, &segments)
+    let lengths: Vec<u32> = segments
+        .iter()
+        .map(|segment| (segment.len() / BYTES_PER_WORD) as u32)
+        .collect();
+    let table = segment_table_bytes(&lengths);
+    let mut bufs = Vec::with_capacity(segments.len() + 1);
+    bufs.push(&table[..]);
+    bufs.extend(segments.iter().copied());
+    write.write_all_vectored(&bufs)
 }
 
 /// Like `write_message()`, but takes a `ReaderSegments`, allowing it to be
-/// used on `message::Reader` objects (via `into_segments()`).
+/// used on `message::Reader` objects (via `into_segments()`), and on segments kept apart from
+/// any message, such as a slice of `&[u8]` or `&[Word]` segments saved from
+/// `get_segments_for_output()`. `write_message()` is this function applied to the builder's
+/// segments, so the bytes written are the same.
 pub fn write_message_segments<W, R>(mut write: W, segments: &R) -> Result<()>
 where
     W: Write,
-    R: message::ReaderSegments,
+    R: message::ReaderSegments + ?Sized,
 {
     write_segment_table_internal(&mut write, segments)?;
     write_segments(&mut write, segments)
 }
 
-fn write_segment_table<W>(write: &mut W, segments: &[&[u8]]) -> Result<()>
+/// Writes `message` in [canonical](https://capnproto.org/encoding.html#canonicalization) form,
+/// framed as `write_message()` frames a message of one segment, which holds the words that
+/// `message::Reader::canonicalize()` returns. The bytes depend only on the value of the message,
+/// not on how it was built or where its objects lie in its segments, so that they are suitable for
+/// hashing and signing: two messages with the same value are written identically, whatever their
+/// allocation order, far pointers, or trailing zero words.
+///
+/// Fails if the message cannot be canonicalized, for instance because it holds capabilities.
+#[cfg(feature = "alloc")]
+pub fn write_canonical<W, S>(write: W, message: &message::Reader<S>) -> Result<()>
 where
     W: Write,
+    S: message::ReaderSegments,
 {
-    write_segment_table_internal(write, segments)
+    let words = message.canonicalize()?;
+    write_message_segments(write, &[crate::Word::words_to_bytes(&words)][..])
 }
 
 /// Writes a segment table to `write`.
@@ -607,48 +1357,47 @@ fn write_segment_table_internal<W, R>(write: &mut W, segments: &R) -> Result<()>
 where
     W: Write,
     R: message::ReaderSegments + ?Sized,
+{
+    write_segment_table_lengths(
+        write,
+        (0..segments.len())
+            .map(|idx| (segments.get_segment(idx as u32).unwrap().len() / BYTES_PER_WORD) as u32),
+    )
+}
+
+/// Writes the segment table for segments of the given lengths in words to `write`.
+///
+/// `lengths` must contain at least one length.
+fn write_segment_table_lengths<W>(
+    write: &mut W,
+    mut lengths: impl ExactSizeIterator<Item = u32>,
+) -> Result<()>
+where
+    W: Write,
 {
     let mut buf: [u8; 8] = [0; 8];
-    let segment_count = segments.len();
+    let segment_count = lengths.len();
 
     // write the first Word, which contains segment_count and the 1st segment length
     buf[0..4].copy_from_slice(&(segment_count as u32 - 1).to_le_bytes());
-    buf[4..8].copy_from_slice(
-        &((segments.get_segment(0).unwrap().len() / BYTES_PER_WORD) as u32).to_le_bytes(),
-    );
+    buf[4..8].copy_from_slice(&lengths.next().unwrap().to_le_bytes());
     write.write_all(&buf)?;
 
     if segment_count > 1 {
         if segment_count < 4 {
-            for idx in 1..segment_count {
-                buf[(idx - 1) * 4..idx * 4].copy_from_slice(
-                    &((segments.get_segment(idx as u32).unwrap().len() / BYTES_PER_WORD) as u32)
-                        .to_le_bytes(),
-                );
+            // The padding after two segments is zero.
+            buf = [0; 8];
+            for (idx, length) in lengths.enumerate() {
+                buf[idx * 4..(idx + 1) * 4].copy_from_slice(&length.to_le_bytes());
             }
-            if segment_count == 2 {
-                for b in &mut buf[4..8] {
-                    *b = 0
-                }
-            }
-
             write.write_all(&buf)?;
         } else {
             #[cfg(feature = "alloc")]
             {
+                // Zeroed, so that the padding after an even number of segments is zero.
                 let mut buf = vec![0; (segment_count & !1) * 4];
-                for idx in 1..segment_count {
-                    buf[(idx - 1) * 4..idx * 4].copy_from_slice(
-                        &((segments.get_segment(idx as u32).unwrap().len() / BYTES_PER_WORD)
-                            as u32)
-                            .to_le_bytes(),
-                    );
-                }
-                if segment_count % 2 == 0 {
-                    let start_idx = buf.len() - 4;
-                    for b in &mut buf[start_idx..] {
-                        *b = 0
-                    }
+                for (idx, length) in lengths.enumerate() {
+                    buf[idx * 4..(idx + 1) * 4].copy_from_slice(&length.to_le_bytes());
                 }
                 write.write_all(&buf)?;
             }
@@ -700,6 +1449,114 @@ where
     compute_serialized_size(&message.get_segments_for_output())
 }
 
+/// Like [`compute_serialized_size_in_words()`], for a message that is being read: returns the
+/// number of words that [`write_message_segments()`] will write for `message`'s segments.
+pub fn compute_reader_serialized_size_in_words<S>(message: &crate::message::Reader<S>) -> usize
+where
+    S: message::ReaderSegments,
+{
+    compute_serialized_size(message.segments())
+}
+
+/// Writes `message` to the file at `path`, replacing any existing file atomically: the message is
+/// written to `path` with a `.tmp` suffix, synced to disk, and then renamed over `path`. A crash
+/// part way through therefore leaves either the old file or the new one, never a truncated message,
+/// which the framing alone cannot always tell apart from a complete one. On failure, the temporary
+/// file is removed.
+#[cfg(all(feature = "std", feature = "alloc"))]
+pub fn write_message_to_file<A>(
+    path: &std::path::Path,
+    message: &message::Builder<A>,
+) -> std::io::Result<()>
+where
+    A: message::Allocator,
+{
+    write_file_atomically(path, &write_message_to_words(message))
+}
+
+/// Like `write_message_to_file()`, but writes the message
+/// [packed](crate::serialize_packed).
+#[cfg(all(feature = "std", feature = "alloc"))]
+pub fn write_packed_message_to_file<A>(
+    path: &std::path::Path,
+    message: &message::Builder<A>,
+) -> std::io::Result<()>
+where
+    A: message::Allocator,
+{
+    let mut bytes = Vec::new();
+    crate::serialize_packed::write_message(&mut bytes, message).expect("writing to a Vec");
+    write_file_atomically(path, &bytes)
+}
+
+#[cfg(all(feature = "std", feature = "alloc"))]
+fn write_file_atomically(path: &std::path::Path, bytes: &[u8]) -> std::io::Result<()> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = std::path::PathBuf::from(temp_path);
+    let result = (|| {
+        let mut file = std::fs::File::create(&temp_path)?;
+        std::io::Write::write_all(&mut file, bytes)?;
+        file.sync_all()?;
+        drop(file);
+        std::fs::rename(&temp_path, path)
+    })();
+    if result.is_err() {
+        // The original error is more useful than any error from the cleanup.
+        let _ = std::fs::remove_file(&temp_path);
+        return result;
+    }
+    // Sync the directory too, so that the rename itself survives a crash.
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        let parent = if parent.as_os_str().is_empty() {
+            std::path::Path::new(".")
+        } else {
+            parent
+        };
+        std::fs::File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Reads a message from a file written by `write_message_to_file()`, or by anything else that
+/// writes a single message with the standard framing. Fails if the file holds anything after the
+/// message.
+#[cfg(all(feature = "std", feature = "alloc"))]
+pub fn read_message_from_file(
+    path: &std::path::Path,
+    options: message::ReaderOptions,
+) -> Result<message::Reader<OwnedSegments>> {
+    let mut read = std::io::BufReader::new(std::fs::File::open(path)?);
+    let message = read_message(&mut read, options)?;
+    check_end_of_file(&mut read)?;
+    Ok(message)
+}
+
+/// Like `read_message_from_file()`, but for a file written by
+/// `write_packed_message_to_file()`.
+#[cfg(all(feature = "std", feature = "alloc"))]
+pub fn read_packed_message_from_file(
+    path: &std::path::Path,
+    options: message::ReaderOptions,
+) -> Result<message::Reader<OwnedSegments>> {
+    let mut read = std::io::BufReader::new(std::fs::File::open(path)?);
+    let message = crate::serialize_packed::read_message(&mut read, options)?;
+    check_end_of_file(&mut read)?;
+    Ok(message)
+}
+
+#[cfg(all(feature = "std", feature = "alloc"))]
+fn check_end_of_file(read: &mut impl std::io::BufRead) -> Result<()> {
+    if read.fill_buf()?.is_empty() {
+        Ok(())
+    } else {
+        let mut error = Error::from_kind(ErrorKind::Failed);
+        write!(error, "file continues after the end of the message");
+        Err(error)
+    }
+}
+
 #[cfg(feature = "alloc")]
 #[cfg(test)]
 pub mod test {
@@ -711,13 +1568,13 @@ pub mod test {
 
     use super::{
         flatten_segments, read_message, read_message_from_flat_slice, read_segment_table,
-        try_read_message, write_segment_table, write_segments,
+        try_read_message,
     };
     use crate::message;
     use crate::message::ReaderSegments;
 
     /// Writes segments as if they were a Capnproto message.
-    pub fn write_message_segments<W>(write: &mut W, segments: &[Vec<crate::Word>])
+    pub fn write_message_segments<W>(write: W, segments: &[Vec<crate::Word>])
     where
         W: Write,
     {
@@ -725,8 +1582,7 @@ pub mod test {
             .iter()
             .map(|segment| crate::Word::words_to_bytes(&segment[..]))
             .collect::<Vec<_>>()[..];
-        write_segment_table(write, borrowed_segments).unwrap();
-        write_segments(write, borrowed_segments).unwrap();
+        super::write_message_segments(write, borrowed_segments).unwrap();
     }
 
     #[test]
@@ -737,6 +1593,40 @@ pub mod test {
             .is_none());
     }
 
+    #[test]
+    fn try_read_stops_only_at_message_boundary() {
+        let mut bytes = vec![];
+        // Two segments, of one word and two words, and so a table of two words.
+        write_message_segments(
+            &mut bytes,
+            &[
+                vec![crate::word(0, 0, 0, 0, 0, 0, 0, 0)],
+                vec![crate::word(0, 0, 0, 0, 0, 0, 0, 0); 2],
+            ],
+        );
+        let message_len = bytes.len();
+        bytes.extend_from_within(..);
+
+        let mut buf = &bytes[..];
+        for _ in 0..2 {
+            let message = try_read_message(&mut buf, message::ReaderOptions::new())
+                .unwrap()
+                .unwrap();
+            assert_eq!(message.into_segments().len(), 2);
+        }
+        assert!(try_read_message(&mut buf, message::ReaderOptions::new())
+            .unwrap()
+            .is_none());
+
+        // Ends in the first word of the table, in its second word, and in the last segment.
+        for len in [4, 12, message_len - 8] {
+            let mut buf = &bytes[message_len..message_len + len];
+            assert!(try_read_message(&mut buf, message::ReaderOptions::new()).is_err());
+            let mut buf = &bytes[message_len..message_len + len];
+            assert!(read_message(&mut buf, message::ReaderOptions::new()).is_err());
+        }
+    }
+
     #[test]
     fn test_read_segment_table() {
         let mut buf = vec![];
@@ -909,43 +1799,42 @@ pub mod test {
 
     #[test]
     fn test_write_segment_table() {
-        let mut buf = vec![];
+        // The segment table that `write_message_segments()` writes ahead of the segments.
+        fn segment_table(segments: &[&[u8]]) -> Vec<u8> {
+            let mut buf = vec![];
+            super::write_message_segments(&mut buf, segments).unwrap();
+            buf.truncate(buf.len() - segments.iter().map(|s| s.len()).sum::<usize>());
+            buf
+        }
 
         let segment_0 = [0u8; 0];
         let segment_1 = [1u8, 1, 1, 1, 1, 1, 1, 1];
         let segment_199 = [201u8; 199 * 8];
 
-        write_segment_table(&mut buf, &[&segment_0]).unwrap();
         assert_eq!(
             &[
                 0, 0, 0, 0, // 1 segments
                 0, 0, 0, 0
             ], // 0 length
-            &buf[..]
+            &segment_table(&[&segment_0])[..]
         );
-        buf.clear();
 
-        write_segment_table(&mut buf, &[&segment_1]).unwrap();
         assert_eq!(
             &[
                 0, 0, 0, 0, // 1 segments
                 1, 0, 0, 0
             ], // 1 length
-            &buf[..]
+            &segment_table(&[&segment_1])[..]
         );
-        buf.clear();
 
-        write_segment_table(&mut buf, &[&segment_199]).unwrap();
         assert_eq!(
             &[
                 0, 0, 0, 0, // 1 segments
                 199, 0, 0, 0
             ], // 199 length
-            &buf[..]
+            &segment_table(&[&segment_199])[..]
         );
-        buf.clear();
 
-        write_segment_table(&mut buf, &[&segment_0, &segment_1]).unwrap();
         assert_eq!(
             &[
                 1, 0, 0, 0, // 2 segments
@@ -953,15 +1842,9 @@ pub mod test {
                 1, 0, 0, 0, // 1 length
                 0, 0, 0, 0
             ], // padding
-            &buf[..]
+            &segment_table(&[&segment_0, &segment_1])[..]
         );
-        buf.clear();
 
-        write_segment_table(
-            &mut buf,
-            &[&segment_199, &segment_1, &segment_199, &segment_0],
-        )
-        .unwrap();
         assert_eq!(
             &[
                 3, 0, 0, 0, // 4 segments
@@ -971,21 +1854,9 @@ pub mod test {
                 0, 0, 0, 0, // 0 length
                 0, 0, 0, 0
             ], // padding
-            &buf[..]
+            &segment_table(&[&segment_199, &segment_1, &segment_199, &segment_0])[..]
         );
-        buf.clear();
 
-        write_segment_table(
-            &mut buf,
-            &[
-                &segment_199,
-                &segment_1,
-                &segment_199,
-                &segment_0,
-                &segment_1,
-            ],
-        )
-        .unwrap();
         assert_eq!(
             &[
                 4, 0, 0, 0, // 5 segments
@@ -995,9 +1866,14 @@ pub mod test {
                 0, 0, 0, 0, // 0 length
                 1, 0, 0, 0
             ], // 1 length
-            &buf[..]
+            &segment_table(&[
+                &segment_199,
+                &segment_1,
+                &segment_199,
+                &segment_0,
+                &segment_1,
+            ])[..]
         );
-        buf.clear();
     }
 
     quickcheck! {
@@ -1033,6 +1909,24 @@ pub mod test {
         }
     }
 
+    quickcheck! {
+        #[cfg_attr(miri, ignore)] // miri takes a long time with quickcheck
+        fn test_round_trip_valid_message(message: crate::arbitrary::ArbitraryMessage) -> TestResult {
+            let mut buf: Vec<u8> = vec![];
+            write_message_segments(&mut buf, message.segments());
+            let read = read_message(&mut &buf[..], message::ReaderOptions::new()).unwrap();
+
+            // The message is valid, and reads back as the same value in the same segments.
+            let root: crate::any_pointer::Reader = read.get_root().unwrap();
+            let original = message.to_reader(message::ReaderOptions::new());
+            let original_root: crate::any_pointer::Reader = original.get_root().unwrap();
+            TestResult::from_bool(
+                root.target_size().unwrap() == original_root.target_size().unwrap()
+                    && flatten_segments(&read.into_segments()) == flatten_segments(&message)
+            )
+        }
+    }
+
     #[test]
     fn read_message_from_flat_slice_with_remainder() {
         let segments = [
@@ -1104,4 +1998,28 @@ pub mod test {
             1 + 1 + LIST_LENGTH_IN_WORDS
         )
     }
+
+    #[test]
+    fn output_segments_write_to() {
+        let mut m = message::Builder::new(message::HeapAllocator::new().first_segment_words(1));
+        {
+            let root: crate::any_pointer::Builder = m.init_root();
+            let _list_builder: crate::primitive_list::Builder<u64> = root.initn_as(5);
+        }
+
+        let segments = m.get_segments_for_output();
+        assert!(segments.len() > 1);
+        let lengths: Vec<usize> = (&segments).into_iter().map(|s| s.len()).collect();
+        assert_eq!(lengths.iter().sum::<usize>() / 8, segments.total_words());
+        let collected: Vec<&[u8]> = m.get_segments_for_output().into_iter().collect();
+        assert_eq!(&collected[..], &segments[..]);
+
+        let mut buf = Vec::new();
+        segments.write_to(&mut buf).unwrap();
+        assert_eq!(buf, super::write_message_to_words(&m));
+        assert_eq!(
+            super::compute_serialized_size_in_words(&m),
+            segments.len() / 2 + 1 + segments.total_words()
+        );
+    }
 }
@@ -0,0 +1,182 @@
+//! Bundles: several independent messages in one container, preceded by a table of contents so that
+//! any one of them can be read without reading the others.
+//!
+//! This is not part of the Cap'n Proto standard, and other implementations cannot read it. A bundle
+//! is laid out as follows, where all integers are little-endian:
+//!
+//! * The number of messages, as a `u32`, followed by four zero bytes.
+//! * The length in bytes of each message, as a `u64`.
+//! * Each message in turn, in the [standard stream framing](super).
+
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+use crate::io::Write;
+use crate::message;
+use crate::private::units::BYTES_PER_WORD;
+use crate::{Error, ErrorKind, Result};
+
+use super::OwnedSegments;
+
+/// Writes a bundle of messages. Each message is serialized as it is added, and the bundle is
+/// written out by [`finish()`](Self::finish), since its table of contents comes first.
+pub struct BundleWriter<W: Write> {
+    write: W,
+    lengths: Vec<u64>,
+    messages: Vec<u8>,
+}
+
+impl<W: Write> BundleWriter<W> {
+    pub fn new(write: W) -> Self {
+        Self {
+            write,
+            lengths: Vec::new(),
+            messages: Vec::new(),
+        }
+    }
+
+    /// Adds `message` to the bundle, and returns its index.
+    ///
+    /// # Panics
+    ///
+    /// If the bundle already holds `u32::MAX` messages.
+    pub fn add<A: message::Allocator>(&mut self, message: &message::Builder<A>) -> u32 {
+        let index = u32::try_from(self.lengths.len())
+            .ok()
+            .filter(|&index| index < u32::MAX)
+            .expect("too many messages in bundle");
+        let start = self.messages.len();
+        message
+            .get_segments_for_output()
+            .write_to(&mut self.messages)
+            .expect("writing to a Vec cannot fail");
+        self.lengths.push((self.messages.len() - start) as u64);
+        index
+    }
+
+    /// The number of messages added so far.
+    pub fn len(&self) -> u32 {
+        self.lengths.len() as u32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lengths.is_empty()
+    }
+
+    /// Writes the bundle, and returns the underlying writer. `flush()` is not called on it.
+    pub fn finish(mut self) -> Result<W> {
+        let mut table = Vec::with_capacity((self.lengths.len() + 1) * BYTES_PER_WORD);
+        table.extend_from_slice(&(self.lengths.len() as u32).to_le_bytes());
+        table.extend_from_slice(&[0; 4]);
+        for length in &self.lengths {
+            table.extend_from_slice(&length.to_le_bytes());
+        }
+        self.write.write_all(&table)?;
+        self.write.write_all(&self.messages)?;
+        Ok(self.write)
+    }
+}
+
+/// Reads a bundle written by [`BundleWriter`]. Only the table of contents is read up front; each
+/// message is read when it is asked for, with its own options.
+pub struct BundleReader<T: Deref<Target = [u8]>> {
+    buffer: T,
+
+    /// The byte range of each message in `buffer`.
+    ranges: Vec<(usize, usize)>,
+}
+
+impl<T: Deref<Target = [u8]>> BundleReader<T> {
+    /// Reads the table of contents of the bundle in `buffer`, checking that the messages it lists
+    /// fill the rest of `buffer` exactly.
+    pub fn new(buffer: T) -> Result<Self> {
+        let Some((header, rest)) = buffer.split_first_chunk::<BYTES_PER_WORD>() else {
+            let mut error = Error::from_kind(ErrorKind::PrematureEndOfFile);
+            write!(error, "bundle of {} bytes has no header", buffer.len());
+            return Err(error);
+        };
+        let count = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let table_len = count.checked_mul(BYTES_PER_WORD);
+        let Some(table_len) = table_len.filter(|&len| len <= rest.len()) else {
+            let mut error = Error::from_kind(ErrorKind::PrematureEndOfFile);
+            write!(
+                error,
+                "bundle of {count} messages ends within its table of contents"
+            );
+            return Err(error);
+        };
+
+        let mut ranges = Vec::with_capacity(count);
+        let mut start = BYTES_PER_WORD + table_len;
+        for length in rest[..table_len].chunks_exact(BYTES_PER_WORD) {
+            let length = u64::from_le_bytes(length.try_into().unwrap());
+            let end = usize::try_from(length)
+                .ok()
+                .and_then(|length| start.checked_add(length))
+                .filter(|&end| end <= buffer.len());
+            let Some(end) = end else {
+                let mut error = Error::from_kind(ErrorKind::PrematureEndOfFile);
+                write!(
+                    error,
+                    "message {} of {length} bytes runs past the end of the bundle",
+                    ranges.len()
+                );
+                return Err(error);
+            };
+            ranges.push((start, end));
+            start = end;
+        }
+        if start != buffer.len() {
+            let mut error = Error::from_kind(ErrorKind::Failed);
+            write!(
+                error,
+                "{} bytes after the last message in the bundle",
+                buffer.len() - start
+            );
+            return Err(error);
+        }
+        Ok(Self { buffer, ranges })
+    }
+
+    /// The number of messages in the bundle.
+    pub fn len(&self) -> u32 {
+        self.ranges.len() as u32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Reads the message at `index`, touching no other message in the bundle.
+    pub fn get(
+        &self,
+        index: u32,
+        options: message::ReaderOptions,
+    ) -> Result<message::Reader<OwnedSegments>> {
+        let Some(&(start, end)) = self.ranges.get(index as usize) else {
+            let mut error = Error::from_kind(ErrorKind::Failed);
+            write!(
+                error,
+                "no message {index} in a bundle of {} messages",
+                self.ranges.len()
+            );
+            return Err(error);
+        };
+        let mut bytes = &self.buffer[start..end];
+        let message = super::read_message(&mut bytes, options)?;
+        if !bytes.is_empty() {
+            let mut error = Error::from_kind(ErrorKind::Failed);
+            write!(
+                error,
+                "message {index} of the bundle ends {} bytes before its entry does",
+                bytes.len()
+            );
+            return Err(error);
+        }
+        Ok(message)
+    }
+
+    pub fn into_buffer(self) -> T {
+        self.buffer
+    }
+}
@@ -5,8 +5,6 @@ use crate::message::ReaderSegments;
 use crate::private::units::BYTES_PER_WORD;
 use crate::{Error, ErrorKind, Result};
 
-use super::SEGMENTS_COUNT_LIMIT;
-
 const U32_LEN_IN_BYTES: usize = core::mem::size_of::<u32>();
 
 /// Information about a message read from its segment table.
@@ -28,10 +26,10 @@ fn read_segment_table(slice: &[u8], options: ReaderOptions) -> Result<NoAllocSeg
 
     let segments_count = u32_to_segments_count(read_u32_le(&mut remaining)?)?;
 
-    if segments_count >= SEGMENTS_COUNT_LIMIT {
-        return Err(Error::from_kind(ErrorKind::InvalidNumberOfSegments(
-            segments_count,
-        )));
+    if segments_count >= options.segments_limit {
+        return Err(super::segment_table_error(
+            ErrorKind::InvalidNumberOfSegments(segments_count),
+        ));
     }
 
     let mut total_segments_length_bytes = 0_usize;
@@ -45,16 +43,10 @@ fn read_segment_table(slice: &[u8], options: ReaderOptions) -> Result<NoAllocSeg
     }
 
     // Don't accept a message which the receiver couldn't possibly traverse without hitting the
-    // traversal limit. Without this check, a malicious client could transmit a very large segment
-    // size to make the receiver allocate excessive space and possibly crash.
-    if let Some(limit) = options.traversal_limit_in_words {
-        let total_segments_length_words = total_segments_length_bytes / 8;
-        if total_segments_length_words > limit {
-            return Err(Error::from_kind(ErrorKind::MessageTooLarge(
-                total_segments_length_words,
-            )));
-        }
-    }
+    // traversal limit, or that is over the total size limit. Without this check, a malicious client
+    // could transmit a very large segment size to make the receiver allocate excessive space and
+    // possibly crash.
+    super::check_total_words(total_segments_length_bytes / 8, options)?;
 
     // If number of segments is even, header length will not be aligned by 8, we need to consume
     // padding from the remainder of the message
@@ -1,6 +1,13 @@
+use crate::dynamic_list;
 use crate::dynamic_value;
 use core::fmt::{self, Formatter};
 
+/// How many elements of a list of primitives the `Debug` output of a typed list reader prints.
+pub(crate) const LIST_HEAD_LEN: u32 = 8;
+
+/// How many characters of text, or bytes of data, `Debug` output prints.
+pub(crate) const BLOB_HEAD_LEN: usize = 64;
+
 #[derive(Clone, Copy, Debug, Default)]
 pub(crate) struct Indent {
     /// None means everything gets printed on a single line.
@@ -77,7 +84,11 @@ pub(crate) fn print(
             }
             None => formatter.write_fmt(format_args!("{}", e.get_value())),
         },
-        dynamic_value::Reader::Text(t) => formatter.write_fmt(format_args!("{t:?}")),
+        // Unlike the `Debug` output of a text reader, this is not truncated.
+        dynamic_value::Reader::Text(t) => match t.to_str() {
+            Ok(s) => formatter.write_fmt(format_args!("{s:?}")),
+            Err(_) => formatter.write_fmt(format_args!("<invalid utf-8: {:?}>", t.as_bytes())),
+        },
         dynamic_value::Reader::Data(d) => {
             formatter.write_str("0x\"")?;
             for b in d {
@@ -179,3 +190,50 @@ impl<'a> fmt::Debug for dynamic_value::Reader<'a> {
         print(*self, f, indent)
     }
 }
+
+/// The `Debug` output of a typed list reader whose elements are primitives or enums: its length,
+/// and its first few elements.
+pub(crate) fn print_list_summary(
+    name: &str,
+    list: dynamic_list::Reader,
+    formatter: &mut Formatter,
+) -> Result<(), fmt::Error> {
+    formatter
+        .debug_struct(name)
+        .field("len", &list.len())
+        .field("head", &ListHead(list))
+        .finish()
+}
+
+/// The `Debug` output of a typed list reader whose elements are pointers: only its length,
+/// because reading the elements would count against the message's traversal limit, and could
+/// fail. To print a whole list, format it as a `dynamic_value::Reader`.
+pub(crate) fn print_pointer_list_summary(
+    name: &str,
+    len: u32,
+    formatter: &mut Formatter,
+) -> Result<(), fmt::Error> {
+    formatter.debug_struct(name).field("len", &len).finish()
+}
+
+struct ListHead<'a>(dynamic_list::Reader<'a>);
+
+impl fmt::Debug for ListHead<'_> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        let len = self.0.len();
+        f.write_str("[")?;
+        for idx in 0..len.min(LIST_HEAD_LEN) {
+            if idx > 0 {
+                f.write_str(", ")?;
+            }
+            match self.0.get(idx) {
+                Ok(value) => print(value, f, Indent::no_indent())?,
+                Err(_) => f.write_str("<error>")?,
+            }
+        }
+        if len > LIST_HEAD_LEN {
+            f.write_str(", ..")?;
+        }
+        f.write_str("]")
+    }
+}
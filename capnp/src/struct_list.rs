@@ -25,7 +25,7 @@ use core::marker::PhantomData;
 
 use crate::introspect;
 use crate::private::layout::{
-    InlineComposite, ListBuilder, ListReader, PointerBuilder, PointerReader,
+    InlineComposite, ListBuilder, ListReader, PointerBuilder, PointerReader, StructElementCursor,
 };
 use crate::traits::{FromPointerBuilder, FromPointerReader, HasStructSize, IndexMove, ListIter};
 use crate::Result;
@@ -88,6 +88,73 @@ where
     pub fn iter(self) -> ListIter<Reader<'a, T>, T::Reader<'a>> {
         ListIter::new(self, self.len())
     }
+
+    /// Splits the list into the elements before `mid` and those from `mid` on, as two readers
+    /// over the same message. Panics if `mid` is greater than `len()`.
+    pub fn split_at(&self, mid: u32) -> (Self, Self) {
+        let (head, tail) = self.reader.split_at(mid);
+        (
+            Reader {
+                reader: head,
+                marker: PhantomData,
+            },
+            Reader {
+                reader: tail,
+                marker: PhantomData,
+            },
+        )
+    }
+
+    /// Splits the list into `n` contiguous ranges, whose lengths differ by at most one, to be
+    /// processed in parallel. To send the ranges to other threads, see
+    /// [`message::Reader::sendable_struct_list()`](crate::message::Reader::sendable_struct_list).
+    /// Panics if `n` is zero.
+    #[cfg(feature = "alloc")]
+    pub fn par_chunks(&self, n: u32) -> alloc::vec::Vec<Self> {
+        assert!(n > 0, "cannot split a list into zero chunks");
+        let mut rest = *self;
+        (0..n)
+            .map(|idx| {
+                let chunk_len = self.len() / n + u32::from(idx < self.len() % n);
+                let (chunk, tail) = rest.split_at(chunk_len);
+                rest = tail;
+                chunk
+            })
+            .collect()
+    }
+}
+
+/// A [`Reader`] that can be sent to another thread, as returned by
+/// [`message::Reader::sendable_struct_list()`](crate::message::Reader::sendable_struct_list).
+#[cfg(feature = "sync_reader")]
+pub struct SendReader<'a, T>
+where
+    T: crate::traits::OwnedStruct,
+{
+    reader: Reader<'a, T>,
+}
+
+// Safety: a `SendReader` is only constructed for lists read from a `message::Reader` whose
+// segments are `Sync`, so that its arena is `Sync` too, and which have no capability table, the
+// only other state that readers share.
+#[cfg(feature = "sync_reader")]
+unsafe impl<T> Send for SendReader<'_, T> where T: crate::traits::OwnedStruct {}
+
+#[cfg(feature = "sync_reader")]
+impl<'a, T> SendReader<'a, T>
+where
+    T: crate::traits::OwnedStruct,
+{
+    /// # Safety
+    ///
+    /// `reader` must read through an arena that is `Sync`, and have no capability table.
+    pub(crate) unsafe fn new_unchecked(reader: Reader<'a, T>) -> Self {
+        Self { reader }
+    }
+
+    pub fn into_reader(self) -> Reader<'a, T> {
+        self.reader
+    }
 }
 
 impl<'a, T> Reader<'a, T>
@@ -187,7 +254,7 @@ where
     /// Sets the list element, with the following limitation based on the fact that structs in a
     /// struct list are allocated inline: if the source struct is larger than the target struct
     /// (as can happen if it was created with a newer version of the schema), then it will be
-    /// truncated, losing fields.
+    /// truncated, losing fields. Panics if `index` is greater than or equal to `len()`.
     pub fn set_with_caveats<'b>(&mut self, index: u32, value: T::Reader<'b>) -> Result<()>
     where
         T::Reader<'b>: crate::traits::IntoInternalStructReader<'b>,
@@ -199,6 +266,79 @@ where
             .get_struct_element(index)
             .copy_content_from(&value.into_internal_struct_reader())
     }
+
+    /// Like `set_with_caveats()`, but returns `None` if `index` is greater than or equal to
+    /// `len()`.
+    pub fn try_set_with_caveats<'b>(
+        &mut self,
+        index: u32,
+        value: T::Reader<'b>,
+    ) -> Option<Result<()>>
+    where
+        T::Reader<'b>: crate::traits::IntoInternalStructReader<'b>,
+    {
+        if index < self.len() {
+            Some(self.set_with_caveats(index, value))
+        } else {
+            None
+        }
+    }
+
+    /// Swaps the elements at positions `a` and `b`. The objects pointed to by their pointer
+    /// fields are not copied. Panics if either index is greater than or equal to `len()`.
+    pub fn swap(&mut self, a: u32, b: u32) {
+        assert!(a < self.len());
+        assert!(b < self.len());
+        self.builder.swap_struct_elements(a, b)
+    }
+
+    /// Swaps the elements at positions `a` and `b`, like `swap()`. Returns `None` if either
+    /// index is greater than or equal to `len()`.
+    pub fn try_swap(&mut self, a: u32, b: u32) -> Option<()> {
+        if a < self.len() && b < self.len() {
+            self.builder.swap_struct_elements(a, b);
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// Sorts the list in place by the key that `f` extracts from each element. The sort is
+    /// stable, and like `swap()`, it moves elements without copying the objects they point to.
+    ///
+    /// Canonicalization preserves list order, so sorting a list that represents a set or
+    /// a map before canonicalizing makes the output independent of the insertion order.
+    #[cfg(feature = "alloc")]
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        K: Ord,
+        F: for<'b> FnMut(T::Reader<'b>) -> K,
+    {
+        let len = self.len();
+        let mut order: alloc::vec::Vec<(K, u32)> = {
+            let reader = self.builder.reborrow().into_reader();
+            (0..len)
+                .map(|idx| (f(reader.get_struct_element(idx).into()), idx))
+                .collect()
+        };
+        order.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        // Move each element into place, tracking where the displaced elements go.
+        let mut position: alloc::vec::Vec<u32> = (0..len).collect();
+        let mut original: alloc::vec::Vec<u32> = (0..len).collect();
+        for (target, (_, source)) in order.into_iter().enumerate() {
+            let target = target as u32;
+            let current = position[source as usize];
+            if current != target {
+                self.builder.swap_struct_elements(target, current);
+                let displaced = original[target as usize];
+                position[displaced as usize] = current;
+                original[current as usize] = displaced;
+                position[source as usize] = target;
+                original[target as usize] = source;
+            }
+        }
+    }
 }
 
 impl<'a, T> Builder<'a, T>
@@ -223,6 +363,12 @@ where
             builder: builder.init_struct_list(size, T::Builder::STRUCT_SIZE),
         }
     }
+    fn try_init_pointer(builder: PointerBuilder<'a>, size: u32) -> Result<Builder<'a, T>> {
+        Ok(Builder {
+            marker: PhantomData,
+            builder: builder.try_init_struct_list(size, T::Builder::STRUCT_SIZE)?,
+        })
+    }
     fn get_from_pointer(
         builder: PointerBuilder<'a>,
         default: Option<&'a [crate::Word]>,
@@ -254,6 +400,45 @@ where
             None
         }
     }
+
+    /// Returns a cursor that visits the elements in order, starting at the first. This is
+    /// cheaper than calling `get()` for each index when filling in a long list.
+    pub fn cursor(&mut self) -> ListCursor<'_, T> {
+        ListCursor {
+            marker: PhantomData,
+            cursor: self.builder.reborrow().into_struct_cursor(),
+        }
+    }
+}
+
+/// Visits the elements of a struct list builder in order. Returned by `Builder::cursor()`.
+pub struct ListCursor<'a, T>
+where
+    T: crate::traits::OwnedStruct,
+{
+    marker: PhantomData<T>,
+    cursor: StructElementCursor<'a>,
+}
+
+impl<T> ListCursor<'_, T>
+where
+    T: crate::traits::OwnedStruct,
+{
+    /// Returns the next element, or `None` once every element has been visited.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<T::Builder<'_>> {
+        self.cursor.next().map(Into::into)
+    }
+
+    /// The number of elements that have not been visited yet.
+    pub fn remaining(&self) -> u32 {
+        self.cursor.remaining()
+    }
+
+    /// The index of the element that `next()` will return.
+    pub fn position(&self) -> u32 {
+        self.cursor.position()
+    }
 }
 
 impl<'a, T> crate::traits::SetPointerBuilder for Reader<'a, T>
@@ -301,9 +486,6 @@ impl<'a, T: crate::traits::OwnedStruct> From<Builder<'a, T>> for crate::dynamic_
 
 impl<'a, T: crate::traits::OwnedStruct> core::fmt::Debug for Reader<'a, T> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        core::fmt::Debug::fmt(
-            &::core::convert::Into::<crate::dynamic_value::Reader<'_>>::into(*self),
-            f,
-        )
+        crate::stringify::print_pointer_list_summary("struct_list::Reader", self.len(), f)
     }
 }
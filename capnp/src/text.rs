@@ -23,6 +23,15 @@
 //!
 //! A `text::Reader<'a>` wraps a `&'a [u8]` that is expected but not guaranteed
 //! to contain UTF-8 encoded text.
+//!
+//! On the wire, text is a list of bytes followed by a NUL terminator, which the list's element
+//! count includes. Readers and builders never include the terminator: `len()`, `as_bytes()` and
+//! `to_str()` all cover the same bytes, so empty text is a list holding only the NUL. A list with
+//! no elements at all is malformed, and reading it as text fails.
+//!
+//! A reader holds the bytes, not the pointer to them, so it cannot tell a field that was never
+//! set from one set to empty text, which read the same. The pointer can: ask the generated
+//! `has_*()` method of the field, or `any_pointer::Reader::is_null()`.
 
 use core::str;
 
@@ -91,11 +100,24 @@ impl<'a> core::cmp::PartialOrd<Reader<'a>> for &'a str {
     }
 }
 
+/// Prints the text escaped, as `str` does, but at most its first 64 characters, followed by its
+/// length in bytes if it is longer.
 impl<'a> core::fmt::Debug for Reader<'a> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        use crate::stringify::BLOB_HEAD_LEN;
+        let bytes = self.as_bytes();
         match self.to_str() {
-            Ok(s) => write!(f, "{:?}", s),
-            Err(_) => write!(f, "<invalid utf-8: {:?}>", self.as_bytes()),
+            Ok(s) => match s.char_indices().nth(BLOB_HEAD_LEN) {
+                None => write!(f, "{s:?}"),
+                Some((end, _)) => write!(f, "{:?}.. ({} bytes)", &s[..end], s.len()),
+            },
+            Err(_) if bytes.len() > BLOB_HEAD_LEN => write!(
+                f,
+                "<invalid utf-8: {:?}.. ({} bytes)>",
+                &bytes[..BLOB_HEAD_LEN],
+                bytes.len()
+            ),
+            Err(_) => write!(f, "<invalid utf-8: {bytes:?}>"),
         }
     }
 }
@@ -138,7 +160,17 @@ impl<'a> crate::traits::FromPointerReader<'a> for Reader<'a> {
 }
 
 impl<'a> Reader<'a> {
-    /// The string's length, in bytes.
+    /// A reader over `value` itself, for tests and adapters that need a `text::Reader` without
+    /// building a message. Nothing in the crate assumes that a text reader points into a message,
+    /// and as the reader never includes the NUL terminator, `value` doesn't need one either.
+    #[allow(clippy::should_implement_trait)]
+    #[inline]
+    pub const fn from_str(value: &'a str) -> Self {
+        Self(value.as_bytes())
+    }
+
+    /// The string's length, in bytes, not including the NUL terminator that follows it in the
+    /// message.
     #[inline]
     pub fn len(&self) -> usize {
         self.as_bytes().len()
@@ -155,6 +187,18 @@ impl<'a> Reader<'a> {
         d
     }
 
+    /// A cursor that reads the string's bytes, without the NUL terminator. See
+    /// [`data::reader()`](crate::data::reader).
+    pub fn reader(&self) -> crate::data::BlobReader<'a> {
+        crate::data::reader(self.as_bytes())
+    }
+
+    /// Writes the string's bytes, without the NUL terminator, to `write`, and returns the number
+    /// of bytes written. See [`data::copy_to()`](crate::data::copy_to).
+    pub fn copy_to<W: crate::io::Write>(&self, write: W) -> Result<u64> {
+        crate::data::copy_to(self.as_bytes(), write)
+    }
+
     /// Converts to a `str`, returning a error if the data contains invalid utf-8.
     #[inline]
     pub fn to_str(self) -> core::result::Result<&'a str, core::str::Utf8Error> {
@@ -168,6 +212,37 @@ impl<'a> Reader<'a> {
     pub fn to_string(self) -> core::result::Result<alloc::string::String, core::str::Utf8Error> {
         Ok(self.to_str()?.into())
     }
+
+    /// Converts to a string using `decoder`, for text fields that hold something other than
+    /// utf-8. `decode_with(&Utf8Decoder)` accepts and returns exactly what `to_str()` does.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn decode_with<D: TextDecoder + ?Sized>(
+        &self,
+        decoder: &D,
+    ) -> Result<alloc::borrow::Cow<'a, str>> {
+        decoder.decode(self.as_bytes())
+    }
+}
+
+/// Decodes the bytes of a text field, for applications whose text fields do not hold utf-8 as the
+/// spec requires. See `Reader::decode_with()`.
+#[cfg(feature = "alloc")]
+pub trait TextDecoder {
+    /// Decodes `bytes`, which do not include the NUL terminator.
+    fn decode<'a>(&self, bytes: &'a [u8]) -> Result<alloc::borrow::Cow<'a, str>>;
+}
+
+/// The utf-8 decoding that `Reader::to_str()` performs, as a `TextDecoder`. Never allocates.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Utf8Decoder;
+
+#[cfg(feature = "alloc")]
+impl TextDecoder for Utf8Decoder {
+    fn decode<'a>(&self, bytes: &'a [u8]) -> Result<alloc::borrow::Cow<'a, str>> {
+        Ok(Reader(bytes).to_str()?.into())
+    }
 }
 
 pub struct Builder<'a> {
@@ -178,6 +253,49 @@ pub struct Builder<'a> {
     pos: usize,
 }
 
+/// Initializes `builder` as text of up to `max_len` bytes, to be truncated to its actual length
+/// once that is known. Generated code offers the same for each text field as
+/// `reserve_<field>()`. Fails with `BlobTooLong` if `max_len` bytes and a NUL terminator do not
+/// fit in a list.
+pub fn reserve(builder: crate::any_pointer::Builder<'_>, max_len: u32) -> Result<Reservation<'_>> {
+    builder.reserve_text(max_len)
+}
+
+/// Space for a text blob whose length is only known once it has been written, obtained from
+/// `reserve()`.
+///
+/// If the reservation is dropped without calling `finish()`, the text keeps its full reserved
+/// length, but can still be truncated later through
+/// `any_pointer::Builder::get_text_reservation()`.
+pub struct Reservation<'a> {
+    inner: crate::private::layout::BlobReservation<'a>,
+}
+
+impl<'a> Reservation<'a> {
+    pub(crate) fn new(inner: crate::private::layout::BlobReservation<'a>) -> Self {
+        Self { inner }
+    }
+
+    /// The number of bytes reserved, not including the NUL terminator.
+    pub fn capacity(&self) -> u32 {
+        self.inner.capacity()
+    }
+
+    /// All of the reserved bytes, which start out zeroed. Does not include the NUL terminator.
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self.inner.as_bytes_mut()
+    }
+
+    /// Truncates the text to its first `len` bytes, moving the NUL terminator to just after them
+    /// and zeroing the rest. If nothing else has been allocated since the reservation was made,
+    /// the unused space is given back to the message.
+    ///
+    /// Panics if `len` is greater than the capacity.
+    pub fn finish(self, len: u32) -> Builder<'a> {
+        Builder::with_pos(self.inner.finish(len), len as usize)
+    }
+}
+
 impl<'a> core::cmp::PartialEq for Builder<'a> {
     fn eq(&self, other: &Self) -> bool {
         self.bytes == other.bytes
@@ -207,7 +325,7 @@ impl<'a> Builder<'a> {
         Builder { bytes, pos }
     }
 
-    /// The string's length, in bytes.
+    /// The string's length, in bytes, not including the NUL terminator.
     #[inline]
     pub fn len(&self) -> usize {
         self.bytes.len()
@@ -241,7 +359,8 @@ impl<'a> Builder<'a> {
         &mut self.bytes[..]
     }
 
-    /// Writes a single ascii character at position `pos` and increments `pos`.
+    /// Writes a single ascii character at position `pos` and increments `pos`. Panics if
+    /// `ascii` is not an ascii character or if the text is already full.
     #[inline]
     pub fn push_ascii(&mut self, ascii: u8) {
         assert!(ascii < 128);
@@ -249,7 +368,20 @@ impl<'a> Builder<'a> {
         self.pos += 1;
     }
 
-    /// Writes a string at position `pos` and increases `pos` a corresponding amount.
+    /// Writes a single ascii character at position `pos` and increments `pos`. Returns `None`
+    /// if `ascii` is not an ascii character or if the text is already full.
+    #[inline]
+    pub fn try_push_ascii(&mut self, ascii: u8) -> Option<()> {
+        if ascii < 128 && self.pos < self.len() {
+            self.push_ascii(ascii);
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// Writes a string at position `pos` and increases `pos` a corresponding amount. Panics if
+    /// there is not enough room left for the string.
     #[inline]
     pub fn push_str(&mut self, string: &str) {
         let bytes = string.as_bytes();
@@ -257,6 +389,18 @@ impl<'a> Builder<'a> {
         self.pos += bytes.len();
     }
 
+    /// Writes a string at position `pos` and increases `pos` a corresponding amount. Returns
+    /// `None`, without writing anything, if there is not enough room left for the string.
+    #[inline]
+    pub fn try_push_str(&mut self, string: &str) -> Option<()> {
+        if string.len() <= self.len().saturating_sub(self.pos) {
+            self.push_str(string);
+            Some(())
+        } else {
+            None
+        }
+    }
+
     /// Zeroes all data and resets `pos`.
     pub fn clear(&mut self) {
         for b in &mut self.bytes[..self.pos] {
@@ -297,6 +441,12 @@ impl<'a> crate::traits::FromPointerBuilder<'a> for Builder<'a> {
     fn init_pointer(builder: crate::private::layout::PointerBuilder<'a>, size: u32) -> Builder<'a> {
         builder.init_text(size)
     }
+    fn try_init_pointer(
+        builder: crate::private::layout::PointerBuilder<'a>,
+        size: u32,
+    ) -> Result<Builder<'a>> {
+        builder.try_init_text(size)
+    }
     fn get_from_pointer(
         builder: crate::private::layout::PointerBuilder<'a>,
         default: Option<&'a [crate::Word]>,
@@ -311,8 +461,7 @@ impl<'a> crate::traits::SetPointerBuilder for Reader<'a> {
         value: Reader<'a>,
         _canonicalize: bool,
     ) -> Result<()> {
-        pointer.set_text(value);
-        Ok(())
+        pointer.try_set_text(value)
     }
 }
 
@@ -324,8 +473,7 @@ impl<'a> crate::traits::SetPointerBuilder for &'a str {
         value: &'a str,
         _canonicalize: bool,
     ) -> Result<()> {
-        pointer.set_text(value.into());
-        Ok(())
+        pointer.try_set_text(value.into())
     }
 }
 
@@ -128,6 +128,8 @@ impl<'a> Builder<'a> {
         self.len() == 0
     }
 
+    /// Sets the element at position `index`. Panics if `index` is greater than or
+    /// equal to `len()`, or if `value` is too long to fit in a list.
     pub fn set(&mut self, index: u32, value: crate::text::Reader) {
         assert!(index < self.len());
         self.builder
@@ -136,6 +138,22 @@ impl<'a> Builder<'a> {
             .set_text(value);
     }
 
+    /// Sets the element at position `index`. Returns `None` if `index`
+    /// is greater than or equal to `len()`, and an error if `value` is
+    /// too long to fit in a list.
+    pub fn try_set(&mut self, index: u32, value: crate::text::Reader) -> Option<Result<()>> {
+        if index < self.len() {
+            Some(
+                self.builder
+                    .reborrow()
+                    .get_pointer_element(index)
+                    .try_set_text(value),
+            )
+        } else {
+            None
+        }
+    }
+
     pub fn into_reader(self) -> Reader<'a> {
         Reader {
             reader: self.builder.into_reader(),
@@ -155,6 +173,11 @@ impl<'a> FromPointerBuilder<'a> for Builder<'a> {
             builder: builder.init_list(Pointer, size),
         }
     }
+    fn try_init_pointer(builder: PointerBuilder<'a>, size: u32) -> Result<Builder<'a>> {
+        Ok(Builder {
+            builder: builder.try_init_list(Pointer, size)?,
+        })
+    }
     fn get_from_pointer(
         builder: PointerBuilder<'a>,
         default: Option<&'a [crate::Word]>,
@@ -212,6 +235,12 @@ impl<'a> From<Reader<'a>> for crate::dynamic_value::Reader<'a> {
     }
 }
 
+impl<'a> core::fmt::Debug for Reader<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        crate::stringify::print_pointer_list_summary("text_list::Reader", self.len(), f)
+    }
+}
+
 impl<'a> From<Builder<'a>> for crate::dynamic_value::Builder<'a> {
     fn from(t: Builder<'a>) -> crate::dynamic_value::Builder<'a> {
         crate::dynamic_value::Builder::List(crate::dynamic_list::Builder {
@@ -0,0 +1,78 @@
+//! Hooks for observing the checks that are performed while decoding a message.
+//! Only available with the `tracing-hooks` feature.
+//!
+//! A decode error on its own does not say where in the message the problem was found.
+//! A hook registered with [`set_decode_trace_hook()`] is called at each validation point
+//! with the segment and word offset being checked.
+
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::private::arena::ReaderArena;
+use crate::private::units::BYTES_PER_WORD;
+
+/// The check that produced a [`DecodeEvent`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeCheck {
+    /// A pointer was rejected because its target is not within the bounds of its segment.
+    OutOfBounds,
+
+    /// The traversal limit was exceeded.
+    TraversalLimit,
+
+    /// A far pointer was followed to a landing pad in another segment. This is not a failure.
+    FarPointerFollowed,
+
+    /// The segment table was rejected.
+    SegmentTable,
+
+    /// Zero words after the segment table were skipped as padding, as permitted by
+    /// `ReaderOptions::lenient_segment_table` or `ReaderOptions::tolerate_legacy_padding`. This
+    /// is not a failure, but it means that the message was written by a non-conforming
+    /// implementation.
+    LegacySegmentTablePadding,
+}
+
+/// An event passed to the hook registered with [`set_decode_trace_hook()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeEvent {
+    /// The segment being read, if the check concerns a particular segment.
+    pub segment_id: Option<u32>,
+
+    /// Offset in words from the start of the segment, if the check concerns a particular location.
+    pub word_offset: Option<u32>,
+
+    pub check: DecodeCheck,
+}
+
+static HOOK: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Registers `hook` to be called on each [`DecodeEvent`], replacing any previously-registered hook.
+/// The hook is process-wide and may be called from any thread.
+pub fn set_decode_trace_hook(hook: fn(&DecodeEvent)) {
+    HOOK.store(hook as *mut (), Ordering::Release);
+}
+
+/// Unregisters the hook registered with [`set_decode_trace_hook()`], if any.
+pub fn clear_decode_trace_hook() {
+    HOOK.store(core::ptr::null_mut(), Ordering::Release);
+}
+
+/// Calls the registered hook with the event returned by `event`. `event` is only evaluated
+/// if a hook is registered.
+#[inline]
+pub(crate) fn emit(event: impl FnOnce() -> DecodeEvent) {
+    let hook = HOOK.load(Ordering::Acquire);
+    if !hook.is_null() {
+        // The only non-null values ever stored are `fn(&DecodeEvent)`s.
+        let hook: fn(&DecodeEvent) = unsafe { core::mem::transmute(hook) };
+        hook(&event());
+    }
+}
+
+/// Returns the offset of `ptr` from the start of segment `segment_id`, in words.
+pub(crate) fn word_offset(arena: &dyn ReaderArena, segment_id: u32, ptr: *const u8) -> Option<u32> {
+    let (start, _) = arena.get_segment(segment_id).ok()?;
+    let offset = (ptr as usize).checked_sub(start as usize)?;
+    u32::try_from(offset / BYTES_PER_WORD).ok()
+}
@@ -23,7 +23,7 @@ use crate::private::layout::CapTable;
 use crate::private::layout::{
     ListReader, PointerBuilder, PointerReader, StructBuilder, StructReader, StructSize,
 };
-use crate::Result;
+use crate::{Error, ErrorKind, Result};
 
 use core::marker::PhantomData;
 
@@ -36,6 +36,11 @@ pub trait IntoInternalStructReader<'a> {
     fn into_internal_struct_reader(self) -> StructReader<'a>;
 }
 
+/// Trait for all types that can be converted to a low-level `StructBuilder`.
+pub trait IntoInternalStructBuilder<'a> {
+    fn into_internal_struct_builder(self) -> StructBuilder<'a>;
+}
+
 /// Trait for all types that can be converted to a low-level `ListReader`.
 pub trait IntoInternalListReader<'a> {
     fn into_internal_list_reader(self) -> ListReader<'a>;
@@ -71,12 +76,58 @@ pub trait OwnedStruct: crate::introspect::Introspect {
     type Builder<'a>: From<StructBuilder<'a>> + HasStructSize;
 }
 
+/// Reinterprets a reader of struct type `A` as a reader of struct type `B`.
+///
+/// This is the supported way to move between generated types that are known to be
+/// wire-compatible, e.g. the same schema compiled at different revisions into two crates. The
+/// result is always memory-safe: fields that `B` has but the underlying struct lacks read as their
+/// defaults, and fields that the struct has but `B` lacks are ignored, exactly as when a newer or
+/// older version of a schema reads a message. Whether the data *means* anything as a `B` is up to
+/// the caller.
+pub fn cast_struct<'a, A: OwnedStruct, B: OwnedStruct>(a: A::Reader<'a>) -> B::Reader<'a> {
+    a.into_internal_struct_reader().into()
+}
+
+/// Reinterprets a builder of struct type `A` as a builder of struct type `B`. See
+/// `cast_struct()`.
+///
+/// A builder cannot grow the struct it points to, so this returns an error if `B` has a larger
+/// data or pointer section than the underlying struct.
+pub fn cast_struct_builder<'a, A: OwnedStruct, B: OwnedStruct>(
+    a: A::Builder<'a>,
+) -> Result<B::Builder<'a>>
+where
+    A::Builder<'a>: IntoInternalStructBuilder<'a>,
+{
+    let builder = a.into_internal_struct_builder();
+    let size = <B::Builder<'a> as HasStructSize>::STRUCT_SIZE;
+    let data_words = builder.get_data_section_size() / 64;
+    let pointers = builder.get_pointer_section_size();
+    if u32::from(size.data) > data_words || size.pointers > pointers {
+        let mut error = Error::from_kind(ErrorKind::Failed);
+        write!(
+            error,
+            "cannot cast a struct with {data_words} data words and {pointers} pointers to one with {} data words and {} pointers",
+            size.data, size.pointers
+        );
+        return Err(error);
+    }
+    Ok(builder.into())
+}
+
 pub trait Pipelined {
     type Pipeline;
 }
 
 pub trait FromPointerBuilder<'a>: Sized {
     fn init_pointer(builder: PointerBuilder<'a>, length: u32) -> Self;
+
+    /// Like `init_pointer()`, but fails with `Overloaded` instead of panicking if the allocator
+    /// has no room for the object.
+    fn try_init_pointer(builder: PointerBuilder<'a>, length: u32) -> Result<Self> {
+        Ok(Self::init_pointer(builder, length))
+    }
+
     fn get_from_pointer(
         builder: PointerBuilder<'a>,
         default: Option<&'a [crate::Word]>,
@@ -0,0 +1,99 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, HeapAllocator, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::{any_pointer, text_list, word, ErrorKind, Word};
+
+fn forward(payload: any_pointer::Reader) -> capnp::Result<message::Builder<HeapAllocator>> {
+    let mut envelope = message::Builder::new_default();
+    envelope
+        .init_root::<any_pointer::Builder>()
+        .set_as(payload)?;
+    Ok(envelope)
+}
+
+#[test]
+fn copies_values_of_unknown_type() {
+    // Small segments, so that the source has far pointers.
+    let mut source = message::Builder::new(HeapAllocator::new().first_segment_words(4));
+    {
+        let mut root: node::Builder = source.init_root();
+        root.set_id(3);
+        root.set_display_name("forwarded".into());
+        root.init_nested_nodes(2).get(1).set_name("inner".into());
+    }
+    assert!(source.get_segments_for_output().len() > 1);
+    let payload: any_pointer::Reader = source.get_root_as_reader().unwrap();
+    let envelope = forward(payload).unwrap();
+    assert_eq!(envelope.get_segments_for_output().len(), 1);
+
+    let copy: any_pointer::Reader = envelope.get_root_as_reader().unwrap();
+    assert!(copy.eq_deep(&payload).unwrap());
+    let node: node::Reader = copy.get_as().unwrap();
+    assert_eq!(node.get_id(), 3);
+    assert_eq!(node.get_display_name().unwrap(), "forwarded");
+    assert_eq!(
+        node.get_nested_nodes().unwrap().get(1).get_name().unwrap(),
+        "inner"
+    );
+
+    let mut source = message::Builder::new_default();
+    {
+        let mut list: text_list::Builder = source.initn_root(2);
+        list.set(0, "a".into());
+        list.set(1, "b".into());
+    }
+    let envelope = forward(source.get_root_as_reader().unwrap()).unwrap();
+    let list: text_list::Reader = envelope.get_root_as_reader().unwrap();
+    assert_eq!(list.get(1).unwrap(), "b");
+
+    let mut source = message::Builder::new_default();
+    source.set_root("text").unwrap();
+    let envelope = forward(source.get_root_as_reader().unwrap()).unwrap();
+    let text: capnp::text::Reader = envelope.get_root_as_reader().unwrap();
+    assert_eq!(text, "text");
+}
+
+#[test]
+fn keeps_section_sizes() {
+    // A struct of two data words, the second of them zero, which canonicalization would drop.
+    let words = [
+        word(0, 0, 0, 0, 2, 0, 0, 0),
+        word(1, 0, 0, 0, 0, 0, 0, 0),
+        word(0, 0, 0, 0, 0, 0, 0, 0),
+    ];
+    let segments = [Word::words_to_bytes(&words)];
+    let source = message::Reader::new(message::SegmentArray::new(&segments), ReaderOptions::new());
+    let envelope = forward(source.get_root().unwrap()).unwrap();
+    let copy: any_pointer::Reader = envelope.get_root_as_reader().unwrap();
+    assert_eq!(copy.target_size().unwrap().word_count, 2);
+    assert_eq!(envelope.get_segments_for_output()[0], segments[0]);
+}
+
+#[test]
+fn copying_null_clears_target() {
+    let mut envelope = message::Builder::new_default();
+    envelope.set_root("old payload").unwrap();
+    let null = message::Reader::empty();
+    envelope
+        .get_root::<any_pointer::Builder>()
+        .unwrap()
+        .set_as(null.get_root::<any_pointer::Reader>().unwrap())
+        .unwrap();
+    let root: any_pointer::Reader = envelope.get_root_as_reader().unwrap();
+    assert!(root.is_null());
+}
+
+#[test]
+fn copying_respects_source_traversal_limit() {
+    let mut source = message::Builder::new_default();
+    source.set_root(&*"long text ".repeat(10)).unwrap();
+    let segments = source.get_segments_for_output();
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(Some(4));
+    let limited = message::Reader::new(message::SegmentArray::new(&segments), options);
+    let Err(error) = forward(limited.get_root().unwrap()) else {
+        panic!("traversal limit not enforced");
+    };
+    assert_eq!(error.kind, ErrorKind::ReadLimitExceeded);
+}
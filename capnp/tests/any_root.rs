@@ -0,0 +1,53 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::{any_pointer, serialize};
+
+/// Fills in a payload whose type the caller does not know.
+fn fill(payload: any_pointer::Builder) {
+    let mut node: node::Builder = payload.init_as();
+    node.set_id(42);
+    node.set_display_name("payload".into());
+}
+
+#[test]
+fn set_through_any_pointer_read_typed() {
+    let mut builder = message::Builder::new_default();
+    {
+        let mut root: node::Builder = builder.init_root();
+        root.set_id(1);
+    }
+    // Initializing the root as an any pointer clears what was there.
+    let root: any_pointer::Builder = builder.init_root();
+    assert!(root.is_null());
+    fill(root);
+
+    let words = serialize::write_message_to_words(&builder);
+    let reader = serialize::read_message(&words[..], ReaderOptions::new()).unwrap();
+    let node: node::Reader = reader.get_root().unwrap();
+    assert_eq!(node.get_id(), 42);
+    assert_eq!(node.get_display_name().unwrap(), "payload");
+}
+
+#[test]
+fn set_typed_read_through_any_pointer() {
+    let mut builder = message::TypedBuilder::<node::Owned>::new_default();
+    builder.init_root().set_id(7);
+    let words = serialize::write_message_to_words(builder.borrow_inner());
+    let reader = serialize::read_message(&words[..], ReaderOptions::new()).unwrap();
+
+    let payload: any_pointer::Reader = reader.get_root().unwrap();
+    assert!(!payload.is_null());
+    let node: node::Reader = payload.get_as().unwrap();
+    assert_eq!(node.get_id(), 7);
+
+    // The payload can be forwarded into another envelope without knowing its type.
+    let mut forwarded = message::Builder::new_default();
+    forwarded
+        .init_root::<any_pointer::Builder>()
+        .set_as(payload)
+        .unwrap();
+    let node: node::Reader = forwarded.get_root_as_reader().unwrap();
+    assert_eq!(node.get_id(), 7);
+}
@@ -0,0 +1,87 @@
+//! Compares the throughput of each scenario in `capnp::bench` against baselines measured on a
+//! development machine, failing only if a scenario is far slower, as after an accidental
+//! quadratic loop or a lost fast path. Run with `cargo test --features bench --test bench_baseline
+//! -- --nocapture` to see the numbers.
+
+#![cfg(feature = "bench")]
+
+use std::time::Duration;
+
+use capnp::bench::{self, Fixture, Scenario, Shape};
+use capnp::schema_capnp::code_generator_request;
+
+/// Words per second of each scenario on the standard message, in an unoptimized test build.
+/// Update these when a change makes a scenario substantially faster.
+const BASELINES: [(Scenario, f64); 7] = [
+    (Scenario::Build, 9_500_000.0),
+    (Scenario::Serialize, 2_000_000_000.0),
+    (Scenario::SerializePacked, 5_000_000.0),
+    (Scenario::Read, 10_000_000.0),
+    (Scenario::ReadPacked, 4_300_000.0),
+    (Scenario::DeepCopy, 8_800_000.0),
+    (Scenario::Canonicalize, 4_800_000.0),
+];
+
+/// How much slower than its baseline a scenario may be. This is generous, since test machines
+/// vary in speed and load, and optimized builds are compared against the same numbers.
+const TOLERANCE: f64 = 20.0;
+
+#[test]
+fn within_baselines() {
+    let fixture = Fixture::new(Shape::STANDARD);
+    let mut failures = Vec::new();
+    for (scenario, baseline) in BASELINES {
+        let measurement = fixture
+            .measure(scenario, Duration::from_millis(500), 5)
+            .unwrap();
+        println!("{measurement} (baseline {baseline:.0} words/s)");
+        if measurement.words_per_sec() * TOLERANCE < baseline {
+            failures.push(scenario.name());
+        }
+    }
+    assert!(failures.is_empty(), "too slow: {failures:?}");
+}
+
+#[test]
+fn every_scenario_has_a_baseline() {
+    for scenario in Scenario::ALL {
+        assert!(BASELINES.iter().any(|(with, _)| *with == scenario));
+    }
+}
+
+#[test]
+fn message_is_deterministic() {
+    let first = Fixture::new(Shape::STANDARD);
+    let second = Fixture::new(Shape::STANDARD);
+    assert_eq!(first.plain, second.plain);
+    assert_eq!(first.packed, second.packed);
+    assert!(first.words() > 20_000, "{}", first.words());
+
+    let other = Fixture::new(Shape {
+        seed: 1,
+        ..Shape::STANDARD
+    });
+    assert_ne!(first.plain, other.plain);
+}
+
+#[test]
+fn every_encoding_reads_the_same() {
+    let fixture = Fixture::new(Shape::STANDARD);
+    let expected = bench::traverse(fixture.message.get_root_as_reader().unwrap()).unwrap();
+    assert_eq!(fixture.run(Scenario::Read).unwrap(), expected);
+    assert_eq!(fixture.run(Scenario::ReadPacked).unwrap(), expected);
+
+    let mut copy = capnp::message::Builder::new_default();
+    copy.set_root(
+        fixture
+            .reader()
+            .unwrap()
+            .get_root::<code_generator_request::Reader>()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        bench::traverse(copy.get_root_as_reader().unwrap()).unwrap(),
+        expected
+    );
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message;
+use capnp::schema_capnp::{node, value};
+use capnp::{data, text};
+
+const NAME: text::Reader<'static> = text::Reader::from_str("from_slices.capnp");
+
+fn display_name_len(name: text::Reader) -> usize {
+    name.len()
+}
+
+#[test]
+fn text_reader_from_str() {
+    assert_eq!(display_name_len(NAME), 17);
+    assert_eq!(NAME, "from_slices.capnp");
+    assert_eq!(NAME.to_str().unwrap(), "from_slices.capnp");
+    assert_eq!(text::Reader::from_str("").as_bytes(), b"");
+
+    // Such a reader can be written into a message, and compares equal to what is read back.
+    let mut builder = message::Builder::new_default();
+    let mut root: node::Builder = builder.init_root();
+    root.set_display_name(NAME);
+    let root = root.into_reader();
+    assert_eq!(root.get_display_name().unwrap(), NAME);
+}
+
+#[test]
+fn data_reader_is_a_slice() {
+    let bytes = [1u8, 2, 3];
+    let reader: data::Reader = &bytes;
+
+    let mut builder = message::Builder::new_default();
+    let mut root: value::Builder = builder.init_root();
+    root.set_data(reader);
+    let value::Data(read) = root.into_reader().which().unwrap() else {
+        panic!("expected data");
+    };
+    assert_eq!(read.unwrap(), reader);
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "alloc")]
+
+use capnp::io::Read;
+use capnp::message::{self, ReaderOptions};
+use capnp::{data, serialize};
+
+const BLOB_LEN: u32 = 100 << 20;
+
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.chunks(8).fold(0u64, |sum, chunk| {
+        let mut word = [0; 8];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.rotate_left(5) ^ u64::from_le_bytes(word)
+    })
+}
+
+#[test]
+fn stream_large_data() {
+    let mut builder = message::Builder::new_default();
+    let blob: data::Builder = builder.initn_root(BLOB_LEN);
+    for (i, chunk) in blob.chunks_mut(4).enumerate() {
+        chunk.copy_from_slice(&(i as u32).wrapping_mul(0x9e37_79b9).to_le_bytes());
+    }
+    let bytes = serialize::write_message_to_bytes(&builder);
+    let options = *ReaderOptions::new().traversal_limit_in_words(None);
+    let message = serialize::read_message_from_flat_slice(&mut &bytes[..], options).unwrap();
+    let blob: data::Reader = message.get_root().unwrap();
+    let expected = checksum(blob);
+
+    // Through `io::Read`, in reads that do not line up with the checksum's words.
+    let mut reader = data::reader(blob);
+    let mut streamed = Vec::with_capacity(blob.len());
+    let mut buf = [0; 65_537];
+    loop {
+        let n = reader.read(&mut buf).unwrap();
+        if n == 0 {
+            break;
+        }
+        streamed.extend_from_slice(&buf[..n]);
+    }
+    assert_eq!(reader.remaining(), 0);
+    assert_eq!(checksum(&streamed), expected);
+
+    streamed.clear();
+    assert_eq!(
+        data::copy_to(blob, &mut streamed).unwrap(),
+        u64::from(BLOB_LEN)
+    );
+    assert_eq!(checksum(&streamed), expected);
+}
+
+#[test]
+fn stream_text() {
+    let text = capnp::text::Reader::from_str("streamed text");
+    let mut reader = text.reader();
+    let mut buf = [0; 8];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"streamed");
+    assert_eq!(reader.remaining(), 5);
+
+    let mut copied = Vec::new();
+    assert_eq!(text.copy_to(&mut copied).unwrap(), 13);
+    assert_eq!(copied, b"streamed text");
+}
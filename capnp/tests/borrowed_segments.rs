@@ -0,0 +1,64 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::{text_list, ErrorKind, Word};
+
+/// Copies the segments of a message into one region of words, as a writer would into shared
+/// memory, and returns the region and where each segment starts and ends in it.
+fn region() -> (Vec<Word>, Vec<(usize, usize)>) {
+    let mut builder = message::Builder::new(message::HeapAllocator::new().first_segment_words(4));
+    {
+        let mut list: text_list::Builder = builder.initn_root(10);
+        for idx in 0..10 {
+            list.set(idx, format!("item {idx}").as_str().into());
+        }
+    }
+    let segments = builder.get_segments_for_output();
+    assert!(segments.len() > 1);
+    let total: usize = segments.iter().map(|segment| segment.len() / 8).sum();
+    let mut region = Word::allocate_zeroed_vec(total);
+    let mut bounds = Vec::new();
+    let mut start = 0;
+    for segment in segments.iter() {
+        let end = start + segment.len() / 8;
+        Word::words_to_bytes_mut(&mut region[start..end]).copy_from_slice(segment);
+        bounds.push((start, end));
+        start = end;
+    }
+    (region, bounds)
+}
+
+#[test]
+fn reads_segments_in_place() {
+    let (region, bounds) = region();
+    let segments: Vec<&[Word]> = bounds
+        .iter()
+        .map(|&(start, end)| &region[start..end])
+        .collect();
+    let message = message::Reader::new(&segments[..], ReaderOptions::new());
+    let list: text_list::Reader = message.get_root().unwrap();
+    assert_eq!(list.len(), 10);
+    for idx in 0..10 {
+        assert_eq!(list.get(idx).unwrap(), format!("item {idx}").as_str());
+    }
+
+    let range = Word::words_to_bytes(&region).as_ptr_range();
+    let text = list.get(9).unwrap().as_bytes().as_ptr();
+    assert!(range.contains(&text));
+}
+
+#[test]
+fn segments_are_checked_as_they_are_read() {
+    let (region, bounds) = region();
+    // Leave out the segments that the root's far pointers lead to.
+    let segments: Vec<&[Word]> = vec![&region[bounds[0].0..bounds[0].1]];
+    let message = message::Reader::new(&segments[..], ReaderOptions::new());
+    let error = message
+        .get_root::<text_list::Reader>()
+        .and_then(|list| list.get(9).map(drop))
+        .unwrap_err();
+    assert!(
+        matches!(error.kind, ErrorKind::InvalidSegmentId(_)),
+        "{error}"
+    );
+}
@@ -0,0 +1,233 @@
+// Build, serialize, read, copy, and canonicalize blobs and lists at their boundary sizes.
+
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, HeapAllocator};
+use capnp::schema_capnp::code_generator_request::requested_file;
+use capnp::traits::Owned;
+use capnp::{data, primitive_list, serialize, struct_list, text};
+
+/// Runs `check` against the root of `builder` after each of: a serialization round trip,
+/// a copy into a fresh message, and canonicalization.
+fn check_all<T, C>(builder: message::Builder<HeapAllocator>, check: C)
+where
+    T: Owned,
+    C: for<'b> Fn(T::Reader<'b>),
+{
+    check_all_with_options::<T, C>(builder, message::ReaderOptions::new(), check)
+}
+
+fn check_all_with_options<T, C>(
+    builder: message::Builder<HeapAllocator>,
+    options: message::ReaderOptions,
+    check: C,
+) where
+    T: Owned,
+    C: for<'b> Fn(T::Reader<'b>),
+{
+    check(builder.get_root_as_reader::<T::Reader<'_>>().unwrap());
+
+    let words = serialize::write_message_to_words(&builder);
+    let reader = serialize::read_message_from_flat_slice(&mut &words[..], options).unwrap();
+    let root = reader.get_root::<T::Reader<'_>>().unwrap();
+    check(root.clone());
+
+    let mut copy = message::Builder::new_default();
+    copy.set_root(root.clone()).unwrap();
+    check(copy.get_root_as_reader::<T::Reader<'_>>().unwrap());
+
+    let mut canonical = message::Builder::new_default();
+    canonical.set_root_canonical(root).unwrap();
+    check(canonical.get_root_as_reader::<T::Reader<'_>>().unwrap());
+
+    let canonical_words = reader.canonicalize().unwrap();
+    let canonical_segments = &[capnp::Word::words_to_bytes(&canonical_words)];
+    let canonical_reader =
+        message::Reader::new(message::SegmentArray::new(canonical_segments), options);
+    assert!(canonical_reader.is_canonical().unwrap());
+    check(canonical_reader.get_root::<T::Reader<'_>>().unwrap());
+}
+
+fn text_of_len(len: u32) {
+    let mut builder = message::Builder::new_default();
+    {
+        let mut text: text::Builder = builder.initn_root(len);
+        for _ in 0..len {
+            text.push_ascii(b'x');
+        }
+    }
+    check_all::<text::Owned, _>(builder, |t| {
+        assert_eq!(t.len(), len as usize);
+        assert!(t.as_bytes().iter().all(|b| *b == b'x'));
+    });
+}
+
+fn data_of_len(len: u32) {
+    let mut builder = message::Builder::new_default();
+    {
+        let data: data::Builder = builder.initn_root(len);
+        assert_eq!(data.len(), len as usize);
+        for b in data.iter_mut() {
+            *b = 0xab;
+        }
+    }
+    check_all::<data::Owned, _>(builder, |d| {
+        assert_eq!(d.len(), len as usize);
+        assert!(d.iter().all(|b| *b == 0xab));
+    });
+}
+
+fn primitive_list_of_len<T>(len: u32, value: T)
+where
+    T: capnp::private::layout::PrimitiveElement
+        + capnp::introspect::Introspect
+        + Copy
+        + PartialEq
+        + core::fmt::Debug,
+{
+    let mut builder = message::Builder::new_default();
+    {
+        let mut list: primitive_list::Builder<T> = builder.initn_root(len);
+        assert_eq!(list.len(), len);
+        for idx in 0..len {
+            list.set(idx, value);
+        }
+    }
+    check_all::<primitive_list::Owned<T>, _>(builder, |l| {
+        assert_eq!(l.len(), len);
+        for idx in 0..len {
+            assert_eq!(l.get(idx), value);
+        }
+    });
+}
+
+#[test]
+fn empty_text() {
+    text_of_len(0);
+
+    // Setting an empty text from a reader should also produce just the NUL terminator.
+    let mut builder = message::Builder::new_default();
+    builder.set_root("").unwrap();
+    check_all::<text::Owned, _>(builder, |t| assert!(t.is_empty()));
+}
+
+#[test]
+fn text_around_word_boundary() {
+    // 7 bytes plus the NUL terminator fill exactly one word.
+    for len in [1, 7, 8, 15, 16] {
+        text_of_len(len);
+    }
+}
+
+#[test]
+fn empty_data() {
+    data_of_len(0);
+
+    let mut builder = message::Builder::new_default();
+    builder.set_root(&[][..]).unwrap();
+    check_all::<data::Owned, _>(builder, |d| assert!(d.is_empty()));
+}
+
+#[test]
+fn data_around_word_boundary() {
+    for len in [1, 7, 8, 9, 16] {
+        data_of_len(len);
+    }
+}
+
+#[test]
+fn empty_text_then_empty_data_in_same_message() {
+    let mut builder = message::Builder::new_default();
+    {
+        let root: requested_file::Builder = builder.init_root();
+        let mut root = root;
+        root.reborrow().init_filename(0);
+        root.set_filename("".into());
+    }
+    {
+        let data: data::Builder = builder.initn_root(0);
+        data.copy_from_slice(&[]);
+    }
+    check_all::<data::Owned, _>(builder, |d| assert!(d.is_empty()));
+}
+
+#[test]
+fn empty_primitive_lists() {
+    primitive_list_of_len::<()>(0, ());
+    primitive_list_of_len::<bool>(0, true);
+    primitive_list_of_len::<u8>(0, 0xff);
+    primitive_list_of_len::<u16>(0, 0xffff);
+    primitive_list_of_len::<u32>(0, 0xffff_ffff);
+    primitive_list_of_len::<u64>(0, u64::MAX);
+    primitive_list_of_len::<f64>(0, 1.5);
+}
+
+#[test]
+fn bool_lists_around_word_boundary() {
+    for len in [1, 7, 8, 63, 64, 65] {
+        primitive_list_of_len::<bool>(len, true);
+    }
+}
+
+#[test]
+fn single_element_primitive_lists() {
+    primitive_list_of_len::<()>(1, ());
+    primitive_list_of_len::<u8>(1, 0xff);
+    primitive_list_of_len::<u16>(1, 0xffff);
+    primitive_list_of_len::<u32>(1, 0xffff_ffff);
+    primitive_list_of_len::<u64>(1, u64::MAX);
+}
+
+#[test]
+fn max_len_void_list() {
+    // The list pointer's element count is 29 bits wide. Void elements take no space, so this is
+    // the largest list that can exist. Reading it counts against the traversal limit, which
+    // guards against amplification from exactly this kind of list, so the limit is lifted here.
+    let len = (1 << 29) - 1;
+    let mut builder = message::Builder::new_default();
+    {
+        let list: primitive_list::Builder<()> = builder.initn_root(len);
+        assert_eq!(list.len(), len);
+    }
+    let mut options = message::ReaderOptions::new();
+    options.traversal_limit_in_words(None);
+    check_all_with_options::<primitive_list::Owned<()>, _>(builder, options, |l| {
+        assert_eq!(l.len(), len);
+        assert_eq!(l.get(len - 1), ());
+    });
+}
+
+#[test]
+fn empty_struct_list() {
+    let mut builder = message::Builder::new_default();
+    {
+        let list: struct_list::Builder<requested_file::Owned> = builder.initn_root(0);
+        assert_eq!(list.len(), 0);
+    }
+    check_all::<struct_list::Owned<requested_file::Owned>, _>(builder, |l| {
+        assert_eq!(l.len(), 0);
+        assert!(l.iter().next().is_none());
+    });
+}
+
+#[test]
+fn empty_text_and_data_lists() {
+    let mut builder = message::Builder::new_default();
+    {
+        let list: capnp::text_list::Builder = builder.initn_root(0);
+        assert_eq!(list.len(), 0);
+    }
+    check_all::<capnp::text_list::Owned, _>(builder, |l| assert_eq!(l.len(), 0));
+
+    let mut builder = message::Builder::new_default();
+    {
+        let mut list: capnp::data_list::Builder = builder.initn_root(2);
+        // Element 1 is left null, which reads back as empty data.
+        list.set(0, &[]);
+    }
+    check_all::<capnp::data_list::Owned, _>(builder, |l| {
+        assert_eq!(l.len(), 2);
+        assert!(l.get(0).unwrap().is_empty());
+        assert!(l.get(1).unwrap().is_empty());
+    });
+}
@@ -0,0 +1,92 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, Allocator, HeapAllocator, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::{serialize, struct_list};
+
+/// A `HeapAllocator` that counts the segments it has allocated and deallocated.
+#[derive(Default)]
+struct Counting {
+    heap: HeapAllocator,
+    allocated: usize,
+    deallocated: usize,
+}
+
+unsafe impl Allocator for Counting {
+    fn allocate_segment(&mut self, minimum_size: u32) -> (*mut u8, u32) {
+        self.allocated += 1;
+        self.heap.allocate_segment(minimum_size)
+    }
+
+    unsafe fn deallocate_segment(&mut self, ptr: *mut u8, word_size: u32, words_used: u32) {
+        self.deallocated += 1;
+        unsafe { self.heap.deallocate_segment(ptr, word_size, words_used) }
+    }
+}
+
+fn build_nodes(builder: &mut message::Builder<&mut Counting>, count: u32, id: u64) {
+    let mut nodes: struct_list::Builder<node::Owned> = builder.initn_root(count);
+    for idx in 0..count {
+        let mut node = nodes.reborrow().get(idx);
+        node.set_id(id);
+        node.set_display_name("reused.capnp".into());
+    }
+}
+
+#[test]
+fn cleared_builder_matches_new_builder() {
+    let mut counting = Counting::default();
+    let mut builder = message::Builder::new(&mut counting);
+    build_nodes(&mut builder, 3, 1);
+    builder.clear();
+
+    let fresh = message::Builder::new_default();
+    assert_eq!(
+        builder.get_segments_for_output()[..],
+        fresh.get_segments_for_output()[..]
+    );
+    let reader = builder.into_reader();
+    assert!(reader
+        .get_root::<capnp::any_pointer::Reader>()
+        .unwrap()
+        .is_null());
+}
+
+#[test]
+fn clear_keeps_only_first_segment() {
+    let mut counting = Counting {
+        heap: HeapAllocator::new().first_segment_words(64),
+        ..Counting::default()
+    };
+    let mut builder = message::Builder::new(&mut counting);
+    // Far more than fits in the first segment.
+    build_nodes(&mut builder, 100, 1);
+    let segments = builder.get_segments_for_output().len();
+    assert!(segments > 1);
+    builder.clear();
+    assert_eq!(builder.get_segments_for_output().len(), 1);
+    drop(builder);
+    assert_eq!(counting.allocated, segments);
+    assert_eq!(counting.deallocated, segments);
+}
+
+#[test]
+fn reused_builder_does_not_allocate() {
+    let mut counting = Counting::default();
+    let mut builder = message::Builder::new(&mut counting);
+    for id in 0..100 {
+        builder.clear();
+        build_nodes(&mut builder, 4, id);
+        let bytes = serialize::write_message_to_words(&builder);
+
+        let message = serialize::read_message(&bytes[..], ReaderOptions::new()).unwrap();
+        let nodes: struct_list::Reader<node::Owned> = message.get_root().unwrap();
+        assert_eq!(nodes.len(), 4);
+        for node in nodes {
+            assert_eq!(node.get_id(), id);
+            assert_eq!(node.get_display_name().unwrap(), "reused.capnp");
+        }
+    }
+    drop(builder);
+    assert_eq!(counting.allocated, 1);
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use capnp::message::{self, ReaderOptions};
+use capnp::private::layout::CapTable;
+use capnp::traits::{Imbue, ImbueMut};
+use capnp::{any_pointer, any_pointer_list, text, text_list, ErrorKind};
+
+use common::Hook;
+
+fn texts() -> message::Builder<message::HeapAllocator> {
+    let mut builder = message::Builder::new_default();
+    let mut list: text_list::Builder = builder.initn_root(3);
+    for (idx, text) in ["one", "two", "three"].into_iter().enumerate() {
+        list.set(idx as u32, text::Reader::from(text));
+    }
+    builder
+}
+
+#[test]
+fn into_reader_reads_built_message() {
+    let builder = texts();
+    let reader = builder.into_reader();
+    let list: text_list::Reader = reader.get_root().unwrap();
+    let texts: Vec<_> = list
+        .iter()
+        .map(|text| text.unwrap().to_str().unwrap())
+        .collect();
+    assert_eq!(texts, ["one", "two", "three"]);
+}
+
+#[test]
+fn into_reader_with_options_enforces_them() {
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(Some(2));
+    let reader = texts().into_reader_with_options(options);
+    let Err(error) = reader.get_root::<text_list::Reader>() else {
+        panic!("traversal limit not enforced");
+    };
+    assert_eq!(error.kind, ErrorKind::ReadLimitExceeded);
+
+    let mut typed = message::TypedBuilder::<text_list::Owned>::new_default();
+    typed.initn_root(1).set(0, "nested".into());
+    let mut options = ReaderOptions::new();
+    options.nesting_limit(0);
+    let reader = typed.into_reader_with_options(options);
+    assert!(reader.get().is_err());
+}
+
+#[test]
+fn caps_resolve_through_builder_cap_table() {
+    let mut caps = CapTable::new();
+    let mut builder = message::Builder::new_default();
+    {
+        let mut root: any_pointer::Builder = builder.init_root();
+        root.imbue_mut(&mut caps);
+        let mut list: any_pointer_list::Builder = root.initn_as(4);
+        for idx in 0..4 {
+            list.reborrow()
+                .get(idx)
+                .set_as_capability(Box::new(Hook(10 + idx as usize)));
+        }
+    }
+
+    let mut options = ReaderOptions::new();
+    options.max_caps(Some(4));
+    let reader = builder.into_reader_with_options(options);
+    let cap_at = |index: u32| -> capnp::Result<usize> {
+        let mut root: any_pointer::Reader = reader.get_root()?;
+        root.imbue(&caps);
+        let list: any_pointer_list::Reader = root.get_as()?;
+        Ok(list.get(index).get_pipelined_cap(&[])?.get_ptr())
+    };
+    assert_eq!(cap_at(0).unwrap(), 10);
+    assert_eq!(cap_at(3).unwrap(), 13);
+    assert_eq!(cap_at(0).unwrap(), 10);
+    // Distinct capabilities are only counted under a limit, so the options took effect.
+    assert_eq!(reader.cap_count_seen(), 2);
+}
@@ -0,0 +1,132 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, AllocationStrategy, BuilderSnapshot, HeapAllocator};
+use capnp::{text_list, word, ErrorKind};
+
+const LEN: u32 = 40;
+
+fn allocator(strategy: AllocationStrategy) -> HeapAllocator {
+    HeapAllocator::new()
+        .first_segment_words(16)
+        .allocation_strategy(strategy)
+}
+
+fn fill(builder: &mut message::Builder<HeapAllocator>, range: core::ops::Range<u32>) {
+    let mut list: text_list::Builder = builder.get_root().unwrap();
+    for idx in range {
+        list.set(idx, format!("item number {idx}").as_str().into());
+    }
+}
+
+fn output(builder: &message::Builder<HeapAllocator>) -> Vec<Vec<u8>> {
+    builder
+        .get_segments_for_output()
+        .iter()
+        .map(|segment| segment.to_vec())
+        .collect()
+}
+
+fn in_one_go(strategy: AllocationStrategy) -> Vec<Vec<u8>> {
+    let mut builder = message::Builder::new(allocator(strategy));
+    builder.initn_root::<text_list::Builder>(LEN);
+    fill(&mut builder, 0..LEN);
+    output(&builder)
+}
+
+fn half_built(strategy: AllocationStrategy) -> BuilderSnapshot {
+    let mut builder = message::Builder::new(allocator(strategy));
+    builder.initn_root::<text_list::Builder>(LEN);
+    fill(&mut builder, 0..LEN / 2);
+    builder.freeze()
+}
+
+#[test]
+fn resume_matches_building_in_one_go() {
+    for strategy in [
+        AllocationStrategy::GrowHeuristically,
+        AllocationStrategy::FixedSize,
+    ] {
+        // The original builder is gone by the time the snapshot is thawed.
+        let snapshot = half_built(strategy);
+        assert!(snapshot.segments.len() > 1);
+
+        let mut builder = message::Builder::thaw(snapshot).unwrap();
+        fill(&mut builder, LEN / 2..LEN);
+        assert_eq!(output(&builder), in_one_go(strategy));
+    }
+}
+
+#[test]
+fn freeze_thaw_freeze_is_identity() {
+    let snapshot = half_built(AllocationStrategy::GrowHeuristically);
+    let builder = message::Builder::thaw(snapshot.clone()).unwrap();
+    assert_eq!(builder.freeze(), snapshot);
+}
+
+#[test]
+fn empty_builder() {
+    let snapshot = message::Builder::new_default().freeze();
+    assert!(snapshot.segments.is_empty());
+    let mut builder = message::Builder::thaw(snapshot).unwrap();
+    builder.set_root("hi").unwrap();
+    let root: capnp::text::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(root, "hi");
+}
+
+#[test]
+fn rejects_pointers_beyond_watermarks() {
+    let mut snapshot = half_built(AllocationStrategy::FixedSize);
+    // Lower the watermark of the last segment, so that the text at its end is out of bounds.
+    snapshot.segments.last_mut().unwrap().pop();
+    assert_eq!(
+        message::Builder::thaw(snapshot).err().unwrap().kind,
+        ErrorKind::MessageContainsOutOfBoundsPointer
+    );
+}
+
+#[test]
+fn rejects_inconsistent_snapshots() {
+    let snapshot = half_built(AllocationStrategy::FixedSize);
+
+    let mut bad = snapshot.clone();
+    bad.capacities.pop();
+    assert_eq!(
+        message::Builder::thaw(bad).err().unwrap().kind,
+        ErrorKind::Failed
+    );
+
+    let mut bad = snapshot.clone();
+    bad.capacities[0] = bad.segments[0].len() as u32 - 1;
+    assert_eq!(
+        message::Builder::thaw(bad).err().unwrap().kind,
+        ErrorKind::Failed
+    );
+
+    let mut bad = snapshot;
+    bad.next_segment_words = bad.max_segment_words + 1;
+    assert_eq!(
+        message::Builder::thaw(bad).err().unwrap().kind,
+        ErrorKind::Failed
+    );
+}
+
+#[test]
+fn rejects_overlapping_objects() {
+    // The root struct's pointer points at itself, as a struct of one data word, so that setting
+    // that struct's field in the thawed builder would rewrite the pointer.
+    let snapshot = BuilderSnapshot {
+        segments: vec![vec![
+            word(0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00),
+            word(0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+            word(0xfc, 0xff, 0xff, 0xff, 0x01, 0x00, 0x00, 0x00),
+        ]],
+        capacities: vec![16],
+        next_segment_words: 16,
+        allocation_strategy: AllocationStrategy::FixedSize,
+        max_segment_words: 1 << 20,
+    };
+    assert_eq!(
+        message::Builder::thaw(snapshot).err().unwrap().kind,
+        ErrorKind::Failed
+    );
+}
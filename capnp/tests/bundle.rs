@@ -0,0 +1,80 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::serialize::{BundleReader, BundleWriter};
+
+fn named(name: &str, nested_nodes: u32) -> message::Builder<message::HeapAllocator> {
+    let mut builder = message::Builder::new_default();
+    let mut root: node::Builder = builder.init_root();
+    root.set_display_name(name.into());
+    root.init_nested_nodes(nested_nodes);
+    builder
+}
+
+/// A manifest followed by three payloads, the last of which has 100 nested nodes.
+fn bundle() -> Vec<u8> {
+    let mut writer = BundleWriter::new(Vec::new());
+    assert!(writer.is_empty());
+    assert_eq!(writer.add(&named("manifest", 0)), 0);
+    for (idx, nested_nodes) in [1, 2, 100].into_iter().enumerate() {
+        let mut payload = named(&format!("payload {idx}"), nested_nodes);
+        assert_eq!(writer.add(&mut payload), idx as u32 + 1);
+    }
+    assert_eq!(writer.len(), 4);
+    writer.finish().unwrap()
+}
+
+fn name(bundle: &BundleReader<Vec<u8>>, index: u32, options: ReaderOptions) -> String {
+    let message = bundle.get(index, options).unwrap();
+    let root: node::Reader = message.get_root().unwrap();
+    root.get_display_name().unwrap().to_string().unwrap()
+}
+
+#[test]
+fn random_access() {
+    let bundle = BundleReader::new(bundle()).unwrap();
+    assert_eq!(bundle.len(), 4);
+    assert_eq!(name(&bundle, 3, ReaderOptions::new()), "payload 2");
+    assert_eq!(name(&bundle, 0, ReaderOptions::new()), "manifest");
+    assert_eq!(name(&bundle, 2, ReaderOptions::new()), "payload 1");
+    assert!(bundle.get(4, ReaderOptions::new()).is_err());
+
+    // Each message is read with its own options.
+    let mut small = ReaderOptions::new();
+    small.traversal_limit_in_words(Some(100));
+    assert_eq!(name(&bundle, 1, small), "payload 0");
+    assert!(bundle.get(3, small).is_err());
+}
+
+#[test]
+fn corrupt_entries_do_not_affect_others() {
+    let mut bytes = bundle();
+    // Overwrite the manifest's segment table. It starts after the bundle's header and its table
+    // of four lengths.
+    let manifest = 8 + 4 * 8;
+    bytes[manifest..manifest + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    let bundle = BundleReader::new(bytes).unwrap();
+    assert!(bundle.get(0, ReaderOptions::new()).is_err());
+    assert_eq!(name(&bundle, 3, ReaderOptions::new()), "payload 2");
+}
+
+#[test]
+fn malformed_bundles() {
+    let bytes = bundle();
+    for len in [0, 4, 8, 16, bytes.len() - 8] {
+        assert!(BundleReader::new(&bytes[..len]).is_err(), "{len}");
+    }
+    let mut longer = bytes.clone();
+    longer.extend_from_slice(&[0; 8]);
+    assert!(BundleReader::new(longer).is_err());
+
+    // A table of contents claiming more messages than the buffer could hold.
+    let mut bytes = bytes;
+    bytes[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(BundleReader::new(bytes).is_err());
+
+    let empty = BundleWriter::new(Vec::new()).finish().unwrap();
+    assert_eq!(empty, [0; 8]);
+    assert!(BundleReader::new(empty).unwrap().is_empty());
+}
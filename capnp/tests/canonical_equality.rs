@@ -0,0 +1,87 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, AllocationStrategy, HeapAllocator, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::{serialize, ErrorKind, Word};
+
+fn read(builder: &message::Builder<HeapAllocator>) -> message::Reader<serialize::OwnedSegments> {
+    let bytes = serialize::write_message_to_words(builder);
+    serialize::read_message(&bytes[..], ReaderOptions::new()).unwrap()
+}
+
+/// A node with a display name and two annotations, built in one segment in field order.
+fn built_in_order() -> message::Builder<HeapAllocator> {
+    let mut builder = message::Builder::new_default();
+    let mut node: node::Builder = builder.init_root();
+    node.set_id(7);
+    node.set_display_name("equal.capnp:Node".into());
+    let mut annotations = node.init_annotations(2);
+    annotations.reborrow().get(0).set_id(1);
+    annotations.get(1).set_id(2);
+    builder
+}
+
+/// The same value, built over many small segments in another order, with an abandoned first
+/// try at the annotations and an explicitly set zero field left behind.
+fn built_otherwise() -> message::Builder<HeapAllocator> {
+    let mut builder = message::Builder::new(
+        HeapAllocator::new()
+            .first_segment_words(2)
+            .allocation_strategy(AllocationStrategy::FixedSize),
+    );
+    let mut node: node::Builder = builder.init_root();
+    node.reborrow().init_annotations(5).get(4).set_id(99);
+    let mut annotations = node.reborrow().init_annotations(2);
+    annotations.reborrow().get(1).set_id(2);
+    annotations.get(0).set_id(1);
+    node.set_scope_id(0);
+    node.set_display_name("equal.capnp:Node".into());
+    node.set_id(7);
+    builder
+}
+
+#[test]
+fn equal_values_canonicalize_identically() {
+    let first = built_in_order();
+    let second = built_otherwise();
+    assert_eq!(first.get_segments_for_output().len(), 1);
+    assert!(second.get_segments_for_output().len() > 1);
+    assert_ne!(
+        serialize::write_message_to_words(&first),
+        serialize::write_message_to_words(&second)
+    );
+
+    let canonical = read(&first).canonicalize().unwrap();
+    assert_eq!(read(&second).canonicalize().unwrap(), canonical);
+
+    // The canonical words are a message of their own, which is canonical.
+    let segments: &[&[Word]] = &[&canonical];
+    let message = message::Reader::new(segments, ReaderOptions::new());
+    assert!(message.is_canonical().unwrap());
+    assert_eq!(message.canonicalize().unwrap(), canonical);
+    assert!(!read(&second).is_canonical().unwrap());
+}
+
+#[test]
+fn different_values_canonicalize_differently() {
+    let first = built_in_order();
+    let mut second = built_in_order();
+    second.get_root::<node::Builder>().unwrap().set_id(8);
+    assert_ne!(
+        read(&first).canonicalize().unwrap(),
+        read(&second).canonicalize().unwrap()
+    );
+}
+
+#[test]
+fn capabilities_have_no_canonical_form() {
+    // A root pointer that is a capability, with cap table index 0.
+    let words = [capnp::word(3, 0, 0, 0, 0, 0, 0, 0)];
+    let segments: &[&[Word]] = &[&words];
+    let message = message::Reader::new(segments, ReaderOptions::new());
+    let error = message.canonicalize().unwrap_err();
+    assert_eq!(
+        error.kind,
+        ErrorKind::CannotCreateACanonicalMessageWithACapability
+    );
+}
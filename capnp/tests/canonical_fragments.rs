@@ -0,0 +1,191 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, AllocationStrategy, HeapAllocator};
+use capnp::schema_capnp::code_generator_request::requested_file;
+use capnp::{any_pointer, any_pointer_list, word, ErrorKind, Word};
+
+fn fragment(id: u64, filename: &str, imports: &[u64]) -> Vec<Word> {
+    let mut builder = message::Builder::new_default();
+    let mut file: requested_file::Builder = builder.init_root();
+    file.set_id(id);
+    file.set_filename(filename.into());
+    let mut list = file.init_imports(imports.len() as u32);
+    for (idx, import) in imports.iter().enumerate() {
+        list.reborrow().get(idx as u32).set_id(*import);
+    }
+    builder.into_reader().canonicalize().unwrap()
+}
+
+fn check_file(file: requested_file::Reader, id: u64, filename: &str, imports: &[u64]) {
+    assert_eq!(file.get_id(), id);
+    assert_eq!(file.get_filename().unwrap(), filename);
+    let got: Vec<u64> = file
+        .get_imports()
+        .unwrap()
+        .iter()
+        .map(|i| i.get_id())
+        .collect();
+    assert_eq!(&got[..], imports);
+}
+
+#[test]
+fn gather_fragments() {
+    let fragments: Vec<Vec<Word>> = (0..10)
+        .map(|idx| fragment(idx, &format!("file{idx}.capnp"), &[idx, idx + 1]))
+        .collect();
+
+    // Small segments, so that some fragments need far pointers.
+    let mut builder = message::Builder::new(
+        HeapAllocator::new()
+            .first_segment_words(8)
+            .allocation_strategy(AllocationStrategy::FixedSize),
+    );
+    let mut list: any_pointer_list::Builder = builder.initn_root(fragments.len() as u32);
+    for (idx, words) in fragments.iter().enumerate() {
+        list.reborrow()
+            .get(idx as u32)
+            .set_from_canonical_words(words)
+            .unwrap();
+    }
+    assert!(builder.get_segments_for_output().len() > 1);
+
+    let list: any_pointer_list::Reader = builder.get_root_as_reader().unwrap();
+    for (idx, item) in list.iter().enumerate() {
+        let idx = idx as u64;
+        let file: requested_file::Reader = item.unwrap().get_as().unwrap();
+        check_file(file, idx, &format!("file{idx}.capnp"), &[idx, idx + 1]);
+    }
+}
+
+#[test]
+fn matches_set_as() {
+    let words = fragment(7, "seven.capnp", &[1, 2, 3]);
+
+    let mut expected = message::Builder::new_default();
+    let segments = [Word::words_to_bytes(&words)];
+    let fragment_message =
+        message::Reader::new(message::SegmentArray::new(&segments), Default::default());
+    expected
+        .init_root::<any_pointer::Builder>()
+        .set_as(fragment_message.get_root::<any_pointer::Reader>().unwrap())
+        .unwrap();
+
+    let mut actual = message::Builder::new_default();
+    actual
+        .init_root::<any_pointer::Builder>()
+        .set_from_canonical_words(&words)
+        .unwrap();
+
+    assert_eq!(
+        expected.get_segments_for_output()[0],
+        actual.get_segments_for_output()[0]
+    );
+    assert!(actual.into_reader().is_canonical().unwrap());
+}
+
+#[test]
+fn unchecked_matches_checked() {
+    let words = fragment(3, "three.capnp", &[4, 5]);
+    let mut checked = message::Builder::new_default();
+    checked
+        .init_root::<any_pointer::Builder>()
+        .set_from_canonical_words(&words)
+        .unwrap();
+    let mut unchecked = message::Builder::new_default();
+    unsafe {
+        unchecked
+            .init_root::<any_pointer::Builder>()
+            .set_from_canonical_words_unchecked(&words)
+            .unwrap()
+    };
+    assert_eq!(
+        checked.get_segments_for_output()[0],
+        unchecked.get_segments_for_output()[0]
+    );
+}
+
+#[test]
+fn replaces_existing_value() {
+    let mut builder = message::Builder::new_default();
+    builder.set_root("an initial value").unwrap();
+    let mut root: any_pointer::Builder = builder.get_root().unwrap();
+    root.set_from_canonical_words(&fragment(1, "a.capnp", &[]))
+        .unwrap();
+    check_file(root.into_reader().get_as().unwrap(), 1, "a.capnp", &[]);
+
+    // Null and zero-sized fragments.
+    let mut root: any_pointer::Builder = builder.get_root().unwrap();
+    root.set_from_canonical_words(&[word(0, 0, 0, 0, 0, 0, 0, 0)])
+        .unwrap();
+    assert!(root.is_null());
+    root.set_from_canonical_words(&[word(0xfc, 0xff, 0xff, 0xff, 0, 0, 0, 0)])
+        .unwrap();
+    assert!(!root.is_null());
+    assert_eq!(root.target_size().unwrap().word_count, 0);
+    let file: requested_file::Reader = root.into_reader().get_as().unwrap();
+    check_file(file, 0, "", &[]);
+}
+
+#[test]
+fn rejects_non_canonical_fragments() {
+    let mut builder = message::Builder::new_default();
+    let mut root: any_pointer::Builder = builder.init_root();
+
+    // An extra word at the end.
+    let mut words = fragment(1, "a.capnp", &[]);
+    words.push(word(0, 0, 0, 0, 0, 0, 0, 0));
+    let err = root.set_from_canonical_words(&words).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Failed);
+    assert!(err.extra.contains("unreachable"), "{}", err.extra);
+
+    // A struct whose last data word is zero.
+    let words = [
+        word(0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00),
+        word(0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+    ];
+    assert!(root.set_from_canonical_words(&words).is_err());
+
+    // A pointer out of bounds.
+    let words = [word(0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00)];
+    assert!(root.set_from_canonical_words(&words).is_err());
+
+    assert!(root.set_from_canonical_words(&[]).is_err());
+    assert!(root.is_null());
+}
+
+#[test]
+fn set_root_from_stored_canonical_words() {
+    // Store a message's canonical body, then graft it into an outgoing message.
+    let stored = fragment(7, "stored.capnp", &[1, 2, 3]);
+    let mut builder = message::Builder::new_default();
+    builder.set_root_from_canonical_words(&stored).unwrap();
+    // Copied verbatim, root pointer included.
+    assert_eq!(
+        builder.get_segments_for_output()[0],
+        Word::words_to_bytes(&stored)
+    );
+
+    let mut builder = message::Builder::new_default();
+    builder.set_root("an initial value").unwrap();
+    builder.set_root_from_canonical_words(&stored).unwrap();
+
+    let segments = [Word::words_to_bytes(&stored)];
+    let original = message::Reader::new(
+        message::SegmentArray::new(&segments),
+        message::ReaderOptions::new(),
+    );
+    let root: any_pointer::Reader = original.get_root().unwrap();
+    assert!(root
+        .eq_deep(&builder.get_root_as_reader().unwrap())
+        .unwrap());
+    let file: requested_file::Reader = builder.get_root_as_reader().unwrap();
+    check_file(file, 7, "stored.capnp", &[1, 2, 3]);
+
+    // Non-canonical words are rejected, and the root is left as it was.
+    let mut words = stored.clone();
+    words.push(word(0, 0, 0, 0, 0, 0, 0, 0));
+    let err = builder.set_root_from_canonical_words(&words).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Failed);
+    let file: requested_file::Reader = builder.get_root_as_reader().unwrap();
+    check_file(file, 7, "stored.capnp", &[1, 2, 3]);
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use capnp::capability::CapTable;
+use capnp::message::{self, ReaderOptions};
+use capnp::traits::{Imbue, ImbueMut};
+use capnp::{any_pointer, any_pointer_list, serialize, ErrorKind};
+
+use common::Hook;
+
+/// Builds a list of capabilities, returning the message and the hooks its builder injected.
+fn build(ptrs: &[usize]) -> (Vec<u8>, CapTable) {
+    let mut caps = CapTable::new();
+    let mut builder = message::Builder::new_default();
+    let mut root: any_pointer::Builder = builder.init_root();
+    root.imbue_mut(&mut caps);
+    let mut list: any_pointer_list::Builder = root.initn_as(ptrs.len() as u32);
+    for (idx, &ptr) in ptrs.iter().enumerate() {
+        list.reborrow()
+            .get(idx as u32)
+            .set_as_capability(Box::new(Hook(ptr)));
+    }
+    (serialize::write_message_to_words(&builder), caps)
+}
+
+#[test]
+fn builder_table_holds_injected_caps() {
+    let (_, caps) = build(&[10, 11, 12]);
+    let ptrs: Vec<usize> = caps
+        .iter()
+        .map(|hook| hook.as_ref().unwrap().get_ptr())
+        .collect();
+    assert_eq!(ptrs, [10, 11, 12]);
+}
+
+#[test]
+fn reader_resolves_caps_through_its_table() {
+    let (words, caps) = build(&[10, 11, 12]);
+    let reader = serialize::read_message(&words[..], ReaderOptions::new()).unwrap();
+    let mut root: any_pointer::Reader = reader.get_root().unwrap();
+    root.imbue(&caps);
+    let list: any_pointer_list::Reader = root.get_as().unwrap();
+    for (idx, ptr) in [10, 11, 12].into_iter().enumerate() {
+        let hook = list.get(idx as u32).get_pipelined_cap(&[]).unwrap();
+        assert_eq!(hook.get_ptr(), ptr);
+    }
+
+    // An index past the end of the table.
+    let caps = CapTable::new();
+    root.imbue(&caps);
+    let list: any_pointer_list::Reader = root.get_as().unwrap();
+    let error = list.get(0).get_pipelined_cap(&[]).unwrap_err();
+    assert_eq!(
+        error.kind,
+        ErrorKind::MessageContainsInvalidCapabilityPointer
+    );
+}
+
+#[test]
+fn reading_a_cap_without_a_table_is_an_error() {
+    let (words, _) = build(&[10]);
+    let reader = serialize::read_message(&words[..], ReaderOptions::new()).unwrap();
+    let root: any_pointer::Reader = reader.get_root().unwrap();
+    let list: any_pointer_list::Reader = root.get_as().unwrap();
+    let error = list.get(0).get_pipelined_cap(&[]).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::CannotReadACapabilityWithoutACapTable);
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::{any_pointer, word, ErrorKind, Word};
+
+/// A struct with three pointer fields: capability 2, a list holding capabilities 0 and 2, and
+/// capability 0 again. Capability 1 is not referenced.
+const SEGMENT: &[Word] = &[
+    // Root pointer: struct with no data and three pointers.
+    word(0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00),
+    // Capability 2.
+    word(0x03, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00),
+    // List of two pointers, starting after the last field.
+    word(0x05, 0x00, 0x00, 0x00, 0x16, 0x00, 0x00, 0x00),
+    // Capability 0.
+    word(0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+    // The list: capabilities 0 and 2.
+    word(0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+    word(0x03, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00),
+];
+
+fn walk(options: ReaderOptions) -> capnp::Result<Vec<u32>> {
+    let segments = [Word::words_to_bytes(SEGMENT)];
+    let message = message::Reader::new(message::SegmentArray::new(&segments), options);
+    capnp::cap_walk(message.get_root::<any_pointer::Reader>()?)
+}
+
+#[test]
+fn first_encounter_order_without_duplicates() {
+    assert_eq!(walk(ReaderOptions::new()).unwrap(), [2, 0]);
+}
+
+#[test]
+fn respects_limits() {
+    let mut options = ReaderOptions::new();
+    options.nesting_limit(2);
+    assert_eq!(
+        walk(options).unwrap_err().kind,
+        ErrorKind::MessageIsTooDeeplyNested
+    );
+
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(Some(3));
+    assert_eq!(
+        walk(options).unwrap_err().kind,
+        ErrorKind::ReadLimitExceeded
+    );
+}
+
+#[test]
+fn no_capabilities() {
+    let mut builder = message::Builder::new_default();
+    builder.set_root("no caps here").unwrap();
+    let root: any_pointer::Reader = builder.get_root_as_reader().unwrap();
+    assert!(capnp::cap_walk(root).unwrap().is_empty());
+
+    let builder = message::Builder::new_default();
+    let root: any_pointer::Reader = builder.get_root_as_reader().unwrap();
+    assert!(capnp::cap_walk(root).unwrap().is_empty());
+}
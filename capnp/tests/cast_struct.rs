@@ -0,0 +1,223 @@
+//! Casts between two hand-written struct types standing in for two revisions of the same schema:
+//!
+//! ```capnp
+//! struct Old { id @0 :UInt32; name @1 :Text; }
+//! struct New { id @0 :UInt32; name @1 :Text; score @2 :UInt64; tag @3 :Text; }
+//! ```
+
+#![cfg(feature = "alloc")]
+
+use capnp::private::layout::{
+    PointerBuilder, PointerReader, StructBuilder, StructReader, StructSize,
+};
+use capnp::traits::{self, HasStructSize, IntoInternalStructBuilder, IntoInternalStructReader};
+use capnp::{any_pointer, message, ErrorKind, Result};
+
+macro_rules! mock_struct {
+    ($name:ident, $data:expr, $pointers:expr) => {
+        pub mod $name {
+            use super::*;
+
+            pub struct Owned;
+
+            impl capnp::introspect::Introspect for Owned {
+                fn introspect() -> capnp::introspect::Type {
+                    capnp::introspect::TypeVariant::Void.into()
+                }
+            }
+
+            impl traits::OwnedStruct for Owned {
+                type Reader<'a> = Reader<'a>;
+                type Builder<'a> = Builder<'a>;
+            }
+
+            #[derive(Clone, Copy)]
+            pub struct Reader<'a> {
+                pub reader: StructReader<'a>,
+            }
+
+            impl<'a> From<StructReader<'a>> for Reader<'a> {
+                fn from(reader: StructReader<'a>) -> Self {
+                    Self { reader }
+                }
+            }
+
+            impl<'a> IntoInternalStructReader<'a> for Reader<'a> {
+                fn into_internal_struct_reader(self) -> StructReader<'a> {
+                    self.reader
+                }
+            }
+
+            impl<'a> traits::FromPointerReader<'a> for Reader<'a> {
+                fn get_from_pointer(
+                    reader: &PointerReader<'a>,
+                    default: Option<&'a [capnp::Word]>,
+                ) -> Result<Self> {
+                    Ok(reader.get_struct(default)?.into())
+                }
+            }
+
+            impl traits::SetPointerBuilder for Reader<'_> {
+                fn set_pointer_builder(
+                    mut pointer: PointerBuilder<'_>,
+                    value: Self,
+                    canonicalize: bool,
+                ) -> Result<()> {
+                    pointer.set_struct(&value.reader, canonicalize)
+                }
+            }
+
+            pub struct Builder<'a> {
+                pub builder: StructBuilder<'a>,
+            }
+
+            impl HasStructSize for Builder<'_> {
+                const STRUCT_SIZE: StructSize = StructSize {
+                    data: $data,
+                    pointers: $pointers,
+                };
+            }
+
+            impl<'a> From<StructBuilder<'a>> for Builder<'a> {
+                fn from(builder: StructBuilder<'a>) -> Self {
+                    Self { builder }
+                }
+            }
+
+            impl<'a> IntoInternalStructBuilder<'a> for Builder<'a> {
+                fn into_internal_struct_builder(self) -> StructBuilder<'a> {
+                    self.builder
+                }
+            }
+
+            impl<'a> traits::FromPointerBuilder<'a> for Builder<'a> {
+                fn init_pointer(builder: PointerBuilder<'a>, _size: u32) -> Self {
+                    builder
+                        .init_struct(<Self as HasStructSize>::STRUCT_SIZE)
+                        .into()
+                }
+                fn get_from_pointer(
+                    builder: PointerBuilder<'a>,
+                    default: Option<&'a [capnp::Word]>,
+                ) -> Result<Self> {
+                    Ok(builder
+                        .get_struct(<Self as HasStructSize>::STRUCT_SIZE, default)?
+                        .into())
+                }
+            }
+
+            impl Reader<'_> {
+                pub fn get_id(&self) -> u32 {
+                    self.reader.get_data_field::<u32>(0)
+                }
+                pub fn get_name(&self) -> Result<capnp::text::Reader<'_>> {
+                    self.reader.get_pointer_field(0).get_text(None)
+                }
+            }
+
+            impl Builder<'_> {
+                pub fn set_id(&mut self, value: u32) {
+                    self.builder.set_data_field::<u32>(0, value)
+                }
+                pub fn set_name(&mut self, value: &str) {
+                    self.builder
+                        .reborrow()
+                        .get_pointer_field(0)
+                        .set_text(value.into())
+                }
+            }
+        }
+    };
+}
+
+mock_struct!(old, 1, 1);
+mock_struct!(new, 2, 2);
+
+impl new::Reader<'_> {
+    fn get_score(&self) -> u64 {
+        self.reader.get_data_field::<u64>(1)
+    }
+    fn get_tag(&self) -> Result<capnp::text::Reader<'_>> {
+        self.reader.get_pointer_field(1).get_text(None)
+    }
+}
+
+impl new::Builder<'_> {
+    fn set_score(&mut self, value: u64) {
+        self.builder.set_data_field::<u64>(1, value)
+    }
+    fn set_tag(&mut self, value: &str) {
+        self.builder
+            .reborrow()
+            .get_pointer_field(1)
+            .set_text(value.into())
+    }
+}
+
+#[test]
+fn old_reader_as_new() {
+    let mut message = message::Builder::new_default();
+    {
+        let mut old: old::Builder = message.init_root::<any_pointer::Builder>().init_as();
+        old.set_id(7);
+        old.set_name("seven");
+    }
+    let root: any_pointer::Reader = message.get_root_as_reader().unwrap();
+    let old: old::Reader = root.get_as().unwrap();
+
+    // Fields the old struct lacks read as their defaults.
+    let new = traits::cast_struct::<old::Owned, new::Owned>(old);
+    assert_eq!(new.get_id(), 7);
+    assert_eq!(new.get_name().unwrap(), "seven");
+    assert_eq!(new.get_score(), 0);
+    assert!(new.get_tag().unwrap().is_empty());
+}
+
+#[test]
+fn new_reader_as_old() {
+    let mut message = message::Builder::new_default();
+    {
+        let mut new: new::Builder = message.init_root::<any_pointer::Builder>().init_as();
+        new.set_id(7);
+        new.set_name("seven");
+        new.set_score(99);
+        new.set_tag("extra");
+    }
+    let root: any_pointer::Reader = message.get_root_as_reader().unwrap();
+    let new: new::Reader = root.get_as().unwrap();
+
+    // Fields the old type doesn't know about are ignored, and survive a round trip.
+    let old = traits::cast_struct::<new::Owned, old::Owned>(new);
+    assert_eq!(old.get_id(), 7);
+    assert_eq!(old.get_name().unwrap(), "seven");
+    let again = traits::cast_struct::<old::Owned, new::Owned>(old);
+    assert_eq!(again.get_score(), 99);
+    assert_eq!(again.get_tag().unwrap(), "extra");
+}
+
+#[test]
+fn cast_builders() {
+    let mut message = message::Builder::new_default();
+    let new: new::Builder = message.init_root::<any_pointer::Builder>().init_as();
+
+    // Narrowing a builder is fine.
+    let mut old = traits::cast_struct_builder::<new::Owned, old::Owned>(new).unwrap();
+    old.set_id(3);
+
+    // Widening back works, since the underlying struct is still the larger size.
+    let mut new = traits::cast_struct_builder::<old::Owned, new::Owned>(old).unwrap();
+    new.set_score(4);
+    let reader = new::Reader::from(new.builder.into_reader());
+    assert_eq!((reader.get_id(), reader.get_score()), (3, 4));
+
+    // But a struct allocated at the smaller size cannot be widened.
+    let mut message = message::Builder::new_default();
+    let old: old::Builder = message.init_root::<any_pointer::Builder>().init_as();
+    assert_eq!(
+        traits::cast_struct_builder::<old::Owned, new::Owned>(old)
+            .err()
+            .unwrap()
+            .kind,
+        ErrorKind::Failed
+    );
+}
@@ -0,0 +1,95 @@
+//! Helpers shared by the tests that put capabilities in messages.
+
+use capnp::capability::{Promise, RemotePromise, Request};
+use capnp::private::capability::{
+    ClientHook, ParamsHook, PipelineHook, PipelineOp, RequestHook, ResultsHook,
+};
+use capnp::traits::ImbueMut;
+use capnp::{any_pointer, message, Error, MessageSize};
+
+fn unimplemented() -> Error {
+    Error::unimplemented("test capabilities cannot be called".into())
+}
+
+/// A capability that can only be told apart from others by `get_ptr()`, which returns the number
+/// it was made with. Calls to it fail with `Unimplemented`.
+pub struct Hook(pub usize);
+
+impl ClientHook for Hook {
+    fn add_ref(&self) -> Box<dyn ClientHook> {
+        Box::new(Hook(self.0))
+    }
+    fn new_call(
+        &self,
+        _interface_id: u64,
+        _method_id: u16,
+        _size_hint: Option<MessageSize>,
+    ) -> Request<any_pointer::Owned, any_pointer::Owned> {
+        Request::new(Box::new(FailingRequest {
+            message: message::Builder::new_default(),
+            cap_table: Vec::new(),
+        }))
+    }
+    fn call(
+        &self,
+        _interface_id: u64,
+        _method_id: u16,
+        _params: Box<dyn ParamsHook>,
+        _results: Box<dyn ResultsHook>,
+    ) -> Promise<(), Error> {
+        Promise::err(unimplemented())
+    }
+    fn get_brand(&self) -> usize {
+        0
+    }
+    fn get_ptr(&self) -> usize {
+        self.0
+    }
+    fn get_resolved(&self) -> Option<Box<dyn ClientHook>> {
+        None
+    }
+    fn when_more_resolved(&self) -> Option<Promise<Box<dyn ClientHook>, Error>> {
+        None
+    }
+    fn when_resolved(&self) -> Promise<(), Error> {
+        Promise::ok(())
+    }
+}
+
+/// A request whose parameters can be filled in, but which fails when sent.
+struct FailingRequest {
+    message: message::Builder<message::HeapAllocator>,
+    cap_table: Vec<Option<Box<dyn ClientHook>>>,
+}
+
+impl RequestHook for FailingRequest {
+    fn get(&mut self) -> any_pointer::Builder<'_> {
+        let mut params: any_pointer::Builder = self.message.init_root();
+        params.imbue_mut(&mut self.cap_table);
+        params
+    }
+    fn get_brand(&self) -> usize {
+        0
+    }
+    fn send(self: Box<Self>) -> RemotePromise<any_pointer::Owned> {
+        RemotePromise {
+            promise: Promise::err(unimplemented()),
+            pipeline: any_pointer::Pipeline::new(Box::new(FailingPipeline)),
+        }
+    }
+    fn tail_send(self: Box<Self>) -> Option<(u32, Promise<(), Error>, Box<dyn PipelineHook>)> {
+        None
+    }
+}
+
+/// The pipeline of a `FailingRequest`, whose capabilities fail like every `Hook`.
+struct FailingPipeline;
+
+impl PipelineHook for FailingPipeline {
+    fn add_ref(&self) -> Box<dyn PipelineHook> {
+        Box::new(FailingPipeline)
+    }
+    fn get_pipelined_cap(&self, _ops: &[PipelineOp]) -> Box<dyn ClientHook> {
+        Box::new(Hook(0))
+    }
+}
@@ -0,0 +1,118 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use capnp::message::{self, AllocationStrategy, HeapAllocator};
+use capnp::private::layout::CapTable;
+use capnp::schema_capnp::node;
+use capnp::traits::{Imbue, ImbueMut};
+use capnp::{any_pointer, any_pointer_list};
+
+use common::Hook;
+
+/// A builder that puts every allocation in a segment of its own.
+fn fragmenting_builder() -> message::Builder<HeapAllocator> {
+    message::Builder::new(
+        HeapAllocator::new()
+            .first_segment_words(1)
+            .allocation_strategy(AllocationStrategy::FixedSize),
+    )
+}
+
+#[test]
+fn shrink_to_fit_coalesces_segments() {
+    let mut builder = fragmenting_builder();
+    {
+        let mut root: node::Builder = builder.init_root();
+        root.set_display_name("compact.capnp".into());
+        let mut nested = root.init_nested_nodes(8);
+        for idx in 0..8 {
+            nested
+                .reborrow()
+                .get(idx)
+                .set_name(format!("nested{idx}").as_str().into());
+        }
+    }
+    let before = builder.get_segments_for_output().len();
+    let size_before = builder
+        .get_root_as_reader::<node::Reader>()
+        .unwrap()
+        .total_size()
+        .unwrap();
+
+    builder.shrink_to_fit().unwrap();
+    // One segment each for the root pointer, the struct, its name, the list and the 8 names.
+    assert_eq!(before, 12);
+    assert_eq!(builder.get_segments_for_output().len(), 1);
+
+    let root: node::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(root.get_display_name().unwrap(), "compact.capnp");
+    let nested = root.get_nested_nodes().unwrap();
+    assert_eq!(nested.len(), 8);
+    for (idx, nested) in nested.iter().enumerate() {
+        assert_eq!(nested.get_name().unwrap(), format!("nested{idx}").as_str());
+    }
+    // Far pointers and their landing pads are gone, but the content is the same size.
+    let size = root.total_size().unwrap();
+    assert_eq!(size.word_count, size_before.word_count);
+    assert_eq!(
+        builder.get_segments_for_output()[0].len(),
+        (size.word_count as usize + 1) * 8
+    );
+
+    // Shrinking again changes nothing.
+    let words = capnp::serialize::write_message_to_words(&builder);
+    builder.shrink_to_fit().unwrap();
+    assert_eq!(capnp::serialize::write_message_to_words(&builder), words);
+}
+
+#[test]
+fn shrink_to_fit_keeps_cap_indices() {
+    const CAP_COUNT: u32 = 20;
+    let mut caps = CapTable::new();
+    let mut builder = fragmenting_builder();
+    {
+        let mut root: any_pointer::Builder = builder.init_root();
+        root.imbue_mut(&mut caps);
+        let mut list: any_pointer_list::Builder = root.initn_as(CAP_COUNT);
+        // A capability pointer next to some content, in the reverse of cap table order.
+        for idx in (0..CAP_COUNT).rev() {
+            let mut element = list.reborrow().get(idx);
+            if idx % 2 == 0 {
+                element.set_as_capability(Box::new(Hook(idx as usize)));
+            } else {
+                element.set_as(format!("text{idx}").as_str()).unwrap();
+            }
+        }
+    }
+    let before = builder.get_segments_for_output().len();
+
+    builder.shrink_to_fit().unwrap();
+    // One segment each for the root pointer, the list and the 10 texts.
+    assert_eq!(before, 12);
+    assert_eq!(builder.get_segments_for_output().len(), 1);
+
+    let mut root: any_pointer::Reader = builder.get_root_as_reader().unwrap();
+    root.imbue(&caps);
+    let list: any_pointer_list::Reader = root.get_as().unwrap();
+    for idx in 0..CAP_COUNT {
+        let element = list.get(idx);
+        if idx % 2 == 0 {
+            let cap = element.get_pipelined_cap(&[]).unwrap();
+            assert_eq!(cap.get_ptr(), idx as usize);
+        } else {
+            let text: capnp::text::Reader = element.get_as().unwrap();
+            assert_eq!(text, format!("text{idx}").as_str());
+        }
+    }
+    assert_eq!(caps.len(), (CAP_COUNT / 2) as usize);
+}
+
+#[test]
+fn shrink_to_fit_empty_message() {
+    let mut builder = message::Builder::new_default();
+    builder.shrink_to_fit().unwrap();
+    assert_eq!(builder.get_segments_for_output().len(), 1);
+    let root: any_pointer::Reader = builder.get_root_as_reader().unwrap();
+    assert!(root.is_null());
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, CopyOptions};
+use capnp::schema_capnp::code_generator_request;
+use capnp::{any_pointer, text};
+
+const INVALID_UTF8: &[u8] = b"ab\xff\xfecd";
+
+fn request_with_filename(filename: &[u8]) -> message::Builder<message::HeapAllocator> {
+    let mut message = message::Builder::new_default();
+    {
+        let root: code_generator_request::Builder = message.init_root();
+        let mut files = root.init_requested_files(2);
+        files.reborrow().get(0).set_filename("ok.capnp".into());
+        files.reborrow().get(1).set_filename(filename.into());
+    }
+    message
+}
+
+fn copied_filename(message: &message::Builder<message::HeapAllocator>) -> Vec<u8> {
+    let root: code_generator_request::Reader = message.get_root_as_reader().unwrap();
+    let files = root.get_requested_files().unwrap();
+    files.get(1).get_filename().unwrap().as_bytes().to_vec()
+}
+
+#[test]
+fn copy_is_byte_faithful_by_default() {
+    let original = request_with_filename(INVALID_UTF8);
+    let root: code_generator_request::Reader = original.get_root_as_reader().unwrap();
+
+    let mut copy = message::Builder::new_default();
+    copy.set_root(root).unwrap();
+    assert_eq!(copied_filename(&copy), INVALID_UTF8);
+
+    let mut copy = message::Builder::new_default();
+    copy.set_root_with_options(root, CopyOptions::new())
+        .unwrap();
+    assert_eq!(copied_filename(&copy), INVALID_UTF8);
+
+    let mut copy = message::Builder::new_default();
+    let text: text::Reader = INVALID_UTF8.into();
+    copy.set_root_with_options(text, CopyOptions::new())
+        .unwrap();
+    let copied: text::Reader = copy.get_root_as_reader().unwrap();
+    assert_eq!(copied.as_bytes(), INVALID_UTF8);
+}
+
+#[test]
+fn copy_with_validate_utf8() {
+    let mut options = CopyOptions::new();
+    options.validate_utf8(true);
+
+    let original = request_with_filename(INVALID_UTF8);
+    let root: code_generator_request::Reader = original.get_root_as_reader().unwrap();
+    let mut copy = message::Builder::new_default();
+    let err = copy.set_root_with_options(root, options).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        capnp::ErrorKind::TextContainsNonUtf8Data(_)
+    ));
+    // Nothing was copied.
+    assert!(copy
+        .get_root_as_reader::<any_pointer::Reader>()
+        .unwrap()
+        .is_null());
+
+    let text: text::Reader = INVALID_UTF8.into();
+    assert!(copy.set_root_with_options(text, options).is_err());
+
+    let valid = request_with_filename(b"valid.capnp");
+    let root: code_generator_request::Reader = valid.get_root_as_reader().unwrap();
+    copy.set_root_with_options(root, options).unwrap();
+    assert_eq!(copied_filename(&copy), b"valid.capnp");
+}
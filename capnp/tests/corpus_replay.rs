@@ -0,0 +1,261 @@
+#![cfg(feature = "alloc")]
+
+//! Replays every file in `tests/corpus/` through each stage of decoding, so that inputs found
+//! by fuzzing stay fixed. Each file holds a message in the standard, unpacked, encoding. A file
+//! passes if no stage panics or hangs, and every error is one that malformed input may
+//! legitimately cause. Files whose names start with `valid_` must also get through every stage
+//! without any error.
+//!
+//! To add a regression case, drop the input that triggered the bug into `tests/corpus/` with a
+//! name that says what is wrong with it.
+
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use capnp::message::{self, CopyOptions, ReaderOptions, TraversalLimits};
+use capnp::policy::{Policy, Validator, ViolationKind};
+use capnp::{any_pointer, overlap, serialize, serialize_packed, ErrorKind};
+
+/// The budget of each stage, which bounds the work done on any input.
+const LIMITS: TraversalLimits = TraversalLimits {
+    words: 1 << 20,
+    max_ops: 1 << 16,
+};
+
+/// How long a file may take before it is reported as hanging. The limits above should keep
+/// every stage far below this.
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Stage {
+    ReadMessage,
+    ReadPackedMessage,
+    DeepCopy,
+    Canonicalize,
+    CheckAll,
+}
+
+impl Stage {
+    const ALL: [Self; 5] = [
+        Self::ReadMessage,
+        Self::ReadPackedMessage,
+        Self::DeepCopy,
+        Self::Canonicalize,
+        Self::CheckAll,
+    ];
+}
+
+/// What went wrong with a file.
+enum Problem {
+    Panicked(String),
+    Hung,
+    UnexpectedErrorKind(capnp::Error),
+    /// A `valid_` file failed.
+    Rejected(capnp::Error),
+}
+
+struct Failure {
+    file: PathBuf,
+    stage: Stage,
+    problem: Problem,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {:?}: ", self.file.display(), self.stage)?;
+        match &self.problem {
+            Problem::Panicked(message) => write!(f, "panicked: {message}"),
+            Problem::Hung => write!(f, "did not finish within {TIMEOUT:?}"),
+            Problem::UnexpectedErrorKind(error) => write!(f, "unexpected error kind: {error}"),
+            Problem::Rejected(error) => write!(f, "rejected a valid message: {error}"),
+        }
+    }
+}
+
+/// Malformed input must only fail in the ways that Cap'n Proto classes as `Failed`, which here
+/// includes every specific decoding error, or as `Overloaded`, when a limit is exceeded. The
+/// other classes would mislead a caller into retrying elsewhere or giving up on a feature.
+fn is_expected(kind: ErrorKind) -> bool {
+    !matches!(kind, ErrorKind::Disconnected | ErrorKind::Unimplemented)
+}
+
+fn reader_options() -> ReaderOptions {
+    let mut options = ReaderOptions::new();
+    options.traversal_limits(LIMITS);
+    options
+}
+
+fn read(bytes: &[u8]) -> capnp::Result<message::Reader<serialize::OwnedSegments>> {
+    serialize::read_message(bytes, reader_options())
+}
+
+fn check_all(bytes: &[u8]) -> capnp::Result<()> {
+    let message = read(bytes)?;
+    let root: any_pointer::Reader = message.get_root()?;
+    root.target_size()?;
+    message.is_canonical()?;
+    overlap::check_no_overlap(root)?;
+    capnp::cap_walk(root)?;
+    if let Err(violations) = Validator::new(Policy::new()).validate(root) {
+        for violation in violations {
+            if let ViolationKind::Malformed(error) = violation.kind {
+                return Err(error);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs a single stage. Each stage reads the message afresh, so that it gets its own budget.
+fn run(stage: Stage, bytes: &[u8]) -> capnp::Result<()> {
+    match stage {
+        Stage::ReadMessage => {
+            let message = read(bytes)?;
+            message.get_root::<any_pointer::Reader>()?.target_size()?;
+        }
+        Stage::ReadPackedMessage => {
+            let message = serialize_packed::read_message(&pack(bytes)[..], reader_options())?;
+            message.get_root::<any_pointer::Reader>()?.target_size()?;
+        }
+        Stage::DeepCopy => {
+            let message = read(bytes)?;
+            let mut copy = message::Builder::new_default();
+            let mut options = CopyOptions::new();
+            options.traversal_limits(Some(LIMITS));
+            copy.set_root_with_options(message.get_root::<any_pointer::Reader>()?, options)?;
+        }
+        Stage::Canonicalize => {
+            read(bytes)?.canonicalize_with_limits(LIMITS)?;
+        }
+        Stage::CheckAll => check_all(bytes)?,
+    }
+    Ok(())
+}
+
+/// Packs `bytes` without run-length encoding, which is a valid if unusually long packing. If the
+/// length is not a whole number of words, `bytes` has no packed form, and is returned as is.
+fn pack(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() % 8 != 0 {
+        return bytes.to_vec();
+    }
+    let mut packed = Vec::new();
+    for word in bytes.chunks(8) {
+        let tag = word
+            .iter()
+            .enumerate()
+            .fold(0u8, |tag, (idx, &byte)| tag | (u8::from(byte != 0) << idx));
+        packed.push(tag);
+        packed.extend(word.iter().filter(|&&byte| byte != 0));
+        if tag == 0x00 || tag == 0xff {
+            // The number of words in the run that follows.
+            packed.push(0);
+        }
+    }
+    packed
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => (*message).into(),
+            Err(_) => "(non-string payload)".into(),
+        },
+    }
+}
+
+/// Runs every stage on the file, and returns the problems found.
+fn replay(file: &Path) -> Vec<Failure> {
+    let bytes = std::fs::read(file).unwrap();
+    let valid = file
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("valid_"));
+
+    // Run on another thread, to notice hangs. The stage being run is shared so that a hang can
+    // be attributed to it.
+    let current = Arc::new(Mutex::new(Stage::ReadMessage));
+    let (sender, receiver) = mpsc::channel();
+    {
+        let current = current.clone();
+        let file = file.to_path_buf();
+        thread::spawn(move || {
+            let mut failures = Vec::new();
+            for stage in Stage::ALL {
+                *current.lock().unwrap() = stage;
+                let problem = match panic::catch_unwind(AssertUnwindSafe(|| run(stage, &bytes))) {
+                    Ok(Ok(())) => continue,
+                    Ok(Err(error)) if !is_expected(error.kind) => {
+                        Problem::UnexpectedErrorKind(error)
+                    }
+                    Ok(Err(error)) if valid => Problem::Rejected(error),
+                    Ok(Err(_)) => continue,
+                    Err(payload) => Problem::Panicked(panic_message(payload)),
+                };
+                failures.push(Failure {
+                    file: file.clone(),
+                    stage,
+                    problem,
+                });
+            }
+            let _ = sender.send(failures);
+        });
+    }
+    match receiver.recv_timeout(TIMEOUT) {
+        Ok(failures) => failures,
+        Err(_) => vec![Failure {
+            file: file.to_path_buf(),
+            stage: *current.lock().unwrap_or_else(|e| e.into_inner()),
+            problem: Problem::Hung,
+        }],
+    }
+}
+
+fn corpus_files() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn replay_corpus() {
+    let files = corpus_files();
+    assert!(!files.is_empty());
+    let failures: Vec<Failure> = files.iter().flat_map(|file| replay(file)).collect();
+    for failure in &failures {
+        eprintln!("{failure}");
+    }
+    assert!(
+        failures.is_empty(),
+        "{} of {} stages failed; see above",
+        failures.len(),
+        files.len() * Stage::ALL.len()
+    );
+}
+
+#[test]
+fn failures_are_reported() {
+    let dir = std::env::temp_dir().join(format!("capnp-corpus-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("valid_but_truncated.bin");
+    std::fs::write(&file, [0, 0, 0, 0, 1, 0, 0, 0]).unwrap();
+    let failures = replay(&file);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let stages: Vec<Stage> = failures.iter().map(|failure| failure.stage).collect();
+    assert_eq!(stages, Stage::ALL);
+    let report = failures[0].to_string();
+    assert!(
+        report.contains("valid_but_truncated.bin: ReadMessage: rejected"),
+        "{report}"
+    );
+}
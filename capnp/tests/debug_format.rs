@@ -0,0 +1,186 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::{node, ElementSize};
+use capnp::{
+    any_pointer, data, enum_list, primitive_list, struct_list, text, text_list, word, MessageSize,
+    Word,
+};
+
+#[test]
+fn text() {
+    assert_eq!(
+        format!("{:?}", text::Reader::from_str("a\"b\n")),
+        r#""a\"b\n""#
+    );
+
+    let long = "a".repeat(100);
+    assert_eq!(
+        format!("{:?}", text::Reader::from_str(&long)),
+        format!("\"{}\".. (100 bytes)", "a".repeat(64))
+    );
+    // Truncation counts characters, not bytes.
+    let long = "\u{e9}".repeat(70);
+    assert_eq!(
+        format!("{:?}", text::Reader::from_str(&long)),
+        format!("\"{}\".. (140 bytes)", "\u{e9}".repeat(64))
+    );
+    let exact = "b".repeat(64);
+    assert_eq!(
+        format!("{:?}", text::Reader::from_str(&exact)),
+        format!("{exact:?}")
+    );
+
+    assert_eq!(
+        format!("{:?}", text::Reader(b"\xff\x00")),
+        "<invalid utf-8: [255, 0]>"
+    );
+    let invalid = [0xff; 65];
+    assert_eq!(
+        format!("{:?}", text::Reader(&invalid)),
+        format!("<invalid utf-8: {:?}.. (65 bytes)>", [0xff; 64])
+    );
+}
+
+#[test]
+fn data() {
+    assert_eq!(format!("{:?}", data::Hex(&[])), r#"0x"""#);
+    assert_eq!(
+        format!("{:?}", data::Hex(&[0x00, 0xab, 0x10])),
+        r#"0x"00ab10""#
+    );
+    let long = [0xcd; 100];
+    assert_eq!(
+        format!("{:?}", data::Hex(&long)),
+        format!("0x\"{}\".. (100 bytes)", "cd".repeat(64))
+    );
+}
+
+#[test]
+fn lists() {
+    let mut builder = message::Builder::new_default();
+    let mut root: primitive_list::Builder<u16> = builder.initn_root(10);
+    for idx in 0..10 {
+        root.set(idx, idx as u16 * 3);
+    }
+    let list: primitive_list::Reader<u16> = builder.get_root_as_reader().unwrap();
+    assert_eq!(
+        format!("{list:?}"),
+        "primitive_list::Reader { len: 10, head: [0, 3, 6, 9, 12, 15, 18, 21, ..] }"
+    );
+    builder.initn_root::<primitive_list::Builder<bool>>(0);
+    let list: primitive_list::Reader<bool> = builder.get_root_as_reader().unwrap();
+    assert_eq!(
+        format!("{list:?}"),
+        "primitive_list::Reader { len: 0, head: [] }"
+    );
+
+    // An enum list prints the names of its enumerants, and the values of anything else.
+    let mut root: primitive_list::Builder<u16> = builder.initn_root(3);
+    root.set(0, 1);
+    root.set(1, 42);
+    root.set(2, 7);
+    let list: enum_list::Reader<ElementSize> = builder.get_root_as_reader().unwrap();
+    assert_eq!(
+        format!("{list:?}"),
+        "enum_list::Reader { len: 3, head: [bit, 42, inlineComposite] }"
+    );
+
+    // Lists of pointers print only their length.
+    let mut root: text_list::Builder = builder.initn_root(2);
+    root.set(0, "zero".into());
+    let list: text_list::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(format!("{list:?}"), "text_list::Reader { len: 2 }");
+    builder.initn_root::<struct_list::Builder<node::Owned>>(3);
+    let list: struct_list::Reader<node::Owned> = builder.get_root_as_reader().unwrap();
+    assert_eq!(format!("{list:?}"), "struct_list::Reader { len: 3 }");
+}
+
+#[test]
+fn any_pointer() {
+    let mut builder = message::Builder::new_default();
+    let root: any_pointer::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(format!("{root:?}"), "any_pointer::Reader(null)");
+    builder.init_root::<node::Builder>();
+    let root: any_pointer::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(format!("{root:?}"), "any_pointer::Reader(struct)");
+    builder.set_root("text").unwrap();
+    let root: any_pointer::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(format!("{root:?}"), "any_pointer::Reader(list)");
+
+    // With no room next to the root pointer, the root is a far pointer.
+    let mut builder = message::Builder::new(message::HeapAllocator::new().first_segment_words(1));
+    builder.init_root::<node::Builder>();
+    let root: any_pointer::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(format!("{root:?}"), "any_pointer::Reader(far)");
+}
+
+#[test]
+fn formatting_reads_nothing() {
+    // The root points at a struct of one data word, ten words past the end of the segment, which
+    // cannot be read at all.
+    let segment: [Word; 1] = [word(0x28, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00)];
+    let segments = [Word::words_to_bytes(&segment)];
+    let message = message::Reader::new(message::SegmentArray::new(&segments), ReaderOptions::new());
+    let root: any_pointer::Reader = message.get_root().unwrap();
+    assert!(root.get_as::<node::Reader>().is_err());
+    assert_eq!(format!("{root:?}"), "any_pointer::Reader(struct)");
+
+    let mut builder = message::Builder::new_default();
+    let mut list: text_list::Builder = builder.initn_root(2);
+    list.set(0, "a text long enough to cost more than the limit".into());
+    let segments = builder.get_segments_for_output();
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(Some(4));
+    let message = message::Reader::new(message::SegmentArray::new(&segments), options);
+    let list: text_list::Reader = message.get_root().unwrap();
+    let remaining = message.remaining_traversal_limit_in_words();
+    for _ in 0..10 {
+        assert_eq!(format!("{list:?}"), "text_list::Reader { len: 2 }");
+    }
+    assert_eq!(message.remaining_traversal_limit_in_words(), remaining);
+}
+
+#[test]
+fn structs_still_print_everything() {
+    let mut builder = message::Builder::new_default();
+    let long = "x".repeat(100);
+    builder
+        .init_root::<node::Builder>()
+        .set_display_name(long.as_str().into());
+    let root: node::Reader = builder.get_root_as_reader().unwrap();
+    assert!(format!("{root:?}").contains(&long));
+}
+
+#[test]
+fn sizes_options_and_segments() {
+    let size = MessageSize {
+        word_count: 3,
+        cap_count: 1,
+    };
+    assert_eq!(
+        format!("{size:?}"),
+        "MessageSize { word_count: 3, cap_count: 1 }"
+    );
+    let options = format!("{:?}", ReaderOptions::new());
+    assert!(
+        options.starts_with("ReaderOptions { traversal_limit_in_words: Some("),
+        "{options}"
+    );
+    assert!(options.contains("nesting_limit: 64"), "{options}");
+
+    let mut builder = message::Builder::new(message::HeapAllocator::new().first_segment_words(1));
+    builder
+        .init_root::<node::Builder>()
+        .set_display_name("debug_format.capnp".into());
+    let segments = builder.get_segments_for_output();
+    let lengths: Vec<usize> = segments.iter().map(|s| s.len() / 8).collect();
+    assert!(lengths.len() > 1);
+    assert_eq!(
+        format!("{segments:?}"),
+        format!(
+            "OutputSegments {{ len: {}, words: {lengths:?} }}",
+            lengths.len()
+        )
+    );
+}
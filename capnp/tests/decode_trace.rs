@@ -0,0 +1,151 @@
+#![cfg(all(feature = "tracing-hooks", feature = "alloc"))]
+
+use std::cell::RefCell;
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::code_generator_request::requested_file;
+use capnp::{serialize, DecodeCheck, DecodeEvent};
+
+thread_local! {
+    // The hook is process-wide, but tests run on separate threads, so each test
+    // only sees its own events.
+    static EVENTS: RefCell<Vec<DecodeEvent>> = const { RefCell::new(Vec::new()) };
+}
+
+fn collect(event: &DecodeEvent) {
+    EVENTS.with(|events| events.borrow_mut().push(*event));
+}
+
+fn take_events() -> Vec<DecodeEvent> {
+    EVENTS.with(|events| events.take())
+}
+
+#[test]
+fn far_pointer_to_out_of_bounds_struct() {
+    capnp::set_decode_trace_hook(collect);
+    let segment0: &[capnp::Word] = &[
+        // Far pointer to word 0 of segment 1.
+        capnp::word(0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00),
+    ];
+    let segment1: &[capnp::Word] = &[
+        // Landing pad: struct pointer with five data words, but the segment ends here.
+        capnp::word(0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00),
+    ];
+    let segments = &[
+        capnp::Word::words_to_bytes(segment0),
+        capnp::Word::words_to_bytes(segment1),
+    ];
+    let message = message::Reader::new(message::SegmentArray::new(segments), Default::default());
+    assert!(message.get_root::<requested_file::Reader>().is_err());
+
+    assert_eq!(
+        take_events(),
+        [
+            DecodeEvent {
+                segment_id: Some(1),
+                word_offset: Some(0),
+                check: DecodeCheck::FarPointerFollowed,
+            },
+            DecodeEvent {
+                segment_id: Some(1),
+                word_offset: Some(1),
+                check: DecodeCheck::OutOfBounds,
+            },
+        ]
+    );
+}
+
+#[test]
+fn traversal_limit_exceeded() {
+    capnp::set_decode_trace_hook(collect);
+    let mut builder = message::Builder::new_default();
+    builder.set_root("more than one word of text").unwrap();
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(Some(2));
+    let message = message::Reader::new(builder.get_segments_for_output(), options);
+    assert!(message.get_root::<capnp::text::Reader>().is_err());
+
+    assert_eq!(
+        take_events(),
+        [DecodeEvent {
+            segment_id: Some(0),
+            word_offset: Some(1),
+            check: DecodeCheck::TraversalLimit,
+        }]
+    );
+}
+
+#[test]
+fn segment_table_rejected() {
+    capnp::set_decode_trace_hook(collect);
+    // Claims 1000 segments.
+    let words: &[capnp::Word] = &[capnp::word(0xe7, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00)];
+    let mut bytes = capnp::Word::words_to_bytes(words);
+    assert!(serialize::read_message_from_flat_slice(&mut bytes, ReaderOptions::new()).is_err());
+
+    assert_eq!(
+        take_events(),
+        [DecodeEvent {
+            segment_id: None,
+            word_offset: None,
+            check: DecodeCheck::SegmentTable,
+        }]
+    );
+}
+
+#[test]
+fn valid_message_has_no_failure_events() {
+    capnp::set_decode_trace_hook(collect);
+    let mut builder = message::Builder::new_default();
+    builder.set_root("hello").unwrap();
+    let words = serialize::write_message_to_words(&builder);
+    let message =
+        serialize::read_message_from_flat_slice(&mut &words[..], ReaderOptions::new()).unwrap();
+    assert_eq!(message.get_root::<capnp::text::Reader>().unwrap(), "hello");
+    assert!(take_events().is_empty());
+}
+
+#[test]
+fn legacy_segment_table_padding() {
+    capnp::set_decode_trace_hook(collect);
+    // Three segments, framed with the unneeded padding word that some old writers emit.
+    let words: &[capnp::Word] = &[
+        // Segment table: three segments, of one, two, and one words.
+        capnp::word(0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00),
+        capnp::word(0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00),
+        // Padding.
+        capnp::word(0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+        // Segment 0: far pointer to word 0 of segment 1.
+        capnp::word(0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00),
+        // Segment 1: landing pad, pointing to a list of three bytes.
+        capnp::word(0x01, 0x00, 0x00, 0x00, 0x1a, 0x00, 0x00, 0x00),
+        capnp::word(b'h', b'i', 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+        // Segment 2: unused.
+        capnp::word(0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+    ];
+    let mut lenient = ReaderOptions::new();
+    lenient.lenient_segment_table(true);
+    let mut legacy = ReaderOptions::new();
+    legacy.tolerate_legacy_padding(true);
+    for options in [lenient, legacy] {
+        let mut bytes = capnp::Word::words_to_bytes(words);
+        let message = serialize::read_message_from_flat_slice(&mut bytes, options).unwrap();
+        assert_eq!(message.get_root::<capnp::text::Reader>().unwrap(), "hi");
+
+        assert_eq!(
+            take_events(),
+            [
+                DecodeEvent {
+                    segment_id: None,
+                    word_offset: None,
+                    check: DecodeCheck::LegacySegmentTablePadding,
+                },
+                DecodeEvent {
+                    segment_id: Some(1),
+                    word_offset: Some(0),
+                    check: DecodeCheck::FarPointerFollowed,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "alloc")]
+
+//! A message of structs nested far deeper than the nesting limit, each holding just a pointer to
+//! the next. Code that recurses over it must fail when it reaches the limit, instead of
+//! overflowing the stack.
+
+use capnp::message::{self, ReaderOptions};
+use capnp::{any_pointer, ErrorKind, Word};
+
+const DEPTH: usize = 10_000;
+
+/// A root pointer and `DEPTH` structs, each with no data and one pointer, to the word after it.
+/// The innermost struct's pointer is null.
+fn chain() -> Vec<Word> {
+    let mut words = vec![capnp::word(0, 0, 0, 0, 0, 0, 1, 0); DEPTH];
+    words.push(capnp::word(0, 0, 0, 0, 0, 0, 0, 0));
+    words
+}
+
+fn check_too_deep(result: capnp::Result<impl Sized>) {
+    let Err(error) = result else {
+        panic!("{DEPTH} levels of nesting accepted");
+    };
+    assert!(
+        matches!(
+            error.kind,
+            ErrorKind::MessageIsTooDeeplyNested
+                | ErrorKind::MessageIsTooDeeplyNestedOrContainsCycles
+        ),
+        "{error}"
+    );
+}
+
+#[test]
+fn deep_nesting_is_an_error() {
+    let words = chain();
+    let segments: &[&[Word]] = &[&words];
+    let reader = message::Reader::new(segments, ReaderOptions::new());
+    let root: any_pointer::Reader = reader.get_root().unwrap();
+
+    check_too_deep(root.target_size());
+    check_too_deep(reader.canonicalize());
+    check_too_deep(reader.is_canonical());
+    let mut builder = message::Builder::new_default();
+    check_too_deep(builder.set_root(root));
+}
+
+#[test]
+fn nesting_limit_counts_each_level() {
+    let words = chain();
+    let segments: &[&[Word]] = &[&words[DEPTH - 10..]];
+    // Ten levels of structs below the root pointer.
+    for (limit, ok) in [(9, false), (10, true)] {
+        let mut options = ReaderOptions::new();
+        options.nesting_limit(limit);
+        let reader = message::Reader::new(segments, options);
+        let root: any_pointer::Reader = reader.get_root().unwrap();
+        assert_eq!(root.target_size().is_ok(), ok, "limit {limit}");
+    }
+}
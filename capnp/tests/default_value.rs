@@ -0,0 +1,56 @@
+#![cfg(feature = "alloc")]
+
+use capnp::schema_capnp::{node, ElementSize};
+use capnp::{
+    any_pointer, data, data_list, default_value, enum_list, list_list, message, primitive_list,
+    struct_list, text, text_list,
+};
+
+fn describe(node: node::Reader<'_>) -> String {
+    format!(
+        "{} {:?} {}",
+        node.get_id(),
+        node.get_display_name().unwrap().to_str().unwrap(),
+        node.get_nested_nodes().unwrap().len()
+    )
+}
+
+#[test]
+fn struct_default() {
+    let node = default_value::<node::Owned>();
+    assert_eq!(describe(node), "0 \"\" 0");
+    assert!(matches!(node.which(), Ok(node::File(()))));
+    assert_eq!(node.total_size().unwrap().word_count, 0);
+
+    // The default can be copied into a message like any other reader.
+    let mut builder = message::Builder::new_default();
+    builder.set_root(default_value::<node::Owned>()).unwrap();
+    let copied: node::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(describe(copied), describe(node));
+}
+
+#[test]
+fn blob_defaults() {
+    assert!(default_value::<text::Owned>().is_empty());
+    assert!(default_value::<data::Owned>().is_empty());
+    assert!(default_value::<any_pointer::Owned>().is_null());
+}
+
+#[test]
+fn list_defaults() {
+    assert!(default_value::<primitive_list::Owned<u64>>().is_empty());
+    assert!(default_value::<primitive_list::Owned<bool>>().is_empty());
+    assert!(default_value::<text_list::Owned>().is_empty());
+    assert!(default_value::<data_list::Owned>().is_empty());
+    assert!(default_value::<enum_list::Owned<ElementSize>>().is_empty());
+    assert!(default_value::<struct_list::Owned<node::Owned>>().is_empty());
+    assert!(default_value::<list_list::Owned<text_list::Owned>>().is_empty());
+}
+
+#[test]
+fn static_lifetime() {
+    // Readers of the default value outlive any message.
+    let nodes: Vec<node::Reader<'static>> =
+        (0..3).map(|_| default_value::<node::Owned>()).collect();
+    assert!(nodes.iter().all(|node| node.get_id() == 0));
+}
@@ -0,0 +1,37 @@
+use capnp::message::{self, ReaderSegments};
+use capnp::schema_capnp::node;
+use capnp::{any_pointer, text_list};
+
+fn id_or_default<S: ReaderSegments>(message: &message::Reader<S>) -> u64 {
+    message.get_root::<node::Reader>().unwrap().get_id()
+}
+
+#[test]
+fn empty_message_reads_defaults() {
+    let empty = message::Reader::empty();
+    let root: any_pointer::Reader = empty.get_root().unwrap();
+    assert!(root.is_null());
+
+    let node: node::Reader = empty.get_root().unwrap();
+    assert_eq!(node.get_id(), 0);
+    assert!(!node.has_display_name());
+    assert_eq!(node.get_display_name().unwrap(), "");
+
+    let list: text_list::Reader = empty.get_root().unwrap();
+    assert!(list.is_empty());
+
+    assert_eq!(id_or_default(&empty), 0);
+}
+
+#[test]
+fn empty_message_outlives_its_caller() {
+    fn absent() -> message::Reader<&'static [&'static [capnp::Word]]> {
+        message::Reader::empty()
+    }
+    let reader = absent();
+    assert_eq!(reader.get_root::<node::Reader>().unwrap().get_scope_id(), 0);
+    assert_eq!(
+        message::NULL_MESSAGE_WORDS,
+        [capnp::word(0, 0, 0, 0, 0, 0, 0, 0)]
+    );
+}
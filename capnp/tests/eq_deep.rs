@@ -0,0 +1,146 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::{any_pointer, word, ErrorKind, Word};
+
+fn build(
+    first_segment_words: u32,
+    id: u64,
+    name: &str,
+    parameters: &[&str],
+) -> message::Builder<message::HeapAllocator> {
+    let mut builder = message::Builder::new(
+        message::HeapAllocator::new().first_segment_words(first_segment_words),
+    );
+    let mut root: node::Builder = builder.init_root();
+    root.set_id(id);
+    root.set_display_name(name.into());
+    let mut list = root.init_parameters(parameters.len() as u32);
+    for (idx, parameter) in parameters.iter().enumerate() {
+        list.reborrow()
+            .get(idx as u32)
+            .set_name((*parameter).into());
+    }
+    builder
+}
+
+fn eq_deep(
+    one: &message::Builder<message::HeapAllocator>,
+    other: &message::Builder<message::HeapAllocator>,
+) -> capnp::Result<bool> {
+    let one: any_pointer::Reader = one.get_root_as_reader()?;
+    one.eq_deep(&other.get_root_as_reader()?)
+}
+
+fn eq_words(one: &[Word], other: &[Word]) -> capnp::Result<bool> {
+    let one = [Word::words_to_bytes(one)];
+    let other = [Word::words_to_bytes(other)];
+    let one = message::Reader::new(message::SegmentArray::new(&one), ReaderOptions::new());
+    let other = message::Reader::new(message::SegmentArray::new(&other), ReaderOptions::new());
+    let root: any_pointer::Reader = one.get_root()?;
+    root.eq_deep(&other.get_root()?)
+}
+
+#[test]
+fn equal_values_in_different_layouts() {
+    let parameters = ["first", "second", "third"];
+    let one_segment = build(1024, 7, "name", &parameters);
+    let many_segments = build(1, 7, "name", &parameters);
+    assert_ne!(
+        one_segment.get_segments_for_output().len(),
+        many_segments.get_segments_for_output().len()
+    );
+    assert!(eq_deep(&one_segment, &many_segments).unwrap());
+    assert!(eq_deep(&many_segments, &one_segment).unwrap());
+}
+
+#[test]
+fn unequal_values() {
+    let value = build(1024, 7, "name", &["first"]);
+    for other in [
+        build(1024, 8, "name", &["first"]),
+        build(1024, 7, "nams", &["first"]),
+        build(1024, 7, "name", &["firsT"]),
+        build(1024, 7, "name", &["first", "second"]),
+        build(1024, 7, "name", &[]),
+    ] {
+        assert!(!eq_deep(&value, &other).unwrap());
+        assert!(!eq_deep(&other, &value).unwrap());
+    }
+}
+
+#[test]
+fn null_pointers_are_equal() {
+    let null = [word(0, 0, 0, 0, 0, 0, 0, 0)];
+    assert!(eq_words(&null, &null).unwrap());
+    // An empty struct is not null.
+    let empty = [word(0xfc, 0xff, 0xff, 0xff, 0, 0, 0, 0)];
+    assert!(!eq_words(&null, &empty).unwrap());
+}
+
+#[test]
+fn trailing_zeros_and_nulls_do_not_matter() {
+    // A struct with one data word.
+    let short = [word(0, 0, 0, 0, 1, 0, 0, 0), word(1, 2, 3, 4, 5, 6, 7, 8)];
+    // The same struct with a zero data word and a null pointer more.
+    let long = [
+        word(0, 0, 0, 0, 2, 0, 1, 0),
+        word(1, 2, 3, 4, 5, 6, 7, 8),
+        word(0, 0, 0, 0, 0, 0, 0, 0),
+        word(0, 0, 0, 0, 0, 0, 0, 0),
+    ];
+    assert!(eq_words(&short, &long).unwrap());
+    assert!(eq_words(&long, &short).unwrap());
+
+    let mut nonzero = long;
+    nonzero[2] = word(0, 0, 0, 0, 0, 0, 0, 1);
+    assert!(!eq_words(&short, &nonzero).unwrap());
+
+    let mut non_null = long;
+    // A pointer to an empty struct.
+    non_null[3] = word(0xfc, 0xff, 0xff, 0xff, 0, 0, 0, 0);
+    assert!(!eq_words(&short, &non_null).unwrap());
+}
+
+#[test]
+fn bits_past_the_end_of_a_list_do_not_matter() {
+    // A list of 3 bits, holding true, false, true.
+    let clean = [
+        word(1, 0, 0, 0, 0x19, 0, 0, 0),
+        word(5, 0, 0, 0, 0, 0, 0, 0),
+    ];
+    let mut dirty = clean;
+    dirty[1] = word(0xf5, 0, 0, 0, 0, 0, 0, 0);
+    assert!(eq_words(&clean, &dirty).unwrap());
+
+    let mut changed = clean;
+    changed[1] = word(4, 0, 0, 0, 0, 0, 0, 0);
+    assert!(!eq_words(&clean, &changed).unwrap());
+}
+
+#[test]
+fn capabilities_cannot_be_compared() {
+    let cap = [word(3, 0, 0, 0, 0, 0, 0, 0)];
+    let null = [word(0, 0, 0, 0, 0, 0, 0, 0)];
+    for (one, other) in [(&cap, &cap), (&cap, &null), (&null, &cap)] {
+        assert_eq!(
+            eq_words(one, other).unwrap_err().kind,
+            ErrorKind::CannotCompareCapabilities
+        );
+    }
+}
+
+#[test]
+fn comparison_respects_traversal_limit() {
+    let value = build(1024, 7, &"long name ".repeat(20), &["first"]);
+    let segments = value.get_segments_for_output();
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(Some(8));
+    let limited = message::Reader::new(message::SegmentArray::new(&segments), options);
+    let root: any_pointer::Reader = limited.get_root().unwrap();
+    let error = root
+        .eq_deep(&value.get_root_as_reader().unwrap())
+        .unwrap_err();
+    assert_eq!(error.kind, ErrorKind::ReadLimitExceeded);
+}
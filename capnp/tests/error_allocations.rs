@@ -0,0 +1,123 @@
+// Capturing a backtrace allocates, so these errors only allocate nothing without it.
+#![cfg(all(feature = "alloc", not(feature = "backtrace")))]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::{node, type_};
+use capnp::{word, ErrorKind, Word};
+
+/// Counts the allocations made on each thread, so that tests running in parallel do not see each
+/// other's allocations.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the result of `f`, and the number of allocations that it made.
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+fn read_root<'a, T: capnp::traits::FromPointerReader<'a>>(
+    message: &'a message::Reader<message::SegmentArray<'a>>,
+) -> (ErrorKind, usize) {
+    let (result, allocations) = count_allocations(|| message.get_root::<T>().map(|_| ()));
+    (result.unwrap_err().kind, allocations)
+}
+
+#[test]
+fn errors_with_context_do_allocate() {
+    let (error, allocations) = count_allocations(|| capnp::Error::failed("context".into()));
+    assert_eq!(error.kind, ErrorKind::Failed);
+    assert!(allocations > 0);
+}
+
+#[test]
+fn read_limit_exceeded() {
+    let mut builder = message::Builder::new_default();
+    builder
+        .init_root::<node::Builder>()
+        .set_display_name("read_limit_exceeded.capnp".into());
+    let segments = builder.get_segments_for_output();
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(Some(2));
+    let message = message::Reader::new(message::SegmentArray::new(&segments), options);
+    assert_eq!(
+        read_root::<node::Reader>(&message),
+        (ErrorKind::ReadLimitExceeded, 0)
+    );
+}
+
+#[test]
+fn ops_limit_exceeded() {
+    let mut builder = message::Builder::new_default();
+    builder
+        .init_root::<node::Builder>()
+        .set_display_name("ops_limit_exceeded.capnp".into());
+    let segments = builder.get_segments_for_output();
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_ops(Some(0));
+    let message = message::Reader::new(message::SegmentArray::new(&segments), options);
+    assert_eq!(
+        read_root::<node::Reader>(&message),
+        (ErrorKind::Overloaded, 0)
+    );
+}
+
+#[test]
+fn nesting_limit_exceeded() {
+    let mut builder = message::Builder::new_default();
+    let mut ty: type_::Builder = builder.init_root();
+    for _ in 0..10 {
+        ty = ty.init_list().init_element_type();
+    }
+    ty.set_void(());
+    let segments = builder.get_segments_for_output();
+    let mut options = ReaderOptions::new();
+    options.nesting_limit(3);
+    let message = message::Reader::new(message::SegmentArray::new(&segments), options);
+
+    let (result, allocations) = count_allocations(|| {
+        let mut ty: type_::Reader = message.get_root()?;
+        while let type_::List(list) = ty.which()? {
+            ty = list.get_element_type()?;
+        }
+        Ok::<_, capnp::Error>(())
+    });
+    assert_eq!(
+        result.unwrap_err().kind,
+        ErrorKind::MessageIsTooDeeplyNestedOrContainsCycles
+    );
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn out_of_bounds_pointer() {
+    // A root pointer to a struct of one data word, ten words past the end of the segment.
+    let segment: [Word; 1] = [word(0x28, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00)];
+    let segments = [Word::words_to_bytes(&segment)];
+    let message = message::Reader::new(message::SegmentArray::new(&segments), ReaderOptions::new());
+    assert_eq!(
+        read_root::<node::Reader>(&message),
+        (ErrorKind::MessageContainsOutOfBoundsPointer, 0)
+    );
+}
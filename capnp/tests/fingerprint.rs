@@ -0,0 +1,142 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::{any_pointer, fingerprint, primitive_list, word, Word};
+use quickcheck::{quickcheck, TestResult};
+
+fn build(
+    first_segment_words: u32,
+    id: u64,
+    name: &str,
+    parameters: &[String],
+) -> message::Builder<message::HeapAllocator> {
+    let mut builder = message::Builder::new(
+        message::HeapAllocator::new().first_segment_words(first_segment_words),
+    );
+    let mut root: node::Builder = builder.init_root();
+    root.set_id(id);
+    root.set_display_name(name.into());
+    let mut list = root.init_parameters(parameters.len() as u32);
+    for (idx, parameter) in parameters.iter().enumerate() {
+        list.reborrow()
+            .get(idx as u32)
+            .set_name(parameter.as_str().into());
+    }
+    builder
+}
+
+fn of(builder: &message::Builder<message::HeapAllocator>) -> u64 {
+    fingerprint(builder.get_root_as_reader().unwrap()).unwrap()
+}
+
+quickcheck! {
+    #[cfg_attr(miri, ignore)] // miri takes a long time with quickcheck
+    fn equal_values_have_equal_fingerprints(
+        first_segment_words: u8,
+        id: u64,
+        name: String,
+        parameters: Vec<String>
+    ) -> TestResult {
+        let one_segment = build(1024, id, &name, &parameters);
+        let many_segments = build(u32::from(first_segment_words) + 1, id, &name, &parameters);
+
+        // The same value, read back from its canonical form.
+        let reader = message::Reader::new(
+            message::SegmentArray::new(&many_segments.get_segments_for_output()),
+            ReaderOptions::new(),
+        )
+        .canonicalize()
+        .unwrap();
+        let canonical = [Word::words_to_bytes(&reader)];
+        let canonical = message::Reader::new(message::SegmentArray::new(&canonical), ReaderOptions::new());
+
+        let expected = of(&one_segment);
+        TestResult::from_bool(
+            of(&many_segments) == expected
+                && fingerprint(canonical.get_root().unwrap()).unwrap() == expected,
+        )
+    }
+
+    #[cfg_attr(miri, ignore)] // miri takes a long time with quickcheck
+    fn changing_a_bit_changes_the_fingerprint(id: u64, bit: u8, name: String) -> TestResult {
+        let changed = id ^ (1 << (bit % 64));
+        TestResult::from_bool(
+            of(&build(1024, id, &name, &[])) != of(&build(1024, changed, &name, &[])),
+        )
+    }
+}
+
+#[test]
+fn distinct_values_spread_out() {
+    let mut seen = std::collections::HashSet::new();
+    for id in 0..10_000 {
+        assert!(seen.insert(of(&build(1024, id, "", &[]))), "{id}");
+    }
+    for idx in 0..1_000 {
+        assert!(
+            seen.insert(of(&build(1024, 0, &idx.to_string(), &[]))),
+            "{idx}"
+        );
+    }
+}
+
+#[test]
+fn layout_does_not_matter() {
+    // A node with only its display name set holds the same words as a parameter with that name.
+    let mut node = message::Builder::new_default();
+    node.init_root::<node::Builder>()
+        .set_display_name("fingerprint.capnp".into());
+    let mut parameter = message::Builder::new_default();
+    parameter
+        .init_root::<node::parameter::Builder>()
+        .set_name("fingerprint.capnp".into());
+    assert_eq!(of(&node), of(&parameter));
+
+    // Bits past the end of a list of bits are ignored.
+    let mut bits = message::Builder::new_default();
+    let mut list: primitive_list::Builder<bool> = bits.initn_root(3);
+    for idx in 0..3 {
+        list.set(idx, true);
+    }
+    let segment = [
+        word(0x01, 0x00, 0x00, 0x00, 0x19, 0x00, 0x00, 0x00),
+        word(0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+    ];
+    let segments = [Word::words_to_bytes(&segment)];
+    let padded = message::Reader::new(message::SegmentArray::new(&segments), ReaderOptions::new());
+    assert_eq!(fingerprint(padded.get_root().unwrap()).unwrap(), of(&bits));
+}
+
+#[test]
+fn kinds_are_told_apart() {
+    let mut builder = message::Builder::new_default();
+    let null = of(&builder);
+    builder.init_root::<node::Builder>();
+    let empty_struct = of(&builder);
+    builder.initn_root::<primitive_list::Builder<u64>>(0);
+    let empty_list = of(&builder);
+    builder.initn_root::<primitive_list::Builder<u64>>(1);
+    let zero_list = of(&builder);
+    builder.initn_root::<primitive_list::Builder<u32>>(2);
+    let other_zero_list = of(&builder);
+    let all = [null, empty_struct, empty_list, zero_list, other_zero_list];
+    for (idx, a) in all.iter().enumerate() {
+        assert!(!all[idx + 1..].contains(a), "{idx}");
+    }
+
+    let root: any_pointer::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(fingerprint(root).unwrap(), other_zero_list);
+
+    // Capabilities are told apart by their cap table index.
+    let capability = |index| {
+        let segment = [word(0x03, 0x00, 0x00, 0x00, index, 0x00, 0x00, 0x00)];
+        let segments = [Word::words_to_bytes(&segment)];
+        let message =
+            message::Reader::new(message::SegmentArray::new(&segments), ReaderOptions::new());
+        fingerprint(message.get_root().unwrap()).unwrap()
+    };
+    assert_eq!(capability(5), capability(5));
+    assert_ne!(capability(5), capability(6));
+    assert!(!all.contains(&capability(0)));
+}
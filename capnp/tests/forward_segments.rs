@@ -0,0 +1,136 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, AllocationStrategy, HeapAllocator, ReaderOptions};
+use capnp::serialize::{self, OwnedSegments};
+use capnp::{serialize_packed, text_list};
+
+fn build(first_segment_words: u32) -> message::Builder<HeapAllocator> {
+    let mut builder = message::Builder::new(
+        HeapAllocator::new()
+            .first_segment_words(first_segment_words)
+            .allocation_strategy(AllocationStrategy::FixedSize),
+    );
+    {
+        let mut list: text_list::Builder = builder.initn_root(20);
+        for idx in 0..20 {
+            list.set(idx, format!("item {idx}").as_str().into());
+        }
+    }
+    builder
+}
+
+fn items(message: &message::Reader<OwnedSegments>) -> Vec<String> {
+    let list: text_list::Reader = message.get_root().unwrap();
+    list.iter()
+        .map(|item| item.unwrap().to_string().unwrap())
+        .collect()
+}
+
+fn forward(message: &message::Reader<OwnedSegments>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    message
+        .as_output_segments()
+        .unwrap()
+        .write_to(&mut bytes)
+        .unwrap();
+    bytes
+}
+
+#[test]
+fn forwarded_bytes_are_identical() {
+    for first_segment_words in [4, 1024] {
+        let builder = build(first_segment_words);
+        let segment_count = builder.get_segments_for_output().len();
+        assert_eq!(segment_count > 1, first_segment_words == 4);
+
+        let original = serialize::write_message_to_words(&builder);
+        let message = serialize::read_message(&original[..], ReaderOptions::new()).unwrap();
+        assert_eq!(message.as_output_segments().unwrap().len(), segment_count);
+
+        let forwarded = forward(&message);
+        assert_eq!(forwarded, original);
+
+        let again = serialize::read_message(&forwarded[..], ReaderOptions::new()).unwrap();
+        assert_eq!(items(&again), items(&message));
+    }
+}
+
+#[test]
+fn forward_packed_message_unpacked() {
+    let builder = build(4);
+    let unpacked = serialize::write_message_to_words(&builder);
+    let mut packed = Vec::new();
+    serialize_packed::write_message(&mut packed, &builder).unwrap();
+
+    let message = serialize_packed::read_message(&packed[..], ReaderOptions::new()).unwrap();
+    assert_eq!(forward(&message), unpacked);
+
+    // The segments can also go through `write_message_segments()`.
+    let mut bytes = Vec::new();
+    serialize::write_message_segments(&mut bytes, &message.as_output_segments().unwrap()).unwrap();
+    assert_eq!(bytes, unpacked);
+}
+
+#[test]
+fn saved_segments_are_written_without_builder() {
+    let builder = build(4);
+    let original = serialize::write_message_to_words(&builder);
+    let saved: Vec<Vec<capnp::Word>> = builder
+        .get_segments_for_output()
+        .iter()
+        .map(|segment| {
+            let mut words = capnp::Word::allocate_zeroed_vec(segment.len() / 8);
+            capnp::Word::words_to_bytes_mut(&mut words).copy_from_slice(segment);
+            words
+        })
+        .collect();
+    drop(builder);
+
+    let word_segments: Vec<&[capnp::Word]> = saved.iter().map(|segment| &segment[..]).collect();
+    // The same segments can go to any number of sinks.
+    for _ in 0..2 {
+        let mut bytes = Vec::new();
+        serialize::write_message_segments(&mut bytes, &word_segments[..]).unwrap();
+        assert_eq!(bytes, original);
+    }
+
+    let byte_segments: Vec<&[u8]> = saved
+        .iter()
+        .map(|segment| capnp::Word::words_to_bytes(segment))
+        .collect();
+    let mut bytes = Vec::new();
+    serialize::write_message_segments(&mut bytes, &byte_segments[..]).unwrap();
+    assert_eq!(bytes, original);
+}
+
+#[test]
+fn reader_segments_are_written_identically() {
+    let original = serialize::write_message_to_words(&build(4));
+    let message = serialize::read_message(&original[..], ReaderOptions::new()).unwrap();
+    let mut bytes = Vec::new();
+    serialize::write_message_segments(&mut bytes, &message.into_segments()).unwrap();
+    assert_eq!(bytes, original);
+}
+
+#[test]
+fn slice_backed_reader_is_forwarded_identically() {
+    let original = serialize::write_message_to_words(&build(4));
+    let mut slice = &original[..];
+    let message =
+        serialize::read_message_from_flat_slice(&mut slice, ReaderOptions::new()).unwrap();
+    // A proxy inspects one field, then forwards the message unchanged.
+    let list: text_list::Reader = message.get_root().unwrap();
+    assert_eq!(list.get(3).unwrap(), "item 3");
+
+    let mut bytes = Vec::new();
+    message
+        .as_output_segments()
+        .unwrap()
+        .write_to(&mut bytes)
+        .unwrap();
+    assert_eq!(bytes, original);
+
+    let mut bytes = Vec::new();
+    serialize::write_message_segments(&mut bytes, message.segments()).unwrap();
+    assert_eq!(bytes, original);
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::{node, value, ElementSize};
+use capnp::{
+    any_pointer, data, data_list, enum_list, list_list, primitive_list, struct_list, text,
+    text_list,
+};
+
+/// Reads `builder` back, and returns whether its root `Value` has an `anyPointer`, checking that
+/// asking costs nothing against the traversal limit.
+fn has_any_pointer(builder: &message::Builder<message::HeapAllocator>) -> bool {
+    let segments = builder.get_segments_for_output();
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(Some(1000));
+    let message = message::Reader::new(message::SegmentArray::new(&segments), options);
+    let root: value::Reader = message.get_root().unwrap();
+    let remaining = message.remaining_traversal_limit_in_words();
+    let has = root.has_any_pointer();
+    assert_eq!(message.remaining_traversal_limit_in_words(), remaining);
+    let value::AnyPointer(pointer) = root.which().unwrap() else {
+        panic!("expected an anyPointer");
+    };
+    assert_eq!(pointer.is_null(), !has);
+    has
+}
+
+/// Checks that a `Value`'s `anyPointer` reads as absent until `set_to_empty()` sets it, after
+/// which it reads as present, although it is empty.
+fn unset_and_set_to_empty(set_to_empty: impl FnOnce(any_pointer::Builder)) {
+    let mut builder = message::Builder::new_default();
+    let mut unset: any_pointer::Builder = builder.init_root::<value::Builder>().init_any_pointer();
+    assert!(unset.is_null());
+    assert!(unset.reborrow().into_reader().is_null());
+    assert!(!has_any_pointer(&builder));
+
+    let mut root: value::Builder = builder.get_root().unwrap();
+    assert!(!root.has_any_pointer());
+    let Ok(value::AnyPointer(pointer)) = root.reborrow().which() else {
+        panic!("expected an anyPointer");
+    };
+    set_to_empty(pointer);
+    assert!(root.has_any_pointer());
+    assert!(has_any_pointer(&builder));
+}
+
+#[test]
+fn text_and_data() {
+    unset_and_set_to_empty(|pointer| {
+        pointer.initn_as::<text::Builder>(0);
+    });
+    unset_and_set_to_empty(|pointer| {
+        pointer.initn_as::<data::Builder>(0);
+    });
+
+    // Through a generated text field.
+    let mut builder = message::Builder::new_default();
+    let mut root: node::Builder = builder.init_root();
+    assert!(!root.has_display_name());
+    assert_eq!(root.reborrow().get_display_name().unwrap(), "");
+    root.set_display_name("".into());
+    assert!(root.has_display_name());
+    let root = root.into_reader();
+    assert!(root.has_display_name());
+    assert_eq!(root.get_display_name().unwrap(), "");
+}
+
+#[test]
+fn lists() {
+    unset_and_set_to_empty(|pointer| {
+        pointer.initn_as::<primitive_list::Builder<()>>(0);
+    });
+    unset_and_set_to_empty(|pointer| {
+        pointer.initn_as::<primitive_list::Builder<bool>>(0);
+    });
+    unset_and_set_to_empty(|pointer| {
+        pointer.initn_as::<primitive_list::Builder<u64>>(0);
+    });
+    unset_and_set_to_empty(|pointer| {
+        pointer.initn_as::<enum_list::Builder<ElementSize>>(0);
+    });
+    unset_and_set_to_empty(|pointer| {
+        pointer.initn_as::<struct_list::Builder<node::parameter::Owned>>(0);
+    });
+    unset_and_set_to_empty(|pointer| {
+        pointer.initn_as::<text_list::Builder>(0);
+    });
+    unset_and_set_to_empty(|pointer| {
+        pointer.initn_as::<data_list::Builder>(0);
+    });
+    unset_and_set_to_empty(|pointer| {
+        pointer.initn_as::<list_list::Builder<primitive_list::Owned<u8>>>(0);
+    });
+
+    // Through a generated struct list field.
+    let mut builder = message::Builder::new_default();
+    let mut root: node::Builder = builder.init_root();
+    assert!(!root.has_nested_nodes());
+    root.reborrow().init_nested_nodes(0);
+    assert!(root.has_nested_nodes());
+    let root = root.into_reader();
+    assert!(root.has_nested_nodes());
+    assert!(root.get_nested_nodes().unwrap().is_empty());
+}
+
+#[test]
+fn is_null_never_fails() {
+    // A root pointer to a struct far past the end of its only segment.
+    let words = [capnp::word(0x00, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00)];
+    let segments = [capnp::Word::words_to_bytes(&words)];
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(Some(1));
+    let message = message::Reader::new(message::SegmentArray::new(&segments), options);
+    let root: any_pointer::Reader = message.get_root().unwrap();
+    assert!(!root.is_null());
+    assert!(root.get_as::<node::Reader>().is_err());
+    assert!(!root.is_null());
+
+    let null = message::Reader::empty();
+    assert!(null.get_root::<any_pointer::Reader>().unwrap().is_null());
+}
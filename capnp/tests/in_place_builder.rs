@@ -0,0 +1,264 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, InPlaceAllocator, ReaderOptions};
+use capnp::schema_capnp::{node, value};
+use capnp::{any_pointer, data, primitive_list, serialize, struct_list, text};
+use capnp::{word, ErrorKind, Word};
+
+/// `bytes`, copied into word-aligned memory.
+fn aligned(bytes: &[u8]) -> Vec<Word> {
+    let mut words = Word::allocate_zeroed_vec(bytes.len() / 8);
+    Word::words_to_bytes_mut(&mut words).copy_from_slice(bytes);
+    words
+}
+
+/// A serialized node spread over several segments, followed by a word that is not part of it.
+fn node_file() -> Vec<Word> {
+    let mut builder = message::Builder::new(message::HeapAllocator::new().first_segment_words(1));
+    let mut root: node::Builder = builder.init_root();
+    root.set_id(41);
+    root.set_display_name("in_place_builder.capnp".into());
+    root.init_struct().set_data_word_count(3);
+    let mut bytes = serialize::write_message_to_words(&builder);
+    assert!(builder.get_segments_for_output().len() > 1);
+    bytes.extend_from_slice(&[0xee; 8]);
+    aligned(&bytes)
+}
+
+/// The offsets of the bytes that differ between `a` and `b`.
+fn changed_bytes(a: &[u8], b: &[u8]) -> Vec<usize> {
+    assert_eq!(a.len(), b.len());
+    (0..a.len()).filter(|&idx| a[idx] != b[idx]).collect()
+}
+
+#[test]
+fn set_primitive_fields_in_place() {
+    let original = node_file();
+    let mut file = original.clone();
+    {
+        let buffer = Word::words_to_bytes_mut(&mut file);
+        let mut message =
+            message::Builder::from_flat_slice_mut(buffer, ReaderOptions::new()).unwrap();
+        let mut root: node::Builder = message.get_root().unwrap();
+        let id = root.reborrow().get_id();
+        root.set_id(id + 1);
+        let node::Struct(mut st) = root.which().unwrap() else {
+            panic!("expected a struct node");
+        };
+        st.set_is_group(true);
+    }
+
+    let original = Word::words_to_bytes(&original);
+    let bytes = Word::words_to_bytes(&file);
+    // Only the low byte of the id, and the byte holding the flag, have changed.
+    assert_eq!(changed_bytes(original, bytes).len(), 2);
+    assert_eq!(&bytes[bytes.len() - 8..], &[0xee; 8]);
+
+    let message =
+        serialize::read_message_from_flat_slice(&mut &bytes[..], ReaderOptions::new()).unwrap();
+    let root: node::Reader = message.get_root().unwrap();
+    assert_eq!(root.get_id(), 42);
+    assert_eq!(root.get_display_name().unwrap(), "in_place_builder.capnp");
+    let node::Struct(st) = root.which().unwrap() else {
+        panic!("expected a struct node");
+    };
+    assert!(st.get_is_group());
+    assert_eq!(st.get_data_word_count(), 3);
+}
+
+#[test]
+fn clear_pointer_in_place() {
+    let mut builder = message::Builder::new_default();
+    builder
+        .init_root::<value::Builder>()
+        .init_any_pointer()
+        .set_as("cleared")
+        .unwrap();
+    let original = aligned(&serialize::write_message_to_words(&builder));
+    let mut file = original.clone();
+    {
+        let buffer = Word::words_to_bytes_mut(&mut file);
+        let mut message =
+            message::Builder::from_flat_slice_mut(buffer, ReaderOptions::new()).unwrap();
+        let root: value::Builder = message.get_root().unwrap();
+        let value::AnyPointer(mut pointer) = root.which().unwrap() else {
+            panic!("expected an anyPointer");
+        };
+        pointer.clear();
+    }
+
+    // The pointer and the text that it pointed to are zeroed, and nothing else changes.
+    let bytes = Word::words_to_bytes(&file);
+    let changed = changed_bytes(Word::words_to_bytes(&original), bytes);
+    assert!(changed.iter().all(|&idx| bytes[idx] == 0));
+    let message =
+        serialize::read_message_from_flat_slice(&mut &bytes[..], ReaderOptions::new()).unwrap();
+    let root: value::Reader = message.get_root().unwrap();
+    assert!(!root.has_any_pointer());
+}
+
+#[test]
+fn allocating_fails_with_overloaded() {
+    let mut source = message::Builder::new_default();
+    {
+        let mut parameters: struct_list::Builder<node::parameter::Owned> = source.initn_root(1);
+        parameters.reborrow().get(0).set_name("T".into());
+    }
+    let parameters: struct_list::Reader<node::parameter::Owned> =
+        source.get_root_as_reader().unwrap();
+
+    let original = node_file();
+    let mut file = original.clone();
+    {
+        let buffer = Word::words_to_bytes_mut(&mut file);
+        let mut message =
+            message::Builder::from_flat_slice_mut(buffer, ReaderOptions::new()).unwrap();
+        let error = message.set_root("a new root").unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Overloaded);
+        let mut root: node::Builder = message.get_root().unwrap();
+        let error = root.set_parameters(parameters).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Overloaded);
+    }
+
+    // Nothing could be allocated, so nothing was changed.
+    assert_eq!(file, original);
+}
+
+/// A serialized value whose any pointer points at text.
+fn any_pointer_file() -> Vec<Word> {
+    let mut builder = message::Builder::new_default();
+    builder
+        .init_root::<value::Builder>()
+        .init_any_pointer()
+        .set_as("in place")
+        .unwrap();
+    aligned(&serialize::write_message_to_words(&builder))
+}
+
+/// Opens a copy of `original` in place and runs `op` on it.
+fn open_in_place(original: &[Word], op: impl FnOnce(&mut message::Builder<InPlaceAllocator>)) {
+    let mut file = original.to_vec();
+    let buffer = Word::words_to_bytes_mut(&mut file);
+    let mut message = message::Builder::from_flat_slice_mut(buffer, ReaderOptions::new()).unwrap();
+    op(&mut message);
+}
+
+/// Runs `op` on the any pointer of a message from `any_pointer_file()`.
+fn open_any_pointer(op: impl FnOnce(any_pointer::Builder)) {
+    open_in_place(&any_pointer_file(), |message| {
+        let root: value::Builder = message.get_root().unwrap();
+        let value::AnyPointer(pointer) = root.which().unwrap() else {
+            panic!("expected an anyPointer");
+        };
+        op(pointer);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Overloaded")]
+fn init_struct_panics() {
+    open_any_pointer(|pointer| {
+        pointer.init_as::<node::Builder>();
+    });
+}
+
+#[test]
+#[should_panic(expected = "Overloaded")]
+fn init_list_panics() {
+    open_any_pointer(|pointer| {
+        pointer.initn_as::<primitive_list::Builder<u64>>(4);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Overloaded")]
+fn init_struct_list_panics() {
+    open_any_pointer(|pointer| {
+        pointer.initn_as::<struct_list::Builder<node::Owned>>(2);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Overloaded")]
+fn init_text_panics() {
+    open_any_pointer(|pointer| {
+        pointer.initn_as::<text::Builder>(40);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Overloaded")]
+fn init_data_panics() {
+    open_any_pointer(|pointer| {
+        pointer.initn_as::<data::Builder>(40);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Overloaded")]
+fn set_text_panics() {
+    open_in_place(&node_file(), |message| {
+        let mut root: node::Builder = message.get_root().unwrap();
+        root.set_display_name("a longer name than the one that is there".into());
+    });
+}
+
+#[test]
+#[should_panic(expected = "Overloaded")]
+fn set_data_panics() {
+    let mut builder = message::Builder::new_default();
+    builder.init_root::<value::Builder>().set_data(b"ab");
+    let original = aligned(&serialize::write_message_to_words(&builder));
+    open_in_place(&original, |message| {
+        let mut root: value::Builder = message.get_root().unwrap();
+        root.set_data(b"more bytes than fit in the word that is there");
+    });
+}
+
+#[test]
+fn overlapping_objects_are_rejected() {
+    // The root struct has one data word and one pointer, which points at itself as a struct of
+    // one data word, so that setting that struct's field would rewrite the pointer.
+    let mut file = aligned(&serialize::segment_table_bytes(&[3]));
+    file.push(word(0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00));
+    file.push(word(0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00));
+    file.push(word(0xfc, 0xff, 0xff, 0xff, 0x01, 0x00, 0x00, 0x00));
+    let original = file.clone();
+    let buffer = Word::words_to_bytes_mut(&mut file);
+    let Err(error) = message::Builder::from_flat_slice_mut(buffer, ReaderOptions::new()) else {
+        panic!("opened a message with overlapping objects");
+    };
+    assert_eq!(error.kind, ErrorKind::Failed);
+    assert_eq!(file, original);
+}
+
+#[test]
+fn invalid_messages_are_rejected() {
+    // A root pointer to a struct of one data word, ten words past the end of the segment.
+    let mut file = aligned(&serialize::segment_table_bytes(&[1]));
+    file.push(word(0x28, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00));
+    let buffer = Word::words_to_bytes_mut(&mut file);
+    let Err(error) = message::Builder::from_flat_slice_mut(buffer, ReaderOptions::new()) else {
+        panic!("opened a message with an out-of-bounds pointer");
+    };
+    assert_eq!(error.kind, ErrorKind::MessageContainsOutOfBoundsPointer);
+
+    // A segment table declaring more words than there are.
+    let mut file = node_file();
+    file.truncate(file.len() - 2);
+    let buffer = Word::words_to_bytes_mut(&mut file);
+    let Err(error) = message::Builder::from_flat_slice_mut(buffer, ReaderOptions::new()) else {
+        panic!("opened a truncated message");
+    };
+    assert!(matches!(error.kind, ErrorKind::MessageEndsPrematurely(..)));
+
+    // A traversal limit too small for the whole message.
+    let mut file = node_file();
+    let buffer = Word::words_to_bytes_mut(&mut file);
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(Some(2));
+    let Err(error) = message::Builder::from_flat_slice_mut(buffer, options) else {
+        panic!("opened a message beyond the traversal limit");
+    };
+    assert!(matches!(error.kind, ErrorKind::MessageTooLarge(_)));
+}
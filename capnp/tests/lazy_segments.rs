@@ -0,0 +1,144 @@
+#![cfg(feature = "alloc")]
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::OnceLock;
+
+use capnp::message::{self, ReaderOptions, ReaderSegments};
+use capnp::schema_capnp::node;
+use capnp::{Error, ErrorKind, Word};
+
+/// Holds segment 0 in memory, and loads the other segments from `store` the first time a far
+/// pointer refers to them.
+struct LazySegments {
+    first: Vec<Word>,
+    store: Vec<Vec<Word>>,
+    fetched: Vec<OnceLock<Vec<Word>>>,
+    fetch_count: AtomicU32,
+    store_available: AtomicBool,
+}
+
+impl LazySegments {
+    fn new(segments: &[&[u8]]) -> Self {
+        let mut words: Vec<Vec<Word>> = segments
+            .iter()
+            .map(|segment| {
+                let mut words = Word::allocate_zeroed_vec(segment.len() / 8);
+                Word::words_to_bytes_mut(&mut words).copy_from_slice(segment);
+                words
+            })
+            .collect();
+        let first = words.remove(0);
+        Self {
+            first,
+            fetched: words.iter().map(|_| OnceLock::new()).collect(),
+            store: words,
+            fetch_count: AtomicU32::new(0),
+            store_available: AtomicBool::new(true),
+        }
+    }
+}
+
+impl ReaderSegments for LazySegments {
+    fn get_segment(&self, idx: u32) -> Option<&[u8]> {
+        if idx == 0 {
+            return Some(Word::words_to_bytes(&self.first));
+        }
+        let words = self.fetched.get(idx as usize - 1)?.get()?;
+        Some(Word::words_to_bytes(words))
+    }
+
+    fn get_segment_or_fetch(&self, idx: u32) -> capnp::Result<&[u8]> {
+        let Some(slot) = idx
+            .checked_sub(1)
+            .and_then(|idx| self.fetched.get(idx as usize))
+        else {
+            return Err(Error::from_kind(ErrorKind::InvalidSegmentId(idx)));
+        };
+        if !self.store_available.load(Ordering::Relaxed) {
+            return Err(Error::failed("block store is unreachable".into()));
+        }
+        let words = slot.get_or_init(|| {
+            self.fetch_count.fetch_add(1, Ordering::Relaxed);
+            self.store[idx as usize - 1].clone()
+        });
+        Ok(Word::words_to_bytes(words))
+    }
+
+    fn len(&self) -> usize {
+        self.store.len() + 1
+    }
+}
+
+/// A message whose root pointer is alone in segment 0, and is a far pointer to a node in
+/// segment 1, whose display name is in segment 2.
+fn three_segment_message() -> LazySegments {
+    let mut builder = message::Builder::new(message::HeapAllocator::new().first_segment_words(1));
+    let mut root: node::Builder = builder.init_root();
+    root.set_id(0xabcd);
+    root.set_display_name("lazy.capnp".into());
+    let segments = builder.get_segments_for_output();
+    assert_eq!(segments.len(), 3);
+    LazySegments::new(&segments)
+}
+
+#[test]
+fn segment_is_fetched_when_far_pointer_is_followed() {
+    let segments = three_segment_message();
+    assert_eq!(segments.len(), 3);
+    let message = message::Reader::new(&segments, ReaderOptions::new());
+    message.get_root::<capnp::any_pointer::Reader>().unwrap();
+    assert_eq!(segments.fetch_count.load(Ordering::Relaxed), 0);
+
+    let root: node::Reader = message.get_root().unwrap();
+    assert_eq!(root.get_id(), 0xabcd);
+    assert_eq!(segments.fetch_count.load(Ordering::Relaxed), 1);
+    assert_eq!(root.get_display_name().unwrap(), "lazy.capnp");
+    assert_eq!(segments.fetch_count.load(Ordering::Relaxed), 2);
+
+    // Once fetched, a segment is not fetched again.
+    let again: node::Reader = message.get_root().unwrap();
+    assert_eq!(again.get_display_name().unwrap(), "lazy.capnp");
+    assert_eq!(segments.fetch_count.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn fetch_failure_is_disconnected_and_can_be_retried() {
+    let segments = three_segment_message();
+    segments.store_available.store(false, Ordering::Relaxed);
+    let message = message::Reader::new(&segments, ReaderOptions::new());
+    let error = message.get_root::<node::Reader>().unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Disconnected);
+    assert!(
+        error.to_string().contains("block store is unreachable"),
+        "{error}"
+    );
+    assert_eq!(segments.fetch_count.load(Ordering::Relaxed), 0);
+
+    segments.store_available.store(true, Ordering::Relaxed);
+    let root: node::Reader = message.get_root().unwrap();
+    assert_eq!(root.get_display_name().unwrap(), "lazy.capnp");
+}
+
+#[test]
+fn missing_segment_is_still_invalid() {
+    let mut segments = three_segment_message();
+    segments.store.clear();
+    segments.fetched.clear();
+    let message = message::Reader::new(&segments, ReaderOptions::new());
+    let error = message.get_root::<node::Reader>().unwrap_err();
+    assert_eq!(error.kind, ErrorKind::InvalidSegmentId(1));
+}
+
+#[test]
+fn forwarding_fetches_segments_and_reports_failure() {
+    let segments = three_segment_message();
+    segments.store_available.store(false, Ordering::Relaxed);
+    let message = message::Reader::new(&segments, ReaderOptions::new());
+    let error = message.as_output_segments().unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Disconnected);
+
+    segments.store_available.store(true, Ordering::Relaxed);
+    let output = message.as_output_segments().unwrap();
+    assert_eq!(output.len(), 3);
+    assert_eq!(segments.fetch_count.load(Ordering::Relaxed), 2);
+}
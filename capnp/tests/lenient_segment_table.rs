@@ -0,0 +1,296 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, AllocationStrategy, HeapAllocator, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::{serialize, text, text_list, word, ErrorKind, Word};
+
+/// Messages in the framing of the archived files: the segment table of each is followed by a
+/// zero word that it does not need, and the buffer ends where the message does. They are built
+/// by hand to that description, rather than written by the implementation that produced them.
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/padded_segment_table");
+
+/// The text "hi", in one segment.
+const ONE_SEGMENT: &str = "one_segment.bin";
+
+/// A list of the texts "one" and "two", each in a segment of its own, reached through far
+/// pointers from the list in the first of three segments.
+const THREE_SEGMENTS: &str = "three_segments.bin";
+
+/// A `schema.capnp` `Node` whose id is 42 and whose display name is "go", in one segment, as
+/// the Go implementation lays out structs.
+const ONE_SEGMENT_STRUCT: &str = "one_segment_struct.bin";
+
+/// Like `ONE_SEGMENT`, but with two words of padding.
+const TWO_PADDING_WORDS: &str = "one_segment_two_padding_words.bin";
+
+/// Like the `ONE_SEGMENT` fixture, but the extra word is not zero.
+const NONZERO_PADDING: &[Word] = &[
+    word(0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00),
+    word(0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+    word(0x01, 0x00, 0x00, 0x00, 0x1a, 0x00, 0x00, 0x00),
+    word(b'h', b'i', 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+];
+
+/// Reads a fixture into a buffer that is aligned as a reader requires.
+fn fixture(name: &str) -> Vec<Word> {
+    let bytes = std::fs::read(format!("{FIXTURES}/{name}")).unwrap();
+    assert_eq!(bytes.len() % 8, 0);
+    let mut words = Word::allocate_zeroed_vec(bytes.len() / 8);
+    Word::words_to_bytes_mut(&mut words).copy_from_slice(&bytes);
+    words
+}
+
+fn lenient() -> ReaderOptions {
+    let mut options = ReaderOptions::new();
+    options.lenient_segment_table(true);
+    options
+}
+
+fn legacy() -> ReaderOptions {
+    let mut options = ReaderOptions::new();
+    options.tolerate_legacy_padding(true);
+    options
+}
+
+/// Serializes a list of `len` texts, split across segments of at most `segment_words` words, and
+/// inserts an extra zero word after the segment table.
+fn padded_list_message(len: u32, segment_words: u32) -> (Vec<u8>, usize) {
+    let mut builder = message::Builder::new(
+        HeapAllocator::new()
+            .first_segment_words(segment_words)
+            .allocation_strategy(AllocationStrategy::FixedSize),
+    );
+    {
+        let mut list: text_list::Builder = builder.initn_root(len);
+        for idx in 0..len {
+            list.set(idx, format!("item {idx}").as_str().into());
+        }
+    }
+    let segment_count = builder.get_segments_for_output().len();
+    let mut bytes = serialize::write_message_to_words(&builder);
+    let table_bytes = (segment_count / 2 + 1) * 8;
+    bytes.splice(table_bytes..table_bytes, [0; 8]);
+    (bytes, segment_count)
+}
+
+fn check_list(message: &message::Reader<impl message::ReaderSegments>, len: u32) {
+    let list: text_list::Reader = message.get_root().unwrap();
+    assert_eq!(list.len(), len);
+    for idx in 0..len {
+        assert_eq!(list.get(idx).unwrap(), format!("item {idx}").as_str());
+    }
+}
+
+fn check_three_segments(message: &message::Reader<impl message::ReaderSegments>) {
+    let list: text_list::Reader = message.get_root().unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list.get(0).unwrap(), "one");
+    assert_eq!(list.get(1).unwrap(), "two");
+}
+
+#[test]
+fn strict_by_default() {
+    for name in [
+        ONE_SEGMENT,
+        THREE_SEGMENTS,
+        ONE_SEGMENT_STRUCT,
+        TWO_PADDING_WORDS,
+    ] {
+        let words = fixture(name);
+        let bytes = Word::words_to_bytes(&words);
+        let error = serialize::read_message_from_flat_slice(&mut &bytes[..], ReaderOptions::new())
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.kind,
+            ErrorKind::SegmentTableFollowedByPadding,
+            "{name}"
+        );
+        let error = serialize::BufferSegments::new(bytes, ReaderOptions::new())
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.kind,
+            ErrorKind::SegmentTableFollowedByPadding,
+            "{name}"
+        );
+    }
+
+    // Without the padding, the same messages are read as they are.
+    let words = fixture(ONE_SEGMENT);
+    let mut bytes = Word::words_to_bytes(&words).to_vec();
+    bytes.drain(8..16);
+    let message =
+        serialize::read_message_from_flat_slice(&mut &bytes[..], ReaderOptions::new()).unwrap();
+    assert_eq!(message.get_root::<text::Reader>().unwrap(), "hi");
+}
+
+#[test]
+fn lenient_flat_slice() {
+    let words = fixture(ONE_SEGMENT);
+    let mut slice = Word::words_to_bytes(&words);
+    let message = serialize::read_message_from_flat_slice(&mut slice, lenient()).unwrap();
+    let root: text::Reader = message.get_root().unwrap();
+    assert_eq!(root, "hi");
+    assert!(slice.is_empty());
+
+    let words = fixture(TWO_PADDING_WORDS);
+    let message =
+        serialize::read_message_from_flat_slice(&mut Word::words_to_bytes(&words), lenient())
+            .unwrap();
+    assert_eq!(message.get_root::<text::Reader>().unwrap(), "hi");
+}
+
+#[test]
+fn lenient_buffer_segments() {
+    let words = fixture(ONE_SEGMENT);
+    let segments = serialize::BufferSegments::new(Word::words_to_bytes(&words), lenient()).unwrap();
+    let message = message::Reader::new(segments, lenient());
+    let root: text::Reader = message.get_root().unwrap();
+    assert_eq!(root, "hi");
+}
+
+#[test]
+fn lenient_multiple_segments() {
+    let words = fixture(THREE_SEGMENTS);
+    let bytes = Word::words_to_bytes(&words);
+    let message = serialize::read_message_from_flat_slice(&mut &bytes[..], lenient()).unwrap();
+    check_three_segments(&message);
+    let message = message::Reader::new(
+        serialize::BufferSegments::new(bytes, lenient()).unwrap(),
+        lenient(),
+    );
+    check_three_segments(&message);
+
+    let (bytes, segment_count) = padded_list_message(10, 4);
+    assert_eq!(segment_count % 2, 1);
+
+    let message = serialize::read_message_from_flat_slice(&mut &bytes[..], lenient()).unwrap();
+    check_list(&message, 10);
+
+    let message = message::Reader::new(
+        serialize::BufferSegments::new(&bytes[..], lenient()).unwrap(),
+        lenient(),
+    );
+    check_list(&message, 10);
+}
+
+#[test]
+fn lenient_accepts_well_formed_messages() {
+    let mut builder = message::Builder::new_default();
+    builder.set_root("hi").unwrap();
+    let bytes = serialize::write_message_to_words(&builder);
+    let message = serialize::read_message_from_flat_slice(&mut &bytes[..], lenient()).unwrap();
+    let root: text::Reader = message.get_root().unwrap();
+    assert_eq!(root, "hi");
+}
+
+#[test]
+fn lenient_rejects_inconsistent_tables() {
+    // A nonzero extra word is not skipped, with or without the option.
+    for options in [ReaderOptions::new(), lenient()] {
+        let mut slice = Word::words_to_bytes(NONZERO_PADDING);
+        let message = serialize::read_message_from_flat_slice(&mut slice, options).unwrap();
+        assert!(message.get_root::<text::Reader>().is_err());
+    }
+
+    // The padding is not skipped unless the message ends exactly where it would if it were.
+    let words = fixture(ONE_SEGMENT);
+    let mut bytes = Word::words_to_bytes(&words).to_vec();
+    bytes.extend_from_slice(&[0; 8]);
+    let mut slice = &bytes[..];
+    let message = serialize::read_message_from_flat_slice(&mut slice, lenient()).unwrap();
+    assert_eq!(message.get_root::<text::Reader>().unwrap(), "");
+    assert_eq!(slice.len(), 16);
+
+    // A truncated message is still rejected.
+    let bytes = Word::words_to_bytes(&words);
+    let error = serialize::read_message_from_flat_slice(&mut &bytes[..16], lenient())
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error.kind,
+        ErrorKind::MessageEndsPrematurely(2, 1)
+    ));
+}
+
+#[test]
+fn even_segment_counts() {
+    // The table for an even number of segments is already padded to a word boundary; a further
+    // word of padding is treated the same way.
+    let (bytes, segment_count) = padded_list_message(4, 4);
+    assert_eq!(segment_count % 2, 0);
+    let message = serialize::read_message_from_flat_slice(&mut &bytes[..], lenient()).unwrap();
+    check_list(&message, 4);
+
+    let error = serialize::read_message_from_flat_slice(&mut &bytes[..], ReaderOptions::new())
+        .err()
+        .unwrap();
+    assert_eq!(error.kind, ErrorKind::SegmentTableFollowedByPadding);
+}
+
+#[test]
+fn tolerate_legacy_padding() {
+    let words = fixture(ONE_SEGMENT);
+    let mut slice = Word::words_to_bytes(&words);
+    let message = serialize::read_message_from_flat_slice(&mut slice, legacy()).unwrap();
+    assert_eq!(message.get_root::<text::Reader>().unwrap(), "hi");
+    assert!(slice.is_empty());
+
+    let words = fixture(THREE_SEGMENTS);
+    let bytes = Word::words_to_bytes(&words);
+    let message = serialize::read_message_from_flat_slice(&mut &bytes[..], legacy()).unwrap();
+    check_three_segments(&message);
+    let message = message::Reader::new(
+        serialize::BufferSegments::new(bytes, legacy()).unwrap(),
+        legacy(),
+    );
+    check_three_segments(&message);
+
+    let words = fixture(ONE_SEGMENT_STRUCT);
+    let message =
+        serialize::read_message_from_flat_slice(&mut Word::words_to_bytes(&words), legacy())
+            .unwrap();
+    let root: node::Reader = message.get_root().unwrap();
+    assert_eq!(root.get_id(), 42);
+    assert_eq!(root.get_display_name().unwrap(), "go");
+
+    let (bytes, _) = padded_list_message(10, 4);
+    let message = serialize::read_message_from_flat_slice(&mut &bytes[..], legacy()).unwrap();
+    check_list(&message, 10);
+}
+
+#[test]
+fn tolerate_legacy_padding_only() {
+    // More than one word of padding.
+    let words = fixture(TWO_PADDING_WORDS);
+    let error =
+        serialize::read_message_from_flat_slice(&mut Word::words_to_bytes(&words), legacy())
+            .err()
+            .unwrap();
+    assert_eq!(error.kind, ErrorKind::SegmentTableFollowedByPadding);
+
+    // Padding after a table for an even number of segments.
+    let (bytes, _) = padded_list_message(4, 4);
+    let error = serialize::read_message_from_flat_slice(&mut &bytes[..], legacy())
+        .err()
+        .unwrap();
+    assert_eq!(error.kind, ErrorKind::SegmentTableFollowedByPadding);
+
+    // Where the buffer is longer than the message even with the padding, the word is not
+    // skipped.
+    let words = fixture(ONE_SEGMENT);
+    let mut bytes = Word::words_to_bytes(&words).to_vec();
+    bytes.extend_from_slice(&[0; 8]);
+    let mut slice = &bytes[..];
+    let message = serialize::read_message_from_flat_slice(&mut slice, legacy()).unwrap();
+    assert_eq!(message.get_root::<text::Reader>().unwrap(), "");
+    assert_eq!(slice.len(), 16);
+
+    // Well-formed messages are read as they are.
+    let mut builder = message::Builder::new_default();
+    builder.set_root("hi").unwrap();
+    let bytes = serialize::write_message_to_words(&builder);
+    let message = serialize::read_message_from_flat_slice(&mut &bytes[..], legacy()).unwrap();
+    assert_eq!(message.get_root::<text::Reader>().unwrap(), "hi");
+}
@@ -0,0 +1,109 @@
+#![cfg(feature = "alloc")]
+
+use capnp::schema_capnp::{capnp_version, node};
+use capnp::{message, primitive_list, struct_list};
+
+#[test]
+fn struct_cursor_visits_every_element() {
+    let mut message = message::Builder::new_default();
+    let mut nodes: struct_list::Builder<node::Owned> = message.initn_root(5);
+    let mut cursor = nodes.cursor();
+    assert_eq!(cursor.remaining(), 5);
+    while let Some(mut node) = cursor.next() {
+        let id = node.reborrow().get_id();
+        node.set_id(100 + id);
+    }
+    assert_eq!(cursor.remaining(), 0);
+    assert_eq!(cursor.position(), 5);
+    assert!(cursor.next().is_none());
+
+    for idx in 0..5 {
+        assert_eq!(nodes.reborrow().get(idx).get_id(), 100);
+    }
+}
+
+#[test]
+fn struct_cursor_interleaved_with_indexed_access() {
+    let mut message = message::Builder::new_default();
+    let mut versions: struct_list::Builder<capnp_version::Owned> = message.initn_root(6);
+    for idx in 0..6 {
+        versions.reborrow().get(idx).set_major(idx as u16);
+    }
+
+    // Write the even elements through indexed access and the odd ones through cursors, and
+    // check that each sees the other's writes.
+    for round in 0..3u8 {
+        for idx in (0..6).step_by(2) {
+            versions.reborrow().get(idx).set_minor(round);
+        }
+        let mut cursor = versions.cursor();
+        while let Some(mut version) = cursor.next() {
+            let idx = u32::from(version.reborrow().get_major());
+            if idx % 2 == 1 {
+                version.set_minor(round);
+            } else {
+                assert_eq!(version.get_minor(), round);
+            }
+        }
+        for idx in 0..6 {
+            let version = versions.reborrow().get(idx).into_reader();
+            assert_eq!(
+                (u32::from(version.get_major()), version.get_minor()),
+                (idx, round)
+            );
+        }
+    }
+
+    // A cursor stopped partway leaves the rest of the list alone.
+    let mut cursor = versions.cursor();
+    cursor.next().unwrap().set_micro(9);
+    assert_eq!(cursor.position(), 1);
+    assert_eq!(cursor.remaining(), 5);
+    let micros: Vec<u8> = (0..6)
+        .map(|idx| versions.reborrow().get(idx).get_micro())
+        .collect();
+    assert_eq!(micros, [9, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+fn primitive_cursor() {
+    let mut message = message::Builder::new_default();
+    let mut list: primitive_list::Builder<u32> = message.initn_root(4);
+    list.set(3, 7);
+    let mut cursor = list.cursor();
+    assert_eq!(cursor.write_next(1), Some(()));
+    assert_eq!(cursor.write_next(2), Some(()));
+    assert_eq!(cursor.remaining(), 2);
+    assert_eq!(list.get(3), 7);
+    assert_eq!(
+        list.reborrow().into_reader().iter().collect::<Vec<_>>(),
+        [1, 2, 0, 7]
+    );
+
+    let mut cursor = list.cursor();
+    for value in 10..14 {
+        assert_eq!(cursor.write_next(value), Some(()));
+    }
+    assert_eq!(cursor.write_next(14), None);
+    assert_eq!(cursor.position(), 4);
+    assert_eq!(
+        list.into_reader().iter().collect::<Vec<_>>(),
+        [10, 11, 12, 13]
+    );
+}
+
+#[test]
+fn bool_cursor() {
+    let mut message = message::Builder::new_default();
+    let mut list: primitive_list::Builder<bool> = message.initn_root(10);
+    let mut cursor = list.cursor();
+    for idx in 0..10 {
+        cursor.write_next(idx % 3 == 0).unwrap();
+    }
+    assert!(cursor.write_next(true).is_none());
+    let values: Vec<bool> = list.into_reader().iter().collect();
+    assert_eq!(
+        values,
+        [true, false, false, true, false, false, true, false, false, true]
+    );
+}
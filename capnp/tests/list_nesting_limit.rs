@@ -0,0 +1,132 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::type_;
+use capnp::{list_list, primitive_list, ErrorKind};
+
+type Bytes = primitive_list::Owned<u8>;
+type ListListList<'a> = list_list::Reader<'a, list_list::Owned<list_list::Owned<Bytes>>>;
+
+fn read<T>(
+    builder: &message::Builder<message::HeapAllocator>,
+    options: ReaderOptions,
+    f: impl FnOnce(message::Reader<message::SegmentArray>) -> T,
+) -> T {
+    let segments = builder.get_segments_for_output();
+    f(message::Reader::new(
+        message::SegmentArray::new(&segments),
+        options,
+    ))
+}
+
+/// A `List(List(List(List(UInt8))))` holding a single `[1, 2, 3]`.
+fn nested_lists() -> message::Builder<message::HeapAllocator> {
+    let mut builder = message::Builder::new_default();
+    let root: list_list::Builder<list_list::Owned<list_list::Owned<Bytes>>> = builder.initn_root(1);
+    let mut bytes = root.init(0, 1).init(0, 1).init(0, 3);
+    for (idx, value) in [1, 2, 3].into_iter().enumerate() {
+        bytes.set(idx as u32, value);
+    }
+    builder
+}
+
+/// Three lists of lists deep, to reach the bytes.
+fn innermost(root: ListListList) -> capnp::Result<Vec<u8>> {
+    let bytes = root.get(0)?.get(0)?.get(0)?;
+    Ok(bytes.iter().collect())
+}
+
+/// A `Type` that is a list of a list of ... of `Void`, `depth` structs deep.
+fn nested_structs(depth: u32) -> message::Builder<message::HeapAllocator> {
+    let mut builder = message::Builder::new_default();
+    let mut ty: type_::Builder = builder.init_root();
+    for _ in 1..depth {
+        ty = ty.init_list().init_element_type();
+    }
+    ty.set_void(());
+    builder
+}
+
+fn struct_depth(mut ty: type_::Reader) -> capnp::Result<u32> {
+    let mut depth = 1;
+    while let Ok(type_::List(list)) = ty.which() {
+        ty = list.get_element_type()?;
+        depth += 1;
+    }
+    Ok(depth)
+}
+
+fn options(nesting_limit: i32, list_nesting_limit: Option<i32>) -> ReaderOptions {
+    let mut options = ReaderOptions::new();
+    options
+        .nesting_limit(nesting_limit)
+        .list_nesting_limit(list_nesting_limit);
+    options
+}
+
+#[test]
+fn default_is_only_the_nesting_limit() {
+    assert_eq!(ReaderOptions::new().list_nesting_limit, None);
+    let lists = nested_lists();
+    let values = read(&lists, ReaderOptions::new(), |message| {
+        innermost(message.get_root().unwrap())
+    });
+    assert_eq!(values.unwrap(), [1, 2, 3]);
+}
+
+#[test]
+fn list_nesting_limit_binds_on_lists() {
+    let lists = nested_lists();
+    let values = read(&lists, options(1000, Some(3)), |message| {
+        innermost(message.get_root().unwrap())
+    });
+    assert_eq!(values.unwrap(), [1, 2, 3]);
+
+    let error = read(&lists, options(1000, Some(2)), |message| {
+        innermost(message.get_root().unwrap())
+    })
+    .unwrap_err();
+    assert_eq!(error.kind, ErrorKind::NestingLimitExceeded);
+
+    // `try_get()` is charged too.
+    let error = read(&lists, options(1000, Some(0)), |message| {
+        let root: ListListList = message.get_root().unwrap();
+        root.try_get(0).unwrap().map(|_| ())
+    })
+    .unwrap_err();
+    assert_eq!(error.kind, ErrorKind::NestingLimitExceeded);
+}
+
+#[test]
+fn list_nesting_limit_does_not_bind_on_structs() {
+    let structs = nested_structs(50);
+    let depth = read(&structs, options(100, Some(1)), |message| {
+        struct_depth(message.get_root().unwrap())
+    });
+    assert_eq!(depth.unwrap(), 50);
+}
+
+#[test]
+fn nesting_limit_still_binds_on_both() {
+    let structs = nested_structs(50);
+    let error = read(&structs, options(20, Some(1000)), |message| {
+        struct_depth(message.get_root()?)
+    })
+    .unwrap_err();
+    assert_eq!(
+        error.kind,
+        ErrorKind::MessageIsTooDeeplyNestedOrContainsCycles
+    );
+
+    // The root and each of the three inner lists count against the nesting limit.
+    let lists = nested_lists();
+    let error = read(&lists, options(3, Some(1000)), |message| {
+        innermost(message.get_root()?)
+    })
+    .unwrap_err();
+    assert_eq!(error.kind, ErrorKind::NestingLimitExceeded);
+    let values = read(&lists, options(4, Some(1000)), |message| {
+        innermost(message.get_root()?)
+    });
+    assert_eq!(values.unwrap(), [1, 2, 3]);
+}
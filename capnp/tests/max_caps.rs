@@ -0,0 +1,112 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use capnp::message::{self, ReaderOptions};
+use capnp::private::capability::ClientHook;
+use capnp::private::layout::CapTable;
+use capnp::traits::{Imbue, ImbueMut};
+use capnp::{any_pointer, any_pointer_list, serialize, word, ErrorKind, Word};
+
+use common::Hook;
+
+const CAP_COUNT: u32 = 1000;
+const LIMIT: u32 = 16;
+
+/// A serialized list of `CAP_COUNT` capability pointers, along with the cap table they index.
+fn many_caps() -> (Vec<u8>, CapTable) {
+    let mut caps = CapTable::new();
+    let mut builder = message::Builder::new_default();
+    {
+        let mut root: any_pointer::Builder = builder.init_root();
+        root.imbue_mut(&mut caps);
+        let mut list: any_pointer_list::Builder = root.initn_as(CAP_COUNT);
+        for idx in 0..CAP_COUNT {
+            list.reborrow()
+                .get(idx)
+                .set_as_capability(Box::new(Hook(idx as usize)));
+        }
+    }
+    assert_eq!(caps.len(), CAP_COUNT as usize);
+    (serialize::write_message_to_words(&builder), caps)
+}
+
+fn cap_at(
+    message: &message::Reader<serialize::OwnedSegments>,
+    caps: &CapTable,
+    index: u32,
+) -> capnp::Result<usize> {
+    let mut root: any_pointer::Reader = message.get_root()?;
+    root.imbue(caps);
+    let list: any_pointer_list::Reader = root.get_as()?;
+    Ok(list.get(index).get_pipelined_cap(&[])?.get_ptr())
+}
+
+#[test]
+fn oversized_cap_table_is_rejected() {
+    let (bytes, caps) = many_caps();
+    let mut options = ReaderOptions::new();
+    options.max_caps(Some(LIMIT));
+    let message = serialize::read_message(&bytes[..], options).unwrap();
+
+    // The table has more entries than the limit, so it is refused when imbued, and no
+    // capability can be read through it, however small its index.
+    for idx in [0, LIMIT, CAP_COUNT - 1] {
+        let error = cap_at(&message, &caps, idx).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Overloaded);
+        assert!(error.extra.is_empty());
+    }
+    assert_eq!(message.cap_count_seen(), 0);
+}
+
+/// A list of `CAP_COUNT` capability pointers that refer to just `LIMIT` distinct entries of the
+/// cap table, pointer `i` to entry `i % LIMIT`, along with that table.
+fn repeated_caps() -> (Vec<Word>, CapTable) {
+    let mut words = vec![word(1, 0, 0, 0, 0, 0, 0, 0); 1 + CAP_COUNT as usize];
+    // A list of pointers, right after the root pointer.
+    let tag = (CAP_COUNT << 3 | 6).to_le_bytes();
+    words[0] = word(1, 0, 0, 0, tag[0], tag[1], tag[2], tag[3]);
+    for idx in 0..CAP_COUNT {
+        let cap = (idx % LIMIT).to_le_bytes();
+        words[1 + idx as usize] = word(3, 0, 0, 0, cap[0], cap[1], cap[2], cap[3]);
+    }
+    let caps = (0..LIMIT as usize)
+        .map(|idx| Some(Box::new(Hook(idx)) as Box<dyn ClientHook>))
+        .collect();
+    (words, caps)
+}
+
+#[test]
+fn distinct_caps_are_counted() {
+    let (words, caps) = repeated_caps();
+    let segments = [Word::words_to_bytes(&words)];
+    let mut options = ReaderOptions::new();
+    options.max_caps(Some(LIMIT));
+    let message = message::Reader::new(message::SegmentArray::new(&segments), options);
+    assert_eq!(message.cap_count_seen(), 0);
+
+    let mut root: any_pointer::Reader = message.get_root().unwrap();
+    root.imbue(&caps);
+    let list: any_pointer_list::Reader = root.get_as().unwrap();
+    for idx in [7, 0, 7 + LIMIT, 15, 0] {
+        let cap = list.get(idx).get_pipelined_cap(&[]).unwrap();
+        assert_eq!(cap.get_ptr(), (idx % LIMIT) as usize);
+    }
+    assert_eq!(message.cap_count_seen(), 3);
+
+    // All of the pointers together refer to no more capabilities than the limit.
+    for idx in 0..CAP_COUNT {
+        list.get(idx).get_pipelined_cap(&[]).unwrap();
+    }
+    assert_eq!(message.cap_count_seen(), LIMIT);
+}
+
+#[test]
+fn no_limit_by_default() {
+    let (bytes, caps) = many_caps();
+    let message = serialize::read_message(&bytes[..], ReaderOptions::new()).unwrap();
+    for idx in 0..CAP_COUNT {
+        assert_eq!(cap_at(&message, &caps, idx).unwrap(), idx as usize);
+    }
+    assert_eq!(message.cap_count_seen(), 0);
+}
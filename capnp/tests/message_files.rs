@@ -0,0 +1,125 @@
+#![cfg(all(feature = "std", feature = "alloc"))]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use capnp::message::{self, HeapAllocator, ReaderOptions};
+use capnp::serialize::{self, OwnedSegments};
+use capnp::{text_list, ErrorKind};
+
+/// A fresh directory for the named test.
+fn test_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("capnp-message-files-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    temp.into()
+}
+
+fn build(items: &[&str]) -> message::Builder<HeapAllocator> {
+    let mut builder = message::Builder::new_default();
+    {
+        let mut list: text_list::Builder = builder.initn_root(items.len() as u32);
+        for (idx, item) in items.iter().enumerate() {
+            list.set(idx as u32, (*item).into());
+        }
+    }
+    builder
+}
+
+fn items(message: &message::Reader<OwnedSegments>) -> Vec<String> {
+    let list: text_list::Reader = message.get_root().unwrap();
+    list.iter()
+        .map(|item| item.unwrap().to_string().unwrap())
+        .collect()
+}
+
+#[test]
+fn round_trip() {
+    let dir = test_dir("round_trip");
+    let plain = dir.join("plain.bin");
+    let packed = dir.join("packed.bin");
+    let builder = build(&["a", "b", "c"]);
+    serialize::write_message_to_file(&plain, &builder).unwrap();
+    serialize::write_packed_message_to_file(&packed, &builder).unwrap();
+    assert!(!temp_path(&plain).exists());
+    assert!(!temp_path(&packed).exists());
+
+    assert_eq!(
+        fs::read(&plain).unwrap(),
+        serialize::write_message_to_words(&builder)
+    );
+    assert!(fs::metadata(&packed).unwrap().len() < fs::metadata(&plain).unwrap().len());
+    let message = serialize::read_message_from_file(&plain, ReaderOptions::new()).unwrap();
+    assert_eq!(items(&message), ["a", "b", "c"]);
+    let message = serialize::read_packed_message_from_file(&packed, ReaderOptions::new()).unwrap();
+    assert_eq!(items(&message), ["a", "b", "c"]);
+
+    // Writing again replaces the file.
+    serialize::write_message_to_file(&plain, &build(&["d"])).unwrap();
+    let message = serialize::read_message_from_file(&plain, ReaderOptions::new()).unwrap();
+    assert_eq!(items(&message), ["d"]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn crash_leaves_original_untouched() {
+    let dir = test_dir("crash");
+    let path = dir.join("message.bin");
+    serialize::write_message_to_file(&path, &build(&["original"])).unwrap();
+
+    // A crash part way through a later write leaves a truncated temporary file behind.
+    let update = serialize::write_message_to_words(&build(&["update", "that", "was", "cut off"]));
+    fs::write(temp_path(&path), &update[..update.len() / 2]).unwrap();
+    let message = serialize::read_message_from_file(&path, ReaderOptions::new()).unwrap();
+    assert_eq!(items(&message), ["original"]);
+    assert_eq!(
+        serialize::read_message_from_file(&temp_path(&path), ReaderOptions::new())
+            .err()
+            .unwrap()
+            .kind,
+        ErrorKind::FailedToFillTheWholeBuffer
+    );
+
+    // The next write replaces the leftover.
+    serialize::write_message_to_file(&path, &build(&["retried"])).unwrap();
+    assert!(!temp_path(&path).exists());
+    let message = serialize::read_message_from_file(&path, ReaderOptions::new()).unwrap();
+    assert_eq!(items(&message), ["retried"]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn failed_write_cleans_up() {
+    let dir = test_dir("failed");
+    // Renaming a file over a non-empty directory fails.
+    let path = dir.join("occupied");
+    fs::create_dir_all(path.join("child")).unwrap();
+    assert!(serialize::write_message_to_file(&path, &build(&["x"])).is_err());
+    assert!(!temp_path(&path).exists());
+    assert!(path.join("child").is_dir());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn trailing_bytes_are_rejected() {
+    let dir = test_dir("trailing");
+    let path = dir.join("message.bin");
+    let mut bytes = serialize::write_message_to_words(&build(&["a"]));
+    bytes.extend_from_slice(&[0; 8]);
+    fs::write(&path, bytes).unwrap();
+    assert_eq!(
+        serialize::read_message_from_file(&path, ReaderOptions::new())
+            .err()
+            .unwrap()
+            .kind,
+        ErrorKind::Failed
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::serialize::{self, MessageIterator};
+use capnp::text;
+
+/// Messages whose roots are the texts "message 0", "message 1", and so on. The even ones take
+/// many segments, so that their segment tables do not fit on the stack.
+fn stream(count: usize) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for idx in 0..count {
+        let mut builder = message::Builder::new_default();
+        builder
+            .set_root(text::Reader::from(&*format!("message {idx}")))
+            .unwrap();
+        let mut segments: Vec<&[u8]> = vec![&builder.get_segments_for_output()[0]];
+        if idx % 2 == 0 {
+            segments.extend([&[][..]; 8]);
+        }
+        serialize::write_message_segments(&mut bytes, &message::SegmentArray::new(&segments))
+            .unwrap();
+    }
+    bytes
+}
+
+fn root_text(message: &message::Reader<serialize::OwnedSegments>) -> String {
+    message
+        .get_root::<text::Reader>()
+        .unwrap()
+        .to_string()
+        .unwrap()
+}
+
+#[test]
+fn reads_every_message_then_stops() {
+    let bytes = stream(5);
+    let mut messages = MessageIterator::new(&bytes[..], ReaderOptions::new());
+    for idx in 0..5 {
+        let message = messages.next().unwrap().unwrap();
+        assert_eq!(root_text(&message), format!("message {idx}"));
+    }
+    assert!(messages.next().is_none());
+    assert!(messages.next().is_none());
+}
+
+#[test]
+fn empty_stream_has_no_messages() {
+    let mut messages = MessageIterator::new(&[][..], ReaderOptions::new());
+    assert!(messages.next().is_none());
+}
+
+#[test]
+fn truncated_message_is_an_error() {
+    let whole = stream(3);
+    let two = stream(2).len();
+    // Cut inside the first word of the segment table, inside the table, and inside the segments.
+    for len in [two + 4, two + 12, whole.len() - 1] {
+        let mut messages = MessageIterator::new(&whole[..len], ReaderOptions::new());
+        assert_eq!(root_text(&messages.next().unwrap().unwrap()), "message 0");
+        assert_eq!(root_text(&messages.next().unwrap().unwrap()), "message 1");
+        assert!(messages.next().unwrap().is_err(), "cut at {len}");
+        assert!(messages.next().is_none());
+    }
+}
+
+#[test]
+fn malformed_message_ends_iteration() {
+    let mut bytes = stream(1);
+    bytes.extend(serialize::segment_table_bytes(&[u32::MAX]));
+    bytes.extend(stream(1));
+    let mut messages = MessageIterator::new(&bytes[..], ReaderOptions::new());
+    messages.next().unwrap().unwrap();
+    assert!(messages.next().unwrap().is_err());
+    assert!(messages.next().is_none());
+}
+
+#[test]
+fn into_inner_is_positioned_after_last_message() {
+    let bytes = stream(2);
+    let first = stream(1).len();
+    let mut messages = MessageIterator::new(&bytes[..], ReaderOptions::new());
+    messages.next().unwrap().unwrap();
+    assert_eq!(messages.into_inner(), &bytes[first..]);
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::serialize::{self, MessageReadState, OwnedSegments};
+use capnp::ErrorKind;
+
+/// A serialized node, spread over several segments unless `first_segment_words` is large.
+fn serialized(id: u64, first_segment_words: u32) -> Vec<u8> {
+    let mut builder = message::Builder::new(
+        message::HeapAllocator::new().first_segment_words(first_segment_words),
+    );
+    let mut root: node::Builder = builder.init_root();
+    root.set_id(id);
+    root.set_display_name("message_read_state.capnp".into());
+    serialize::write_message_to_words(&builder)
+}
+
+fn id(message: message::Reader<OwnedSegments>) -> u64 {
+    let root: node::Reader = message.get_root().unwrap();
+    assert_eq!(root.get_display_name().unwrap(), "message_read_state.capnp");
+    root.get_id()
+}
+
+#[test]
+fn byte_at_a_time() {
+    let bytes = serialized(1, 1);
+    let mut state = MessageReadState::new(ReaderOptions::new());
+    assert!(!state.is_mid_message());
+    for (idx, byte) in bytes.iter().enumerate() {
+        let message = state.push(&[*byte]).unwrap();
+        if idx + 1 < bytes.len() {
+            assert!(message.is_none(), "{idx}");
+            assert!(state.is_mid_message());
+        } else {
+            assert_eq!(id(message.unwrap()), 1);
+        }
+    }
+    assert!(!state.is_mid_message());
+}
+
+#[test]
+fn chunks_spanning_messages() {
+    let mut stream = Vec::new();
+    for id in 0..5 {
+        stream.extend(serialized(id, if id % 2 == 0 { 1 } else { 1024 }));
+    }
+    for chunk_len in [3, 8, 13, 100, stream.len()] {
+        let mut state = MessageReadState::new(ReaderOptions::new());
+        let mut ids = Vec::new();
+        for chunk in stream.chunks(chunk_len) {
+            let mut message = state.push(chunk).unwrap();
+            while let Some(complete) = message {
+                ids.push(id(complete));
+                message = state.push(&[]).unwrap();
+            }
+        }
+        assert_eq!(ids, [0, 1, 2, 3, 4], "{chunk_len}");
+        assert!(!state.is_mid_message());
+    }
+}
+
+#[test]
+fn bad_segment_tables_are_errors() {
+    let mut state = MessageReadState::new(ReaderOptions::new());
+    // Zero segments, and too many.
+    for first_word in [[0xff; 8], [0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]] {
+        assert!(state.push(&first_word[..4]).unwrap().is_none());
+        let Err(error) = state.push(&first_word[4..]) else {
+            panic!("accepted a segment table starting with {first_word:?}");
+        };
+        assert!(
+            matches!(error.kind, ErrorKind::InvalidNumberOfSegments(_)),
+            "{error}"
+        );
+        assert!(!state.is_mid_message());
+    }
+
+    // A message larger than the traversal limit is rejected before its segments arrive.
+    let bytes = serialized(2, 1024);
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(Some(2));
+    let mut state = MessageReadState::new(options);
+    let Err(error) = state.push(&bytes[..8]) else {
+        panic!("accepted a message larger than the traversal limit");
+    };
+    assert!(
+        matches!(error.kind, ErrorKind::MessageTooLarge(_)),
+        "{error}"
+    );
+
+    // The state starts over after an error.
+    let mut state = MessageReadState::new(ReaderOptions::new());
+    assert!(state.push(&[0xff; 8]).is_err());
+    assert_eq!(id(state.push(&bytes).unwrap().unwrap()), 2);
+}
+
+#[test]
+fn truncated_stream() {
+    let bytes = serialized(3, 1);
+    let mut state = MessageReadState::new(ReaderOptions::new());
+    assert!(state.push(&bytes[..bytes.len() - 1]).unwrap().is_none());
+    assert!(state.is_mid_message());
+    assert_eq!(
+        id(state.push(&bytes[bytes.len() - 1..]).unwrap().unwrap()),
+        3
+    );
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message;
+use capnp::schema_capnp::ElementSize;
+use capnp::{enum_list, primitive_list, ErrorKind, NotInSchema};
+
+#[test]
+fn from_not_in_schema_names_the_type() {
+    let error = capnp::Error::from_not_in_schema(NotInSchema(42), "schema.capnp:ElementSize");
+    assert_eq!(
+        error.kind,
+        ErrorKind::EnumValueOrUnionDiscriminantNotPresent(NotInSchema(42))
+    );
+    assert_eq!(
+        error.to_string(),
+        "Enum value or union discriminant 42 was not present in schema: \
+         reading schema.capnp:ElementSize"
+    );
+
+    // The plain conversion still names only the value.
+    let error: capnp::Error = NotInSchema(42).into();
+    assert_eq!(
+        error.to_string(),
+        "Enum value or union discriminant 42 was not present in schema"
+    );
+}
+
+#[test]
+fn enum_list_get_with_context() {
+    let mut builder = message::Builder::new_default();
+    let mut values: primitive_list::Builder<u16> = builder.initn_root(3);
+    values.set(0, 1);
+    values.set(1, 42);
+    values.set(2, 7);
+    let list: enum_list::Reader<ElementSize> = builder.get_root_as_reader().unwrap();
+
+    assert_eq!(list.get_with_context(0).unwrap(), ElementSize::Bit);
+    assert_eq!(
+        list.get_with_context(2).unwrap(),
+        ElementSize::InlineComposite
+    );
+    assert_eq!(list.get(1), Err(NotInSchema(42)));
+
+    let error = list.get_with_context(1).unwrap_err();
+    assert_eq!(
+        error.kind,
+        ErrorKind::EnumValueOrUnionDiscriminantNotPresent(NotInSchema(42))
+    );
+    assert_eq!(
+        error.to_string(),
+        "Enum value or union discriminant 42 was not present in schema: \
+         reading capnp::schema_capnp::ElementSize, element 1 of an enum list"
+    );
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::capnp_version;
+use capnp::traits::Owned;
+use capnp::{any_pointer_list, primitive_list, struct_list, word, ErrorKind, Word};
+
+/// The number of words in each segment after the root pointer.
+const CONTENT_WORDS: usize = 2;
+
+fn pointer_word(lower: u32, upper: u32) -> Word {
+    let [b0, b1, b2, b3] = lower.to_le_bytes();
+    let [b4, b5, b6, b7] = upper.to_le_bytes();
+    word(b0, b1, b2, b3, b4, b5, b6, b7)
+}
+
+/// A message whose root is a list of `count` elements of size `element_size`, as encoded in a
+/// list pointer, followed by `CONTENT_WORDS` words of zeros.
+fn list_message(element_size: u32, count: u32) -> Vec<Word> {
+    let mut words = vec![pointer_word(1, (count << 3) | element_size)];
+    words.resize(1 + CONTENT_WORDS, word(0, 0, 0, 0, 0, 0, 0, 0));
+    words
+}
+
+/// A message whose root is an inline-composite list of `word_count` words, with a tag that says
+/// it holds `count` single-word structs. The tag takes up the first of the `CONTENT_WORDS`.
+fn struct_list_message(word_count: u32, count: u32) -> Vec<Word> {
+    let mut words = list_message(7, word_count);
+    words[1] = pointer_word(count << 2, 1);
+    words
+}
+
+/// Gets the root, without reading any elements.
+fn get_root<T: Owned>(words: &[Word]) -> capnp::Result<()> {
+    let segments = [Word::words_to_bytes(words)];
+    let message = message::Reader::new(message::SegmentArray::new(&segments), ReaderOptions::new());
+    message.get_root::<T::Reader<'_>>().map(drop)
+}
+
+/// Checks that a list of `fits` elements of size `element_size` can be read, and that declaring
+/// one more fails as soon as the list is obtained.
+fn check<T: Owned>(element_size: u32, fits: u32) {
+    get_root::<T>(&list_message(element_size, fits)).unwrap();
+    let error = get_root::<T>(&list_message(element_size, fits + 1)).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::MessageContainsOutOfBoundsPointer);
+    let error = get_root::<T>(&list_message(element_size, (1 << 29) - 1)).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::MessageContainsOutOfBoundsPointer);
+}
+
+#[test]
+fn bit() {
+    check::<primitive_list::Owned<bool>>(1, 64 * CONTENT_WORDS as u32);
+}
+
+#[test]
+fn byte() {
+    check::<primitive_list::Owned<u8>>(2, 8 * CONTENT_WORDS as u32);
+}
+
+#[test]
+fn two_bytes() {
+    check::<primitive_list::Owned<u16>>(3, 4 * CONTENT_WORDS as u32);
+}
+
+#[test]
+fn four_bytes() {
+    check::<primitive_list::Owned<u32>>(4, 2 * CONTENT_WORDS as u32);
+}
+
+#[test]
+fn eight_bytes() {
+    check::<primitive_list::Owned<u64>>(5, CONTENT_WORDS as u32);
+}
+
+#[test]
+fn pointer() {
+    check::<any_pointer_list::Owned>(6, CONTENT_WORDS as u32);
+}
+
+#[test]
+fn inline_composite() {
+    type Versions = struct_list::Owned<capnp_version::Owned>;
+    let fits = CONTENT_WORDS as u32 - 1;
+    get_root::<Versions>(&struct_list_message(fits, fits)).unwrap();
+
+    // The word count runs past the end of the segment.
+    let error = get_root::<Versions>(&struct_list_message(fits + 1, fits)).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::MessageContainsOutOfBoundsPointer);
+
+    // The tag declares more elements than the word count has room for.
+    let error = get_root::<Versions>(&struct_list_message(fits, fits + 1)).unwrap_err();
+    assert_eq!(
+        error.kind,
+        ErrorKind::InlineCompositeListsElementsOverrunItsWordCount
+    );
+}
@@ -0,0 +1,171 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, AllocationStrategy, HeapAllocator, ReaderOptions};
+use capnp::overlap::{Gap, Overlap, PathStep, PointerPath};
+use capnp::{any_pointer, text_list, word, ErrorKind, OverlapReport, Word};
+
+/// A struct whose two pointer fields point at the same one-word struct.
+const ALIASED: &[Word] = &[
+    // Root pointer: struct with no data and two pointers.
+    word(0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00),
+    // Struct with one data word, at word 3.
+    word(0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00),
+    // Struct with one data word, also at word 3.
+    word(0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00),
+    word(0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+];
+
+/// A struct whose only pointer field points back at the struct itself.
+const CYCLE: &[Word] = &[
+    // Root pointer: struct with no data and one pointer.
+    word(0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00),
+    // Struct with no data and one pointer, at word 1.
+    word(0xfc, 0xff, 0xff, 0xff, 0x00, 0x00, 0x01, 0x00),
+];
+
+/// A struct whose first pointer is a list of sixteen bytes, and whose second pointer is a struct
+/// that lives in the second word of the list.
+const PARTIAL: &[Word] = &[
+    // Root pointer: struct with no data and two pointers.
+    word(0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00),
+    // List of sixteen bytes, at word 3.
+    word(0x05, 0x00, 0x00, 0x00, 0x82, 0x00, 0x00, 0x00),
+    // Struct with one data word, at word 4.
+    word(0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00),
+    word(0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08),
+    word(0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10),
+];
+
+/// A struct with one data word, followed by a word that nothing points to.
+const TRAILING: &[Word] = &[
+    word(0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00),
+    word(0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+    word(0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff),
+];
+
+fn check(segment: &[Word], options: ReaderOptions) -> capnp::Result<OverlapReport> {
+    let segments = [Word::words_to_bytes(segment)];
+    let message = message::Reader::new(message::SegmentArray::new(&segments), options);
+    capnp::check_no_overlap(message.get_root::<any_pointer::Reader>()?)
+}
+
+fn path(steps: &[PathStep]) -> PointerPath {
+    PointerPath(steps.to_vec())
+}
+
+#[test]
+fn aliased_pointers() {
+    let report = check(ALIASED, ReaderOptions::new()).unwrap();
+    assert_eq!(
+        report.overlaps,
+        [Overlap {
+            segment_id: 0,
+            word_offset: 3,
+            word_count: 1,
+            first: path(&[PathStep::Pointer(0)]),
+            second: path(&[PathStep::Pointer(1)]),
+        }]
+    );
+    assert!(report.gaps.is_empty());
+    assert!(!report.is_clean());
+}
+
+#[test]
+fn cycle_is_reported_once() {
+    let report = check(CYCLE, ReaderOptions::new()).unwrap();
+    assert_eq!(
+        report.overlaps,
+        [Overlap {
+            segment_id: 0,
+            word_offset: 1,
+            word_count: 1,
+            first: path(&[]),
+            second: path(&[PathStep::Pointer(0)]),
+        }]
+    );
+    assert!(report.gaps.is_empty());
+}
+
+#[test]
+fn partial_overlap() {
+    let report = check(PARTIAL, ReaderOptions::new()).unwrap();
+    assert_eq!(report.overlaps.len(), 1);
+    let overlap = &report.overlaps[0];
+    assert_eq!((overlap.word_offset, overlap.word_count), (4, 1));
+    assert_eq!(overlap.first.to_string(), "root.0");
+    assert_eq!(overlap.second.to_string(), "root.1");
+}
+
+#[test]
+fn unreachable_words() {
+    let report = check(TRAILING, ReaderOptions::new()).unwrap();
+    assert!(report.overlaps.is_empty());
+    assert_eq!(
+        report.gaps,
+        [Gap {
+            segment_id: 0,
+            word_offset: 2,
+            word_count: 1,
+        }]
+    );
+}
+
+#[test]
+fn built_messages_are_clean() {
+    // Tiny segments force far pointers, whose landing pads count as occupied.
+    let mut builder = message::Builder::new(
+        HeapAllocator::new()
+            .first_segment_words(3)
+            .allocation_strategy(AllocationStrategy::FixedSize),
+    );
+    {
+        let mut list: text_list::Builder = builder.initn_root(5);
+        for idx in 0..5 {
+            list.set(idx, format!("item {idx}").as_str().into());
+        }
+    }
+    assert!(builder.get_segments_for_output().len() > 1);
+    let reader = builder.into_reader();
+    let report =
+        capnp::check_no_overlap(reader.get_root::<any_pointer::Reader>().unwrap()).unwrap();
+    assert!(report.is_clean(), "{report:?}");
+}
+
+#[test]
+fn overlaps_and_gaps_are_not_canonical() {
+    // is_canonical() walks the message in the same way, so it agrees about each of these.
+    for segment in [ALIASED, CYCLE, PARTIAL, TRAILING] {
+        let segments = [Word::words_to_bytes(segment)];
+        let message =
+            message::Reader::new(message::SegmentArray::new(&segments), ReaderOptions::new());
+        assert!(!message.is_canonical().unwrap());
+    }
+
+    let mut builder = message::Builder::new_default();
+    builder.set_root("canonical").unwrap();
+    let words = builder.into_reader().canonicalize().unwrap();
+    let segments = [Word::words_to_bytes(&words)];
+    let message = message::Reader::new(message::SegmentArray::new(&segments), ReaderOptions::new());
+    assert!(message.is_canonical().unwrap());
+    let report = capnp::check_no_overlap(message.get_root().unwrap()).unwrap();
+    assert!(report.is_clean(), "{report:?}");
+}
+
+#[test]
+fn respects_limits() {
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(Some(1));
+    assert_eq!(
+        check(PARTIAL, options).unwrap_err().kind,
+        ErrorKind::ReadLimitExceeded
+    );
+
+    // Out-of-bounds pointers are errors rather than overlaps.
+    let segments = [Word::words_to_bytes(&ALIASED[..3])];
+    let message = message::Reader::new(message::SegmentArray::new(&segments), ReaderOptions::new());
+    let root = message.get_root::<any_pointer::Reader>().unwrap();
+    assert_eq!(
+        capnp::check_no_overlap(root).unwrap_err().kind,
+        ErrorKind::MessageContainsOutOfBoundsPointer
+    );
+}
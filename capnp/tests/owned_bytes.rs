@@ -0,0 +1,54 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions, ReaderSegments};
+use capnp::{serialize, text_list, ErrorKind};
+
+fn message_bytes() -> Vec<u8> {
+    let mut builder = message::Builder::new(message::HeapAllocator::new().first_segment_words(4));
+    {
+        let mut list: text_list::Builder = builder.initn_root(10);
+        for idx in 0..10 {
+            list.set(idx, format!("item {idx}").as_str().into());
+        }
+    }
+    assert!(builder.get_segments_for_output().len() > 1);
+    serialize::write_message_to_bytes(&builder)
+}
+
+#[test]
+fn segments_borrow_the_vector() {
+    let bytes = message_bytes();
+    let range = bytes.as_ptr_range();
+    let message = serialize::read_message_from_owned_bytes(bytes, ReaderOptions::new()).unwrap();
+    {
+        let list: text_list::Reader = message.get_root().unwrap();
+        assert_eq!(list.len(), 10);
+        assert_eq!(list.get(9).unwrap(), "item 9");
+    }
+
+    let segments = message.into_segments();
+    for id in 0..ReaderSegments::len(&segments) as u32 {
+        let segment = segments.get_segment(id).unwrap().as_ptr_range();
+        assert!(range.start <= segment.start && segment.end <= range.end);
+    }
+    assert!(!segments.into_buffer().was_copied());
+}
+
+#[test]
+fn trailing_bytes_are_ignored() {
+    let mut bytes = message_bytes();
+    bytes.extend([0xff; 11]);
+    let message = serialize::read_message_from_owned_bytes(bytes, ReaderOptions::new()).unwrap();
+    let list: text_list::Reader = message.get_root().unwrap();
+    assert_eq!(list.get(0).unwrap(), "item 0");
+}
+
+#[test]
+fn truncated_message_is_rejected() {
+    let mut bytes = message_bytes();
+    bytes.truncate(bytes.len() - 8);
+    let Err(error) = serialize::read_message_from_owned_bytes(bytes, ReaderOptions::new()) else {
+        panic!("truncated message accepted");
+    };
+    assert!(matches!(error.kind, ErrorKind::MessageEndsPrematurely(..)));
+}
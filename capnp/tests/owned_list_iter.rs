@@ -0,0 +1,71 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, TypedReader};
+use capnp::schema_capnp::code_generator_request;
+use capnp::serialize::{self, OwnedSegments};
+
+fn serialized_request(filenames: &[&str]) -> Vec<u8> {
+    let mut message = message::Builder::new_default();
+    {
+        let root: code_generator_request::Builder = message.init_root();
+        let mut files = root.init_requested_files(filenames.len() as u32);
+        for (idx, filename) in filenames.iter().enumerate() {
+            let mut file = files.reborrow().get(idx as u32);
+            file.set_id(idx as u64);
+            file.set_filename((*filename).into());
+        }
+    }
+    serialize::write_message_to_words(&message)
+}
+
+fn read_request(bytes: &[u8]) -> TypedReader<OwnedSegments, code_generator_request::Owned> {
+    serialize::read_message(bytes, message::ReaderOptions::new())
+        .unwrap()
+        .into_typed()
+}
+
+// The point of the owned iterator: the message is read and iterated in a function that
+// does not outlive the iterator it returns.
+fn filenames(bytes: &[u8]) -> capnp::Result<impl Iterator<Item = capnp::Result<String>>> {
+    read_request(bytes).into_struct_list_iter(
+        |message| message.get()?.get_requested_files(),
+        |file| Ok(file.get_filename()?.to_string()?),
+    )
+}
+
+#[test]
+pub fn owned_struct_list_iter_returned_from_function() {
+    let bytes = serialized_request(&["a.capnp", "b.capnp", "c.capnp"]);
+    let names: Vec<String> = filenames(&bytes).unwrap().map(|n| n.unwrap()).collect();
+    assert_eq!(names, ["a.capnp", "b.capnp", "c.capnp"]);
+}
+
+#[test]
+pub fn owned_struct_list_iter_len_and_into_inner() {
+    let bytes = serialized_request(&["x.capnp", "y.capnp"]);
+    let mut iter = read_request(&bytes)
+        .into_struct_list_iter(
+            |message| message.get()?.get_requested_files(),
+            |file| file.get_id(),
+        )
+        .unwrap();
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.len(), 1);
+
+    let message = iter.into_inner();
+    let files = message.get().unwrap().get_requested_files().unwrap();
+    assert_eq!(files.get(1).get_filename().unwrap(), "y.capnp");
+}
+
+#[test]
+pub fn owned_struct_list_iter_empty_list() {
+    let bytes = serialized_request(&[]);
+    let mut iter = read_request(&bytes)
+        .into_struct_list_iter(
+            |message| message.get()?.get_requested_files(),
+            |file| file.get_id(),
+        )
+        .unwrap();
+    assert_eq!(iter.next(), None);
+}
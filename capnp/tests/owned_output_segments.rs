@@ -0,0 +1,59 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, AllocationStrategy, HeapAllocator, ReaderOptions, ReaderSegments};
+use capnp::{serialize, serialize_packed, text_list};
+
+fn build(first_segment_words: u32) -> message::Builder<HeapAllocator> {
+    let mut builder = message::Builder::new(
+        HeapAllocator::new()
+            .first_segment_words(first_segment_words)
+            .allocation_strategy(AllocationStrategy::FixedSize),
+    );
+    let mut list: text_list::Builder = builder.initn_root(20);
+    for idx in 0..20 {
+        list.set(idx, format!("item {idx}").as_str().into());
+    }
+    builder
+}
+
+#[test]
+fn owned_segments_outlive_builder() {
+    for first_segment_words in [4, 1024] {
+        let builder = build(first_segment_words);
+        let segment_count = builder.get_segments_for_output().len();
+        let unpacked = serialize::write_message_to_words(&builder);
+        let packed = serialize_packed::write_message_to_bytes(&builder);
+        let owned = builder.get_segments_for_output().to_owned_segments();
+        drop(builder);
+
+        assert_eq!(owned.len(), segment_count);
+        let mut bytes = Vec::new();
+        serialize::write_message_segments(&mut bytes, &owned).unwrap();
+        assert_eq!(bytes, unpacked);
+        let mut bytes = Vec::new();
+        serialize_packed::write_message_segments(&mut bytes, &owned).unwrap();
+        assert_eq!(bytes, packed);
+
+        let reader = message::Reader::new(owned, ReaderOptions::new());
+        let list: text_list::Reader = reader.get_root().unwrap();
+        assert_eq!(list.get(19).unwrap(), "item 19");
+    }
+}
+
+#[test]
+fn owned_segments_are_sent_to_another_thread() {
+    let mut builder = build(4);
+    let expected = serialize::write_message_to_words(&builder);
+    let owned = builder.get_segments_for_output().to_owned_segments();
+    // The builder can go on changing while the copy is being sent.
+    builder.set_root("something else").unwrap();
+
+    let sent = std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        owned.as_output_segments().write_to(&mut bytes).unwrap();
+        bytes
+    })
+    .join()
+    .unwrap();
+    assert_eq!(sent, expected);
+}
@@ -0,0 +1,92 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message;
+use capnp::raw::{self, OwnedStructData};
+use capnp::schema_capnp::{capnp_version, node};
+use capnp::traits::IntoInternalStructReader;
+
+fn version(major: u16, minor: u8, micro: u8) -> message::Builder<message::HeapAllocator> {
+    let mut builder = message::Builder::new_default();
+    let mut version: capnp_version::Builder = builder.init_root();
+    version.set_major(major);
+    version.set_minor(minor);
+    version.set_micro(micro);
+    builder
+}
+
+#[test]
+fn round_trip_primitives() {
+    let builder = version(1, 2, 3);
+    let reader: capnp_version::Reader = builder.get_root_as_reader().unwrap();
+    let internal = reader.into_internal_struct_reader();
+    assert_eq!(internal.copy_data_section(), [1, 0, 2, 3, 0, 0, 0, 0]);
+
+    let owned = internal.to_owned_data();
+    drop(builder);
+    let copy = owned.get::<capnp_version::Owned>();
+    assert_eq!(
+        (copy.get_major(), copy.get_minor(), copy.get_micro()),
+        (1, 2, 3)
+    );
+    assert_eq!(owned.as_bytes(), [1, 0, 2, 3, 0, 0, 0, 0]);
+    assert_eq!(owned.reader().get_pointer_section_size(), 0);
+}
+
+#[test]
+fn rebuild_from_bytes() {
+    let builder = version(7, 8, 9);
+    let reader: capnp_version::Reader = builder.get_root_as_reader().unwrap();
+    let bytes = raw::get_struct_data_section(reader);
+
+    let owned = OwnedStructData::new(bytes);
+    assert_eq!(owned, OwnedStructData::new(bytes));
+    let copy = owned.get::<capnp_version::Owned>();
+    assert_eq!(
+        (copy.get_major(), copy.get_minor(), copy.get_micro()),
+        (7, 8, 9)
+    );
+
+    // A short data section reads as an older version of the struct.
+    let copy = OwnedStructData::new(&bytes[..2]);
+    let copy = copy.get::<capnp_version::Owned>();
+    assert_eq!(
+        (copy.get_major(), copy.get_minor(), copy.get_micro()),
+        (7, 0, 0)
+    );
+    let empty = OwnedStructData::new(&[]);
+    assert_eq!(empty.get::<capnp_version::Owned>().get_major(), 0);
+}
+
+#[test]
+fn pointer_fields_read_as_defaults() {
+    let mut builder = message::Builder::new_default();
+    let mut node: node::Builder = builder.init_root();
+    node.set_id(0x1234);
+    node.set_display_name("foo.capnp".into());
+    node.init_nested_nodes(2);
+    let reader: node::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(reader.get_display_name().unwrap(), "foo.capnp");
+
+    let owned = raw::to_owned_struct_data(reader);
+    let copy = owned.get::<node::Owned>();
+    assert_eq!(copy.get_id(), 0x1234);
+    assert!(!copy.has_display_name());
+    assert_eq!(copy.get_display_name().unwrap(), "");
+    assert!(copy.get_nested_nodes().unwrap().is_empty());
+}
+
+#[test]
+fn send_to_another_thread() {
+    let owned = {
+        let builder = version(4, 5, 6);
+        let reader: capnp_version::Reader = builder.get_root_as_reader().unwrap();
+        raw::to_owned_struct_data(reader)
+    };
+    let fields = std::thread::spawn(move || {
+        let copy = owned.get::<capnp_version::Owned>();
+        (copy.get_major(), copy.get_minor(), copy.get_micro())
+    })
+    .join()
+    .unwrap();
+    assert_eq!(fields, (4, 5, 6));
+}
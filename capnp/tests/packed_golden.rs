@@ -0,0 +1,119 @@
+#![cfg(feature = "alloc")]
+
+//! Checks that the packed encoding of a fixed message stays byte for byte what it was, so that
+//! changes to the packer cannot quietly change its output. The sample covers every kind of
+//! packed word: zero runs longer than a count byte can hold, uncompressed runs made longer by
+//! words with a single zero byte, sparse words, and words right before a segment ends.
+//!
+//! If the packer is meant to change its output, regenerate the golden file with
+//! `CAPNP_BLESS=1 cargo test --test packed_golden`.
+
+use capnp::message::{self, HeapAllocator, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::{primitive_list, serialize, serialize_packed, struct_list};
+
+const GOLDEN: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/golden/packed_sample.bin"
+);
+
+/// Words of the sample, from a fixed pseudo-random sequence.
+fn words() -> Vec<u64> {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        state
+    };
+    let mut words = Vec::new();
+    // Long zero runs, and runs just within and past a count byte.
+    words.extend([0; 300]);
+    words.push(1);
+    words.extend([0; 256]);
+    words.push(u64::MAX);
+    // Long runs of dense words, some of them with one zero byte.
+    for idx in 0..600 {
+        let word = next() | 0x0101_0101_0101_0101;
+        words.push(if idx % 7 == 0 {
+            word & !(0xff << (8 * (idx % 8)))
+        } else {
+            word
+        });
+    }
+    // Sparse words, of every tag between them.
+    for _ in 0..512 {
+        let mask = next() >> 56;
+        let mut word = next();
+        for byte in 0..8 {
+            if mask & (1 << byte) == 0 {
+                word &= !(0xff << (8 * byte));
+            }
+        }
+        words.push(word);
+    }
+    words
+}
+
+/// A message of structs, texts and lists, in segments small enough that it has far pointers.
+fn sample() -> message::Builder<HeapAllocator> {
+    let words = words();
+    let mut builder = message::Builder::new(HeapAllocator::new().first_segment_words(64));
+    let mut nodes: struct_list::Builder<node::Owned> = builder.initn_root(8);
+    for idx in 0..8 {
+        let mut node = nodes.reborrow().get(idx);
+        node.set_id(words[idx as usize * 200]);
+        node.set_display_name(format!("sample.capnp:Node{idx}").as_str().into());
+        node.set_scope_id(u64::from(idx) << 40);
+        let mut annotations = node.init_annotations(idx % 3);
+        for annotation in 0..idx % 3 {
+            annotations
+                .reborrow()
+                .get(annotation)
+                .set_id(u64::from(annotation));
+        }
+    }
+    builder
+}
+
+/// The words of the sample, in a message of its own, split over several segments.
+fn words_message() -> message::Builder<HeapAllocator> {
+    let words = words();
+    let mut message = message::Builder::new(HeapAllocator::new().first_segment_words(128));
+    let mut list: primitive_list::Builder<u64> = message.initn_root(words.len() as u32);
+    for (idx, &word) in words.iter().enumerate() {
+        list.set(idx as u32, word);
+    }
+    message
+}
+
+fn packed() -> Vec<u8> {
+    let mut packed = Vec::new();
+    serialize_packed::write_message(&mut packed, &sample()).unwrap();
+    serialize_packed::write_message(&mut packed, &words_message()).unwrap();
+    packed
+}
+
+#[test]
+fn packed_output_matches_golden() {
+    let packed = packed();
+    if std::env::var_os("CAPNP_BLESS").is_some() {
+        std::fs::write(GOLDEN, &packed).unwrap();
+    }
+    let golden = std::fs::read(GOLDEN).unwrap();
+    assert!(packed == golden, "packed output differs from {GOLDEN}");
+}
+
+#[test]
+fn golden_unpacks_to_sample() {
+    let golden = std::fs::read(GOLDEN).unwrap();
+    let mut read = &golden[..];
+    for expected in [sample(), words_message()] {
+        let message = serialize_packed::read_message(&mut read, ReaderOptions::new()).unwrap();
+        assert_eq!(
+            serialize::write_message_segments_to_words(&message.into_segments()),
+            serialize::write_message_to_words(&expected)
+        );
+    }
+    assert!(read.is_empty());
+}
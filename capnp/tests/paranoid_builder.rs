@@ -0,0 +1,51 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message;
+use capnp::schema_capnp::node;
+use capnp::{raw, word};
+
+// A list pointer to 5 bytes a million words past the end of the message.
+const OUT_OF_BOUNDS: capnp::Word = word(0x01, 0x09, 0x3d, 0x00, 42, 0, 0, 0);
+
+fn write_bad_pointer(builder: &mut message::Builder<message::HeapAllocator>) {
+    let mut root: node::Builder = builder.init_root();
+    root.set_id(7);
+    unsafe { raw::set_struct_pointer_word(root, 0, OUT_OF_BOUNDS) };
+}
+
+#[test]
+#[cfg(any(debug_assertions, feature = "paranoid-builder"))]
+#[should_panic(
+    expected = "paranoid builder check: the target of the pointer at word 6 of segment 0"
+)]
+fn caught_when_written() {
+    let mut builder = message::Builder::new_default();
+    write_bad_pointer(&mut builder);
+}
+
+#[test]
+#[cfg(not(any(debug_assertions, feature = "paranoid-builder")))]
+fn caught_when_read() {
+    use capnp::message::ReaderOptions;
+    use capnp::serialize;
+
+    let mut builder = message::Builder::new_default();
+    write_bad_pointer(&mut builder);
+    let words = serialize::write_message_to_words(&builder);
+    let message = serialize::read_message(&words[..], ReaderOptions::new()).unwrap();
+    let root: node::Reader = message.get_root().unwrap();
+    assert_eq!(root.get_id(), 7);
+    assert!(root.get_display_name().is_err());
+}
+
+#[test]
+fn valid_words_are_accepted() {
+    let mut builder = message::Builder::new_default();
+    let mut root: node::Builder = builder.init_root();
+    root.set_display_name("paranoid.capnp".into());
+    let display_name = raw::get_struct_pointer_section_words(root.reborrow_as_reader()).unwrap()[0];
+    unsafe { raw::set_struct_pointer_word(root.reborrow(), 0, word(0, 0, 0, 0, 0, 0, 0, 0)) };
+    assert!(!root.has_display_name());
+    unsafe { raw::set_struct_pointer_word(root.reborrow(), 0, display_name) };
+    assert_eq!(root.get_display_name().unwrap(), "paranoid.capnp");
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::code_generator_request::requested_file;
+use capnp::{any_pointer, any_pointer_list, serialize, text, ErrorKind};
+
+/// Builds a message holding two independent files, and returns it along with the
+/// (segment, word offset) locations of their pointers.
+fn two_objects() -> (Vec<u8>, [(u32, u32); 2]) {
+    let mut builder = message::Builder::new_default();
+    let mut slots: any_pointer_list::Builder = builder.initn_root(2);
+    for (idx, name) in ["first.capnp", "second.capnp"].iter().enumerate() {
+        let mut file = slots
+            .reborrow()
+            .get(idx as u32)
+            .init_as::<requested_file::Builder>();
+        file.set_id(idx as u64 + 100);
+        file.set_filename((*name).into());
+    }
+    // Word 0 is the root pointer, and the list's pointers immediately follow it.
+    (
+        serialize::write_message_to_words(&builder),
+        [(0, 1), (0, 2)],
+    )
+}
+
+#[test]
+fn read_objects_at_offsets() {
+    let (bytes, locations) = two_objects();
+    let message = serialize::read_message(&bytes[..], ReaderOptions::new()).unwrap();
+
+    let file: requested_file::Reader = message
+        .get_pointer_at(locations[0].0, locations[0].1)
+        .unwrap()
+        .get_as()
+        .unwrap();
+    assert_eq!(file.get_id(), 100);
+    assert_eq!(file.get_filename().unwrap(), "first.capnp");
+
+    let file: requested_file::Reader = message
+        .get_pointer_at(locations[1].0, locations[1].1)
+        .unwrap()
+        .get_as()
+        .unwrap();
+    assert_eq!(file.get_id(), 101);
+    assert_eq!(file.get_filename().unwrap(), "second.capnp");
+
+    // Word 0 is the ordinary root.
+    let root = message.get_pointer_at(0, 0).unwrap();
+    assert_eq!(root.get_as::<any_pointer_list::Reader>().unwrap().len(), 2);
+}
+
+#[test]
+fn out_of_range_locations() {
+    let (bytes, _) = two_objects();
+    let message = serialize::read_message(&bytes[..], ReaderOptions::new()).unwrap();
+    let segment_words = (bytes.len() / 8 - 1) as u32;
+
+    let err = message.get_pointer_at(0, segment_words).err().unwrap();
+    assert_eq!(err.kind, ErrorKind::MessageContainsOutOfBoundsPointer);
+    // Offsets whose byte offset would not fit in a 32-bit usize.
+    for offset in [u32::MAX, u32::MAX / 8 + 1] {
+        let err = message.get_pointer_at(0, offset).err().unwrap();
+        assert_eq!(err.kind, ErrorKind::MessageContainsOutOfBoundsPointer);
+    }
+    assert_eq!(
+        message.get_pointer_at(1, 0).err().unwrap().kind,
+        ErrorKind::InvalidSegmentId(1)
+    );
+}
+
+#[test]
+fn locations_count_against_traversal_limit() {
+    let (bytes, locations) = two_objects();
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(Some(1));
+    // Skip the segment table, whose check would reject the message outright.
+    let segments = [&bytes[8..]];
+    let message = message::Reader::new(message::SegmentArray::new(&segments), options);
+    message
+        .get_pointer_at(locations[0].0, locations[0].1)
+        .unwrap();
+    let err = message
+        .get_pointer_at(locations[1].0, locations[1].1)
+        .err()
+        .unwrap();
+    assert_eq!(err.kind, ErrorKind::ReadLimitExceeded);
+}
+
+#[test]
+fn patch_in_place() {
+    let mut builder = message::Builder::new_default();
+    let mut slots: any_pointer_list::Builder = builder.initn_root(2);
+    slots.reborrow().get(0).set_as("first").unwrap();
+    slots.reborrow().get(1).set_as("second").unwrap();
+
+    // The list's pointers are at words 1 and 2, as recorded when the message was built.
+    let mut slot: any_pointer::Builder = unsafe { builder.get_pointer_at_mut(0, 2) }.unwrap();
+    slot.set_as("patched").unwrap();
+
+    let slots: any_pointer_list::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(slots.get(0).get_as::<text::Reader>().unwrap(), "first");
+    assert_eq!(slots.get(1).get_as::<text::Reader>().unwrap(), "patched");
+
+    let words = builder.get_segments_for_output()[0].len() as u32 / 8;
+    assert!(unsafe { builder.get_pointer_at_mut(0, words) }.is_err());
+    assert!(unsafe { builder.get_pointer_at_mut(1, 0) }.is_err());
+}
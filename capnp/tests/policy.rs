@@ -0,0 +1,268 @@
+#![cfg(feature = "alloc")]
+
+use capnp::introspect::{Introspect, Type};
+use capnp::message::{self, HeapAllocator, ReaderOptions};
+use capnp::overlap::{PathStep, PointerPath};
+use capnp::policy::{Policy, Validator, Violation, ViolationKind};
+use capnp::schema_capnp::{code_generator_request, value};
+use capnp::{any_pointer, any_pointer_list, data_list, text, text_list, word, ErrorKind, Word};
+
+use PathStep::{Element, Pointer};
+
+fn validate(builder: &message::Builder<HeapAllocator>, policy: Policy) -> Vec<Violation> {
+    let root: any_pointer::Reader = builder.get_root_as_reader().unwrap();
+    match Validator::new(policy).validate(root) {
+        Ok(()) => Vec::new(),
+        Err(violations) => violations,
+    }
+}
+
+fn validate_as(
+    builder: &message::Builder<HeapAllocator>,
+    policy: Policy,
+    ty: Type,
+) -> Vec<Violation> {
+    let root: any_pointer::Reader = builder.get_root_as_reader().unwrap();
+    match Validator::new(policy).validate_as(root, ty) {
+        Ok(()) => Vec::new(),
+        Err(violations) => violations,
+    }
+}
+
+fn validate_words(words: &[Word], policy: Policy) -> Vec<Violation> {
+    let segments = [Word::words_to_bytes(words)];
+    let message = message::Reader::new(message::SegmentArray::new(&segments), ReaderOptions::new());
+    match Validator::new(policy).validate(message.get_root().unwrap()) {
+        Ok(()) => Vec::new(),
+        Err(violations) => violations,
+    }
+}
+
+fn paths(violations: &[Violation]) -> Vec<String> {
+    violations.iter().map(|v| v.path.to_string()).collect()
+}
+
+fn word_of(value: u64) -> Word {
+    let [b0, b1, b2, b3, b4, b5, b6, b7] = value.to_le_bytes();
+    word(b0, b1, b2, b3, b4, b5, b6, b7)
+}
+
+/// A request with three nodes, the second of which has a long display name.
+fn request() -> message::Builder<HeapAllocator> {
+    let mut builder = message::Builder::new_default();
+    let request: code_generator_request::Builder = builder.init_root();
+    let mut nodes = request.init_nodes(3);
+    for idx in 0..3 {
+        let name = if idx == 1 { "a.capnp:LongName" } else { "a:B" };
+        nodes.reborrow().get(idx).set_display_name(name.into());
+    }
+    builder
+}
+
+/// Lists of pointers nested `depth` deep.
+fn nested_lists(depth: u32) -> message::Builder<HeapAllocator> {
+    let mut builder = message::Builder::new_default();
+    let mut list: any_pointer_list::Builder = builder.initn_root(1);
+    for _ in 1..depth {
+        list = list.get(0).initn_as(1);
+    }
+    builder
+}
+
+/// A list of two pointers: text with the given bytes, followed by its NUL, and data.
+fn blobs(text: &[u8], data: &[u8]) -> message::Builder<HeapAllocator> {
+    let mut builder = message::Builder::new_default();
+    let mut list: any_pointer_list::Builder = builder.initn_root(2);
+    list.reborrow()
+        .get(0)
+        .set_as(text::Reader::from(text))
+        .unwrap();
+    list.get(1).set_as(data).unwrap();
+    builder
+}
+
+/// A list of the given blobs, each followed by a NUL, as text is.
+fn terminated(blobs: &[&[u8]]) -> message::Builder<HeapAllocator> {
+    let mut builder = message::Builder::new_default();
+    let mut list: text_list::Builder = builder.initn_root(blobs.len() as u32);
+    for (idx, blob) in blobs.iter().enumerate() {
+        list.set(idx as u32, text::Reader::from(*blob));
+    }
+    builder
+}
+
+/// A list of `count` capability pointers.
+fn caps(count: u32) -> Vec<Word> {
+    let mut words = vec![word_of((u64::from(count) << 35) | (6 << 32) | 1)];
+    for idx in 0..count {
+        words.push(word_of((u64::from(idx) << 32) | 3));
+    }
+    words
+}
+
+#[test]
+fn default_policy_allows_everything() {
+    let policy = Policy::new();
+    assert!(validate(&request(), policy).is_empty());
+    assert!(validate(&nested_lists(30), policy).is_empty());
+    assert!(validate(&blobs(b"\xff", b"\xff"), policy).is_empty());
+    assert!(validate_words(&caps(3), policy).is_empty());
+}
+
+#[test]
+fn max_list_elements() {
+    let mut policy = Policy::new();
+    policy.max_list_elements(Some(10));
+    let violations = validate(&request(), policy);
+    assert_eq!(paths(&violations), ["root.0[1].0"]);
+    assert_eq!(
+        violations[0].path,
+        PointerPath(vec![Pointer(0), Element(1), Pointer(0)])
+    );
+    // The display name and its NUL terminator.
+    assert!(matches!(
+        violations[0].kind,
+        ViolationKind::ListTooLong { elements: 17 }
+    ));
+
+    policy.max_list_elements(Some(2));
+    let violations = validate(&request(), policy);
+    assert_eq!(
+        paths(&violations),
+        ["root.0", "root.0[0].0", "root.0[1].0", "root.0[2].0"]
+    );
+}
+
+#[test]
+fn require_utf8_text() {
+    let mut policy = Policy::new();
+    policy.require_utf8_text(true);
+    let list = terminated(&["héllo".as_bytes(), b"ok\xc3("]);
+    let violations = validate_as(&list, policy, text_list::Owned::introspect());
+    assert_eq!(paths(&violations), ["root.1"]);
+    assert!(matches!(
+        violations[0].kind,
+        ViolationKind::TextNotUtf8 { valid_up_to: 2 }
+    ));
+
+    // Without a type, text cannot be told from data, so none is checked.
+    assert!(validate(&list, policy).is_empty());
+    // Data that happens to end in a NUL byte is not mistaken for text.
+    assert!(validate_as(&list, policy, data_list::Owned::introspect()).is_empty());
+}
+
+#[test]
+fn require_utf8_text_of_struct_fields() {
+    let mut policy = Policy::new();
+    policy.require_utf8_text(true);
+    let mut builder = request();
+    {
+        let request: code_generator_request::Builder = builder.get_root().unwrap();
+        let mut node = request.get_nodes().unwrap().get(2);
+        node.set_display_name(text::Reader::from(&b"a:\xff"[..]));
+        // A data field holding the same bytes.
+        let mut annotation = node.init_annotations(1).get(0);
+        annotation
+            .reborrow()
+            .init_value()
+            .set_data(&b"a:\xff\0"[..]);
+    }
+    let violations = validate_as(
+        &builder,
+        policy,
+        code_generator_request::Owned::introspect(),
+    );
+    assert_eq!(paths(&violations), ["root.0[2].0"]);
+    assert!(matches!(
+        violations[0].kind,
+        ViolationKind::TextNotUtf8 { valid_up_to: 2 }
+    ));
+
+    // The text and data members of `Value` share a pointer, so neither can be checked.
+    let mut builder = message::Builder::new_default();
+    builder
+        .init_root::<value::Builder>()
+        .set_text(text::Reader::from(&b"\xff"[..]));
+    assert!(validate_as(&builder, policy, value::Owned::introspect()).is_empty());
+}
+
+#[test]
+fn max_nesting() {
+    let mut policy = Policy::new();
+    policy.max_nesting(Some(8));
+    assert!(validate(&nested_lists(8), policy).is_empty());
+
+    let violations = validate(&nested_lists(12), policy);
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].path, PointerPath(vec![Pointer(0); 8]));
+    assert!(matches!(
+        violations[0].kind,
+        ViolationKind::TooDeeplyNested { depth: 9 }
+    ));
+
+    policy.max_nesting(Some(0));
+    let violations = validate(&nested_lists(1), policy);
+    assert_eq!(paths(&violations), ["root"]);
+}
+
+#[test]
+fn allow_capabilities() {
+    let mut policy = Policy::new();
+    policy.allow_capabilities(false);
+    assert!(validate(&request(), policy).is_empty());
+    let violations = validate_words(&caps(3), policy);
+    assert_eq!(paths(&violations), ["root.0", "root.1", "root.2"]);
+    assert!(matches!(
+        violations[2].kind,
+        ViolationKind::Capability { index: 2 }
+    ));
+}
+
+#[test]
+fn combined() {
+    let mut policy = Policy::new();
+    policy
+        .max_list_elements(Some(3))
+        .require_utf8_text(true)
+        .max_nesting(Some(3))
+        .allow_capabilities(false);
+
+    let mut builder = message::Builder::new_default();
+    {
+        let request: code_generator_request::Builder = builder.init_root();
+        let mut nodes = request.init_nodes(4);
+        nodes
+            .reborrow()
+            .get(0)
+            .set_display_name(text::Reader::from(&b"\xff"[..]));
+        nodes.get(1).init_annotations(1).get(0).init_value();
+    }
+    let violations = validate_as(
+        &builder,
+        policy,
+        code_generator_request::Owned::introspect(),
+    );
+    assert_eq!(
+        violations
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        [
+            "root.0: list has 4 elements",
+            "root.0[0].0: text is not UTF-8 after byte 0",
+            "root.0[1].2[0].0: object is nested 4 deep",
+        ]
+    );
+}
+
+#[test]
+fn malformed_message() {
+    // A list of one pointer, which points past the end of the segment.
+    let words = [word_of((1 << 35) | (6 << 32) | 1), word_of(100 << 2)];
+    let violations = validate_words(&words, Policy::new());
+    assert_eq!(paths(&violations), ["root.0"]);
+    let ViolationKind::Malformed(error) = &violations[0].kind else {
+        panic!("{}", violations[0]);
+    };
+    assert_eq!(error.kind, ErrorKind::MessageContainsOutOfBoundsPointer);
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, Allocator, ReaderOptions};
+use capnp::{serialize, text, text_list, Word};
+
+/// Keeps the segments of dropped messages for later ones, re-zeroing only the words that were
+/// used.
+struct Pool {
+    slabs: Vec<Box<[Word]>>,
+    free: Vec<usize>,
+    slab_words: u32,
+}
+
+impl Pool {
+    fn new(slab_words: u32) -> Self {
+        Self {
+            slabs: Vec::new(),
+            free: Vec::new(),
+            slab_words,
+        }
+    }
+}
+
+unsafe impl Allocator for Pool {
+    fn allocate_segment(&mut self, minimum_size: u32) -> (*mut u8, u32) {
+        assert!(
+            minimum_size <= self.slab_words,
+            "segment too big for the pool"
+        );
+        let idx = self.free.pop().unwrap_or_else(|| {
+            let zeroed = Word::allocate_zeroed_vec(self.slab_words as usize);
+            self.slabs.push(zeroed.into_boxed_slice());
+            self.slabs.len() - 1
+        });
+        (self.slabs[idx].as_mut_ptr().cast(), self.slab_words)
+    }
+
+    unsafe fn deallocate_segment(&mut self, ptr: *mut u8, word_size: u32, words_used: u32) {
+        assert_eq!(word_size, self.slab_words);
+        let idx = self
+            .slabs
+            .iter()
+            .position(|slab| slab.as_ptr().cast() == ptr)
+            .expect("segment not from this pool");
+        let slab = &mut self.slabs[idx];
+        assert!(
+            slab[words_used as usize..]
+                .iter()
+                .all(|word| *word == capnp::word(0, 0, 0, 0, 0, 0, 0, 0)),
+            "nonzero word after the {words_used} words used"
+        );
+        slab[..words_used as usize].fill(capnp::word(0, 0, 0, 0, 0, 0, 0, 0));
+        self.free.push(idx);
+    }
+}
+
+#[test]
+fn pool_is_shared_across_messages() {
+    let mut pool = Pool::new(16);
+    for idx in 0..100 {
+        let mut builder = message::Builder::new(&mut pool);
+        // Texts of 13 words, no two of which fit in a segment.
+        let texts: Vec<String> = (0..1 + idx % 5)
+            .map(|n| format!("{idx:3} {n}").repeat(20))
+            .collect();
+        let mut list: text_list::Builder = builder.initn_root(texts.len() as u32);
+        for (n, text) in texts.iter().enumerate() {
+            list.set(n as u32, text::Reader::from(&**text));
+        }
+        assert!(builder.get_segments_for_output().len() >= texts.len());
+        let bytes = serialize::write_message_to_words(&builder);
+        drop(builder);
+
+        let reader = serialize::read_message(&bytes[..], ReaderOptions::new()).unwrap();
+        let list: text_list::Reader = reader.get_root().unwrap();
+        for (n, text) in texts.iter().enumerate() {
+            assert_eq!(list.get(n as u32).unwrap(), &**text);
+        }
+    }
+    // Each message reused the segments of the ones before it.
+    assert!(pool.slabs.len() <= 6, "{} slabs", pool.slabs.len());
+    assert_eq!(pool.free.len(), pool.slabs.len());
+    for slab in &pool.slabs {
+        assert!(slab
+            .iter()
+            .all(|word| *word == capnp::word(0, 0, 0, 0, 0, 0, 0, 0)));
+    }
+}
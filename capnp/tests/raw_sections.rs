@@ -0,0 +1,101 @@
+#![cfg(feature = "alloc")]
+
+use std::ops::Range;
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::{field, node};
+use capnp::traits::IntoInternalStructReader;
+use capnp::{raw, serialize, word, Word};
+
+/// `(name = "foo", id = 0x0123456789abcdef)` as a `Node.NestedNode`, in the canonical encoding
+/// that the C++ implementation writes, e.g. with `capnp encode --canonical`.
+const FIXTURE: [Word; 5] = [
+    // Segment table: one segment of four words.
+    word(0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00),
+    // Root pointer: a struct of one data word and one pointer, right after it.
+    word(0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00),
+    // id
+    word(0xef, 0xcd, 0xab, 0x89, 0x67, 0x45, 0x23, 0x01),
+    // name: a list of four bytes, right after the struct.
+    word(0x01, 0x00, 0x00, 0x00, 0x22, 0x00, 0x00, 0x00),
+    word(b'f', b'o', b'o', 0x00, 0x00, 0x00, 0x00, 0x00),
+];
+
+/// The bytes of `id`, which is treated as a volatile field, within the data section.
+const ID: Range<usize> = 0..8;
+
+/// FNV-1a, over the raw sections of `nested_node` and the name it points to, skipping `ID`.
+fn digest(nested_node: node::nested_node::Reader) -> u64 {
+    let mut hash = 0xcbf29ce484222325u64;
+    let mut add = |bytes: &[u8]| {
+        for &byte in bytes {
+            hash = (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3);
+        }
+    };
+    let data = raw::get_struct_data_section(nested_node);
+    add(&data[..ID.start]);
+    add(&data[ID.end..]);
+    add(Word::words_to_bytes(
+        raw::get_struct_pointer_section_words(nested_node).unwrap(),
+    ));
+    add(nested_node.get_name().unwrap().as_bytes());
+    hash
+}
+
+#[test]
+fn digest_skipping_a_field_matches_fixture() {
+    let mut builder = message::Builder::new_default();
+    let mut nested_node: node::nested_node::Builder = builder.init_root();
+    nested_node.set_name("foo".into());
+    nested_node.set_id(7);
+    let ours: node::nested_node::Reader = builder.get_root_as_reader().unwrap();
+
+    let fixture = Word::words_to_bytes(&FIXTURE);
+    let theirs =
+        serialize::read_message_from_flat_slice(&mut &fixture[..], ReaderOptions::new()).unwrap();
+    let theirs: node::nested_node::Reader = theirs.get_root().unwrap();
+    assert_eq!(theirs.get_id(), 0x0123456789abcdef);
+
+    assert_eq!(digest(ours), digest(theirs));
+    // The skipped field does differ.
+    assert_ne!(
+        raw::get_struct_data_section(ours),
+        raw::get_struct_data_section(theirs)
+    );
+
+    // The sections are the exact words on the wire.
+    let internal = theirs.into_internal_struct_reader();
+    assert_eq!(internal.raw_data(), Word::words_to_bytes(&FIXTURE[2..3]));
+    assert_eq!(internal.raw_pointer_section_words(), Some(&FIXTURE[3..4]));
+}
+
+#[test]
+fn data_is_masked_by_defaults() {
+    let mut builder = message::Builder::new_default();
+    let mut field: field::Builder = builder.init_root();
+    // The default discriminant value is 0xffff, which is stored as zero.
+    field.set_discriminant_value(0xffff);
+    let reader = field.reborrow_as_reader();
+    let data = reader.into_internal_struct_reader().raw_data();
+    assert_eq!(&data[2..4], [0, 0]);
+
+    field.set_discriminant_value(0);
+    let reader = field.into_reader();
+    assert_eq!(reader.get_discriminant_value(), 0);
+    let data = reader.into_internal_struct_reader().raw_data();
+    assert_eq!(&data[2..4], [0xff, 0xff]);
+}
+
+#[test]
+fn empty_pointer_section() {
+    let mut builder = message::Builder::new_default();
+    builder
+        .init_root::<capnp::schema_capnp::capnp_version::Builder>()
+        .set_major(1);
+    let reader: capnp::schema_capnp::capnp_version::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(raw::get_struct_pointer_section_words(reader), Some(&[][..]));
+    assert_eq!(
+        raw::get_struct_data_section(reader),
+        [1, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
@@ -0,0 +1,129 @@
+#![cfg(feature = "alloc")]
+
+use std::cell::Cell;
+
+use capnp::io::{BufRead, Read};
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::{serialize, Result};
+
+/// Hands out the bytes of `bytes` through a buffer of at most `capacity` bytes, so that the
+/// segment table and the segments straddle the ends of the buffer. Counts the times the buffer
+/// is refilled in `fills`, and the bytes not yet consumed in `left`.
+struct SmallBuffers<'a> {
+    bytes: &'a [u8],
+    capacity: usize,
+    buffer: &'a [u8],
+    fills: &'a Cell<usize>,
+    left: &'a Cell<usize>,
+}
+
+impl<'a> SmallBuffers<'a> {
+    fn new(
+        bytes: &'a [u8],
+        capacity: usize,
+        fills: &'a Cell<usize>,
+        left: &'a Cell<usize>,
+    ) -> Self {
+        left.set(bytes.len());
+        Self {
+            bytes,
+            capacity,
+            buffer: &[],
+            fills,
+            left,
+        }
+    }
+}
+
+impl Read for SmallBuffers<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = self.fill_buf()?.len().min(buf.len());
+        buf[..n].copy_from_slice(&self.buffer[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for SmallBuffers<'_> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        if self.buffer.is_empty() {
+            let n = self.bytes.len().min(self.capacity);
+            (self.buffer, self.bytes) = self.bytes.split_at(n);
+            self.fills.set(self.fills.get() + 1);
+        }
+        Ok(self.buffer)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.buffer = &self.buffer[amt..];
+        self.left.set(self.left.get() - amt);
+    }
+}
+
+fn three_segments() -> Vec<u8> {
+    let mut builder = message::Builder::new(message::HeapAllocator::new().first_segment_words(1));
+    let mut root: node::Builder = builder.init_root();
+    root.set_id(5);
+    root.set_display_name("read_message_buffered.capnp".into());
+    root.init_nested_nodes(3);
+    assert_eq!(builder.get_segments_for_output().len(), 3);
+    serialize::write_message_to_bytes(&builder)
+}
+
+fn check(message: message::Reader<serialize::OwnedSegments>) {
+    let root: node::Reader = message.get_root().unwrap();
+    assert_eq!(root.get_id(), 5);
+    assert_eq!(
+        root.get_display_name().unwrap(),
+        "read_message_buffered.capnp"
+    );
+    assert_eq!(root.get_nested_nodes().unwrap().len(), 3);
+}
+
+#[test]
+fn whole_message_in_buffer() {
+    let bytes = three_segments();
+    let (fills, left) = (Cell::new(0), Cell::new(0));
+    let read = SmallBuffers::new(&bytes, usize::MAX, &fills, &left);
+    check(serialize::read_message_buffered(read, ReaderOptions::new()).unwrap());
+    assert_eq!(fills.get(), 1);
+    assert_eq!(left.get(), 0);
+}
+
+#[test]
+fn table_and_segments_straddle_buffers() {
+    let bytes = three_segments();
+    for capacity in [1, 3, 8, 12, 17] {
+        let (fills, left) = (Cell::new(0), Cell::new(0));
+        let read = SmallBuffers::new(&bytes, capacity, &fills, &left);
+        check(serialize::read_message_buffered(read, ReaderOptions::new()).unwrap());
+        assert!(fills.get() > 1);
+        assert_eq!(left.get(), 0);
+    }
+}
+
+#[test]
+fn several_messages_and_eof() {
+    let message = three_segments();
+    let bytes = [&message[..], &message[..]].concat();
+    let mut read = &bytes[..];
+    check(serialize::read_message_buffered(&mut read, ReaderOptions::new()).unwrap());
+    check(serialize::read_message_buffered(&mut read, ReaderOptions::new()).unwrap());
+    assert!(serialize::read_message_buffered(&mut read, ReaderOptions::new()).is_err());
+
+    let mut truncated = &message[..message.len() - 1];
+    assert!(serialize::read_message_buffered(&mut truncated, ReaderOptions::new()).is_err());
+}
+
+#[test]
+fn empty_segments_need_no_more_bytes() {
+    // The segment table of a message of two empty segments, and nothing after it.
+    let bytes = [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    let (fills, left) = (Cell::new(0), Cell::new(0));
+    let read = SmallBuffers::new(&bytes, bytes.len(), &fills, &left);
+    let message = serialize::read_message_buffered(read, ReaderOptions::new()).unwrap();
+    assert_eq!(fills.get(), 1);
+    assert_eq!(left.get(), 0);
+    assert!(message.get_root::<node::Reader>().is_err());
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, AllocationStrategy, HeapAllocator, ReaderOptions};
+use capnp::{serialize, text_list, ErrorKind, Word};
+
+/// The segments of a message of several segments, as a framing layer might hand them over
+/// after parsing the segment table itself.
+fn framed_segments() -> Vec<Vec<Word>> {
+    let mut builder = message::Builder::new(
+        HeapAllocator::new()
+            .first_segment_words(4)
+            .allocation_strategy(AllocationStrategy::FixedSize),
+    );
+    let mut list: text_list::Builder = builder.initn_root(10);
+    for idx in 0..10 {
+        list.set(idx, format!("item {idx}").as_str().into());
+    }
+    let bytes = serialize::write_message_to_words(&builder);
+    let (lengths, table_len) = serialize::parse_segment_table_bytes(&bytes).unwrap();
+    assert!(lengths.len() > 1);
+    let mut rest = &bytes[table_len..];
+    lengths
+        .into_iter()
+        .map(|len| {
+            let (segment, after) = rest.split_at(len as usize * 8);
+            rest = after;
+            let mut words = Word::allocate_zeroed_vec(len as usize);
+            Word::words_to_bytes_mut(&mut words).copy_from_slice(segment);
+            words
+        })
+        .collect()
+}
+
+#[test]
+fn reads_caller_framed_segments() {
+    let owned = framed_segments();
+    let segments: Vec<&[Word]> = owned.iter().map(|segment| &segment[..]).collect();
+    let message = serialize::read_message_from_segments(&segments, ReaderOptions::new()).unwrap();
+    let list: text_list::Reader = message.get_root().unwrap();
+    assert_eq!(list.len(), 10);
+    assert_eq!(list.get(9).unwrap(), "item 9");
+}
+
+#[test]
+fn no_segments_is_an_error() {
+    let Err(error) = serialize::read_message_from_segments(&[], ReaderOptions::new()) else {
+        panic!("empty segment list accepted");
+    };
+    assert_eq!(error.kind, ErrorKind::InvalidNumberOfSegments(0));
+}
+
+#[test]
+fn options_limit_segments_up_front() {
+    let owned = framed_segments();
+    let segments: Vec<&[Word]> = owned.iter().map(|segment| &segment[..]).collect();
+    let total: usize = owned.iter().map(Vec::len).sum();
+
+    let mut options = ReaderOptions::new();
+    options.segments_limit(segments.len());
+    let Err(error) = serialize::read_message_from_segments(&segments, options) else {
+        panic!("too many segments accepted");
+    };
+    assert_eq!(
+        error.kind,
+        ErrorKind::InvalidNumberOfSegments(segments.len())
+    );
+
+    let mut options = ReaderOptions::new();
+    options.total_size_limit_in_words(Some(total - 1));
+    let Err(error) = serialize::read_message_from_segments(&segments, options) else {
+        panic!("oversized message accepted");
+    };
+    assert_eq!(error.kind, ErrorKind::Overloaded);
+}
+
+#[test]
+fn bounds_are_checked_as_segments_are_read() {
+    // A root pointer to a struct of one data word, beyond the end of the only segment.
+    let words = [capnp::word(0, 0, 0, 0, 1, 0, 0, 0)];
+    let segments: &[&[Word]] = &[&words];
+    let message = serialize::read_message_from_segments(segments, ReaderOptions::new()).unwrap();
+    let Err(error) = message
+        .get_root::<capnp::any_pointer::Reader>()
+        .unwrap()
+        .target_size()
+    else {
+        panic!("out-of-bounds pointer accepted");
+    };
+    assert_eq!(error.kind, ErrorKind::MessageContainsOutOfBoundsPointer);
+}
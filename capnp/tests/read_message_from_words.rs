@@ -0,0 +1,110 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions, ReaderSegments};
+use capnp::schema_capnp::node;
+use capnp::{serialize, ErrorKind, Word};
+
+/// A serialized node spread over several segments, as words.
+fn datagram() -> Vec<Word> {
+    let mut builder = message::Builder::new(message::HeapAllocator::new().first_segment_words(1));
+    let mut root: node::Builder = builder.init_root();
+    root.set_id(7);
+    root.set_display_name("read_message_from_words.capnp".into());
+    assert!(builder.get_segments_for_output().len() > 1);
+    let bytes = serialize::write_message_to_words(&builder);
+    let mut words = Word::allocate_zeroed_vec(bytes.len() / 8);
+    Word::words_to_bytes_mut(&mut words).copy_from_slice(&bytes);
+    words
+}
+
+fn display_name<'a, S: ReaderSegments>(message: &'a message::Reader<S>) -> &'a str {
+    let root: node::Reader = message.get_root().unwrap();
+    root.get_display_name().unwrap().to_str().unwrap()
+}
+
+#[test]
+fn segments_borrow_the_words() {
+    let words = datagram();
+    let message = serialize::read_message_from_words(&words, ReaderOptions::new()).unwrap();
+    assert_eq!(display_name(&message), "read_message_from_words.capnp");
+    // Roots can be read again, for as long as the words are held.
+    let root: node::Reader = message.get_root().unwrap();
+    assert_eq!(root.get_id(), 7);
+    assert_eq!(display_name(&message), "read_message_from_words.capnp");
+
+    let bytes = Word::words_to_bytes(&words).as_ptr_range();
+    let segments = message.into_segments();
+    assert!(segments.len() > 1);
+    for id in 0..segments.len() as u32 {
+        let segment = segments.get_segment(id).unwrap().as_ptr_range();
+        assert!(bytes.start <= segment.start && segment.end <= bytes.end);
+    }
+}
+
+#[test]
+fn trailing_words_are_ignored() {
+    let mut words = datagram();
+    words.extend(Word::allocate_zeroed_vec(3));
+    let message = serialize::read_message_from_words(&words, ReaderOptions::new()).unwrap();
+    assert_eq!(display_name(&message), "read_message_from_words.capnp");
+}
+
+#[test]
+fn truncated_messages_are_rejected() {
+    let words = datagram();
+    for len in [0, 1, words.len() - 1] {
+        let Err(error) = serialize::read_message_from_words(&words[..len], ReaderOptions::new())
+        else {
+            panic!("read a message truncated to {len} words");
+        };
+        assert!(
+            matches!(error.kind, ErrorKind::MessageEndsPrematurely(..)),
+            "{len}: {error}"
+        );
+    }
+
+    // The same holds for segments of a buffer that is held by value.
+    let bytes = Word::words_to_bytes(&words[..words.len() - 1]).to_vec();
+    let Err(error) = serialize::BufferSegments::new(bytes, ReaderOptions::new()) else {
+        panic!("read a truncated buffer");
+    };
+    assert!(matches!(error.kind, ErrorKind::MessageEndsPrematurely(..)));
+}
+
+#[test]
+fn word_slice_advances_past_each_message() {
+    let mut log = Vec::new();
+    for id in 0..3 {
+        let mut builder = message::Builder::new_default();
+        builder.init_root::<node::Builder>().set_id(id);
+        log.extend(serialize::write_message_to_words(&builder));
+    }
+    let mut words = Word::allocate_zeroed_vec(log.len() / 8);
+    Word::words_to_bytes_mut(&mut words).copy_from_slice(&log);
+    words.extend(datagram());
+
+    let mut slice = &words[..];
+    for id in 0..3 {
+        let message =
+            serialize::read_message_from_word_slice(&mut slice, ReaderOptions::new()).unwrap();
+        let root: node::Reader = message.get_root().unwrap();
+        assert_eq!(root.get_id(), id);
+    }
+    let before_last = slice.len();
+    let message =
+        serialize::read_message_from_word_slice(&mut slice, ReaderOptions::new()).unwrap();
+    assert_eq!(display_name(&message), "read_message_from_words.capnp");
+    assert_eq!(before_last, datagram().len());
+    assert!(slice.is_empty());
+    assert!(serialize::read_message_from_word_slice(&mut slice, ReaderOptions::new()).is_err());
+
+    // A message whose segments run past the end of the slice leaves the slice where it was.
+    let truncated = &words[..words.len() - 1];
+    let mut slice = &truncated[log.len() / 8..];
+    let Err(error) = serialize::read_message_from_word_slice(&mut slice, ReaderOptions::new())
+    else {
+        panic!("read a truncated message");
+    };
+    assert!(matches!(error.kind, ErrorKind::MessageEndsPrematurely(..)));
+    assert_eq!(slice.len(), before_last - 1);
+}
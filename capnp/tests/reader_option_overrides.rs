@@ -0,0 +1,234 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::{any_pointer, any_pointer_list, data, serialize, word, ErrorKind, Word};
+
+/// A list nested `depth` lists deep.
+fn nested_lists(depth: u32) -> Vec<u8> {
+    let mut builder = message::Builder::new_default();
+    let mut list: any_pointer_list::Builder = builder.initn_root(1);
+    for _ in 1..depth {
+        list = list.get(0).initn_as(1);
+    }
+    serialize::write_message_to_words(&builder)
+}
+
+fn blob(len: usize) -> Vec<u8> {
+    let mut builder = message::Builder::new_default();
+    builder.set_root(&vec![7u8; len][..]).unwrap();
+    serialize::write_message_to_words(&builder)
+}
+
+fn word_of(value: u64) -> Word {
+    let [b0, b1, b2, b3, b4, b5, b6, b7] = value.to_le_bytes();
+    word(b0, b1, b2, b3, b4, b5, b6, b7)
+}
+
+/// The words of a message whose root is a list of `count` capability pointers.
+fn caps(count: u32) -> Vec<Word> {
+    let mut words = vec![word_of((u64::from(count) << 35) | (6 << 32) | 1)];
+    for idx in 0..count {
+        words.push(word_of((u64::from(idx) << 32) | 3));
+    }
+    words
+}
+
+fn read(bytes: &[u8], options: ReaderOptions) -> message::Reader<serialize::OwnedSegments> {
+    serialize::read_message(bytes, options).unwrap()
+}
+
+fn kind<T>(result: capnp::Result<T>) -> ErrorKind {
+    result.err().unwrap().kind
+}
+
+#[test]
+fn presets() {
+    assert_eq!(
+        ReaderOptions::STRICT.traversal_limit_in_words,
+        Some(128 * 1024)
+    );
+    assert_eq!(ReaderOptions::STRICT.nesting_limit, 16);
+    assert_eq!(ReaderOptions::PERMISSIVE.traversal_limit_in_words, None);
+    assert_eq!(
+        ReaderOptions::PERMISSIVE.nesting_limit,
+        ReaderOptions::new().nesting_limit
+    );
+    assert!(!ReaderOptions::PERMISSIVE.lenient_segment_table);
+}
+
+#[test]
+fn permissive_rejects_padded_segment_table() {
+    // One segment, with a zero word after the segment table that it does not need.
+    let mut bytes = blob(8);
+    bytes.splice(8..8, [0; 8]);
+    let error = serialize::read_message_from_flat_slice(&mut &bytes[..], ReaderOptions::PERMISSIVE)
+        .err()
+        .unwrap();
+    assert_eq!(error.kind, ErrorKind::SegmentTableFollowedByPadding);
+
+    let mut lenient = ReaderOptions::PERMISSIVE;
+    lenient.lenient_segment_table(true);
+    let message = serialize::read_message_from_flat_slice(&mut &bytes[..], lenient).unwrap();
+    let value: data::Reader = message.get_root().unwrap();
+    assert_eq!(value, [7; 8]);
+}
+
+/// The depth of a list nested as by `nested_lists()`.
+fn depth(mut list: any_pointer_list::Reader) -> capnp::Result<u32> {
+    let mut depth = 1;
+    while !list.get(0).is_null() {
+        list = list.get(0).get_as()?;
+        depth += 1;
+    }
+    Ok(depth)
+}
+
+#[test]
+fn override_tightens_word_limit() {
+    let bytes = blob(2 * 1024 * 1024);
+    let message = read(&bytes, ReaderOptions::new());
+    let strict = message
+        .get_root_with_options(&ReaderOptions::STRICT)
+        .unwrap();
+    assert_eq!(kind(strict.get_as::<data::Reader>()), ErrorKind::Overloaded);
+    let permissive = message
+        .get_root_with_options(&ReaderOptions::PERMISSIVE)
+        .unwrap();
+    let value: data::Reader = permissive.get_as().unwrap();
+    assert_eq!(value.len(), 2 * 1024 * 1024);
+
+    let bytes = blob(512 * 1024);
+    let message = read(&bytes, ReaderOptions::new());
+    let strict = message
+        .get_root_with_options(&ReaderOptions::STRICT)
+        .unwrap();
+    let value: data::Reader = strict.get_as().unwrap();
+    assert_eq!(value.len(), 512 * 1024);
+}
+
+#[test]
+fn override_reads_the_value_once() {
+    // 64K words of data, under a message limit that only has room to read it once.
+    let segments = read(&blob(512 * 1024), ReaderOptions::new()).into_segments();
+    let mut base = ReaderOptions::new();
+    base.traversal_limit_in_words(Some(100 * 1024));
+    let message = message::Reader::new(segments, base);
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(Some(160 * 1024));
+
+    let limited = message.get_root_with_options(&options).unwrap();
+    let value: data::Reader = limited.get_as().unwrap();
+    assert_eq!(value.len(), 512 * 1024);
+    let left = message.remaining_traversal_limit_in_words().unwrap();
+    assert!(left < 40 * 1024, "{left} words left");
+
+    // The view's limit still has room for a second read, but the message's does not.
+    assert_eq!(
+        kind(limited.get_as::<data::Reader>()),
+        ErrorKind::ReadLimitExceeded
+    );
+}
+
+#[test]
+fn override_cannot_loosen_word_limit() {
+    // `read_message()` would reject the message outright, so apply the limit afterwards.
+    let segments = read(&blob(16 * 1024), ReaderOptions::new()).into_segments();
+    let mut base = ReaderOptions::new();
+    base.traversal_limit_in_words(Some(1024));
+    let message = message::Reader::new(segments, base);
+    let permissive = message
+        .get_root_with_options(&ReaderOptions::PERMISSIVE)
+        .unwrap();
+    assert_eq!(
+        kind(permissive.get_as::<data::Reader>()),
+        ErrorKind::ReadLimitExceeded
+    );
+}
+
+#[test]
+fn override_tightens_nesting_limit() {
+    let bytes = nested_lists(20);
+    let message = read(&bytes, ReaderOptions::new());
+    let permissive = message
+        .get_root_with_options(&ReaderOptions::PERMISSIVE)
+        .unwrap();
+    assert_eq!(depth(permissive.get_as().unwrap()).unwrap(), 20);
+    let strict = message
+        .get_root_with_options(&ReaderOptions::STRICT)
+        .unwrap();
+    assert_eq!(
+        kind(depth(strict.get_as().unwrap())),
+        ErrorKind::NestingLimitExceeded
+    );
+
+    // The same holds below the root.
+    let root: any_pointer::Reader = message.get_root().unwrap();
+    let list: any_pointer_list::Reader = root.get_as().unwrap();
+    let strict = list.get(0).with_options(&ReaderOptions::STRICT);
+    assert_eq!(
+        kind(depth(strict.get_as().unwrap())),
+        ErrorKind::NestingLimitExceeded
+    );
+    let bytes = nested_lists(10);
+    let message = read(&bytes, ReaderOptions::new());
+    let strict = message
+        .get_root_with_options(&ReaderOptions::STRICT)
+        .unwrap();
+    assert_eq!(depth(strict.get_as().unwrap()).unwrap(), 10);
+}
+
+#[test]
+fn override_cannot_loosen_nesting_limit() {
+    let bytes = nested_lists(10);
+    let mut base = ReaderOptions::new();
+    base.nesting_limit(4);
+    let message = read(&bytes, base);
+    let permissive = message
+        .get_root_with_options(&ReaderOptions::PERMISSIVE)
+        .unwrap();
+    assert_eq!(
+        kind(depth(permissive.get_as().unwrap())),
+        ErrorKind::NestingLimitExceeded
+    );
+
+    // Nor can options that loosen every limit at once.
+    let mut loose = ReaderOptions::PERMISSIVE;
+    loose
+        .nesting_limit(i32::MAX)
+        .list_nesting_limit(None)
+        .lenient_segment_table(true);
+    let loosened = message.get_root_with_options(&loose).unwrap();
+    assert_eq!(
+        kind(depth(loosened.get_as().unwrap())),
+        ErrorKind::NestingLimitExceeded
+    );
+}
+
+#[test]
+fn override_limits_caps() {
+    let words = caps(10);
+    let segments = [Word::words_to_bytes(&words)];
+    let message = message::Reader::new(message::SegmentArray::new(&segments), ReaderOptions::new());
+    let mut options = ReaderOptions::new();
+    options.max_caps(Some(4));
+
+    // Without a cap table, the capabilities cannot be read, but they still count.
+    let limited = message.get_root_with_options(&options).unwrap();
+    let list: any_pointer_list::Reader = limited.get_as().unwrap();
+    for idx in 0..4 {
+        assert_eq!(
+            kind(list.get(idx).get_pipelined_cap(&[])),
+            ErrorKind::CannotReadACapabilityWithoutACapTable
+        );
+    }
+    let error = list.get(4).get_pipelined_cap(&[]).err().unwrap();
+    assert_eq!(error.kind, ErrorKind::Overloaded);
+    assert!(error.extra.is_empty());
+    // The message itself has no limit.
+    let list: any_pointer_list::Reader = message.get_root().unwrap();
+    assert_eq!(
+        kind(list.get(4).get_pipelined_cap(&[])),
+        ErrorKind::CannotReadACapabilityWithoutACapTable
+    );
+    assert_eq!(message.cap_count_seen(), 0);
+}
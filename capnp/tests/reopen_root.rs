@@ -0,0 +1,53 @@
+#![cfg(feature = "alloc")]
+
+use capnp::schema_capnp::node;
+use capnp::{message, serialize};
+
+#[test]
+fn get_root_reopens_existing_root() {
+    let mut builder = message::Builder::new_default();
+    {
+        let mut root: node::Builder = builder.init_root();
+        root.set_id(0x1234);
+        root.set_display_name("reopened".into());
+    }
+    let words_before = serialize::compute_serialized_size_in_words(&builder);
+
+    {
+        let mut root: node::Builder = builder.get_root().unwrap();
+        assert_eq!(root.reborrow().get_id(), 0x1234);
+        root.set_scope_id(0x5678);
+    }
+    // Nothing was orphaned.
+    assert_eq!(
+        serialize::compute_serialized_size_in_words(&builder),
+        words_before
+    );
+
+    let root: node::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(root.get_id(), 0x1234);
+    assert_eq!(root.get_display_name().unwrap(), "reopened");
+    assert_eq!(root.get_scope_id(), 0x5678);
+}
+
+#[test]
+fn get_root_initializes_unset_root() {
+    let mut builder = message::Builder::new_default();
+    {
+        let mut root: node::Builder = builder.get_root().unwrap();
+        assert_eq!(root.reborrow().get_id(), 0);
+        assert!(!root.reborrow().has_display_name());
+        root.set_id(7);
+    }
+    let root: node::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(root.get_id(), 7);
+}
+
+#[test]
+fn typed_get_root_reopens_existing_root() {
+    let mut builder = message::TypedBuilder::<node::Owned>::new_default();
+    builder.init_root().set_id(1);
+    builder.get_root().unwrap().set_scope_id(2);
+    let root = builder.get_root_as_reader().unwrap();
+    assert_eq!((root.get_id(), root.get_scope_id()), (1, 2));
+}
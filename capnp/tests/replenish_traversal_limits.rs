@@ -0,0 +1,101 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions, TraversalLimits};
+use capnp::{serialize, text, ErrorKind};
+
+/// A message whose root is a text of 15 bytes and a NUL, or two words.
+fn message(options: ReaderOptions) -> message::Reader<serialize::OwnedSegments> {
+    let mut builder = message::Builder::new_default();
+    builder.set_root("fifteen letters").unwrap();
+    let bytes = serialize::write_message_to_words(&builder);
+    serialize::read_message(&bytes[..], options).unwrap()
+}
+
+fn lookup(message: &message::Reader<serialize::OwnedSegments>) -> capnp::Result<()> {
+    let text: text::Reader = message.get_root()?;
+    assert_eq!(text, "fifteen letters");
+    Ok(())
+}
+
+fn lookups_until_exhausted(message: &message::Reader<serialize::OwnedSegments>) -> usize {
+    let mut count = 0;
+    loop {
+        match lookup(message) {
+            Ok(()) => count += 1,
+            Err(error) => {
+                assert_eq!(error.kind, ErrorKind::ReadLimitExceeded);
+                return count;
+            }
+        }
+    }
+}
+
+/// The words and pointer dereferences that one lookup takes.
+fn cost() -> (usize, u64) {
+    let mut options = ReaderOptions::new();
+    options
+        .traversal_limit_in_words(Some(1000))
+        .traversal_limit_in_ops(Some(1000));
+    let message = message(options);
+    lookup(&message).unwrap();
+    (
+        1000 - message.remaining_traversal_limit_in_words().unwrap(),
+        1000 - message.remaining_traversal_limit_in_ops().unwrap(),
+    )
+}
+
+#[test]
+fn reset_restores_configured_limit() {
+    let (words, _) = cost();
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(Some(5 * words));
+    let message = message(options);
+    assert_eq!(lookups_until_exhausted(&message), 5);
+
+    message.reset_traversal_limits();
+    assert_eq!(
+        message.remaining_traversal_limit_in_words(),
+        Some(5 * words)
+    );
+    assert_eq!(lookups_until_exhausted(&message), 5);
+}
+
+#[test]
+fn refill_adds_to_what_is_left() {
+    let (words, ops) = cost();
+    let mut options = ReaderOptions::new();
+    options
+        .traversal_limit_in_words(Some(words + 1))
+        .traversal_limit_in_ops(Some(ops));
+    let message = message(options);
+    lookup(&message).unwrap();
+    assert_eq!(message.remaining_traversal_limit_in_words(), Some(1));
+    assert_eq!(message.remaining_traversal_limit_in_ops(), Some(0));
+    let error = lookup(&message).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Overloaded);
+
+    message.refill_traversal_limits(TraversalLimits {
+        words: 2 * words as u64,
+        max_ops: 2 * ops,
+    });
+    assert_eq!(message.remaining_traversal_limit_in_ops(), Some(2 * ops));
+    lookup(&message).unwrap();
+    lookup(&message).unwrap();
+    assert_eq!(message.remaining_traversal_limit_in_words(), Some(1));
+    assert_eq!(message.remaining_traversal_limit_in_ops(), Some(0));
+}
+
+#[test]
+fn unset_limits_stay_unlimited() {
+    let message = message(ReaderOptions::PERMISSIVE);
+    message.refill_traversal_limits(TraversalLimits {
+        words: 4,
+        max_ops: 2,
+    });
+    message.reset_traversal_limits();
+    assert_eq!(message.remaining_traversal_limit_in_words(), None);
+    assert_eq!(message.remaining_traversal_limit_in_ops(), None);
+    for _ in 0..1000 {
+        lookup(&message).unwrap();
+    }
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::{serialize, serialize_packed, word, ErrorKind, Word};
+
+/// A struct with one data word and one pointer to a second struct with one data word, in
+/// canonical form.
+const CANONICAL: &[Word] = &[
+    // Segment table: one segment of four words.
+    word(0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00),
+    // Root pointer: struct with one data word and one pointer.
+    word(0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00),
+    word(0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+    // Pointer to a struct with one data word, immediately following.
+    word(0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00),
+    word(0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+];
+
+/// `CANONICAL`, with an extra zero word at the end of the segment.
+const EXTRA_PADDING_WORD: &[Word] = &[
+    word(0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00),
+    word(0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00),
+    word(0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+    word(0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00),
+    word(0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+    word(0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+];
+
+/// A struct with two pointers, whose targets are laid out in the opposite order.
+const OUT_OF_ORDER_POINTERS: &[Word] = &[
+    word(0x00, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00),
+    // Root pointer: struct with two pointers.
+    word(0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00),
+    // Pointer to the last word.
+    word(0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00),
+    // Pointer to the word just after this one.
+    word(0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00),
+    word(0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+    word(0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+];
+
+fn strict() -> ReaderOptions {
+    let mut options = ReaderOptions::new();
+    options.require_canonical(true);
+    options
+}
+
+fn assert_rejected(words: &[Word], expected: &str) {
+    let bytes = Word::words_to_bytes(words);
+    for err in [
+        serialize::read_message(&mut &bytes[..], strict()).err(),
+        serialize::read_message_from_flat_slice(&mut &bytes[..], strict()).err(),
+        serialize::read_message_from_flat_slice_no_alloc(&mut &bytes[..], strict()).err(),
+    ] {
+        let err = err.expect("non-canonical message was accepted");
+        assert_eq!(err.kind, ErrorKind::Failed);
+        assert!(err.extra.contains(expected), "{}", err.extra);
+    }
+
+    // Without the option, the message reads fine.
+    let message = serialize::read_message(&mut &bytes[..], ReaderOptions::new()).unwrap();
+    assert!(!message.is_canonical().unwrap());
+}
+
+#[test]
+fn accepts_canonical_message() {
+    let bytes = Word::words_to_bytes(CANONICAL);
+    let message = serialize::read_message(&mut &bytes[..], strict()).unwrap();
+    assert!(message.is_canonical().unwrap());
+    let message = serialize::read_message_from_flat_slice(&mut &bytes[..], strict()).unwrap();
+    assert!(message.is_canonical().unwrap());
+}
+
+#[test]
+fn rejects_extra_padding_word() {
+    assert_rejected(EXTRA_PADDING_WORD, "unreachable");
+}
+
+#[test]
+fn rejects_out_of_order_pointers() {
+    assert_rejected(OUT_OF_ORDER_POINTERS, "depth-first");
+}
+
+#[test]
+fn rejects_multiple_segments() {
+    let mut builder = message::Builder::new(message::HeapAllocator::new().first_segment_words(1));
+    builder.set_root("hello").unwrap();
+    assert!(builder.get_segments_for_output().len() > 1);
+
+    let words = serialize::write_message_to_words(&builder);
+    let err = serialize::read_message(&mut &words[..], strict())
+        .err()
+        .unwrap();
+    assert!(err.extra.contains("more than one segment"), "{}", err.extra);
+
+    let mut packed = Vec::new();
+    serialize_packed::write_message(&mut packed, &builder).unwrap();
+    let err = serialize_packed::read_message(&packed[..], strict())
+        .err()
+        .unwrap();
+    assert!(err.extra.contains("more than one segment"), "{}", err.extra);
+}
+
+#[test]
+fn check_does_not_count_against_traversal_limit() {
+    let bytes = Word::words_to_bytes(CANONICAL);
+    let mut options = strict();
+    // Enough to read the message once, but not twice.
+    options.traversal_limit_in_words(Some(4));
+    let message = serialize::read_message(&mut &bytes[..], options).unwrap();
+    assert!(message.is_canonical().unwrap());
+}
@@ -0,0 +1,173 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, HeapAllocator};
+use capnp::{any_pointer_list, data, text, ErrorKind};
+
+const MAX_LEN: u32 = 100;
+
+/// Words taken by the root pointer and the root list of two pointers.
+const HEADER_WORDS: usize = 3;
+
+fn new_builder() -> message::Builder<HeapAllocator> {
+    let mut builder = message::Builder::new_default();
+    builder.initn_root::<any_pointer_list::Builder>(2);
+    builder
+}
+
+fn segment_words(builder: &message::Builder<HeapAllocator>) -> usize {
+    let segments = builder.get_segments_for_output();
+    assert_eq!(segments.len(), 1);
+    segments[0].len() / 8
+}
+
+fn get(builder: &message::Builder<HeapAllocator>, index: u32) -> capnp::any_pointer::Reader<'_> {
+    let list: any_pointer_list::Reader = builder.get_root_as_reader().unwrap();
+    list.get(index)
+}
+
+fn reserve_and_finish_data(len: u32) -> message::Builder<HeapAllocator> {
+    let mut builder = new_builder();
+    {
+        let list: any_pointer_list::Builder = builder.get_root().unwrap();
+        let mut reservation = data::reserve(list.get(0), MAX_LEN).unwrap();
+        assert_eq!(reservation.capacity(), MAX_LEN);
+        reservation.as_bytes_mut().fill(0xaa);
+        let finished = reservation.finish(len);
+        assert_eq!(finished.len(), len as usize);
+    }
+    builder
+}
+
+#[test]
+fn finish_data_reclaims_tail() {
+    for (len, words) in [(0, 0), (37, 5), (MAX_LEN, 13)] {
+        let builder = reserve_and_finish_data(len);
+        assert_eq!(segment_words(&builder), HEADER_WORDS + words);
+        let value: data::Reader = get(&builder, 0).get_as().unwrap();
+        assert_eq!(value, &vec![0xaa; len as usize][..]);
+    }
+}
+
+#[test]
+fn finish_text_reclaims_tail() {
+    for (len, words) in [(0, 1), (37, 5), (MAX_LEN, 13)] {
+        let mut builder = new_builder();
+        {
+            let list: any_pointer_list::Builder = builder.get_root().unwrap();
+            let mut reservation = text::reserve(list.get(0), MAX_LEN).unwrap();
+            reservation.as_bytes_mut().fill(b'x');
+            let mut finished = reservation.finish(len);
+            assert_eq!(finished.len(), len as usize);
+            // The builder is positioned at the end of the text.
+            if len < MAX_LEN {
+                finished.clear();
+                finished.push_str(&"y".repeat(len as usize));
+            }
+        }
+        assert_eq!(segment_words(&builder), HEADER_WORDS + words);
+        let value: text::Reader = get(&builder, 0).get_as().unwrap();
+        let expected = if len < MAX_LEN { "y" } else { "x" };
+        assert_eq!(value, expected.repeat(len as usize).as_str());
+    }
+}
+
+#[test]
+fn later_allocation_prevents_reclamation() {
+    for len in [0, 37, MAX_LEN] {
+        let mut builder = new_builder();
+        {
+            let mut list: any_pointer_list::Builder = builder.get_root().unwrap();
+            let mut reservation = list.reborrow().get(0).reserve_data(MAX_LEN).unwrap();
+            reservation.as_bytes_mut().fill(0xaa);
+        }
+        {
+            let mut list: any_pointer_list::Builder = builder.get_root().unwrap();
+            list.reborrow().get(1).set_as("later").unwrap();
+            let reservation = list.get(0).get_data_reservation().unwrap();
+            assert_eq!(reservation.capacity(), MAX_LEN);
+            reservation.finish(len);
+        }
+        assert_eq!(segment_words(&builder), HEADER_WORDS + 13 + 1);
+        let value: data::Reader = get(&builder, 0).get_as().unwrap();
+        assert_eq!(value, &vec![0xaa; len as usize][..]);
+        let later: text::Reader = get(&builder, 1).get_as().unwrap();
+        assert_eq!(later, "later");
+
+        // The slack is zeroed.
+        let segment = builder.get_segments_for_output()[0];
+        let start = HEADER_WORDS * 8;
+        assert!(segment[start + len as usize..start + MAX_LEN as usize]
+            .iter()
+            .all(|&b| b == 0));
+    }
+}
+
+#[test]
+fn later_allocation_prevents_text_reclamation() {
+    let mut builder = new_builder();
+    {
+        let mut list: any_pointer_list::Builder = builder.get_root().unwrap();
+        list.reborrow()
+            .get(0)
+            .reserve_text(MAX_LEN)
+            .unwrap()
+            .as_bytes_mut()
+            .fill(b'x');
+        list.reborrow().get(1).set_as("later").unwrap();
+        list.get(0).get_text_reservation().unwrap().finish(37);
+    }
+    assert_eq!(segment_words(&builder), HEADER_WORDS + 13 + 1);
+    let value: text::Reader = get(&builder, 0).get_as().unwrap();
+    assert_eq!(value, "x".repeat(37).as_str());
+}
+
+#[test]
+fn reservation_of_non_blob() {
+    let mut builder = new_builder();
+    let mut list: any_pointer_list::Builder = builder.get_root().unwrap();
+    assert_eq!(
+        list.reborrow()
+            .get(0)
+            .get_data_reservation()
+            .err()
+            .unwrap()
+            .kind,
+        ErrorKind::ExistingPointerIsNotAList
+    );
+    list.reborrow().get(0).set_as(&[1u8, 2, 3][..]).unwrap();
+    assert_eq!(
+        list.get(0).get_text_reservation().err().unwrap().kind,
+        ErrorKind::TextBlobMissingNULTerminator
+    );
+}
+
+#[test]
+#[should_panic]
+fn finish_beyond_capacity() {
+    let mut builder = new_builder();
+    let list: any_pointer_list::Builder = builder.get_root().unwrap();
+    list.get(0).reserve_data(8).unwrap().finish(9);
+}
+
+#[test]
+fn reserve_too_long() {
+    let mut builder = new_builder();
+    let mut list: any_pointer_list::Builder = builder.get_root().unwrap();
+    let too_long = 1 << 29;
+    assert_eq!(
+        data::reserve(list.reborrow().get(0), too_long)
+            .err()
+            .unwrap()
+            .kind,
+        ErrorKind::BlobTooLong(too_long as usize)
+    );
+    // Text needs room for its NUL terminator too.
+    assert_eq!(
+        text::reserve(list.reborrow().get(0), too_long - 1)
+            .err()
+            .unwrap()
+            .kind,
+        ErrorKind::BlobTooLong(too_long as usize - 1)
+    );
+    assert!(list.get(0).is_null());
+}
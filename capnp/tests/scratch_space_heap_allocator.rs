@@ -20,3 +20,51 @@ pub fn scratch_space_heap_allocator() {
         assert_eq!(w, capnp::word(0, 0, 0, 0, 0, 0, 0, 0));
     }
 }
+
+#[test]
+fn message_that_fits_in_scratch_space_stays_in_it() {
+    let mut scratch = capnp::Word::allocate_zeroed_vec(16);
+    let scratch_start = scratch.as_ptr().cast::<u8>();
+    let mut msg =
+        message::Builder::new(message::ScratchSpaceHeapAllocator::from_words(&mut scratch));
+    msg.set_root("hello world!").unwrap();
+    let segments = msg.get_segments_for_output();
+    // Only the scratch space, so nothing was allocated on the heap for segments.
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].as_ptr(), scratch_start);
+    // The root pointer, and the text with its NUL terminator.
+    assert_eq!(segments[0].len(), 3 * 8);
+}
+
+#[test]
+fn message_that_outgrows_scratch_space_continues_on_heap() {
+    let mut scratch = capnp::Word::allocate_zeroed_vec(4);
+    let scratch_start = scratch.as_ptr().cast::<u8>();
+    let text = "a text too long for the scratch space";
+    let bytes = {
+        let mut msg =
+            message::Builder::new(message::ScratchSpaceHeapAllocator::from_words(&mut scratch));
+        msg.set_root(text).unwrap();
+        let segments = msg.get_segments_for_output();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].as_ptr(), scratch_start);
+        capnp::serialize::write_message_to_words(&msg)
+    };
+    let reader = capnp::serialize::read_message(&bytes[..], message::ReaderOptions::new()).unwrap();
+    assert_eq!(reader.get_root::<capnp::text::Reader>().unwrap(), text);
+
+    // The scratch space was zeroed again when the builder was dropped.
+    for w in scratch {
+        assert_eq!(w, capnp::word(0, 0, 0, 0, 0, 0, 0, 0));
+    }
+}
+
+#[test]
+fn message_that_exactly_fills_scratch_space_stays_in_it() {
+    let mut scratch = capnp::Word::allocate_zeroed_vec(3);
+    let mut msg =
+        message::Builder::new(message::ScratchSpaceHeapAllocator::from_words(&mut scratch));
+    // The root pointer, and sixteen bytes of text with its NUL terminator.
+    msg.set_root("fifteen letters").unwrap();
+    assert_eq!(msg.get_segments_for_output().len(), 1);
+}
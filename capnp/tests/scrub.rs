@@ -0,0 +1,81 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, AllocationStrategy, HeapAllocator, ReaderOptions};
+use capnp::{data, serialize};
+
+const SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+/// Returns the start and length in bytes of each segment of `builder`.
+fn segment_spans<A: message::Allocator>(builder: &message::Builder<A>) -> Vec<(*const u8, usize)> {
+    builder
+        .get_segments_for_output()
+        .iter()
+        .map(|segment| (segment.as_ptr(), segment.len()))
+        .collect()
+}
+
+fn assert_zeroed(spans: &[(*const u8, usize)]) {
+    for (ptr, len) in spans {
+        // The memory is still owned by a live message, and nothing is writing to it.
+        let bytes = unsafe { std::slice::from_raw_parts(*ptr, *len) };
+        assert!(bytes.iter().all(|b| *b == 0));
+    }
+}
+
+#[test]
+fn scrub_builder() {
+    let mut builder = message::Builder::new(
+        HeapAllocator::new()
+            .first_segment_words(2)
+            .allocation_strategy(AllocationStrategy::FixedSize),
+    );
+    builder.set_root(SECRET).unwrap();
+    let spans = segment_spans(&builder);
+    assert!(spans.len() > 1);
+
+    builder.scrub();
+    assert_zeroed(&spans);
+
+    // The root is now null, and the builder can be reused.
+    let root: data::Reader = builder.get_root_as_reader().unwrap();
+    assert!(root.is_empty());
+    builder.set_root(&b"public"[..]).unwrap();
+    let root: data::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(root, b"public");
+}
+
+#[test]
+fn scrub_scratch_space_builder() {
+    let mut scratch = capnp::Word::allocate_zeroed_vec(16);
+    {
+        let mut allocator =
+            message::ScratchSpaceHeapAllocator::new(capnp::Word::words_to_bytes_mut(&mut scratch));
+        let mut builder = message::Builder::new(&mut allocator);
+        builder.set_root(SECRET).unwrap();
+        let spans = segment_spans(&builder);
+        builder.scrub();
+        assert_zeroed(&spans);
+    }
+    assert!(capnp::Word::words_to_bytes(&scratch)
+        .iter()
+        .all(|b| *b == 0));
+}
+
+#[test]
+fn scrub_owned_segments() {
+    let mut builder = message::Builder::new_default();
+    builder.set_root(SECRET).unwrap();
+    let bytes = serialize::write_message_to_words(&builder);
+    let reader = serialize::read_message(&mut &bytes[..], ReaderOptions::new()).unwrap();
+    let root: data::Reader = reader.get_root().unwrap();
+    assert_eq!(root, SECRET);
+
+    let mut segments = reader.into_segments();
+    assert!(segments.len() > SECRET.len());
+    segments.scrub();
+    assert!(segments.iter().all(|b| *b == 0));
+
+    // Scrubbing through the reader consumes it.
+    let reader = serialize::read_message(&mut &bytes[..], ReaderOptions::new()).unwrap();
+    reader.scrub();
+}
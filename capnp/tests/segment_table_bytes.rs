@@ -0,0 +1,103 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::{serialize, ErrorKind};
+
+fn round_trip(lengths: &[u32], fixture: &[u8]) {
+    assert_eq!(serialize::segment_table_bytes(lengths), fixture);
+    let (parsed, table_len) = serialize::parse_segment_table_bytes(fixture).unwrap();
+    assert_eq!(parsed, lengths);
+    assert_eq!(table_len, fixture.len());
+
+    // Whatever follows the table is left alone.
+    let mut framed = fixture.to_vec();
+    framed.extend_from_slice(&[0xaa; 16]);
+    assert_eq!(
+        serialize::parse_segment_table_bytes(&framed).unwrap(),
+        (lengths.to_vec(), fixture.len())
+    );
+}
+
+#[test]
+fn one_segment() {
+    round_trip(&[5], &[0, 0, 0, 0, 5, 0, 0, 0]);
+}
+
+#[test]
+fn two_segments() {
+    // An even number of segments is followed by four bytes of padding.
+    round_trip(&[1, 2], &[1, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+fn three_segments() {
+    round_trip(
+        &[1, 0x0102_0304, 3],
+        &[2, 0, 0, 0, 1, 0, 0, 0, 4, 3, 2, 1, 3, 0, 0, 0],
+    );
+}
+
+#[test]
+fn five_hundred_segments() {
+    let lengths: Vec<u32> = (0..500).map(|idx| idx * 3).collect();
+    let mut fixture = vec![0xf3, 0x01, 0, 0];
+    for length in &lengths {
+        fixture.extend_from_slice(&length.to_le_bytes());
+    }
+    fixture.extend_from_slice(&[0; 4]);
+    assert_eq!(fixture.len(), 251 * 8);
+    round_trip(&lengths, &fixture);
+}
+
+#[test]
+fn matches_write_message() {
+    let mut builder = message::Builder::new(message::HeapAllocator::new().first_segment_words(1));
+    let mut root: node::Builder = builder.init_root();
+    root.set_display_name("segment_table_bytes.capnp".into());
+    root.init_nested_nodes(4);
+    let segments = builder.get_segments_for_output();
+    let lengths: Vec<u32> = segments
+        .iter()
+        .map(|segment| (segment.len() / 8) as u32)
+        .collect();
+    assert!(lengths.len() > 1);
+
+    let framed = serialize::write_message_to_words(&builder);
+    let table = serialize::segment_table_bytes(&lengths);
+    assert!(framed.starts_with(&table));
+    assert_eq!(
+        serialize::parse_segment_table_bytes(&framed).unwrap(),
+        (lengths, table.len())
+    );
+
+    // Segments framed by hand read back as a message.
+    let mut bytes = table;
+    for segment in &*segments {
+        bytes.extend_from_slice(segment);
+    }
+    let message =
+        serialize::read_message_from_flat_slice(&mut &bytes[..], ReaderOptions::new()).unwrap();
+    let root: node::Reader = message.get_root().unwrap();
+    assert_eq!(
+        root.get_display_name().unwrap(),
+        "segment_table_bytes.capnp"
+    );
+}
+
+#[test]
+fn rejects_bad_tables() {
+    let error = serialize::parse_segment_table_bytes(&[0, 0, 0, 0]).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::PrematureEndOfFile);
+
+    // Three segments declared, but only the first word of the table.
+    let error = serialize::parse_segment_table_bytes(&[2, 0, 0, 0, 1, 0, 0, 0]).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::PrematureEndOfFile);
+
+    let mut table = serialize::segment_table_bytes(&[1; 512]);
+    let error = serialize::parse_segment_table_bytes(&table).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::InvalidNumberOfSegments(512));
+    table[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+    let error = serialize::parse_segment_table_bytes(&table).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::InvalidNumberOfSegments(0));
+}
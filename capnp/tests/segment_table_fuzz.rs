@@ -0,0 +1,150 @@
+#![cfg(feature = "alloc")]
+
+//! Runs truncated and corrupted segment tables through every reader of the standard encoding,
+//! checking that malformed tables fail with an error rather than a panic.
+
+use std::panic::{self, AssertUnwindSafe};
+
+use capnp::message::{self, ReaderOptions, ReaderSegments};
+use capnp::{any_pointer, serialize, ErrorKind, Word};
+
+/// The contents of a struct with one data word.
+const DATA: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+/// A struct pointer to the word right after it, which holds the struct's one data word.
+const STRUCT_POINTER: [u8; 8] = [0, 0, 0, 0, 1, 0, 0, 0];
+
+/// Frames `segments` with a segment table.
+fn frame(segments: &[&[[u8; 8]]]) -> Vec<u8> {
+    let lengths: Vec<u32> = segments
+        .iter()
+        .map(|segment| segment.len() as u32)
+        .collect();
+    let mut bytes = serialize::segment_table_bytes(&lengths);
+    for segment in segments {
+        bytes.extend(segment.iter().flatten());
+    }
+    bytes
+}
+
+/// Messages with one, two and three segments, whose roots are all the struct with `DATA`. The
+/// table of two segments ends in a word of padding.
+fn messages() -> Vec<Vec<u8>> {
+    // A far pointer to the start of segment 1, and the landing pad there.
+    let far = [0x02, 0, 0, 0, 1, 0, 0, 0];
+    vec![
+        frame(&[&[STRUCT_POINTER, DATA]]),
+        frame(&[&[far], &[STRUCT_POINTER, DATA]]),
+        frame(&[&[far], &[STRUCT_POINTER, DATA], &[]]),
+    ]
+}
+
+/// Copies `bytes` to an aligned buffer, for the readers that borrow their input.
+fn aligned(bytes: &[u8]) -> Vec<Word> {
+    let mut words = Word::allocate_zeroed_vec(bytes.len().div_ceil(8));
+    Word::words_to_bytes_mut(&mut words)[..bytes.len()].copy_from_slice(bytes);
+    words
+}
+
+fn read_root<S: ReaderSegments>(message: capnp::Result<message::Reader<S>>) -> capnp::Result<()> {
+    message?
+        .get_root::<any_pointer::Reader>()?
+        .target_size()
+        .map(drop)
+}
+
+/// Reads `bytes` with each reader, returning their results.
+fn read_all(bytes: &[u8]) -> Vec<capnp::Result<()>> {
+    let options = ReaderOptions::new();
+    let words = aligned(bytes);
+    let aligned_bytes = &Word::words_to_bytes(&words)[..bytes.len()];
+    let mut buffer = Word::allocate_zeroed_vec(64);
+    vec![
+        read_root(serialize::read_message(bytes, options)),
+        read_root(serialize::read_message_buffered(bytes, options)),
+        read_root(serialize::read_message_from_flat_slice(
+            &mut { aligned_bytes },
+            options,
+        )),
+        read_root(serialize::read_message_from_flat_slice_no_alloc(
+            &mut { aligned_bytes },
+            options,
+        )),
+        read_root(serialize::read_message_from_words(&words, options)),
+        read_root(serialize::read_message_no_alloc(
+            bytes,
+            Word::words_to_bytes_mut(&mut buffer),
+            options,
+        )),
+        serialize::parse_segment_table_bytes(bytes).map(drop),
+    ]
+}
+
+#[test]
+fn fuzzed_segment_tables_do_not_panic() {
+    let mut inputs = Vec::new();
+    for message in messages() {
+        for len in 0..message.len() {
+            inputs.push(message[..len].to_vec());
+        }
+        // Corrupt each byte of the segment table, and of the first word after it.
+        let table_len = serialize::parse_segment_table_bytes(&message).unwrap().1;
+        for idx in 0..table_len + 8 {
+            for value in [0x00, 0x01, 0x02, 0x7f, 0x80, 0xfe, 0xff] {
+                let mut corrupted = message.clone();
+                corrupted[idx] = value;
+                inputs.push(corrupted);
+            }
+        }
+        inputs.push(message);
+    }
+
+    let mut panicked = Vec::new();
+    for input in &inputs {
+        if panic::catch_unwind(AssertUnwindSafe(|| read_all(input))).is_err() {
+            panicked.push(input);
+        }
+    }
+    assert!(panicked.is_empty(), "panicked on {panicked:02x?}");
+}
+
+#[test]
+fn zero_segments() {
+    // The table stores the segment count minus one, so this declares 2^32 segments, which
+    // readers that count in a `u32` see as zero.
+    let bytes = [0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0];
+    for result in read_all(&bytes) {
+        let kind = result.unwrap_err().kind;
+        assert!(
+            matches!(kind, ErrorKind::InvalidNumberOfSegments(_)),
+            "{kind:?}"
+        );
+    }
+}
+
+#[test]
+fn first_segment_too_small_for_root_pointer() {
+    let empty_first = [frame(&[&[]]), frame(&[&[], &[STRUCT_POINTER, DATA]])];
+    for bytes in empty_first {
+        let results = read_all(&bytes);
+        // parse_segment_table_bytes() reads the table alone, which is well formed.
+        let (last, readers) = results.split_last().unwrap();
+        assert!(last.is_ok());
+        for result in readers {
+            assert!(result.is_err());
+        }
+    }
+}
+
+#[test]
+fn padding_of_even_segment_count_is_ignored() {
+    let mut bytes = messages().swap_remove(1);
+    // The padding is the last four bytes of the table's two words.
+    bytes[12..16].copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+    for result in read_all(&bytes) {
+        result.unwrap();
+    }
+    let message = serialize::read_message(&bytes[..], ReaderOptions::new()).unwrap();
+    let root: any_pointer::Reader = message.get_root().unwrap();
+    assert_eq!(root.target_size().unwrap().word_count, 1);
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "alloc")]
+
+use capnp::io::Read;
+use capnp::message::{ReaderOptions, ReaderSegments};
+use capnp::serialize::{self, SEGMENTS_COUNT_LIMIT};
+use capnp::{ErrorKind, Result};
+
+/// Returns the first word of a segment table and then panics, so that a reader that does not
+/// stop at the segment count is caught.
+struct FirstWordOnly {
+    first_word: [u8; 8],
+    read: bool,
+}
+
+impl Read for FirstWordOnly {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        assert!(!self.read, "read past the first word of the segment table");
+        self.read = true;
+        assert!(buf.len() >= 8);
+        buf[..8].copy_from_slice(&self.first_word);
+        Ok(8)
+    }
+}
+
+#[test]
+fn huge_segment_count_fails_on_first_word() {
+    // Declares 2^32 - 1 segments, whose table would take 16 GiB.
+    let read = FirstWordOnly {
+        first_word: [0xfe, 0xff, 0xff, 0xff, 1, 0, 0, 0],
+        read: false,
+    };
+    let Err(error) = serialize::read_message(read, ReaderOptions::new()) else {
+        panic!("message with 2^32 - 1 segments accepted");
+    };
+    assert_eq!(
+        error.kind,
+        ErrorKind::InvalidNumberOfSegments(u32::MAX as usize)
+    );
+}
+
+/// A message of `segment_count` one-word segments, the first of which holds a null root pointer.
+fn many_segments(segment_count: usize) -> Vec<u8> {
+    let mut bytes = serialize::segment_table_bytes(&vec![1; segment_count]);
+    bytes.resize(bytes.len() + segment_count * 8, 0);
+    bytes
+}
+
+#[test]
+fn limit_is_configurable() {
+    let bytes = many_segments(600);
+    for result in [
+        serialize::read_message(&bytes[..], ReaderOptions::new()).map(drop),
+        serialize::read_message_from_flat_slice(&mut &bytes[..], ReaderOptions::new()).map(drop),
+        serialize::MessageReadState::new(ReaderOptions::new())
+            .push(&bytes)
+            .map(drop),
+    ] {
+        assert_eq!(
+            result.unwrap_err().kind,
+            ErrorKind::InvalidNumberOfSegments(600)
+        );
+    }
+
+    let mut options = ReaderOptions::new();
+    options.segments_limit(1024);
+    let message = serialize::read_message(&bytes[..], options).unwrap();
+    assert_eq!(ReaderSegments::len(&message.into_segments()), 600);
+    serialize::read_message_from_flat_slice(&mut &bytes[..], options).unwrap();
+    assert!(serialize::MessageReadState::new(options)
+        .push(&bytes)
+        .unwrap()
+        .is_some());
+
+    // The default lets through one segment fewer than the limit.
+    let bytes = many_segments(SEGMENTS_COUNT_LIMIT - 1);
+    serialize::read_message(&bytes[..], ReaderOptions::new()).unwrap();
+    let bytes = many_segments(SEGMENTS_COUNT_LIMIT);
+    assert!(serialize::read_message(&bytes[..], ReaderOptions::new()).is_err());
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::serialize::SerializedMessage;
+use capnp::{text, text_list, ErrorKind};
+
+fn framed_list(len: u32) -> Vec<u8> {
+    let mut builder = message::Builder::new_default();
+    {
+        let mut list: text_list::Builder = builder.initn_root(len);
+        for idx in 0..len {
+            list.set(idx, format!("item {idx}").as_str().into());
+        }
+    }
+    (&mut builder).into()
+}
+
+fn first_item(bytes: &[u8]) -> capnp::Result<String> {
+    let message = SerializedMessage::try_from(bytes)?;
+    let reader = message.reader();
+    let list: text_list::Reader = reader.get_root()?;
+    Ok(list.get(0)?.to_string()?)
+}
+
+#[test]
+fn round_trip() {
+    let bytes = framed_list(3);
+    assert_eq!(first_item(&bytes).unwrap(), "item 0");
+
+    let message = SerializedMessage::try_from(bytes.clone()).unwrap();
+    assert_eq!(&message[..], &bytes[..]);
+    for _ in 0..2 {
+        let reader = message.reader();
+        let list: text_list::Reader = reader.get_root().unwrap();
+        assert_eq!(list.get(2).unwrap(), "item 2");
+    }
+    assert_eq!(Vec::<u8>::from(message), bytes);
+}
+
+#[test]
+fn unaligned_input() {
+    let bytes = framed_list(3);
+    let mut shifted = vec![0u8];
+    shifted.extend_from_slice(&bytes);
+    assert_eq!(first_item(&shifted[1..]).unwrap(), "item 0");
+}
+
+#[test]
+fn truncated_input() {
+    let bytes = framed_list(3);
+    assert!(matches!(
+        first_item(&bytes[..bytes.len() - 8]).unwrap_err().kind,
+        ErrorKind::MessageEndsPrematurely(_, _)
+    ));
+    assert!(first_item(&bytes[..4]).is_err());
+    assert_eq!(first_item(&[]).unwrap_err().kind, ErrorKind::EmptySlice);
+}
+
+#[test]
+fn trailing_bytes() {
+    let mut bytes = framed_list(3);
+    bytes.extend_from_slice(&[0; 8]);
+    assert_eq!(first_item(&bytes).unwrap_err().kind, ErrorKind::Failed);
+}
+
+#[test]
+fn carries_reader_options() {
+    let bytes = framed_list(3);
+    let mut options = ReaderOptions::new();
+    options.nesting_limit(0);
+    let message = SerializedMessage::new(&bytes, options).unwrap();
+    assert_eq!(message.options().nesting_limit, 0);
+    let reader = message.reader();
+    assert_eq!(
+        reader.get_root::<text_list::Reader>().err().unwrap().kind,
+        ErrorKind::NestingLimitExceeded
+    );
+
+    // A shared builder converts too.
+    let mut builder = message::Builder::new_default();
+    builder.set_root("hi").unwrap();
+    let bytes: Vec<u8> = (&builder).into();
+    let message = SerializedMessage::try_from(bytes).unwrap();
+    let reader = message.reader();
+    assert_eq!(reader.get_root::<text::Reader>().unwrap(), "hi");
+}
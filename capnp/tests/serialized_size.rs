@@ -0,0 +1,35 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::serialize;
+
+#[test]
+fn matches_write_message() {
+    // One segment and three, whose segment tables are a whole number of words, and two, whose
+    // table is padded.
+    for (first_segment_words, segment_count) in [(512, 1), (10, 2), (1, 3)] {
+        let mut builder = message::Builder::new(
+            message::HeapAllocator::new().first_segment_words(first_segment_words),
+        );
+        let mut root: node::Builder = builder.init_root();
+        root.set_display_name("serialized_size.capnp".into());
+        root.init_nested_nodes(3);
+        assert_eq!(builder.get_segments_for_output().len(), segment_count);
+
+        let mut written = Vec::new();
+        serialize::write_message(&mut written, &builder).unwrap();
+        assert_eq!(written.len() % 8, 0);
+        let words = serialize::compute_serialized_size_in_words(&builder);
+        assert_eq!(words, written.len() / 8);
+
+        let message = serialize::read_message(&written[..], ReaderOptions::new()).unwrap();
+        assert_eq!(
+            serialize::compute_reader_serialized_size_in_words(&message),
+            words
+        );
+        let mut rewritten = Vec::new();
+        serialize::write_message_segments(&mut rewritten, &message.into_segments()).unwrap();
+        assert_eq!(rewritten, written);
+    }
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use capnp::message::{self, AllocationStrategy, HeapAllocator, ReaderOptions};
+use capnp::private::layout::CapTable;
+use capnp::schema_capnp::code_generator_request;
+use capnp::traits::{Imbue, ImbueMut};
+use capnp::{any_pointer, any_pointer_list, ErrorKind};
+
+use common::Hook;
+
+/// A message spread over many tiny segments, so that most of its pointers are far pointers.
+fn scattered_request() -> message::Builder<HeapAllocator> {
+    let allocator = HeapAllocator::new()
+        .first_segment_words(1)
+        .allocation_strategy(AllocationStrategy::FixedSize);
+    let mut builder = message::Builder::new(allocator);
+    let mut request: code_generator_request::Builder = builder.init_root();
+    let mut nodes = request.reborrow().init_nodes(3);
+    for idx in 0..3 {
+        let mut node = nodes.reborrow().get(idx);
+        node.set_id(100 + u64::from(idx));
+        node.set_display_name(format!("node{idx}.capnp").as_str().into());
+    }
+    let mut files = request.init_requested_files(1);
+    files.reborrow().get(0).set_filename("file.capnp".into());
+    builder
+}
+
+#[test]
+fn set_root_copies_struct_from_another_message() {
+    let source = scattered_request();
+    assert!(source.get_segments_for_output().len() > 5);
+    let source = source.into_reader();
+
+    let mut copy = message::Builder::new_default();
+    copy.set_root(source.get_root::<code_generator_request::Reader>().unwrap())
+        .unwrap();
+    assert_eq!(copy.get_segments_for_output().len(), 1);
+
+    let root: code_generator_request::Reader = copy.get_root_as_reader().unwrap();
+    let nodes = root.get_nodes().unwrap();
+    assert_eq!(nodes.len(), 3);
+    for (idx, node) in nodes.iter().enumerate() {
+        assert_eq!(node.get_id(), 100 + idx as u64);
+        assert_eq!(
+            node.get_display_name().unwrap(),
+            format!("node{idx}.capnp").as_str()
+        );
+    }
+    assert_eq!(
+        root.get_requested_files()
+            .unwrap()
+            .get(0)
+            .get_filename()
+            .unwrap(),
+        "file.capnp"
+    );
+    let copy = copy.into_reader_with_options(ReaderOptions::new());
+    assert_eq!(copy.canonicalize().unwrap(), source.canonicalize().unwrap());
+}
+
+fn caps_message(caps: &mut CapTable) -> message::Builder<HeapAllocator> {
+    let mut builder = message::Builder::new_default();
+    let mut root: any_pointer::Builder = builder.init_root();
+    root.imbue_mut(caps);
+    let mut list: any_pointer_list::Builder = root.initn_as(2);
+    for idx in 0..2 {
+        list.reborrow()
+            .get(idx)
+            .set_as_capability(Box::new(Hook(20 + idx as usize)));
+    }
+    builder
+}
+
+#[test]
+fn set_root_without_cap_table_rejects_capabilities() {
+    let mut caps = CapTable::new();
+    let source = caps_message(&mut caps);
+    let mut root: any_pointer::Reader = source.get_root_as_reader().unwrap();
+    root.imbue(&caps);
+
+    let mut copy = message::Builder::new_default();
+    let error = copy.set_root(root).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::CannotCopyACapabilityWithoutACapTable);
+}
+
+#[test]
+fn capabilities_are_carried_into_imbued_root() {
+    let mut caps = CapTable::new();
+    let source = caps_message(&mut caps);
+    let mut root: any_pointer::Reader = source.get_root_as_reader().unwrap();
+    root.imbue(&caps);
+
+    let mut copy_caps = CapTable::new();
+    let mut copy = message::Builder::new_default();
+    let mut copy_root: any_pointer::Builder = copy.init_root();
+    copy_root.imbue_mut(&mut copy_caps);
+    copy_root.set_as(root).unwrap();
+
+    let ptrs: Vec<_> = copy_caps
+        .iter()
+        .map(|cap| cap.as_ref().unwrap().get_ptr())
+        .collect();
+    assert_eq!(ptrs, [20, 21]);
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "alloc")]
+
+use std::sync::Arc;
+
+use capnp::message::{self, ReaderOptions, SharedReadBudget, TraversalLimits};
+use capnp::schema_capnp::node;
+use capnp::{serialize, ErrorKind};
+
+fn message_bytes() -> Vec<u8> {
+    let mut builder = message::Builder::new_default();
+    let mut root: node::Builder = builder.init_root();
+    root.set_display_name("shared_read_budget.capnp".into());
+    root.init_nested_nodes(4);
+    serialize::write_message_to_bytes(&builder)
+}
+
+fn read(bytes: &[u8], budget: &Arc<SharedReadBudget>) -> message::Reader<serialize::OwnedSegments> {
+    let mut message = serialize::read_message(bytes, ReaderOptions::new()).unwrap();
+    message.set_shared_budget(budget.clone());
+    message
+}
+
+/// The cost of one `canonicalize()` of the message.
+fn canonicalize_cost(bytes: &[u8]) -> TraversalLimits {
+    let unlimited = TraversalLimits {
+        words: u64::MAX / 2,
+        max_ops: u64::MAX / 2,
+    };
+    let budget = Arc::new(SharedReadBudget::new(unlimited));
+    read(bytes, &budget).canonicalize().unwrap();
+    let remaining = budget.remaining();
+    TraversalLimits {
+        words: unlimited.words - remaining.words,
+        max_ops: unlimited.max_ops - remaining.max_ops,
+    }
+}
+
+#[test]
+fn third_half_budget_operation_fails() {
+    let bytes = message_bytes();
+    let cost = canonicalize_cost(&bytes);
+    assert!(cost.words > 0 && cost.max_ops > 0);
+    // The words run out first; were it the dereferences, the error would be `Overloaded`.
+    let budget = Arc::new(SharedReadBudget::new(TraversalLimits {
+        words: 2 * cost.words,
+        max_ops: 3 * cost.max_ops,
+    }));
+
+    // The operations are spread over two readers of the same message, each of which would have
+    // allowed all three on its own.
+    let first = read(&bytes, &budget);
+    let second = read(&bytes, &budget);
+    first.canonicalize().unwrap();
+    assert_eq!(
+        budget.remaining(),
+        TraversalLimits {
+            words: cost.words,
+            max_ops: 2 * cost.max_ops,
+        }
+    );
+    assert_eq!(
+        second.remaining_traversal_limit_in_words(),
+        Some(cost.words as usize)
+    );
+    second.canonicalize().unwrap();
+    assert_eq!(budget.remaining().words, 0);
+    let error = first.canonicalize().unwrap_err();
+    assert_eq!(error.kind, ErrorKind::ReadLimitExceeded);
+
+    // A refill lets reads go on.
+    budget.refill(cost);
+    second.canonicalize().unwrap();
+}
+
+#[test]
+fn budget_is_charged_by_copies_and_getters() {
+    let bytes = message_bytes();
+    let budget = Arc::new(SharedReadBudget::new(TraversalLimits {
+        words: 1000,
+        max_ops: 1000,
+    }));
+    let message = read(&bytes, &budget);
+    let root: node::Reader = message.get_root().unwrap();
+    let after_root = budget.remaining();
+    assert!(after_root.words < 1000 && after_root.max_ops < 1000);
+
+    let mut copy = message::Builder::new_default();
+    copy.set_root(root).unwrap();
+    assert!(budget.remaining().words < after_root.words);
+
+    // Without a budget, the reader's own limits apply as before.
+    let message = serialize::read_message(&bytes[..], ReaderOptions::new()).unwrap();
+    message.get_root::<node::Reader>().unwrap();
+    assert!(message.remaining_traversal_limit_in_words().unwrap() > 1000);
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, Allocator, HeapAllocator};
+use capnp::{primitive_list, text};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Wraps a `HeapAllocator`, keeping track of how many words are currently allocated.
+struct CountingAllocator {
+    inner: HeapAllocator,
+    live_words: Rc<Cell<usize>>,
+}
+
+unsafe impl Allocator for CountingAllocator {
+    fn allocate_segment(&mut self, minimum_size: u32) -> (*mut u8, u32) {
+        let (ptr, size) = self.inner.allocate_segment(minimum_size);
+        self.live_words.set(self.live_words.get() + size as usize);
+        (ptr, size)
+    }
+
+    fn allocate_replacement_segment(&mut self, minimum_size: u32) -> Option<(*mut u8, u32)> {
+        let (ptr, size) = self.inner.allocate_replacement_segment(minimum_size)?;
+        self.live_words.set(self.live_words.get() + size as usize);
+        Some((ptr, size))
+    }
+
+    unsafe fn deallocate_segment(&mut self, ptr: *mut u8, word_size: u32, words_used: u32) {
+        self.live_words
+            .set(self.live_words.get() - word_size as usize);
+        self.inner.deallocate_segment(ptr, word_size, words_used)
+    }
+}
+
+fn counting_builder() -> (message::Builder<CountingAllocator>, Rc<Cell<usize>>) {
+    let live_words = Rc::new(Cell::new(0));
+    let builder = message::Builder::new(CountingAllocator {
+        inner: HeapAllocator::new(),
+        live_words: live_words.clone(),
+    });
+    (builder, live_words)
+}
+
+#[test]
+fn shrink_after_overwriting_large_root() {
+    let (mut builder, live_words) = counting_builder();
+    {
+        let mut list: primitive_list::Builder<u64> = builder.initn_root(100_000);
+        list.set(99_999, 1);
+    }
+    builder.set_root("small").unwrap();
+    assert!(live_words.get() >= 100_000);
+
+    builder.shrink_to_fit().unwrap();
+    assert_eq!(builder.get_segments_for_output().len(), 1);
+    assert!(live_words.get() <= message::SUGGESTED_FIRST_SEGMENT_WORDS as usize);
+    let root: text::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(root, "small");
+
+    // The builder remains usable.
+    builder.set_root("another value").unwrap();
+    let root: text::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(root, "another value");
+
+    drop(builder);
+    assert_eq!(live_words.get(), 0);
+}
+
+#[test]
+fn shrink_keeps_reachable_content() {
+    let (mut builder, live_words) = counting_builder();
+    {
+        let mut list: primitive_list::Builder<u64> = builder.initn_root(5000);
+        for idx in 0..5000 {
+            list.set(idx, u64::from(idx));
+        }
+    }
+    builder.shrink_to(10_000).unwrap();
+    assert_eq!(builder.get_segments_for_output().len(), 1);
+    assert!(live_words.get() >= 10_000);
+
+    let list: primitive_list::Reader<u64> = builder.get_root_as_reader().unwrap();
+    assert_eq!(list.len(), 5000);
+    for idx in 0..5000 {
+        assert_eq!(list.get(idx), u64::from(idx));
+    }
+}
+
+#[test]
+fn shrink_untouched_builder() {
+    let (mut builder, live_words) = counting_builder();
+    builder.shrink_to_fit().unwrap();
+    assert_eq!(live_words.get(), 0);
+}
+
+#[test]
+fn shrink_without_room_for_the_copy() {
+    let mut words = vec![capnp::word(0, 0, 0, 0, 0, 0, 0, 0); 16];
+    let mut builder = message::Builder::new(message::SingleSegmentAllocator::new(
+        capnp::Word::words_to_bytes_mut(&mut words),
+    ));
+    builder.set_root("kept").unwrap();
+    let error = builder.shrink_to_fit().unwrap_err();
+    assert_eq!(error.kind, capnp::ErrorKind::Overloaded);
+    let root: text::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(root, "kept");
+    builder.set_root("still usable").unwrap();
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::{serialize, ErrorKind, Word};
+
+/// Serializes `message` into words, so that the bytes are aligned.
+fn to_words(message: &message::Builder<message::HeapAllocator>) -> Vec<Word> {
+    let bytes = serialize::write_message_to_words(message);
+    let mut words = Word::allocate_zeroed_vec(bytes.len() / 8);
+    Word::words_to_bytes_mut(&mut words).copy_from_slice(&bytes);
+    words
+}
+
+fn text_round_trip(value: &str) {
+    let message = message::single_text(value).unwrap();
+    assert_eq!(message.get_segments_for_output().len(), 1);
+    // The root pointer, then the text and its NUL terminator.
+    let segment_words = 1 + (value.len() + 1).div_ceil(8);
+    assert_eq!(
+        message.get_segments_for_output()[0].len(),
+        segment_words * 8
+    );
+    let words = to_words(&message);
+    let bytes = Word::words_to_bytes(&words);
+    let text = message::read_single_text(bytes, ReaderOptions::new()).unwrap();
+    assert_eq!(text, value);
+    assert!(bytes.as_ptr_range().contains(&text.as_ptr()) || value.is_empty());
+}
+
+fn data_round_trip(value: &[u8]) {
+    let message = message::single_data(value).unwrap();
+    assert_eq!(message.get_segments_for_output().len(), 1);
+    let segment_words = 1 + value.len().div_ceil(8);
+    assert_eq!(
+        message.get_segments_for_output()[0].len(),
+        segment_words * 8
+    );
+    let words = to_words(&message);
+    let bytes = Word::words_to_bytes(&words);
+    let data = message::read_single_data(bytes, ReaderOptions::new()).unwrap();
+    assert_eq!(data, value);
+}
+
+#[test]
+fn text() {
+    text_round_trip("");
+    text_round_trip("hello");
+    text_round_trip("seven!!");
+    text_round_trip("exactly8");
+    text_round_trip(&"ünïcödé ".repeat(1000));
+}
+
+#[test]
+fn data() {
+    data_round_trip(&[]);
+    data_round_trip(&[1, 2, 3]);
+    data_round_trip(&[0xff; 8]);
+    data_round_trip(&(0..=255).collect::<Vec<u8>>());
+}
+
+#[test]
+fn multi_megabyte() {
+    let value: Vec<u8> = (0..5 * 1024 * 1024).map(|idx| idx as u8).collect();
+    data_round_trip(&value);
+    let value = "0123456789abcdef".repeat(300_000);
+    text_round_trip(&value);
+}
+
+#[test]
+fn unaligned_bytes() {
+    let words = to_words(&message::single_text("unaligned").unwrap());
+    let mut buffer = vec![0u8; 1];
+    buffer.extend_from_slice(Word::words_to_bytes(&words));
+    let bytes = &buffer[1..];
+    let text = message::read_single_text(bytes, ReaderOptions::new()).unwrap();
+    assert_eq!(text, "unaligned");
+    assert!(bytes.as_ptr_range().contains(&text.as_ptr()));
+}
+
+#[test]
+fn rejects_other_messages() {
+    // A struct root.
+    let mut message = message::Builder::new_default();
+    message.init_root::<node::Builder>().set_id(1);
+    let words = to_words(&message);
+    let error =
+        message::read_single_text(Word::words_to_bytes(&words), ReaderOptions::new()).unwrap_err();
+    assert_eq!(
+        error.kind,
+        ErrorKind::MessageContainsNonListPointerWhereTextWasExpected
+    );
+
+    // Trailing bytes.
+    let mut words = to_words(&message::single_data(&[1, 2]).unwrap());
+    words.push(capnp::word(0, 0, 0, 0, 0, 0, 0, 0));
+    let error =
+        message::read_single_data(Word::words_to_bytes(&words), ReaderOptions::new()).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Failed);
+    assert!(error.to_string().contains("8 bytes follow the message"));
+
+    // Data that is not UTF-8, with a NUL terminator so that it is otherwise valid text.
+    let words = to_words(&message::single_data(&[0xff, 0xfe, 0]).unwrap());
+    let error =
+        message::read_single_text(Word::words_to_bytes(&words), ReaderOptions::new()).unwrap_err();
+    assert!(matches!(error.kind, ErrorKind::TextContainsNonUtf8Data(_)));
+}
+
+#[test]
+fn too_long() {
+    let long = vec![0u8; 1 << 29];
+    let Err(error) = message::single_data(&long) else {
+        panic!("built a message with too long data");
+    };
+    assert_eq!(error.kind, ErrorKind::BlobTooLong(1 << 29));
+}
@@ -1,4 +1,5 @@
 use capnp::message;
+use capnp::schema_capnp::node;
 
 #[test]
 pub fn single_segment_allocator() {
@@ -17,3 +18,157 @@ pub fn single_segment_allocator() {
         assert_eq!(w, capnp::word(0, 0, 0, 0, 0, 0, 0, 0));
     }
 }
+
+#[test]
+pub fn set_root_fits_exactly() {
+    // The root pointer, then "hello world!" and its NUL in two words.
+    let mut buffer = [capnp::word(0, 0, 0, 0, 0, 0, 0, 0); 3];
+    let allocator =
+        message::SingleSegmentAllocator::new(capnp::Word::words_to_bytes_mut(&mut buffer[..]));
+    let mut msg = message::Builder::new(allocator);
+    msg.set_root("hello world!").unwrap();
+    let segments = msg.get_segments_for_output();
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].len(), 3 * 8);
+    let s: capnp::text::Reader = msg.get_root_as_reader().unwrap();
+    assert_eq!("hello world!", s);
+}
+
+#[test]
+pub fn set_root_one_word_too_large() {
+    let mut buffer = [capnp::word(0, 0, 0, 0, 0, 0, 0, 0); 8];
+    let allocator =
+        message::SingleSegmentAllocator::new(capnp::Word::words_to_bytes_mut(&mut buffer[..3]));
+    let mut msg = message::Builder::new(allocator);
+    // 16 bytes and a NUL take three words.
+    let err = msg.set_root("sixteen bytes!!!").unwrap_err();
+    assert_eq!(err.kind, capnp::ErrorKind::Overloaded);
+
+    // The message was left as it was, and a value that fits can still be set.
+    assert!(msg
+        .get_root_as_reader::<capnp::any_pointer::Reader>()
+        .unwrap()
+        .is_null());
+    msg.set_root("fifteen bytes!!").unwrap();
+    assert_eq!(msg.get_segments_for_output()[0].len(), 3 * 8);
+}
+
+#[test]
+pub fn copied_struct_must_fit() {
+    let mut source_buffer = [capnp::word(0, 0, 0, 0, 0, 0, 0, 0); 16];
+    let mut source = message::Builder::new(message::SingleSegmentAllocator::new(
+        capnp::Word::words_to_bytes_mut(&mut source_buffer[..]),
+    ));
+    {
+        let mut node: node::Builder = source.init_root();
+        node.set_id(5);
+        node.set_display_name("node".into());
+    }
+    let node: node::Reader = source.get_root_as_reader().unwrap();
+
+    // The root pointer, 11 words of struct and a word of text.
+    for (words, fits) in [(13, true), (12, false)] {
+        let mut buffer = [capnp::word(0, 0, 0, 0, 0, 0, 0, 0); 13];
+        let mut msg = message::Builder::new(message::SingleSegmentAllocator::new(
+            capnp::Word::words_to_bytes_mut(&mut buffer[..words]),
+        ));
+        let result = msg.set_root(node);
+        if fits {
+            result.unwrap();
+            let copy: node::Reader = msg.get_root_as_reader().unwrap();
+            assert_eq!(copy.get_id(), 5);
+            assert_eq!(copy.get_display_name().unwrap(), "node");
+        } else {
+            assert_eq!(result.unwrap_err().kind, capnp::ErrorKind::Overloaded);
+
+            // The struct fit, but its text did not, and it is left out.
+            let copy: node::Reader = msg.get_root_as_reader().unwrap();
+            assert_eq!(copy.get_id(), 5);
+            assert!(!copy.has_display_name());
+        }
+    }
+}
+
+#[test]
+pub fn failed_set_keeps_old_value() {
+    let mut buffer = [capnp::word(0, 0, 0, 0, 0, 0, 0, 0); 3];
+    let mut msg = message::Builder::new(message::SingleSegmentAllocator::new(
+        capnp::Word::words_to_bytes_mut(&mut buffer[..]),
+    ));
+    msg.set_root("hi").unwrap();
+    let err = msg.set_root("fifteen bytes!!").unwrap_err();
+    assert_eq!(err.kind, capnp::ErrorKind::Overloaded);
+    let s: capnp::text::Reader = msg.get_root_as_reader().unwrap();
+    assert_eq!("hi", s);
+}
+
+#[test]
+pub fn reserved_text_must_fit() {
+    for (max_len, fits) in [(15, true), (16, false)] {
+        // The root pointer and two words of text.
+        let mut buffer = [capnp::word(0, 0, 0, 0, 0, 0, 0, 0); 3];
+        let mut msg = message::Builder::new(message::SingleSegmentAllocator::new(
+            capnp::Word::words_to_bytes_mut(&mut buffer[..]),
+        ));
+        let root: capnp::any_pointer::Builder = msg.get_root().unwrap();
+        let result = capnp::text::reserve(root, max_len);
+        match result {
+            Ok(reservation) => assert!(fits, "{}", reservation.capacity()),
+            Err(err) => {
+                assert!(!fits);
+                assert_eq!(err.kind, capnp::ErrorKind::Overloaded);
+            }
+        }
+    }
+}
+
+#[test]
+pub fn init_root_too_large() {
+    let mut buffer = [capnp::word(0, 0, 0, 0, 0, 0, 0, 0); 8];
+    let mut msg = message::Builder::new(message::SingleSegmentAllocator::new(
+        capnp::Word::words_to_bytes_mut(&mut buffer[..]),
+    ));
+    let err = msg.try_init_root::<node::Builder>().unwrap_err();
+    assert_eq!(err.kind, capnp::ErrorKind::Overloaded);
+}
+
+#[test]
+pub fn try_setters_must_fit() {
+    // The root pointer and 11 words of struct, with no room left for text.
+    let mut buffer = [capnp::word(0, 0, 0, 0, 0, 0, 0, 0); 12];
+    let mut msg = message::Builder::new(message::SingleSegmentAllocator::new(
+        capnp::Word::words_to_bytes_mut(&mut buffer[..]),
+    ));
+    let mut node = msg.try_init_root::<node::Builder>().unwrap();
+    let err = node.try_set_display_name("node".into()).unwrap_err();
+    assert_eq!(err.kind, capnp::ErrorKind::Overloaded);
+    let err = node.reborrow().try_init_display_name(4).unwrap_err();
+    assert_eq!(err.kind, capnp::ErrorKind::Overloaded);
+    let err = node.try_init_nested_nodes(1).unwrap_err();
+    assert_eq!(err.kind, capnp::ErrorKind::Overloaded);
+}
+
+#[test]
+pub fn copied_list_must_fit() {
+    let mut source_buffer = [capnp::word(0, 0, 0, 0, 0, 0, 0, 0); 16];
+    let mut source = message::Builder::new(message::SingleSegmentAllocator::new(
+        capnp::Word::words_to_bytes_mut(&mut source_buffer[..]),
+    ));
+    {
+        let mut nodes: capnp::struct_list::Builder<node::parameter::Owned> = source.initn_root(2);
+        nodes.reborrow().get(0).set_name("a".into());
+        nodes.reborrow().get(1).set_name("b".into());
+    }
+    let nodes: capnp::struct_list::Reader<node::parameter::Owned> =
+        source.get_root_as_reader().unwrap();
+
+    // The root pointer, the list's tag, two one-pointer structs and two words of text.
+    for (words, fits) in [(6, true), (5, false)] {
+        let mut buffer = [capnp::word(0, 0, 0, 0, 0, 0, 0, 0); 6];
+        let mut msg = message::Builder::new(message::SingleSegmentAllocator::new(
+            capnp::Word::words_to_bytes_mut(&mut buffer[..words]),
+        ));
+        let result = msg.set_root(nodes);
+        assert_eq!(result.is_ok(), fits, "{result:?}");
+    }
+}
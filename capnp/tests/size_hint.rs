@@ -0,0 +1,80 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, AllocationStrategy, Allocator, HeapAllocator};
+use capnp::schema_capnp::node;
+use capnp::{any_pointer, struct_list, OutputSegments};
+
+fn build<A: Allocator>(builder: &mut message::Builder<A>, count: u32) {
+    let mut nodes: struct_list::Builder<node::Owned> = builder.initn_root(count);
+    for idx in 0..count {
+        let mut node = nodes.reborrow().get(idx);
+        node.set_id(u64::from(idx));
+        node.set_display_name(format!("node{idx}.capnp").as_str().into());
+        node.init_annotations(idx % 3);
+    }
+}
+
+#[test]
+fn size_hint_yields_single_segment() {
+    for strategy in [
+        AllocationStrategy::FixedSize,
+        AllocationStrategy::GrowHeuristically,
+    ] {
+        let allocator = HeapAllocator::new()
+            .first_segment_words(16)
+            .allocation_strategy(strategy);
+        let mut first = message::Builder::new(allocator);
+        build(&mut first, 50);
+        let segments = first.get_segments_for_output();
+        assert!(segments.len() > 1);
+        let words: usize = segments.iter().map(|segment| segment.len() / 8).sum();
+        assert_eq!(first.size_in_words(), words);
+
+        let root: any_pointer::Reader = first.get_root_as_reader().unwrap();
+        let content = root.target_size().unwrap().word_count as usize + 1;
+        assert!(first.size_in_words() >= content);
+
+        let hint = first.size_in_words() as u32;
+        let mut second = message::Builder::new(HeapAllocator::new().first_segment_words(hint));
+        build(&mut second, 50);
+        assert!(matches!(
+            second.get_segments_for_output(),
+            OutputSegments::SingleSegment(_)
+        ));
+        // Without far pointers, the content takes less space than the hint.
+        assert!(second.size_in_words() <= first.size_in_words());
+    }
+}
+
+#[test]
+fn size_of_new_builder() {
+    let mut builder = message::Builder::new_default();
+    assert_eq!(builder.size_in_words(), 0);
+    builder.init_root::<any_pointer::Builder>();
+    assert_eq!(builder.size_in_words(), 1);
+    builder.set_root("four").unwrap();
+    assert_eq!(builder.size_in_words(), 2);
+}
+
+#[test]
+fn allocated_words_and_segments_of_known_structs() {
+    let mut builder = message::Builder::new(HeapAllocator::new().first_segment_words(12));
+    assert_eq!((builder.size_in_words(), builder.segment_count()), (0, 0));
+
+    // The root pointer, then a node of 5 data words and 6 pointers, filling the first segment.
+    let mut root: node::Builder = builder.init_root();
+    root.set_id(1);
+    assert_eq!((builder.size_in_words(), builder.segment_count()), (12, 1));
+
+    // A one-word text that lands in a second segment, behind a far pointer's landing pad.
+    let mut root: node::Builder = builder.get_root().unwrap();
+    root.set_display_name("name".into());
+    assert_eq!((builder.size_in_words(), builder.segment_count()), (14, 2));
+    assert_eq!(
+        builder.segment_count(),
+        builder.get_segments_for_output().len()
+    );
+
+    builder.clear();
+    assert_eq!(builder.segment_count(), 1);
+}
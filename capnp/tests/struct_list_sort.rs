@@ -0,0 +1,122 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, AllocationStrategy, HeapAllocator};
+use capnp::schema_capnp::code_generator_request::{self, requested_file};
+use capnp::struct_list;
+
+// (id, filename, import ids)
+type Entry = (u64, &'static str, &'static [u64]);
+
+const ENTRIES: &[Entry] = &[
+    (3, "c.capnp", &[1, 2]),
+    (1, "a.capnp", &[]),
+    (4, "d.capnp", &[3]),
+    (2, "b.capnp", &[1]),
+    (5, "e.capnp", &[4, 3, 2, 1]),
+];
+
+fn fill(mut files: struct_list::Builder<requested_file::Owned>, entries: &[Entry]) {
+    for (idx, (id, filename, imports)) in entries.iter().enumerate() {
+        let mut file = files.reborrow().get(idx as u32);
+        file.set_id(*id);
+        file.set_filename((*filename).into());
+        let mut list = file.init_imports(imports.len() as u32);
+        for (jdx, import) in imports.iter().enumerate() {
+            list.reborrow().get(jdx as u32).set_id(*import);
+        }
+    }
+}
+
+fn check_sorted(files: struct_list::Reader<requested_file::Owned>) {
+    let mut sorted = ENTRIES.to_vec();
+    sorted.sort_by_key(|(id, _, _)| *id);
+    assert_eq!(files.len() as usize, sorted.len());
+    for (file, (id, filename, imports)) in files.iter().zip(sorted) {
+        assert_eq!(file.get_id(), id);
+        assert_eq!(file.get_filename().unwrap(), filename);
+        let got: Vec<u64> = file
+            .get_imports()
+            .unwrap()
+            .iter()
+            .map(|i| i.get_id())
+            .collect();
+        assert_eq!(&got[..], imports);
+    }
+}
+
+/// Builds a request with the entries in the given order, sorts, and returns the canonical bytes.
+fn sorted_canonical(allocator: HeapAllocator, order: &[usize]) -> Vec<capnp::Word> {
+    let entries: Vec<Entry> = order.iter().map(|idx| ENTRIES[*idx]).collect();
+    let mut message = message::Builder::new(allocator);
+    let root: code_generator_request::Builder = message.init_root();
+    let mut files = root.init_requested_files(entries.len() as u32);
+    fill(files.reborrow(), &entries);
+    files.sort_by_key(|file| file.get_id());
+    check_sorted(files.into_reader());
+
+    let reader = message.into_reader();
+    reader.canonicalize().unwrap()
+}
+
+const ORDERS: &[&[usize]] = &[
+    &[0, 1, 2, 3, 4],
+    &[4, 3, 2, 1, 0],
+    &[1, 3, 0, 2, 4],
+    &[2, 0, 4, 1, 3],
+    &[3, 4, 1, 0, 2],
+];
+
+#[test]
+fn sort_is_independent_of_insertion_order() {
+    let expected = sorted_canonical(HeapAllocator::new(), ORDERS[0]);
+    for order in ORDERS {
+        assert_eq!(sorted_canonical(HeapAllocator::new(), order), expected);
+    }
+}
+
+#[test]
+fn sort_with_far_pointers() {
+    // Tiny segments force the elements' pointer fields to be far pointers.
+    let expected = sorted_canonical(HeapAllocator::new(), ORDERS[0]);
+    for order in ORDERS {
+        let allocator = HeapAllocator::new()
+            .first_segment_words(2)
+            .allocation_strategy(AllocationStrategy::FixedSize);
+        assert_eq!(sorted_canonical(allocator, order), expected);
+    }
+}
+
+#[test]
+fn sort_is_stable() {
+    let mut message = message::Builder::new_default();
+    let mut files: struct_list::Builder<requested_file::Owned> = message.initn_root(6);
+    for (idx, name) in ["b1", "a1", "b2", "a2", "b3", "a3"].iter().enumerate() {
+        files
+            .reborrow()
+            .get(idx as u32)
+            .set_filename((*name).into());
+    }
+    files.sort_by_key(|file| file.get_filename().unwrap().as_bytes()[0]);
+    let names: Vec<String> = files
+        .into_reader()
+        .iter()
+        .map(|file| file.get_filename().unwrap().to_string().unwrap())
+        .collect();
+    assert_eq!(names, ["a1", "a2", "a3", "b1", "b2", "b3"]);
+}
+
+#[test]
+fn swap_and_trivial_sorts() {
+    let mut message = message::Builder::new_default();
+    let mut files: struct_list::Builder<requested_file::Owned> = message.initn_root(2);
+    fill(files.reborrow(), &ENTRIES[..2]);
+    files.swap(0, 1);
+    files.swap(1, 1);
+    let reader = files.reborrow().into_reader();
+    assert_eq!(reader.get(0).get_filename().unwrap(), "a.capnp");
+    assert_eq!(reader.get(1).get_filename().unwrap(), "c.capnp");
+    assert_eq!(reader.get(1).get_imports().unwrap().len(), 2);
+
+    let mut empty: struct_list::Builder<requested_file::Owned> = message.initn_root(0);
+    empty.sort_by_key(|file| file.get_id());
+}
@@ -0,0 +1,133 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message;
+#[cfg(feature = "sync_reader")]
+use capnp::message::ReaderOptions;
+use capnp::schema_capnp::node;
+use capnp::struct_list;
+
+type Parameters<'a> = struct_list::Reader<'a, node::parameter::Owned>;
+
+/// A message whose root is a list of `len` parameters, each named after its index.
+fn parameters(len: u32) -> message::Builder<message::HeapAllocator> {
+    let mut builder = message::Builder::new_default();
+    let mut list: struct_list::Builder<node::parameter::Owned> = builder.initn_root(len);
+    for idx in 0..len {
+        list.reborrow()
+            .get(idx)
+            .set_name(idx.to_string().as_str().into());
+    }
+    builder
+}
+
+fn sum(list: Parameters) -> u64 {
+    list.iter()
+        .map(|parameter| {
+            let name = parameter.get_name().unwrap().to_str().unwrap();
+            name.parse::<u64>().unwrap()
+        })
+        .sum()
+}
+
+#[test]
+fn split_at() {
+    let builder = parameters(10);
+    let list: Parameters = builder.get_root_as_reader().unwrap();
+
+    let (head, tail) = list.split_at(4);
+    assert_eq!((head.len(), tail.len()), (4, 6));
+    assert_eq!(sum(head), 1 + 2 + 3);
+    assert_eq!(tail.get(0).get_name().unwrap(), "4");
+    assert_eq!(sum(tail), 4 + 5 + 6 + 7 + 8 + 9);
+
+    let (empty, all) = list.split_at(0);
+    assert!(empty.is_empty());
+    assert_eq!(sum(all), sum(list));
+    let (all, empty) = list.split_at(10);
+    assert!(empty.is_empty());
+    assert!(empty.try_get(0).is_none());
+    assert_eq!(sum(all), sum(list));
+}
+
+#[test]
+#[should_panic]
+fn split_at_past_the_end() {
+    let builder = parameters(3);
+    let list: Parameters = builder.get_root_as_reader().unwrap();
+    let _ = list.split_at(4);
+}
+
+#[test]
+fn par_chunks() {
+    let builder = parameters(10);
+    let list: Parameters = builder.get_root_as_reader().unwrap();
+    let lens: Vec<u32> = list.par_chunks(4).iter().map(|chunk| chunk.len()).collect();
+    assert_eq!(lens, [3, 3, 2, 2]);
+    assert_eq!(list.par_chunks(4).into_iter().map(sum).sum::<u64>(), 45);
+
+    // More chunks than elements.
+    let lens: Vec<u32> = list
+        .split_at(2)
+        .0
+        .par_chunks(3)
+        .iter()
+        .map(|chunk| chunk.len())
+        .collect();
+    assert_eq!(lens, [1, 1, 0]);
+}
+
+#[cfg(feature = "sync_reader")]
+#[test]
+fn threads_sum_disjoint_chunks() {
+    const LEN: u32 = 1_000_000;
+    let builder = parameters(LEN);
+    let segments = builder.get_segments_for_output();
+    let mut options = ReaderOptions::new();
+    options.traversal_limit_in_words(Some(1 << 30));
+
+    let serial_message = message::Reader::new(message::SegmentArray::new(&segments), options);
+    let serial = sum(serial_message.get_root().unwrap());
+    assert_eq!(serial, u64::from(LEN) * u64::from(LEN - 1) / 2);
+
+    let charged = |message: &message::Reader<message::SegmentArray>| {
+        (1 << 30) - message.remaining_traversal_limit_in_words().unwrap()
+    };
+    let message = message::Reader::new(message::SegmentArray::new(&segments), options);
+    let list: Parameters = message.get_root().unwrap();
+    let root_charge = charged(&message);
+    let parallel: u64 = std::thread::scope(|scope| {
+        let threads: Vec<_> = list
+            .par_chunks(4)
+            .into_iter()
+            .map(|chunk| {
+                let chunk = message.sendable_struct_list(chunk).unwrap();
+                scope.spawn(move || sum(chunk.into_reader()))
+            })
+            .collect();
+        threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .sum()
+    });
+    assert_eq!(parallel, serial);
+
+    // Splitting charges nothing, and the threads share one limiter, so the charge is exactly
+    // that of the serial traversal.
+    assert!(charged(&message) > root_charge);
+    assert_eq!(charged(&message), charged(&serial_message));
+}
+
+#[cfg(feature = "sync_reader")]
+#[test]
+fn sendable_struct_list_requires_the_same_message() {
+    let builder = parameters(2);
+    let segments = builder.get_segments_for_output();
+    let message = message::Reader::new(message::SegmentArray::new(&segments), ReaderOptions::new());
+    let other = message::Reader::new(message::SegmentArray::new(&segments), ReaderOptions::new());
+    let list: Parameters = other.get_root().unwrap();
+    let Err(error) = message.sendable_struct_list(list) else {
+        panic!("sent a list read from another message");
+    };
+    assert!(error.to_string().contains("not read from this message"));
+    assert!(other.sendable_struct_list(list).is_ok());
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "alloc")]
+
+use std::borrow::Cow;
+
+use capnp::text::{self, TextDecoder, Utf8Decoder};
+use capnp::ErrorKind;
+
+/// Rotates ASCII letters by 13 places, and rejects anything that is not ASCII.
+struct Rot13;
+
+impl TextDecoder for Rot13 {
+    fn decode<'a>(&self, bytes: &'a [u8]) -> capnp::Result<Cow<'a, str>> {
+        if !bytes.is_ascii() {
+            return Err(capnp::Error::failed("not ascii".into()));
+        }
+        if !bytes.iter().any(u8::is_ascii_alphabetic) {
+            return Ok(Cow::Borrowed(std::str::from_utf8(bytes)?));
+        }
+        let rotate = |byte: u8, base: u8| (byte - base + 13) % 26 + base;
+        Ok(Cow::Owned(
+            bytes
+                .iter()
+                .map(|&byte| match byte {
+                    b'a'..=b'z' => rotate(byte, b'a') as char,
+                    b'A'..=b'Z' => rotate(byte, b'A') as char,
+                    _ => byte as char,
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[test]
+fn custom_decoder() {
+    let reader = text::Reader::from_str("Uryyb, jbeyq!");
+    assert_eq!(reader.decode_with(&Rot13).unwrap(), "Hello, world!");
+    assert!(matches!(
+        text::Reader::from_str("1, 2").decode_with(&Rot13).unwrap(),
+        Cow::Borrowed("1, 2")
+    ));
+    let error = text::Reader(b"\x82\xa0").decode_with(&Rot13).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Failed);
+
+    // Through a trait object.
+    let decoder: &dyn TextDecoder = &Rot13;
+    assert_eq!(reader.decode_with(decoder).unwrap(), "Hello, world!");
+}
+
+#[test]
+fn utf8_decoder_matches_to_str() {
+    for bytes in [&b""[..], b"hello", "h\u{e9}llo".as_bytes()] {
+        let reader = text::Reader(bytes);
+        let decoded = reader.decode_with(&Utf8Decoder).unwrap();
+        assert!(matches!(decoded, Cow::Borrowed(_)));
+        assert_eq!(decoded, reader.to_str().unwrap());
+    }
+
+    for bytes in [&b"\xff"[..], b"ok\xc3", b"\x82\xa0"] {
+        let reader = text::Reader(bytes);
+        let error = reader.decode_with(&Utf8Decoder).unwrap_err();
+        assert_eq!(
+            error.kind,
+            ErrorKind::TextContainsNonUtf8Data(reader.to_str().unwrap_err())
+        );
+    }
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::{text, word, ErrorKind, Word};
+
+/// Messages whose root is the text "abcdefghi"[..len], for len in 0..=9, as encoded by the C++
+/// implementation.
+const FIXTURES: [&[Word]; 10] = [
+    &[
+        word(0x01, 0x00, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x00),
+        word(0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+    ],
+    &[
+        word(0x01, 0x00, 0x00, 0x00, 0x12, 0x00, 0x00, 0x00),
+        word(b'a', 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+    ],
+    &[
+        word(0x01, 0x00, 0x00, 0x00, 0x1a, 0x00, 0x00, 0x00),
+        word(b'a', b'b', 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+    ],
+    &[
+        word(0x01, 0x00, 0x00, 0x00, 0x22, 0x00, 0x00, 0x00),
+        word(b'a', b'b', b'c', 0x00, 0x00, 0x00, 0x00, 0x00),
+    ],
+    &[
+        word(0x01, 0x00, 0x00, 0x00, 0x2a, 0x00, 0x00, 0x00),
+        word(b'a', b'b', b'c', b'd', 0x00, 0x00, 0x00, 0x00),
+    ],
+    &[
+        word(0x01, 0x00, 0x00, 0x00, 0x32, 0x00, 0x00, 0x00),
+        word(b'a', b'b', b'c', b'd', b'e', 0x00, 0x00, 0x00),
+    ],
+    &[
+        word(0x01, 0x00, 0x00, 0x00, 0x3a, 0x00, 0x00, 0x00),
+        word(b'a', b'b', b'c', b'd', b'e', b'f', 0x00, 0x00),
+    ],
+    &[
+        word(0x01, 0x00, 0x00, 0x00, 0x42, 0x00, 0x00, 0x00),
+        word(b'a', b'b', b'c', b'd', b'e', b'f', b'g', 0x00),
+    ],
+    &[
+        word(0x01, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00),
+        word(b'a', b'b', b'c', b'd', b'e', b'f', b'g', b'h'),
+        word(0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+    ],
+    &[
+        word(0x01, 0x00, 0x00, 0x00, 0x52, 0x00, 0x00, 0x00),
+        word(b'a', b'b', b'c', b'd', b'e', b'f', b'g', b'h'),
+        word(b'i', 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+    ],
+];
+
+const TEXT: &str = "abcdefghi";
+
+fn read_root(words: &[Word]) -> capnp::Result<String> {
+    let segments = [Word::words_to_bytes(words)];
+    let message = message::Reader::new(message::SegmentArray::new(&segments), ReaderOptions::new());
+    let root: text::Reader = message.get_root()?;
+    assert_eq!(root.len(), root.as_bytes().len());
+    assert_eq!(root.len(), root.to_str().unwrap().len());
+    Ok(root.to_string().unwrap())
+}
+
+#[test]
+fn reads_fixtures() {
+    for (len, fixture) in FIXTURES.iter().enumerate() {
+        assert_eq!(read_root(fixture).unwrap(), &TEXT[..len]);
+    }
+}
+
+#[test]
+fn writes_fixtures() {
+    for (len, fixture) in FIXTURES.iter().enumerate() {
+        let mut builder = message::Builder::new_default();
+        builder.set_root(&TEXT[..len]).unwrap();
+        assert_eq!(
+            builder.get_segments_for_output()[0],
+            Word::words_to_bytes(fixture)
+        );
+
+        // Reading the text back through a builder sees the same length.
+        let root: text::Builder = builder.get_root().unwrap();
+        assert_eq!(root.len(), len);
+
+        let mut builder = message::Builder::new_default();
+        builder
+            .initn_root::<text::Builder>(len as u32)
+            .push_str(&TEXT[..len]);
+        assert_eq!(
+            builder.get_segments_for_output()[0],
+            Word::words_to_bytes(fixture)
+        );
+    }
+}
+
+#[test]
+fn rejects_missing_terminator() {
+    // A byte list of zero elements has no room for the terminator.
+    let empty: &[Word] = &[word(0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00)];
+    assert_eq!(
+        read_root(empty).unwrap_err().kind,
+        ErrorKind::MessageContainsTextThatIsNotNULTerminated
+    );
+
+    // The last byte of the list must be the terminator.
+    for (len, fixture) in FIXTURES.iter().enumerate() {
+        let mut fixture = fixture.to_vec();
+        Word::words_to_bytes_mut(&mut fixture)[8 + len] = b'!';
+        assert_eq!(
+            read_root(&fixture).unwrap_err().kind,
+            ErrorKind::MessageContainsTextThatIsNotNULTerminated
+        );
+    }
+
+    let mut builder = message::Builder::new_default();
+    builder.set_root(&b""[..]).unwrap();
+    assert_eq!(
+        builder.get_root::<text::Builder>().err().unwrap().kind,
+        ErrorKind::TextBlobMissingNULTerminator
+    );
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "alloc")]
+
+//! Checks that a segment table declaring a huge message is rejected before anything is allocated
+//! for its segments. This file holds a single test, so that no other test's allocations or thread
+//! stacks move the memory use that it measures.
+
+use capnp::message::ReaderOptions;
+use capnp::{serialize, serialize_packed, ErrorKind};
+
+/// The peak virtual memory size and the peak resident set size of the process, in KiB. Peaks
+/// catch an allocation even if it has been freed again by the time they are read.
+#[cfg(target_os = "linux")]
+fn peak_memory_kib() -> (u64, u64) {
+    let status = std::fs::read_to_string("/proc/self/status").unwrap();
+    let field = |name: &str| -> u64 {
+        let line = status.lines().find(|line| line.starts_with(name)).unwrap();
+        line[name.len()..]
+            .trim()
+            .trim_end_matches("kB")
+            .trim()
+            .parse()
+            .unwrap()
+    };
+    (field("VmPeak:"), field("VmHWM:"))
+}
+
+#[test]
+fn huge_declared_message_is_rejected_before_allocating() {
+    // One segment of almost 2^32 words, or 32 GiB, with none of its contents.
+    let header = serialize::segment_table_bytes(&[u32::MAX - 1]);
+    // The same header packed: a word whose last four bytes are nonzero.
+    let packed = [0xf0, 0xfe, 0xff, 0xff, 0xff];
+
+    #[cfg(target_os = "linux")]
+    let before = peak_memory_kib();
+
+    let mut limited = ReaderOptions::PERMISSIVE;
+    limited.total_size_limit_in_words(Some(1 << 20));
+    let expected = format!(
+        "segment table declares {} words, more than the limit of {}",
+        u32::MAX - 1,
+        1 << 20
+    );
+    for result in [
+        serialize::read_message(&header[..], limited).map(drop),
+        serialize_packed::read_message(&packed[..], limited).map(drop),
+        serialize_packed::read_message_from_slice(&packed, limited).map(drop),
+    ] {
+        let error = result.unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Overloaded);
+        assert_eq!(error.extra, expected);
+    }
+
+    // The traversal limit rejects it too, when there is one.
+    let Err(error) = serialize::read_message(&header[..], ReaderOptions::new()) else {
+        panic!("huge message accepted");
+    };
+    assert!(matches!(error.kind, ErrorKind::MessageTooLarge(_)));
+
+    #[cfg(target_os = "linux")]
+    {
+        let after = peak_memory_kib();
+        // Far less than the 32 GiB that the segment would take.
+        let slack = 64 * 1024;
+        assert!(after.0 < before.0 + slack, "{before:?} -> {after:?}");
+        assert!(after.1 < before.1 + slack, "{before:?} -> {after:?}");
+    }
+}
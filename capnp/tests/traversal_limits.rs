@@ -0,0 +1,163 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, CopyOptions, ReaderOptions, TraversalLimits};
+use capnp::{any_pointer, word, ErrorKind, Word};
+
+/// A message whose root is a list of `count` pointers to zero-sized structs. Traversing it reads
+/// only `count + 1` words, but dereferences `count + 1` pointers.
+fn pointer_dense(count: u32) -> Vec<Word> {
+    let mut words = Vec::new();
+    // Root pointer: list of `count` pointers.
+    let upper = (count << 3) | 6;
+    words.push(word(
+        0x01,
+        0x00,
+        0x00,
+        0x00,
+        upper as u8,
+        (upper >> 8) as u8,
+        (upper >> 16) as u8,
+        (upper >> 24) as u8,
+    ));
+    for _ in 0..count {
+        // Zero-sized struct, pointing at the pointer itself.
+        words.push(word(0xfc, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00));
+    }
+    words
+}
+
+fn limits(words: u64, max_ops: u64) -> TraversalLimits {
+    TraversalLimits { words, max_ops }
+}
+
+fn total_size(words: &[Word], options: ReaderOptions) -> capnp::Result<u64> {
+    let segments = [Word::words_to_bytes(words)];
+    let message = message::Reader::new(message::SegmentArray::new(&segments), options);
+    let root: any_pointer::Reader = message.get_root()?;
+    Ok(root.target_size()?.word_count)
+}
+
+/// Copies `words` into a builder, so that reading it back is not subject to any limits.
+fn unlimited_copy(words: &[Word]) -> message::Builder<message::HeapAllocator> {
+    let segments = [Word::words_to_bytes(words)];
+    let message = message::Reader::new(message::SegmentArray::new(&segments), ReaderOptions::new());
+    let mut builder = message::Builder::new_default();
+    builder
+        .set_root(message.get_root::<any_pointer::Reader>().unwrap())
+        .unwrap();
+    builder
+}
+
+#[test]
+fn op_limit_trips_before_word_limit() {
+    let words = pointer_dense(200);
+
+    let mut options = ReaderOptions::new();
+    options.traversal_limits(limits(10_000, 100));
+    assert_eq!(
+        total_size(&words, options).unwrap_err().kind,
+        ErrorKind::Overloaded
+    );
+
+    options.traversal_limits(limits(10_000, 1_000));
+    assert_eq!(total_size(&words, options).unwrap(), 200);
+
+    // There is no op limit by default.
+    assert_eq!(total_size(&words, ReaderOptions::new()).unwrap(), 200);
+}
+
+#[test]
+fn word_limit_still_applies() {
+    let words = pointer_dense(200);
+    let mut options = ReaderOptions::new();
+    options.traversal_limits(limits(100, 10_000));
+    assert_eq!(
+        total_size(&words, options).unwrap_err().kind,
+        ErrorKind::ReadLimitExceeded
+    );
+}
+
+#[test]
+fn canonicalize_with_limits() {
+    let builder = unlimited_copy(&pointer_dense(200));
+    let reader = builder.into_reader();
+    let expected = reader.canonicalize().unwrap();
+
+    assert_eq!(
+        reader
+            .canonicalize_with_limits(limits(10_000, 100))
+            .unwrap_err()
+            .kind,
+        ErrorKind::Overloaded
+    );
+    assert_eq!(
+        reader
+            .canonicalize_with_limits(limits(100, 10_000))
+            .unwrap_err()
+            .kind,
+        ErrorKind::Overloaded
+    );
+    assert_eq!(
+        reader
+            .canonicalize_with_limits(limits(10_000, 10_000))
+            .unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn copy_with_limits() {
+    let source = unlimited_copy(&pointer_dense(200));
+    let root: any_pointer::Reader = source.get_root_as_reader().unwrap();
+
+    let mut options = CopyOptions::new();
+    options.traversal_limits(Some(limits(10_000, 100)));
+    let mut builder = message::Builder::new_default();
+    assert_eq!(
+        builder
+            .set_root_with_options(root, options)
+            .unwrap_err()
+            .kind,
+        ErrorKind::Overloaded
+    );
+
+    options.traversal_limits(Some(limits(10_000, 1_000)));
+    builder.set_root_with_options(root, options).unwrap();
+    let copy: any_pointer::Reader = builder.get_root_as_reader().unwrap();
+    assert_eq!(copy.target_size().unwrap().word_count, 200);
+}
+
+#[test]
+fn configured_and_remaining_limits() {
+    let words = pointer_dense(10);
+    let segments = [Word::words_to_bytes(&words)];
+    let mut options = ReaderOptions::new();
+    options.traversal_limits(limits(1000, 100)).nesting_limit(7);
+    let message = message::Reader::new(message::SegmentArray::new(&segments), options);
+    assert_eq!(*message.options(), options);
+    assert_eq!(message.remaining_traversal_limit_in_words(), Some(1000));
+    assert_eq!(message.remaining_traversal_limit_in_ops(), Some(100));
+
+    let root: any_pointer::Reader = message.get_root().unwrap();
+    root.target_size().unwrap();
+    let words_left = message.remaining_traversal_limit_in_words().unwrap();
+    let ops_left = message.remaining_traversal_limit_in_ops().unwrap();
+    assert!(words_left < 1000);
+    assert!(ops_left < 100);
+    // Reading spends the budget, but leaves the configured limits alone.
+    assert_eq!(message.options().traversal_limit_in_words, Some(1000));
+    assert_eq!(message.options().traversal_limit_in_ops, Some(100));
+
+    // A second reader with the same options starts with the whole budget.
+    let second = message::Reader::new(message::SegmentArray::new(&segments), *message.options());
+    assert_eq!(second.options(), message.options());
+    assert_eq!(second.remaining_traversal_limit_in_words(), Some(1000));
+
+    let unlimited = message::Reader::new(
+        message::SegmentArray::new(&segments),
+        ReaderOptions::PERMISSIVE,
+    );
+    assert_ne!(unlimited.options(), message.options());
+    assert_eq!(unlimited.remaining_traversal_limit_in_words(), None);
+    assert_eq!(unlimited.remaining_traversal_limit_in_ops(), None);
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "alloc")]
+
+use capnp::schema_capnp::{node, ElementSize};
+use capnp::{
+    any_pointer, any_pointer_list, data_list, enum_list, list_list, message, primitive_list,
+    struct_list, text, text_list, ErrorKind,
+};
+
+/// The length of the shortest data that does not fit in a list. The buffers are allocated
+/// zeroed and never read, so they don't take up memory.
+const TOO_LONG: usize = 1 << 29;
+
+#[test]
+fn primitive_and_enum_list_try_set() {
+    let mut message = message::Builder::new_default();
+    let mut root: any_pointer_list::Builder = message.initn_root(2);
+
+    let mut ints: primitive_list::Builder<u16> = root.reborrow().get(0).initn_as(3);
+    assert_eq!(ints.try_set(2, 7), Some(()));
+    assert_eq!(ints.try_set(3, 8), None);
+    assert_eq!(
+        ints.reborrow().into_reader().iter().collect::<Vec<_>>(),
+        [0, 0, 7]
+    );
+
+    let mut sizes: enum_list::Builder<ElementSize> = root.get(1).initn_as(1);
+    assert_eq!(sizes.try_set(0, ElementSize::Pointer), Some(()));
+    assert_eq!(sizes.try_set(1, ElementSize::Bit), None);
+    assert_eq!(sizes.get(0), Ok(ElementSize::Pointer));
+}
+
+#[test]
+fn blob_list_try_set() {
+    let mut message = message::Builder::new_default();
+    let mut root: any_pointer_list::Builder = message.initn_root(2);
+
+    let mut texts: text_list::Builder = root.reborrow().get(0).initn_as(1);
+    assert!(matches!(texts.try_set(0, "hi".into()), Some(Ok(()))));
+    assert!(texts.try_set(1, "hi".into()).is_none());
+    assert_eq!(texts.reborrow().get(0).unwrap(), "hi");
+
+    let mut datas: data_list::Builder = root.get(1).initn_as(1);
+    assert!(matches!(datas.try_set(0, &[1, 2]), Some(Ok(()))));
+    assert!(datas.try_set(1, &[1, 2]).is_none());
+    let long = vec![0u8; TOO_LONG];
+    let error = datas.try_set(0, &long).unwrap().unwrap_err();
+    assert_eq!(error.kind, ErrorKind::BlobTooLong(TOO_LONG));
+    assert_eq!(datas.get(0).unwrap(), [1, 2]);
+}
+
+#[test]
+fn list_list_try_init_and_try_set() {
+    let mut message = message::Builder::new_default();
+    let mut lists: list_list::Builder<primitive_list::Owned<u8>> = message.initn_root(2);
+    assert!(lists.reborrow().try_init(2, 4).is_none());
+    assert_eq!(lists.reborrow().try_init(1, 4).unwrap().len(), 4);
+
+    let mut other = message::Builder::new_default();
+    other.set_root(&[5u8, 6][..]).unwrap();
+    let value: primitive_list::Reader<u8> = other.get_root_as_reader().unwrap();
+    assert!(lists.try_set(2, value).is_none());
+    assert!(matches!(lists.try_set(0, value), Some(Ok(()))));
+    assert_eq!(
+        lists.get(0).unwrap().into_reader().as_slice(),
+        Some(&[5, 6][..])
+    );
+}
+
+#[test]
+#[should_panic]
+fn list_list_init_out_of_bounds() {
+    let mut message = message::Builder::new_default();
+    let lists: list_list::Builder<primitive_list::Owned<u8>> = message.initn_root(2);
+    lists.init(2, 4);
+}
+
+#[test]
+fn struct_list_try_swap_and_try_set() {
+    let mut message = message::Builder::new_default();
+    let mut nodes: struct_list::Builder<node::Owned> = message.initn_root(2);
+    nodes.reborrow().get(0).set_id(1);
+    nodes.reborrow().get(1).set_id(2);
+    assert_eq!(nodes.try_swap(0, 2), None);
+    assert_eq!(nodes.try_swap(2, 0), None);
+    assert_eq!(nodes.try_swap(0, 1), Some(()));
+    assert_eq!(nodes.reborrow().get(0).get_id(), 2);
+
+    let mut other = message::Builder::new_default();
+    other.init_root::<node::Builder>().set_id(3);
+    let value: node::Reader = other.get_root_as_reader().unwrap();
+    assert!(nodes.try_set_with_caveats(2, value).is_none());
+    assert!(matches!(nodes.try_set_with_caveats(1, value), Some(Ok(()))));
+    assert_eq!(nodes.get(1).get_id(), 3);
+}
+
+#[test]
+fn text_builder_try_push() {
+    let mut message = message::Builder::new_default();
+    let mut text: text::Builder = message.initn_root(4);
+    assert_eq!(text.try_push_ascii(0x80), None);
+    assert_eq!(text.try_push_str("abc"), Some(()));
+    assert_eq!(text.try_push_str("de"), None);
+    assert_eq!(text.try_push_ascii(b'd'), Some(()));
+    assert_eq!(text.try_push_ascii(b'e'), None);
+    assert_eq!(text.try_push_str(""), Some(()));
+    assert_eq!(text, "abcd");
+}
+
+#[test]
+fn set_too_long_blob() {
+    let long = vec![0u8; TOO_LONG];
+    let mut message = message::Builder::new_default();
+    let mut root: any_pointer::Builder = message.init_root();
+    let error = root.reborrow().set_as(&long[..]).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::BlobTooLong(TOO_LONG));
+    // Text needs room for its NUL terminator too.
+    let error = root
+        .reborrow()
+        .set_as(text::Reader::from(&long[..TOO_LONG - 1]))
+        .unwrap_err();
+    assert_eq!(error.kind, ErrorKind::BlobTooLong(TOO_LONG - 1));
+    assert!(root.is_null());
+}
+
+#[test]
+#[should_panic(expected = "too long to fit in a list")]
+fn init_too_long_text() {
+    let mut message = message::Builder::new_default();
+    message.initn_root::<text::Builder>(u32::MAX);
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::code_generator_request::requested_file;
+use capnp::{any_pointer, data, primitive_list, serialize, text};
+
+fn check_default_roots<S: message::ReaderSegments>(reader: &message::Reader<S>) {
+    assert!(reader.get_root::<any_pointer::Reader>().unwrap().is_null());
+    assert!(reader.get_root::<text::Reader>().unwrap().is_empty());
+    assert!(reader.get_root::<data::Reader>().unwrap().is_empty());
+    assert!(reader
+        .get_root::<primitive_list::Reader<u32>>()
+        .unwrap()
+        .is_empty());
+
+    let file: requested_file::Reader = reader.get_root().unwrap();
+    assert_eq!(file.get_id(), 0);
+    assert!(!file.has_filename());
+}
+
+#[test]
+fn serialize_untouched_builder() {
+    let builder = message::Builder::new_default();
+    assert_eq!(builder.get_segments_for_output().len(), 1);
+    assert_eq!(builder.get_segments_for_output().total_words(), 1);
+
+    let words = serialize::write_message_to_words(&builder);
+    // Segment table plus the null root pointer.
+    assert_eq!(words, [0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(serialize::compute_serialized_size_in_words(&builder), 2);
+
+    let mut written = Vec::new();
+    serialize::write_message(&mut written, &builder).unwrap();
+    assert_eq!(written, words);
+
+    let reader =
+        serialize::read_message_from_flat_slice(&mut &words[..], ReaderOptions::new()).unwrap();
+    check_default_roots(&reader);
+    assert!(reader.is_canonical().unwrap());
+}
+
+#[test]
+fn untouched_builder_into_reader() {
+    let reader = message::Builder::new_default().into_reader();
+    check_default_roots(&reader);
+    assert_eq!(
+        reader.canonicalize().unwrap(),
+        [capnp::word(0, 0, 0, 0, 0, 0, 0, 0)]
+    );
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, HeapAllocator, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::serialize::{self, OwnedSegments};
+
+const NAMES: [&str; 3] = ["first", "second", "third"];
+
+/// Builds a node in field order, in a single segment.
+fn in_order() -> message::Builder<HeapAllocator> {
+    let mut builder = message::Builder::new_default();
+    let mut root: node::Builder = builder.init_root();
+    root.set_id(7);
+    root.set_display_name("canonical.capnp".into());
+    let mut nested = root.init_nested_nodes(3);
+    for (idx, name) in NAMES.iter().enumerate() {
+        nested.reborrow().get(idx as u32).set_name((*name).into());
+    }
+    builder
+}
+
+/// Builds the same node, but in reverse order, in segments so small that most pointers are far,
+/// and with the ids of the nested nodes set to zero explicitly.
+fn out_of_order() -> message::Builder<HeapAllocator> {
+    let mut builder = message::Builder::new(HeapAllocator::new().first_segment_words(2));
+    let mut root: node::Builder = builder.init_root();
+    let mut nested = root.reborrow().init_nested_nodes(3);
+    for (idx, name) in NAMES.iter().enumerate().rev() {
+        let mut element = nested.reborrow().get(idx as u32);
+        element.set_name((*name).into());
+        element.set_id(0);
+    }
+    root.set_display_name("canonical.capnp".into());
+    root.set_id(7);
+    assert!(builder.get_segments_for_output().len() > 1);
+    builder
+}
+
+fn canonical_bytes(builder: &message::Builder<HeapAllocator>) -> Vec<u8> {
+    let bytes = serialize::write_message_to_bytes(builder);
+    let message = serialize::read_message(&bytes[..], ReaderOptions::new()).unwrap();
+    let mut canonical = Vec::new();
+    serialize::write_canonical(&mut canonical, &message).unwrap();
+    canonical
+}
+
+fn read(bytes: &[u8]) -> message::Reader<OwnedSegments> {
+    serialize::read_message(bytes, ReaderOptions::new()).unwrap()
+}
+
+#[test]
+fn allocation_order_does_not_matter() {
+    let canonical = canonical_bytes(&in_order());
+    assert_eq!(canonical_bytes(&out_of_order()), canonical);
+
+    let message = read(&canonical);
+    assert!(message.is_canonical().unwrap());
+    let root: node::Reader = message.get_root().unwrap();
+    assert_eq!(root.get_id(), 7);
+    assert_eq!(root.get_display_name().unwrap(), "canonical.capnp");
+}
+
+#[test]
+fn far_pointers_are_collapsed() {
+    let canonical = canonical_bytes(&out_of_order());
+    let (lengths, table_len) = serialize::parse_segment_table_bytes(&canonical).unwrap();
+    assert_eq!(lengths.len(), 1);
+    assert_eq!(table_len + lengths[0] as usize * 8, canonical.len());
+
+    // The segment is the canonical form of the message.
+    let message = read(&canonical);
+    let words = message.canonicalize().unwrap();
+    assert_eq!(capnp::Word::words_to_bytes(&words), &canonical[table_len..]);
+}
+
+#[test]
+fn trailing_zero_words_of_list_elements_are_truncated() {
+    let message = read(&canonical_bytes(&out_of_order()));
+    let root: node::Reader = message.get_root().unwrap();
+    let nested = root.get_nested_nodes().unwrap();
+    assert_eq!(nested.len(), 3);
+    for (element, name) in nested.iter().zip(NAMES) {
+        // The only data field, the id, is zero.
+        assert!(capnp::raw::get_struct_data_section(element).is_empty());
+        assert_eq!(element.get_id(), 0);
+        assert_eq!(element.get_name().unwrap(), name);
+    }
+}
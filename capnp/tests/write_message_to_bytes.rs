@@ -0,0 +1,56 @@
+#![cfg(feature = "alloc")]
+
+use capnp::message::{self, ReaderOptions};
+use capnp::schema_capnp::node;
+use capnp::serialize;
+
+fn round_trip(builder: &message::Builder<message::HeapAllocator>) {
+    let bytes = serialize::write_message_to_bytes(builder);
+    assert_eq!(bytes.capacity(), bytes.len());
+    assert_eq!(
+        bytes.len(),
+        serialize::compute_serialized_size_in_words(builder) * 8
+    );
+
+    let mut written = Vec::new();
+    serialize::write_message(&mut written, builder).unwrap();
+    assert_eq!(bytes, written);
+    assert_eq!(serialize::write_message_to_words(builder), bytes);
+
+    let message = serialize::read_message(&bytes[..], ReaderOptions::new()).unwrap();
+    let root: node::Reader = message.get_root().unwrap();
+    assert_eq!(root.get_id(), 11);
+    assert_eq!(
+        root.get_display_name().unwrap(),
+        "write_message_to_bytes.capnp"
+    );
+    assert_eq!(root.get_nested_nodes().unwrap().len(), 3);
+}
+
+fn build(builder: &mut message::Builder<message::HeapAllocator>) {
+    let mut root: node::Builder = builder.init_root();
+    root.set_id(11);
+    root.set_display_name("write_message_to_bytes.capnp".into());
+    root.init_nested_nodes(3);
+}
+
+#[test]
+fn one_segment() {
+    let mut builder = message::Builder::new_default();
+    build(&mut builder);
+    assert_eq!(builder.get_segments_for_output().len(), 1);
+    round_trip(&builder);
+}
+
+#[test]
+fn several_segments() {
+    // Three segments, whose table is a whole number of words, and two, whose table is padded.
+    for (first_segment_words, segment_count) in [(1, 3), (10, 2)] {
+        let mut builder = message::Builder::new(
+            message::HeapAllocator::new().first_segment_words(first_segment_words),
+        );
+        build(&mut builder);
+        assert_eq!(builder.get_segments_for_output().len(), segment_count);
+        round_trip(&builder);
+    }
+}
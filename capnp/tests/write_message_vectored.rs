@@ -0,0 +1,108 @@
+#![cfg(all(feature = "std", feature = "alloc"))]
+
+use std::io::{self, IoSlice};
+
+use capnp::message;
+use capnp::schema_capnp::node;
+use capnp::serialize;
+
+/// Records the bytes written to it and the calls they took, taking at most `max_write` bytes per
+/// call.
+struct CountingWriter {
+    bytes: Vec<u8>,
+    writes: usize,
+    vectored_writes: usize,
+    max_write: usize,
+    /// Added to the counts `write_vectored()` reports.
+    overcount: usize,
+}
+
+impl CountingWriter {
+    fn new(max_write: usize) -> Self {
+        Self {
+            bytes: Vec::new(),
+            writes: 0,
+            vectored_writes: 0,
+            max_write,
+            overcount: 0,
+        }
+    }
+}
+
+impl io::Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writes += 1;
+        let n = buf.len().min(self.max_write);
+        self.bytes.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.vectored_writes += 1;
+        let mut n = 0;
+        for buf in bufs {
+            let take = buf.len().min(self.max_write - n);
+            self.bytes.extend_from_slice(&buf[..take]);
+            n += take;
+        }
+        Ok(n + self.overcount)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn three_segments() -> message::Builder<message::HeapAllocator> {
+    let mut builder = message::Builder::new(message::HeapAllocator::new().first_segment_words(1));
+    let mut root: node::Builder = builder.init_root();
+    root.set_display_name("write_message_vectored.capnp".into());
+    root.init_nested_nodes(3);
+    assert_eq!(builder.get_segments_for_output().len(), 3);
+    builder
+}
+
+#[test]
+fn one_call_for_all_segments() {
+    let builder = three_segments();
+    let mut writer = CountingWriter::new(usize::MAX);
+    serialize::write_message_vectored(&mut writer, &builder).unwrap();
+    assert_eq!(writer.vectored_writes, 1);
+    assert_eq!(writer.writes, 0);
+    assert_eq!(writer.bytes, serialize::write_message_to_bytes(&builder));
+
+    // Plain write_message() still writes each word of the table, and each segment, separately.
+    let mut writer = CountingWriter::new(usize::MAX);
+    serialize::write_message(&mut writer, &builder).unwrap();
+    assert_eq!(writer.vectored_writes, 0);
+    assert_eq!(writer.writes, 5);
+}
+
+#[test]
+fn short_writes_are_resumed() {
+    let builder = three_segments();
+    let expected = serialize::write_message_to_bytes(&builder);
+    for max_write in [1, 5, 8, 13] {
+        let mut writer = CountingWriter::new(max_write);
+        serialize::write_message_vectored(&mut writer, &builder).unwrap();
+        assert_eq!(writer.bytes, expected);
+        assert_eq!(writer.vectored_writes, expected.len().div_ceil(max_write));
+    }
+}
+
+#[test]
+fn zero_length_write_is_an_error() {
+    let builder = three_segments();
+    let mut writer = CountingWriter::new(0);
+    assert!(serialize::write_message_vectored(&mut writer, &builder).is_err());
+}
+
+#[test]
+fn overcounted_write_is_an_error() {
+    let builder = three_segments();
+    for max_write in [5, usize::MAX] {
+        let mut writer = CountingWriter::new(max_write);
+        writer.overcount = 1;
+        assert!(serialize::write_message_vectored(&mut writer, &builder).is_err());
+    }
+}
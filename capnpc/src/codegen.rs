@@ -1020,10 +1020,16 @@ fn generate_setter(
     let params_struct_impl_prefix = if is_params_struct { "" } else { "self." };
     let mut setter_interior = Vec::new();
     let mut setter_param = "value".to_string();
+    let mut try_setter_interior = Vec::new();
+    let mut has_try_setter = false;
     let mut initter_interior = Vec::new();
     let mut initter_mut = false;
     let mut initn_interior = Vec::new();
     let mut initter_params = Vec::new();
+    let mut try_initter_interior = Vec::new();
+    let mut has_try_initter = false;
+    let mut reserver_interior = Vec::new();
+    let mut reservation_type = None;
     let mut no_discriminant = true;
 
     let discriminant_value = field.get_discriminant_value();
@@ -1037,7 +1043,10 @@ fn generate_setter(
             "self.builder.set_data_field::<u16>({}, {});",
             discriminant_offset as usize, discriminant_value as usize
         ));
+        try_setter_interior.push(init_discrim.clone());
         initter_interior.push(init_discrim.clone());
+        try_initter_interior.push(init_discrim.clone());
+        reserver_interior.push(init_discrim.clone());
         initn_interior.push(init_discrim);
     }
 
@@ -1138,10 +1147,20 @@ fn generate_setter(
                     setter_interior.push(Line(format!(
                         "self.builder.reborrow().get_pointer_field({offset}).set_text(value);"
                     )));
+                    try_setter_interior.push(Line(format!(
+                        "self.builder.reborrow().get_pointer_field({offset}).try_set_text(value)"
+                    )));
+                    has_try_setter = true;
                     initter_interior.push(Line(format!(
                         "self.builder.get_pointer_field({offset}).init_text(size)"
                     )));
+                    try_initter_interior.push(Line(format!(
+                        "self.builder.get_pointer_field({offset}).try_init_text(size)"
+                    )));
+                    has_try_initter = true;
                     initter_params.push("size: u32");
+                    reserver_interior.push(Line(fmt!(ctx, "{capnp}::text::reserve({capnp}::any_pointer::Builder::new(self.builder.get_pointer_field({offset})), max_len)")));
+                    reservation_type = Some(fmt!(ctx, "{capnp}::text::Reservation<'a>"));
                     if no_discriminant {
                         rust_struct_inner.push_str(
                             format!("{params_struct_prefix}_{styled_name}: String,").as_str(),
@@ -1157,10 +1176,20 @@ fn generate_setter(
                     setter_interior.push(Line(format!(
                         "self.builder.reborrow().get_pointer_field({offset}).set_data(value);"
                     )));
+                    try_setter_interior.push(Line(format!(
+                        "self.builder.reborrow().get_pointer_field({offset}).try_set_data(value)"
+                    )));
+                    has_try_setter = true;
                     initter_interior.push(Line(format!(
                         "self.builder.get_pointer_field({offset}).init_data(size)"
                     )));
+                    try_initter_interior.push(Line(format!(
+                        "self.builder.get_pointer_field({offset}).try_init_data(size)"
+                    )));
+                    has_try_initter = true;
                     initter_params.push("size: u32");
+                    reserver_interior.push(Line(fmt!(ctx, "{capnp}::data::reserve({capnp}::any_pointer::Builder::new(self.builder.get_pointer_field({offset})), max_len)")));
+                    reservation_type = Some(fmt!(ctx, "{capnp}::data::Reservation<'a>"));
                     if no_discriminant {
                         rust_struct_inner.push_str(
                             format!("{params_struct_prefix}_{styled_name}: Vec<u8>,").as_str(),
@@ -1180,6 +1209,9 @@ fn generate_setter(
                     initter_params.push("size: u32");
                     initter_interior.push(
                         Line(fmt!(ctx,"{capnp}::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field({offset}), size)")));
+                    try_initter_interior.push(
+                        Line(fmt!(ctx,"{capnp}::traits::FromPointerBuilder::try_init_pointer(self.builder.get_pointer_field({offset}), size)")));
+                    has_try_initter = true;
 
                     if no_discriminant {
                         if let Ok(vec_of_list_element_types) =
@@ -1252,6 +1284,9 @@ fn generate_setter(
                     return_result = true;
                     initter_interior.push(
                       Line(fmt!(ctx,"{capnp}::traits::FromPointerBuilder::init_pointer(self.builder.get_pointer_field({offset}), 0)")));
+                    try_initter_interior.push(
+                      Line(fmt!(ctx,"{capnp}::traits::FromPointerBuilder::try_init_pointer(self.builder.get_pointer_field({offset}), 0)")));
+                    has_try_initter = true;
 
                     let type_string = get_params_struct_path_string(ctx, st)?;
                     if no_discriminant && get_params(ctx, st.get_type_id())?.is_empty() {
@@ -1372,6 +1407,15 @@ fn generate_setter(
         )));
         result.push(indent(setter_interior));
         result.push(line("}"));
+        if has_try_setter {
+            result.push(line("#[inline]"));
+            result.push(Line(fmt!(
+                ctx,
+                "pub fn try_set_{styled_name}(&mut self, {setter_param}: {reader_type}) -> {capnp}::Result<()> {{"
+            )));
+            result.push(indent(try_setter_interior));
+            result.push(line("}"));
+        }
     }
     if let Some(builder_type) = maybe_builder_type {
         result.push(line("#[inline]"));
@@ -1382,6 +1426,24 @@ fn generate_setter(
         )));
         result.push(indent(initter_interior));
         result.push(line("}"));
+        if has_try_initter {
+            result.push(line("#[inline]"));
+            result.push(Line(fmt!(
+                ctx,
+                "pub fn try_init_{styled_name}(self, {args}) -> {capnp}::Result<{builder_type}> {{"
+            )));
+            result.push(indent(try_initter_interior));
+            result.push(line("}"));
+        }
+    }
+    if let Some(reservation_type) = reservation_type {
+        result.push(line("#[inline]"));
+        result.push(Line(fmt!(
+            ctx,
+            "pub fn reserve_{styled_name}(self, max_len: u32) -> {capnp}::Result<{reservation_type}> {{"
+        )));
+        result.push(indent(reserver_interior));
+        result.push(line("}"));
     }
     Ok(Branch(result))
 }
@@ -2814,6 +2876,9 @@ fn generate_node(
                         Line(fmt!(ctx,"fn init_pointer(builder: {capnp}::private::layout::PointerBuilder<'a>, _size: u32) -> Self {{")),
                         indent(Line(fmt!(ctx,"builder.init_struct(<Self as {capnp}::traits::HasStructSize>::STRUCT_SIZE).into()"))),
                         line("}"),
+                        Line(fmt!(ctx,"fn try_init_pointer(builder: {capnp}::private::layout::PointerBuilder<'a>, _size: u32) -> {capnp}::Result<Self> {{")),
+                        indent(Line(fmt!(ctx,"::core::result::Result::Ok(builder.try_init_struct(<Self as {capnp}::traits::HasStructSize>::STRUCT_SIZE)?.into())"))),
+                        line("}"),
                         Line(fmt!(ctx,"fn get_from_pointer(builder: {capnp}::private::layout::PointerBuilder<'a>, default: ::core::option::Option<&'a [{capnp}::Word]>) -> {capnp}::Result<Self> {{")),
                         indent(Line(fmt!(ctx,"::core::result::Result::Ok(builder.get_struct(<Self as {capnp}::traits::HasStructSize>::STRUCT_SIZE, default)?.into())"))),
                         line("}")
@@ -2975,6 +3040,15 @@ fn generate_node(
                 ]),
                 line("}"),
                 BlankLine,
+                Line(fmt!(ctx,"impl <'a,{0}> {capnp}::traits::IntoInternalStructBuilder<'a> for Builder<'a,{0}> {1} {{",
+                            params.params, params.where_clause)),
+                indent(vec![
+                    Line(fmt!(ctx,"fn into_internal_struct_builder(self) -> {capnp}::private::layout::StructBuilder<'a> {{")),
+                    indent(line("self.builder")),
+                    line("}")
+                ]),
+                line("}"),
+                BlankLine,
                 Line(fmt!(ctx,"impl <'a,{0}> ::core::convert::From<Builder<'a,{0}>> for {capnp}::dynamic_value::Builder<'a> {1} {{",
                             params.params, params.where_clause)),
                 indent(vec![
@@ -299,6 +299,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_reserve_blob() {
+        use crate::test_capnp::{test_blob, test_union};
+
+        let mut message = message::Builder::new(message::HeapAllocator::new());
+        let mut test_blob = message.init_root::<test_blob::Builder<'_>>();
+
+        let mut text = test_blob.reborrow().reserve_text_field(10).unwrap();
+        text.as_bytes_mut()[..3].copy_from_slice(b"abc");
+        text.finish(3);
+        let mut data = test_blob.reborrow().reserve_data_field(10).unwrap();
+        data.as_bytes_mut()[..2].copy_from_slice(&[1, 2]);
+        data.finish(2);
+        assert_eq!(
+            test_blob.reborrow().into_reader().get_text_field().unwrap(),
+            "abc"
+        );
+        assert!(test_blob.reborrow().into_reader().get_data_field().unwrap() == [1, 2]);
+        assert_eq!(
+            test_blob.reserve_data_field(1 << 29).err().unwrap().kind,
+            capnp::ErrorKind::BlobTooLong(1 << 29)
+        );
+
+        // Reserving a union member sets the discriminant.
+        let mut test_union = message.init_root::<test_union::Builder<'_>>();
+        test_union.reborrow().get_union0().set_u0f0s32(7);
+        let text = test_union
+            .reborrow()
+            .get_union0()
+            .reserve_u0f0sp(10)
+            .unwrap();
+        text.finish(0);
+        assert!(matches!(
+            test_union.into_reader().get_union0().which(),
+            Ok(test_union::union0::U0f0sp(Ok(text))) if text.is_empty()
+        ));
+    }
+
     #[test]
     fn test_big_struct() {
         use crate::test_capnp::test_big_struct;